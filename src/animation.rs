@@ -0,0 +1,94 @@
+//! Frame-driven animation progress.
+//!
+//! Animations advance on the redraw timestamps from [`iced::window::frames`]
+//! by the time elapsed since the previous frame, so they take the same time
+//! at any refresh rate and stop costing anything while a surface is not
+//! being drawn.
+//!
+//! Duration and easing come from `[animations]` (see
+//! `AnimationsConfig::start`), which can also turn them off.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// Curve mapping linear progress to the eased one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Easing {
+    Linear,
+    /// Quadratic, fast start and slow end
+    #[default]
+    EaseOut,
+    /// Cubic, a more pronounced slow end
+    EaseOutCubic,
+    /// Quadratic, slow start and end
+    EaseInOut,
+}
+
+impl Easing {
+    pub fn apply(self, t: f32) -> f32 {
+        match self {
+            Easing::Linear => t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(2),
+            Easing::EaseOutCubic => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut if t < 0.5 => 2.0 * t * t,
+            Easing::EaseInOut => 1.0 - (2.0 - 2.0 * t).powi(2) / 2.0,
+        }
+    }
+}
+
+/// Progress of a single animation from 0.0 to 1.0.
+#[derive(Debug, Clone, Copy)]
+pub struct Animation {
+    progress: f32,
+    duration: Duration,
+    easing: Easing,
+    /// Timestamp of the last frame, `None` until the first frame
+    last_frame: Option<Instant>,
+}
+
+impl Animation {
+    /// A new animation starting at 0.0.
+    pub fn new(duration: Duration, easing: Easing) -> Self {
+        Self {
+            progress: 0.0,
+            duration,
+            easing,
+            last_frame: None,
+        }
+    }
+
+    /// A finished animation (progress 1.0).
+    pub fn finished() -> Self {
+        Self {
+            progress: 1.0,
+            ..Self::new(Duration::ZERO, Easing::default())
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.progress < 1.0
+    }
+
+    /// Linear progress from 0.0 to 1.0.
+    pub fn progress(&self) -> f32 {
+        self.progress
+    }
+
+    /// Progress with the configured easing.
+    pub fn eased(&self) -> f32 {
+        self.easing.apply(self.progress)
+    }
+
+    /// Advance by the time elapsed since the previous frame. The first frame
+    /// only records its timestamp.
+    pub fn advance(&mut self, now: Instant) {
+        if let Some(last) = self.last_frame {
+            let elapsed = now.saturating_duration_since(last).as_secs_f32();
+            let step = elapsed / self.duration.as_secs_f32().max(f32::EPSILON);
+            self.progress = (self.progress + step).min(1.0);
+        }
+        self.last_frame = Some(now);
+    }
+}
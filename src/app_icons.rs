@@ -0,0 +1,183 @@
+//! Icons of applications by their window class, shared by widgets that
+//! show open windows.
+//!
+//! A class is matched, ignoring case, against the desktop entries in the
+//! XDG data directories: by file name (`firefox.desktop`, or the last part
+//! of reverse-DNS names like `org.gnome.Nautilus.desktop`) or by
+//! `StartupWMClass`. The entry's `Icon` is a path or a name looked up in
+//! the hicolor theme and `pixmaps`. Only PNG icons are found, since those
+//! are the ones the bar can draw. Entries are read once and lookups are
+//! cached for the session.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use iced::widget::image;
+
+/// Theme sizes tried for icon names, closest to the bar's icons first.
+const ICON_SIZES: [&str; 7] = [
+    "32x32", "48x48", "24x24", "64x64", "128x128", "256x256", "16x16",
+];
+
+/// Icon of each class looked up so far, by lowercase class.
+static CACHE: Mutex<Option<HashMap<String, Option<image::Handle>>>> = Mutex::new(None);
+
+/// A desktop entry, as far as icons go.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Entry {
+    /// Lowercase file name without `.desktop`
+    id: String,
+    /// Lowercase `StartupWMClass`
+    wm_class: Option<String>,
+    /// `Icon`, a name or a path
+    icon: String,
+}
+
+impl Entry {
+    fn matches(&self, class: &str) -> bool {
+        self.wm_class.as_deref() == Some(class)
+            || self.id == class
+            || self.id.rsplit('.').next() == Some(class)
+    }
+}
+
+/// Icon of the application with a window class, if it has a PNG icon.
+pub fn lookup(class: &str) -> Option<image::Handle> {
+    let class = class.to_lowercase();
+    if class.is_empty() {
+        return None;
+    }
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    let cache = cache.get_or_insert_with(HashMap::new);
+    if let Some(handle) = cache.get(&class) {
+        return handle.clone();
+    }
+
+    let dirs = data_dirs();
+    let handle = entries()
+        .iter()
+        .find(|entry| entry.matches(&class))
+        .map(|entry| entry.icon.as_str())
+        // Apps without an entry often name their icon after the class
+        .or(Some(class.as_str()))
+        .and_then(|icon| find_icon(icon, &dirs))
+        .map(image::Handle::from_path);
+    cache.insert(class, handle.clone());
+    handle
+}
+
+/// `$XDG_DATA_HOME` followed by `$XDG_DATA_DIRS`.
+fn data_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = dirs::data_dir().into_iter().collect();
+    let system = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    dirs.extend(system.split(':').map(PathBuf::from));
+    dirs
+}
+
+/// Desktop entries with an icon; earlier data directories win.
+fn entries() -> &'static [Entry] {
+    static ENTRIES: OnceLock<Vec<Entry>> = OnceLock::new();
+    ENTRIES.get_or_init(|| {
+        let mut entries: Vec<Entry> = Vec::new();
+        for dir in data_dirs() {
+            let Ok(files) = fs::read_dir(dir.join("applications")) else {
+                continue;
+            };
+            for file in files.flatten() {
+                let path = file.path();
+                let Some(id) = path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(|name| name.strip_suffix(".desktop"))
+                    .map(str::to_lowercase)
+                else {
+                    continue;
+                };
+                if entries.iter().any(|entry| entry.id == id) {
+                    continue;
+                }
+                if let Some(entry) = fs::read_to_string(&path)
+                    .ok()
+                    .and_then(|content| parse_entry(id, &content))
+                {
+                    entries.push(entry);
+                }
+            }
+        }
+        entries
+    })
+}
+
+/// The `[Desktop Entry]` group of a desktop file, if it has an icon.
+fn parse_entry(id: String, content: &str) -> Option<Entry> {
+    let mut entry = Entry {
+        id,
+        ..Entry::default()
+    };
+    let mut in_group = false;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_group = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_group {
+            continue;
+        }
+        match line.split_once('=').map(|(key, value)| (key.trim(), value.trim())) {
+            Some(("Icon", icon)) => entry.icon = icon.to_string(),
+            Some(("StartupWMClass", class)) => entry.wm_class = Some(class.to_lowercase()),
+            _ => {}
+        }
+    }
+    (!entry.icon.is_empty()).then_some(entry)
+}
+
+/// PNG file of an icon name or path.
+fn find_icon(icon: &str, dirs: &[PathBuf]) -> Option<PathBuf> {
+    if icon.starts_with('/') {
+        let path = Path::new(icon);
+        return (path.extension().is_some_and(|ext| ext == "png") && path.exists())
+            .then(|| path.to_path_buf());
+    }
+    let file = format!("{}.png", icon);
+    for dir in dirs {
+        let theme = dir.join("icons").join("hicolor");
+        for size in ICON_SIZES {
+            let path = theme.join(size).join("apps").join(&file);
+            if path.exists() {
+                return Some(path);
+            }
+        }
+        let path = dir.join("pixmaps").join(&file);
+        if path.exists() {
+            return Some(path);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_classes_to_entries() {
+        let content = "[Desktop Entry]\nName=Files\nIcon=org.gnome.Nautilus\n\
+                       StartupWMClass=Nautilus\n[Desktop Action new-window]\nIcon=other";
+        let entry = parse_entry("org.gnome.nautilus".to_string(), content).unwrap();
+        assert_eq!(entry.icon, "org.gnome.Nautilus");
+        assert!(entry.matches("nautilus"));
+        assert!(entry.matches("org.gnome.nautilus"));
+        assert!(!entry.matches("firefox"));
+
+        assert_eq!(
+            parse_entry("no-icon".to_string(), "[Desktop Entry]\nName=x"),
+            None
+        );
+    }
+}
@@ -0,0 +1,97 @@
+//! Light/dark switching of the theme.
+//!
+//! With `theme.switch` set, the bar applies `[theme.light]` or
+//! `[theme.dark]` on top of `[theme]`, following either the desktop's
+//! `org.freedesktop.appearance color-scheme` setting (read from the
+//! settings portal) or a time-of-day schedule.
+
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+
+use crate::config::ThemeConfig;
+
+/// Seconds between checks of the portal setting or the schedule.
+pub const POLL_INTERVAL_SECS: u64 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorScheme {
+    Light,
+    Dark,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeSwitch {
+    /// Only `[theme]` applies
+    #[default]
+    Off,
+    /// Follow the desktop's light/dark preference
+    Portal,
+    /// Light from `light_from`, dark from `dark_from`
+    Schedule,
+}
+
+/// The scheme to apply now; `None` when switching is off or the portal has
+/// no preference.
+pub async fn current(theme: ThemeConfig) -> Option<ColorScheme> {
+    match theme.switch {
+        ThemeSwitch::Off => None,
+        ThemeSwitch::Portal => portal().await,
+        ThemeSwitch::Schedule => {
+            let now = chrono::Local::now().time();
+            Some(scheduled(&theme.light_from, &theme.dark_from, now))
+        }
+    }
+}
+
+/// The `color-scheme` setting of the settings portal: 1 prefers dark, 2
+/// prefers light, 0 has no preference.
+async fn portal() -> Option<ColorScheme> {
+    let output = tokio::process::Command::new("busctl")
+        .args([
+            "--user",
+            "call",
+            "org.freedesktop.portal.Desktop",
+            "/org/freedesktop/portal/desktop",
+            "org.freedesktop.portal.Settings",
+            "ReadOne",
+            "ss",
+            "org.freedesktop.appearance",
+            "color-scheme",
+        ])
+        .output()
+        .await
+        .ok()?;
+    // Prints e.g. `v u 1`
+    match String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .last()
+    {
+        Some("1") => Some(ColorScheme::Dark),
+        Some("2") => Some(ColorScheme::Light),
+        _ => None,
+    }
+}
+
+/// Light between `light_from` and `dark_from` ("HH:MM"), dark otherwise.
+/// Unparsable times fall back to 07:00 and 19:00.
+fn scheduled(light_from: &str, dark_from: &str, now: NaiveTime) -> ColorScheme {
+    let parse = |time: &str, default: u32| {
+        NaiveTime::parse_from_str(time.trim(), "%H:%M")
+            .unwrap_or_else(|_| NaiveTime::from_hms_opt(default, 0, 0).unwrap_or_default())
+    };
+    let light = parse(light_from, 7);
+    let dark = parse(dark_from, 19);
+
+    let is_light = if light <= dark {
+        now >= light && now < dark
+    } else {
+        // The light period spans midnight
+        now >= light || now < dark
+    };
+    if is_light {
+        ColorScheme::Light
+    } else {
+        ColorScheme::Dark
+    }
+}
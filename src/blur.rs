@@ -0,0 +1,24 @@
+//! Blur behind the bar under Hyprland (`bar.blur`).
+//!
+//! Hyprland blurs layer surfaces through `layerrule`s matching their
+//! namespace, `bar.namespace` for all of clammy's surfaces. With `bar.blur`
+//! clammy adds the rules itself at runtime; `ignorezero` keeps the fully
+//! transparent corners and gaps of translucent themes from being blurred.
+//! Rules added this way are gone after `hyprctl reload`, so configs that
+//! reload Hyprland often are better off with the same rules in
+//! `hyprland.conf`.
+
+use hyprland::keyword::Keyword;
+
+/// Rules added for the namespace.
+const RULES: &[&str] = &["blur", "ignorezero"];
+
+/// Add the blur layer rules for `namespace`.
+pub async fn enable(namespace: String) {
+    for rule in RULES {
+        let value = format!("{},{}", rule, namespace);
+        if let Err(e) = Keyword::set_async("layerrule", value).await {
+            eprintln!("Failed to add layer rule `{}` for blur: {}", rule, e);
+        }
+    }
+}
@@ -0,0 +1,194 @@
+//! `clammy --check-config [file]`: validate the config without starting the
+//! bar.
+//!
+//! Errors are problems the bar works around in a way the user probably did
+//! not intend: a config that does not parse (the bar falls back to the
+//! defaults), invalid colors and `file:`/`carousel:` modules that are not
+//! defined. Warnings are keys the bar does not know (typos are otherwise
+//! ignored silently), commands that are not found and files that do not
+//! exist yet. Exits with 1 when there are errors.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use toml::{Table, Value};
+
+use crate::config::{Config, cli_args, config_path, expand_home};
+use crate::migrate::CONFIG_VERSION;
+use crate::profile;
+
+/// Argument that checks the config and exits.
+pub const CHECK_FLAG: &str = "--check-config";
+
+#[derive(Debug, Default)]
+struct Report {
+    errors: Vec<String>,
+    warnings: Vec<String>,
+}
+
+/// Handle `--check-config [file]`: print a report on the config (the
+/// default one unless a file is given) and exit. Returns when clammy was
+/// started as the bar.
+pub fn run_if_requested() {
+    let args = cli_args();
+    let path = match args.as_slice() {
+        [flag] if flag == CHECK_FLAG => config_path(),
+        [flag, file] if flag == CHECK_FLAG => PathBuf::from(file),
+        _ => return,
+    };
+
+    let report = check(&path);
+    println!("{}", path.display());
+    for error in &report.errors {
+        println!("  error: {}", error);
+    }
+    for warning in &report.warnings {
+        println!("  warning: {}", warning);
+    }
+    println!(
+        "{} error(s), {} warning(s)",
+        report.errors.len(),
+        report.warnings.len()
+    );
+    std::process::exit(if report.errors.is_empty() { 0 } else { 1 });
+}
+
+fn check(path: &Path) -> Report {
+    let mut report = Report::default();
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => {
+            report.errors.push(format!("reading the config: {}", e));
+            return report;
+        }
+    };
+    let (config, migrated) = match Config::parse(&content) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            report.errors.push(e.to_string().trim_end().to_string());
+            return report;
+        }
+    };
+    let written = match migrated {
+        Some((from, upgraded)) => {
+            report.warnings.push(format!(
+                "written for config version {}, the bar will migrate it to {} (keeping a backup)",
+                from, CONFIG_VERSION
+            ));
+            Ok(upgraded)
+        }
+        None => toml::from_str::<Table>(&content),
+    };
+
+    // Keys the bar knows survive a round trip through `Config`
+    if let (Ok(written), Ok(known)) = (written, Table::try_from(&config)) {
+        unknown_keys(&written, &known, "", &mut report.warnings);
+        // Check every profile as if it applied
+        for (name, profile) in &config.profiles {
+            let section = format!("profiles.{}", name);
+            let mut merged = written.clone();
+            profile::merge(&mut merged, profile.settings.clone());
+            match merged.try_into::<Config>() {
+                Ok(merged) => {
+                    if let Ok(known) = Table::try_from(&merged) {
+                        unknown_keys(&profile.settings, &known, &section, &mut report.warnings);
+                    }
+                }
+                Err(e) => report.errors.push(format!(
+                    "{}: {}",
+                    section,
+                    e.to_string().trim_end().replace('\n', " ")
+                )),
+            }
+        }
+    }
+    report.errors.extend(config.color_errors());
+    report.errors.extend(config.reference_errors());
+    check_commands(&config, &mut report);
+    report
+}
+
+/// Keys of `written` missing from `known`, as dotted paths.
+fn unknown_keys(written: &Table, known: &Table, prefix: &str, unknown: &mut Vec<String>) {
+    for (key, value) in written {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        // Aliases like `max-length` come back under their own name
+        let known_value = known.get(key).or_else(|| known.get(&key.replace('-', "_")));
+        match (value, known_value) {
+            (_, None) => unknown.push(format!("unknown key `{}`", path)),
+            (Value::Table(written), Some(Value::Table(known))) => {
+                unknown_keys(written, known, &path, unknown);
+            }
+            (Value::Array(written), Some(Value::Array(known))) => {
+                for (index, pair) in written.iter().zip(known).enumerate() {
+                    if let (Value::Table(written), Value::Table(known)) = pair {
+                        unknown_keys(written, known, &format!("{}[{}]", path, index), unknown);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Commands of custom widgets and click actions that are not found, and
+/// files of file widgets that do not exist.
+fn check_commands(config: &Config, report: &mut Report) {
+    for widget in &config.file_widgets {
+        let section = format!("file_widgets \"{}\"", widget.name);
+        match (&widget.exec, widget.path.as_str()) {
+            (Some(command), _) => check_command(&section, command, report),
+            (None, "") => report
+                .errors
+                .push(format!("{}: sets neither `path` nor `exec`", section)),
+            (None, path) => {
+                if !expand_home(path).exists() {
+                    report
+                        .warnings
+                        .push(format!("{}: {} does not exist", section, path));
+                }
+            }
+        }
+    }
+    for (kind, actions) in &config.actions {
+        let commands = [
+            &actions.on_click,
+            &actions.on_click_right,
+            &actions.on_click_middle,
+            &actions.on_scroll_up,
+            &actions.on_scroll_down,
+        ];
+        for command in commands.into_iter().flatten() {
+            check_command(&format!("actions.{}", kind), command, report);
+        }
+    }
+}
+
+/// Warn when the program a shell command starts is not found.
+fn check_command(section: &str, command: &str, report: &mut Report) {
+    // Skip `VAR=value` assignments in front of the program
+    let Some(program) = command.split_whitespace().find(|word| !word.contains('=')) else {
+        return;
+    };
+    let program = expand_home(program);
+    // `command -v` also knows shell builtins and functions
+    let found = Command::new("sh")
+        .args(["-c", "command -v \"$1\"", "sh"])
+        .arg(&program)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success());
+    if !found {
+        report.warnings.push(format!(
+            "{}: command `{}` not found",
+            section,
+            program.display()
+        ));
+    }
+}
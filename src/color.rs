@@ -0,0 +1,286 @@
+//! Color values of the config.
+//!
+//! Colors can be written as `#rgb`, `#rrggbb`, `#rrggbbaa`, `rgb(r, g, b)`,
+//! `rgba(r, g, b, a)` (channels 0-255, alpha 0-1) or a CSS color name such as
+//! `"rebeccapurple"`. Invalid colors are reported instead of drawn in white.
+
+use iced::Color;
+
+/// Parse a config color.
+pub fn parse(value: &str) -> Result<Color, String> {
+    let value = value.trim();
+    let invalid = || format!("invalid color {:?}", value);
+
+    if let Some(hex) = value.strip_prefix('#') {
+        return parse_hex(hex).ok_or_else(invalid);
+    }
+    let lower = value.to_ascii_lowercase();
+    if let Some(args) = lower
+        .strip_prefix("rgba(")
+        .or_else(|| lower.strip_prefix("rgb("))
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        return parse_rgb(args).ok_or_else(invalid);
+    }
+    NAMED
+        .iter()
+        .find(|(name, _)| *name == lower)
+        .map(|(_, rgb)| {
+            let [_, r, g, b] = rgb.to_be_bytes();
+            Color::from_rgb8(r, g, b)
+        })
+        .ok_or_else(invalid)
+}
+
+/// Parse a config color and multiply its alpha by `alpha`.
+pub fn parse_with_alpha(value: &str, alpha: f32) -> Result<Color, String> {
+    parse(value).map(|color| Color {
+        a: color.a * alpha,
+        ..color
+    })
+}
+
+fn parse_hex(hex: &str) -> Option<Color> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |range: std::ops::Range<usize>| u8::from_str_radix(&hex[range], 16).ok();
+    // `#rgb` repeats each digit: `#f80` is `#ff8800`
+    let short = |index: usize| channel(index..index + 1).map(|digit| digit * 17);
+    match hex.len() {
+        3 => Some(Color::from_rgb8(short(0)?, short(1)?, short(2)?)),
+        6 => Some(Color::from_rgb8(
+            channel(0..2)?,
+            channel(2..4)?,
+            channel(4..6)?,
+        )),
+        8 => Some(Color::from_rgba8(
+            channel(0..2)?,
+            channel(2..4)?,
+            channel(4..6)?,
+            channel(6..8)? as f32 / 255.0,
+        )),
+        _ => None,
+    }
+}
+
+/// `r, g, b` or `r, g, b, a`.
+fn parse_rgb(args: &str) -> Option<Color> {
+    let args: Vec<&str> = args.split(',').map(str::trim).collect();
+    let (rgb, alpha) = match args.as_slice() {
+        [r, g, b] => ([*r, *g, *b], 1.0),
+        [r, g, b, a] => ([*r, *g, *b], a.parse::<f32>().ok()?),
+        _ => return None,
+    };
+    if !(0.0..=1.0).contains(&alpha) {
+        return None;
+    }
+    let [r, g, b] = rgb;
+    Some(Color::from_rgba8(
+        r.parse().ok()?,
+        g.parse().ok()?,
+        b.parse().ok()?,
+        alpha,
+    ))
+}
+
+/// CSS color names.
+const NAMED: &[(&str, u32)] = &[
+    ("aliceblue", 0xf0f8ff),
+    ("antiquewhite", 0xfaebd7),
+    ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd),
+    ("blue", 0x0000ff),
+    ("blueviolet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflowerblue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff),
+    ("darkblue", 0x00008b),
+    ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xa9a9a9),
+    ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f),
+    ("darkorange", 0xff8c00),
+    ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000),
+    ("darksalmon", 0xe9967a),
+    ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f),
+    ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1),
+    ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff),
+    ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xadff2f),
+    ("grey", 0x808080),
+    ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4),
+    ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00),
+    ("lemonchiffon", 0xfffacd),
+    ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080),
+    ("lightcyan", 0xe0ffff),
+    ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90),
+    ("lightgrey", 0xd3d3d3),
+    ("lightpink", 0xffb6c1),
+    ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de),
+    ("lightyellow", 0xffffe0),
+    ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("magenta", 0xff00ff),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd),
+    ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db),
+    ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a),
+    ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead),
+    ("navy", 0x000080),
+    ("oldlace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orangered", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa),
+    ("palegreen", 0x98fb98),
+    ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5),
+    ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xff0000),
+    ("rosybrown", 0xbc8f8f),
+    ("royalblue", 0x4169e1),
+    ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandybrown", 0xf4a460),
+    ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xfffafa),
+    ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("wheat", 0xf5deb3),
+    ("white", 0xffffff),
+    ("whitesmoke", 0xf5f5f5),
+    ("yellow", 0xffff00),
+    ("yellowgreen", 0x9acd32),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hex_forms() {
+        assert_eq!(parse("#f80"), Ok(Color::from_rgb8(0xff, 0x88, 0x00)));
+        assert_eq!(parse("#7aa2f7"), Ok(Color::from_rgb8(0x7a, 0xa2, 0xf7)));
+        assert_eq!(
+            parse("#7aa2f780"),
+            Ok(Color::from_rgba8(0x7a, 0xa2, 0xf7, 128.0 / 255.0))
+        );
+    }
+
+    #[test]
+    fn parses_rgb_functions_and_names() {
+        assert_eq!(parse("rgb(1, 2, 3)"), Ok(Color::from_rgb8(1, 2, 3)));
+        assert_eq!(
+            parse("RGBA(1, 2, 3, 0.5)"),
+            Ok(Color::from_rgba8(1, 2, 3, 0.5))
+        );
+        assert_eq!(
+            parse(" RebeccaPurple "),
+            Ok(Color::from_rgb8(0x66, 0x33, 0x99))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_colors() {
+        for value in [
+            "",
+            "#12",
+            "#ggg",
+            "rgb(1, 2)",
+            "rgba(1, 2, 3, 2)",
+            "rgb(256, 0, 0)",
+            "blurple",
+        ] {
+            assert!(parse(value).is_err(), "{:?} should be invalid", value);
+        }
+    }
+
+    #[test]
+    fn multiplies_alpha() {
+        let color = parse_with_alpha("#00000080", 0.5).unwrap();
+        assert!((color.a - 64.0 / 255.0).abs() < 1e-6);
+    }
+}
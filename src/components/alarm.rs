@@ -0,0 +1,311 @@
+//! Alarms for the clock widget.
+//!
+//! Provides:
+//! - Alarms (time, label, optional command) persisted in the state directory
+//! - Firing with a notification, optional sound and a flashing bar indicator
+//! - Snooze / dismiss, and the alarm manager shown in the calendar popup
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveTime, Timelike};
+use iced::widget::{Column, button, column, row, scrollable, text, text_input};
+use iced::{Alignment, Border, Element, Length};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ClockConfig, ConfigError, state_dir};
+use crate::exec::Exec;
+use crate::theme::get_theme;
+
+/// Alarm rows visible in the popup before the list scrolls.
+const VISIBLE_ROWS: f32 = 3.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Alarm {
+    /// Time of day as "HH:MM"
+    pub time: String,
+    pub label: String,
+    /// Shell command run when the alarm fires
+    #[serde(default)]
+    pub command: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Day the alarm last fired, so it fires once per day
+    #[serde(skip)]
+    last_fired: Option<NaiveDate>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// On-disk format of the alarm state file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AlarmFile {
+    #[serde(default)]
+    alarms: Vec<Alarm>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    TimeInput(String),
+    LabelInput(String),
+    CommandInput(String),
+    Add,
+    Toggle(usize),
+    Remove(usize),
+    Snooze,
+    Dismiss,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Alarms {
+    alarms: Vec<Alarm>,
+    /// Label of the alarm currently ringing
+    ringing: Option<String>,
+    /// Snoozed alarm label and when it rings again
+    snoozed: Option<(String, DateTime<Local>)>,
+    /// Flash phase of the bar indicator, flipped every tick while ringing
+    flash: bool,
+    // Inputs of the "new alarm" form
+    time_input: String,
+    label_input: String,
+    command_input: String,
+}
+
+impl Alarms {
+    /// Load alarms from the state file.
+    pub fn load() -> Self {
+        let alarms = fs::read_to_string(alarms_path())
+            .ok()
+            .and_then(|content| match toml::from_str::<AlarmFile>(&content) {
+                Ok(file) => Some(file.alarms),
+                Err(e) => {
+                    eprintln!("Failed to parse alarms: {}", e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+        Self {
+            alarms,
+            ..Self::default()
+        }
+    }
+
+    fn save(&self) -> Result<(), ConfigError> {
+        let path = alarms_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(ConfigError::Io)?;
+        }
+        let file = AlarmFile {
+            alarms: self.alarms.clone(),
+        };
+        let content = toml::to_string_pretty(&file).map_err(ConfigError::Serialize)?;
+        fs::write(&path, content).map_err(ConfigError::Io)
+    }
+
+    /// Label of the ringing alarm, if any.
+    pub fn ringing(&self) -> Option<&str> {
+        self.ringing.as_deref()
+    }
+
+    /// Whether the bar indicator is in its highlighted flash phase.
+    pub fn flash(&self) -> bool {
+        self.flash
+    }
+
+    /// Check alarms against the current time and fire due ones.
+    pub fn tick(&mut self, now: DateTime<Local>, config: &ClockConfig) {
+        if self.ringing.is_some() {
+            self.flash = !self.flash;
+        }
+
+        let snooze_over = self.snoozed.as_ref().is_some_and(|(_, until)| now >= *until);
+        if let Some((label, _)) = self.snoozed.take_if(|_| snooze_over) {
+            self.ring(label, None, config);
+        }
+
+        let today = now.date_naive();
+        let mut due = Vec::new();
+        for alarm in &mut self.alarms {
+            let Ok(time) = NaiveTime::parse_from_str(&alarm.time, "%H:%M") else {
+                continue;
+            };
+            let is_due = alarm.enabled
+                && alarm.last_fired != Some(today)
+                && now.hour() == time.hour()
+                && now.minute() == time.minute();
+            if is_due {
+                alarm.last_fired = Some(today);
+                due.push((alarm.label.clone(), alarm.command.clone()));
+            }
+        }
+        for (label, command) in due {
+            self.ring(label, command, config);
+        }
+    }
+
+    fn ring(&mut self, label: String, command: Option<String>, config: &ClockConfig) {
+        let _ = Command::new("notify-send")
+            .args(["--app-name=clammy", "--urgency=critical", "Alarm", &label])
+            .spawn();
+        for command in [config.alarm_sound.as_ref(), command.as_ref()].into_iter().flatten() {
+            if let Err(e) = Exec::new(command.as_str()).launch() {
+                eprintln!("Failed to run alarm command: {}", e);
+            }
+        }
+        self.ringing = Some(label);
+        self.flash = true;
+    }
+
+    pub fn update(&mut self, message: Message, config: &ClockConfig) {
+        match message {
+            Message::TimeInput(value) => self.time_input = value,
+            Message::LabelInput(value) => self.label_input = value,
+            Message::CommandInput(value) => self.command_input = value,
+            Message::Add => {
+                let Ok(time) = NaiveTime::parse_from_str(self.time_input.trim(), "%H:%M") else {
+                    return;
+                };
+                let command = self.command_input.trim();
+                self.alarms.push(Alarm {
+                    time: time.format("%H:%M").to_string(),
+                    label: match self.label_input.trim() {
+                        "" => "Alarm".to_string(),
+                        label => label.to_string(),
+                    },
+                    command: (!command.is_empty()).then(|| command.to_string()),
+                    enabled: true,
+                    last_fired: None,
+                });
+                self.alarms.sort_by(|a, b| a.time.cmp(&b.time));
+                self.time_input.clear();
+                self.label_input.clear();
+                self.command_input.clear();
+                self.persist();
+            }
+            Message::Toggle(index) => {
+                if let Some(alarm) = self.alarms.get_mut(index) {
+                    alarm.enabled = !alarm.enabled;
+                    self.persist();
+                }
+            }
+            Message::Remove(index) => {
+                if index < self.alarms.len() {
+                    self.alarms.remove(index);
+                    self.persist();
+                }
+            }
+            Message::Snooze => {
+                if let Some(label) = self.ringing.take() {
+                    let until = Local::now() + Duration::minutes(i64::from(config.snooze_minutes));
+                    self.snoozed = Some((label, until));
+                }
+            }
+            Message::Dismiss => {
+                self.ringing = None;
+                self.snoozed = None;
+            }
+        }
+    }
+
+    fn persist(&self) {
+        if let Err(e) = self.save() {
+            eprintln!("Failed to save alarms: {}", e);
+        }
+    }
+
+    /// Height of the alarm manager in the calendar popup.
+    pub fn manager_height(font_size: f32) -> f32 {
+        let line = font_size + 14.0;
+        // Heading, list rows and the two input rows
+        line * (VISIBLE_ROWS + 3.0) + 16.0
+    }
+
+    /// Render the alarm list and the "new alarm" form.
+    pub fn view_manager(&self) -> Element<'_, Message> {
+        let theme = get_theme();
+        let font_size = theme.font_size();
+        let text_color = theme.text();
+        let muted = theme.muted();
+        let hover_bg = theme.hover();
+        let small = font_size - 2.0;
+
+        let icon_button = move |label: &'static str, message: Message| {
+            button(text(label).size(font_size))
+                .padding([2, 6])
+                .style(move |_theme, status| button::Style {
+                    background: match status {
+                        button::Status::Hovered | button::Status::Pressed => Some(hover_bg.into()),
+                        _ => None,
+                    },
+                    text_color,
+                    border: Border {
+                        radius: 4.0.into(),
+                        ..Border::default()
+                    },
+                    shadow: Default::default(),
+                })
+                .on_press(message)
+        };
+
+        let rows = Column::from_vec(
+            self.alarms
+                .iter()
+                .enumerate()
+                .map(|(index, alarm)| {
+                    let color = if alarm.enabled { text_color } else { muted };
+                    let toggle = if alarm.enabled { "󰂚" } else { "󰂛" };
+                    row![
+                        text(&alarm.time).size(small).color(color),
+                        text(&alarm.label).size(small).color(color).width(Length::Fill),
+                        icon_button(toggle, Message::Toggle(index)),
+                        icon_button("✕", Message::Remove(index)),
+                    ]
+                    .spacing(6)
+                    .align_y(Alignment::Center)
+                    .into()
+                })
+                .collect(),
+        );
+
+        let heading = text(if self.alarms.is_empty() { "No alarms" } else { "Alarms" })
+            .size(small)
+            .color(muted);
+
+        let form = row![
+            text_input("HH:MM", &self.time_input)
+                .on_input(Message::TimeInput)
+                .on_submit(Message::Add)
+                .size(small)
+                .width(Length::Fixed(font_size * 4.0)),
+            text_input("Label", &self.label_input)
+                .on_input(Message::LabelInput)
+                .on_submit(Message::Add)
+                .size(small),
+            icon_button("", Message::Add),
+        ]
+        .spacing(4)
+        .align_y(Alignment::Center);
+
+        let command = text_input("Command (optional)", &self.command_input)
+            .on_input(Message::CommandInput)
+            .on_submit(Message::Add)
+            .size(small);
+
+        column![
+            heading,
+            scrollable(rows).height(Length::Fixed((font_size + 14.0) * VISIBLE_ROWS)),
+            form,
+            command,
+        ]
+        .spacing(4)
+        .padding([0, 8])
+        .into()
+    }
+}
+
+fn alarms_path() -> PathBuf {
+    state_dir().join("alarms.toml")
+}
@@ -1,17 +1,34 @@
 use iced::widget::{container, text};
 use iced::{Element, Subscription, Task, time};
+use std::fmt::Write;
 use std::fs;
 use std::path::PathBuf;
+use std::time::Duration;
 
-use super::tray_widget::tray_text;
+use super::tray_widget::{tray_icon_text_colored, with_tooltip};
+use crate::config::{BatteryConfig, Config};
+use crate::format::{self, NumberFormat};
+use crate::icons::{Icon, Shown};
+use crate::layout::ModuleKind;
+use crate::modules::{Action, BarState, Context, Lookup, Module};
+use crate::sound::SoundEvent;
+use crate::theme::get_theme;
 
 const BATTERY_PATH: &str = "/sys/class/power_supply/BAT0";
 
 #[derive(Debug, Clone)]
 pub struct Battery {
+    config: BatteryConfig,
     percentage: Option<u8>,
     charging: bool,
+    /// Time until empty, or until full while charging
+    remaining: Option<Duration>,
+    /// Charge or discharge rate in watts
+    power: Option<f64>,
+    format: NumberFormat,
+    icon: Shown,
     display_text: String,
+    tooltip_text: String,
 }
 
 #[derive(Debug, Clone)]
@@ -19,56 +36,140 @@ pub enum Message {
     Tick,
 }
 
-impl Default for Battery {
-    fn default() -> Self {
-        let (percentage, charging) = read_battery_info();
+impl Battery {
+    pub fn new(config: BatteryConfig, format: &NumberFormat) -> Self {
         let mut battery = Self {
-            percentage,
-            charging,
+            config,
+            percentage: None,
+            charging: false,
+            remaining: None,
+            power: None,
+            format: format.clone(),
+            icon: Shown::default(),
             display_text: String::new(),
+            tooltip_text: String::new(),
         };
-        battery.update_display();
+        battery.refresh();
         battery
     }
-}
 
-impl Battery {
+    /// Apply a reloaded battery config and format defaults.
+    pub fn set_config(&mut self, config: BatteryConfig, format: &NumberFormat) {
+        self.config = config;
+        self.format = format.clone();
+        self.update_display();
+    }
+
     pub fn update(&mut self, message: Message) -> Task<Message> {
         match message {
             Message::Tick => {
-                let (percentage, charging) = read_battery_info();
-                self.percentage = percentage;
-                self.charging = charging;
-                self.update_display();
+                self.refresh();
                 Task::none()
             }
         }
     }
 
+    fn refresh(&mut self) {
+        let (percentage, charging) = read_battery_info();
+        self.percentage = percentage;
+        self.charging = charging;
+        self.remaining = read_remaining(charging);
+        self.power = read_power();
+        self.update_display();
+    }
+
+    /// Whether the battery is discharging at or below the given percentage.
+    pub fn is_discharging_below(&self, percent: u8) -> bool {
+        !self.charging && self.percentage.is_some_and(|pct| pct <= percent)
+    }
+
+    /// Battery status for `clammy --state`; `null` without a battery.
+    pub fn state(&self) -> serde_json::Value {
+        let Some(percentage) = self.percentage else {
+            return serde_json::Value::Null;
+        };
+        serde_json::json!({
+            "percentage": percentage,
+            "charging": self.charging,
+            "remaining_secs": self.remaining.map(|remaining| remaining.as_secs()),
+            "power_watts": self.power,
+        })
+    }
+
     fn update_display(&mut self) {
         self.display_text.clear();
+        self.tooltip_text.clear();
         if let Some(pct) = self.percentage {
-            let icon = self.get_icon(pct);
-            use std::fmt::Write;
-            let _ = write!(&mut self.display_text, "{} {}%", icon, pct);
+            let percent = self.format.percent(pct as f64);
+            let time = self
+                .remaining
+                .map(|remaining| {
+                    let minutes = remaining.as_secs() / 60;
+                    format!("{}:{:02}", minutes / 60, minutes % 60)
+                })
+                .unwrap_or_default();
+
+            let state = if self.charging {
+                "charging"
+            } else {
+                "discharging"
+            };
+            self.tooltip_text = format!("{}, {}", percent, state);
+            if !time.is_empty() {
+                let until = if self.charging { "full" } else { "empty" };
+                let _ = write!(&mut self.tooltip_text, "\n{} until {}", time, until);
+            }
+            if let Some(watts) = self.power {
+                let _ = write!(
+                    &mut self.tooltip_text,
+                    "\n{} W",
+                    self.format.number(watts, 1)
+                );
+            }
+
+            self.icon = self.get_icon(pct).show(&ModuleKind::Battery);
+            let shown_percent = if self.config.show_percent {
+                percent.as_str()
+            } else {
+                ""
+            };
+            self.display_text = format::render(
+                &self.config.format,
+                &[
+                    ("icon", self.icon.text),
+                    ("percent", shown_percent),
+                    ("time", &time),
+                ],
+            );
+            if !self.config.show_percent {
+                // Drop the space that separated the percentage
+                self.display_text = self.display_text.trim().to_string();
+            }
         }
     }
 
-    fn get_icon(&self, percentage: u8) -> &'static str {
-        if self.charging {
-            return "󰂄"; // nf-md-battery_charging
-        }
-        match percentage {
-            90..=100 => "󰁹", // nf-md-battery
-            80..=89 => "󰂂",  // nf-md-battery_80
-            70..=79 => "󰂁",  // nf-md-battery_70
-            60..=69 => "󰂀",  // nf-md-battery_60
-            50..=59 => "󰁿",  // nf-md-battery_50
-            40..=49 => "󰁾",  // nf-md-battery_40
-            30..=39 => "󰁽",  // nf-md-battery_30
-            20..=29 => "󰁼",  // nf-md-battery_20
-            10..=19 => "󰁻",  // nf-md-battery_10
-            _ => "󰂃",        // nf-md-battery_alert (0-9%)
+    fn get_icon(&self, percentage: u8) -> Icon {
+        // Freedesktop names only have four levels
+        let (glyph, unicode, name) = match (self.charging, percentage) {
+            (true, 70..) => ("󰂄", "⚡", "battery-full-charging"), // nf-md-battery_charging
+            (true, 30..) => ("󰂄", "⚡", "battery-good-charging"),
+            (true, 10..) => ("󰂄", "⚡", "battery-low-charging"),
+            (true, _) => ("󰂄", "⚡", "battery-caution-charging"),
+            (false, 90..) => ("󰁹", "█", "battery-full"), // nf-md-battery
+            (false, 80..) => ("󰂂", "▇", "battery-full"), // nf-md-battery_80
+            (false, 70..) => ("󰂁", "▆", "battery-full"), // nf-md-battery_70
+            (false, 60..) => ("󰂀", "▅", "battery-good"), // nf-md-battery_60
+            (false, 50..) => ("󰁿", "▄", "battery-good"), // nf-md-battery_50
+            (false, 40..) => ("󰁾", "▄", "battery-good"), // nf-md-battery_40
+            (false, 30..) => ("󰁽", "▃", "battery-good"), // nf-md-battery_30
+            (false, 20..) => ("󰁼", "▂", "battery-low"),  // nf-md-battery_20
+            (false, 10..) => ("󰁻", "▁", "battery-low"),  // nf-md-battery_10
+            (false, _) => ("󰂃", "!", "battery-caution"), // nf-md-battery_alert (0-9%)
+        };
+        Icon {
+            glyph,
+            unicode,
+            name,
         }
     }
 
@@ -78,13 +179,78 @@ impl Battery {
             return container(text("")).into();
         }
 
-        tray_text(&self.display_text)
+        let theme = get_theme();
+        let color = if self.is_discharging_below(self.config.critical_percent) {
+            theme.danger()
+        } else if self.is_discharging_below(self.config.warning_percent) {
+            theme.warning()
+        } else {
+            theme.text()
+        };
+        with_tooltip(
+            tray_icon_text_colored(self.icon.image.as_ref(), &self.display_text, color),
+            &self.tooltip_text,
+        )
     }
 
     pub fn subscription(&self) -> Subscription<Message> {
         // Update every 30 seconds (battery changes slowly)
         time::every(std::time::Duration::from_secs(30)).map(|_| Message::Tick)
     }
+
+    /// Faster updates while the pointer is over the widget, so the time
+    /// remaining and power draw in the tooltip stay current.
+    pub fn hover_subscription(&self) -> Subscription<Message> {
+        time::every(std::time::Duration::from_secs(2)).map(|_| Message::Tick)
+    }
+}
+
+impl Module for Battery {
+    type Message = Message;
+
+    fn show(&self, _output: &str) -> Element<'_, Message> {
+        self.view()
+    }
+
+    fn handle(
+        &mut self,
+        message: Message,
+        context: &Context<'_>,
+        actions: &mut Vec<Action>,
+    ) -> Task<Message> {
+        let critical = context.config.sounds.battery_critical_percent;
+        let was_critical = self.is_discharging_below(critical);
+        let task = self.update(message);
+        if !was_critical && self.is_discharging_below(critical) {
+            actions.push(Action::PlaySound(SoundEvent::BatteryCritical));
+        }
+        task
+    }
+
+    // Display-only polling is suspended while the bar is covered or hidden
+    fn watch(&self, bar: &BarState<'_>) -> Subscription<Message> {
+        if bar.visible && bar.config.modules.is_enabled(&ModuleKind::Battery) {
+            self.subscription()
+        } else {
+            Subscription::none()
+        }
+    }
+
+    fn has_live_tooltip(&self) -> bool {
+        true
+    }
+
+    fn watch_hovered(&self) -> Subscription<Message> {
+        self.hover_subscription()
+    }
+
+    fn reconfigure(&mut self, config: &Config) {
+        self.set_config(config.battery.clone(), &config.format);
+    }
+
+    fn first_lookup(&self) -> Option<(Lookup, Message)> {
+        Some((Lookup::Display, Message::Tick))
+    }
 }
 
 /// Read battery info from sysfs, reusing PathBuf to minimize allocations
@@ -110,3 +276,44 @@ fn read_battery_info() -> (Option<u8>, bool) {
 
     (capacity, charging)
 }
+
+/// Read a numeric battery attribute from sysfs.
+fn read_value(name: &str) -> Option<f64> {
+    fs::read_to_string(PathBuf::from(BATTERY_PATH).join(name))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Charge or discharge rate in watts, from `power_now` (µW) or
+/// `current_now` (µA) and `voltage_now` (µV).
+fn read_power() -> Option<f64> {
+    let microwatts = match read_value("power_now") {
+        Some(power) => power,
+        None => read_value("current_now")? * read_value("voltage_now")? / 1e6,
+    };
+    Some(microwatts / 1e6).filter(|watts| *watts > 0.0)
+}
+
+/// Time until the battery is empty, or full while charging, from the
+/// energy (µWh, µW) or charge (µAh, µA) readings.
+fn read_remaining(charging: bool) -> Option<Duration> {
+    let (now, full, rate) = match read_value("power_now") {
+        Some(power) => (read_value("energy_now")?, read_value("energy_full")?, power),
+        None => (
+            read_value("charge_now")?,
+            read_value("charge_full")?,
+            read_value("current_now")?,
+        ),
+    };
+    if rate <= 0.0 {
+        return None;
+    }
+    let hours = if charging {
+        (full - now).max(0.0) / rate
+    } else {
+        now / rate
+    };
+    Some(Duration::from_secs_f64(hours * 3600.0))
+}
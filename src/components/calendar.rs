@@ -0,0 +1,428 @@
+//! Month calendar used by the clock popup.
+//!
+//! Provides:
+//! - Recurring yearly reminders (birthdays, anniversaries) from config
+//! - Public holiday tables for a handful of regions
+//! - The month grid view with colored markers
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use iced::widget::{Column, Row, button, column, container, row, scrollable, text};
+use iced::{Border, Element, Length};
+
+use crate::config::ClockConfig;
+use crate::locale::{self, Locale};
+use crate::theme::get_theme;
+
+/// Width of a single day cell in pixels.
+const CELL_WIDTH: f32 = 32.0;
+
+/// Height of a single day cell in pixels.
+const CELL_HEIGHT: f32 = 26.0;
+
+/// Days ahead to look for the upcoming reminder shown in the clock tooltip.
+const UPCOMING_DAYS: i64 = 30;
+
+// ============================================================================
+// Markers
+// ============================================================================
+
+/// What a calendar marker represents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkerKind {
+    Holiday,
+    Reminder,
+}
+
+/// A labelled date shown in the calendar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Marker {
+    pub date: NaiveDate,
+    pub label: String,
+    pub kind: MarkerKind,
+}
+
+/// Collect all markers (holidays and reminders) for a month, sorted by date.
+pub fn markers_for_month(config: &ClockConfig, year: i32, month: u32) -> Vec<Marker> {
+    let mut markers: Vec<Marker> = markers_for_year(config, year)
+        .into_iter()
+        .filter(|m| m.date.month() == month)
+        .collect();
+    markers.sort_by_key(|m| m.date);
+    markers
+}
+
+/// The next holiday or reminder within [`UPCOMING_DAYS`], as a tooltip line.
+pub fn upcoming_line(config: &ClockConfig, today: NaiveDate) -> Option<String> {
+    if !config.show_upcoming {
+        return None;
+    }
+
+    let horizon = today + Duration::days(UPCOMING_DAYS);
+    let mut candidates = markers_for_year(config, today.year());
+    if horizon.year() != today.year() {
+        candidates.extend(markers_for_year(config, horizon.year()));
+    }
+
+    let next = candidates
+        .into_iter()
+        .filter(|m| m.date >= today && m.date <= horizon)
+        .min_by_key(|m| m.date)?;
+
+    let days = (next.date - today).num_days();
+    Some(match days {
+        0 => format!("{} today", next.label),
+        1 => format!("{} tomorrow", next.label),
+        n => format!("{} in {} days", next.label, n),
+    })
+}
+
+fn markers_for_year(config: &ClockConfig, year: i32) -> Vec<Marker> {
+    let mut markers = Vec::new();
+
+    if let Some(region) = &config.holiday_region {
+        for (label, rule) in region_holidays(region) {
+            if let Some(date) = rule.resolve(year) {
+                markers.push(Marker {
+                    date,
+                    label: label.to_string(),
+                    kind: MarkerKind::Holiday,
+                });
+            }
+        }
+    }
+
+    for reminder in &config.reminders {
+        if let Some(date) = parse_month_day(&reminder.date, year) {
+            markers.push(Marker {
+                date,
+                label: reminder.name.clone(),
+                kind: MarkerKind::Reminder,
+            });
+        }
+    }
+
+    markers
+}
+
+/// Parse a recurring "MM-DD" date for the given year.
+/// Feb 29 falls back to Feb 28 in non-leap years.
+fn parse_month_day(value: &str, year: i32) -> Option<NaiveDate> {
+    let (month, day) = value.trim().split_once('-')?;
+    let month: u32 = month.parse().ok()?;
+    let day: u32 = day.parse().ok()?;
+
+    NaiveDate::from_ymd_opt(year, month, day).or_else(|| {
+        (month == 2 && day == 29)
+            .then(|| NaiveDate::from_ymd_opt(year, 2, 28))
+            .flatten()
+    })
+}
+
+// ============================================================================
+// Public holidays
+// ============================================================================
+
+/// How a holiday's date is determined each year.
+#[derive(Debug, Clone, Copy)]
+enum HolidayRule {
+    /// Same month and day every year
+    Fixed(u32, u32),
+    /// Days relative to (Western) Easter Sunday
+    Easter(i64),
+    /// Days relative to Orthodox Easter Sunday
+    OrthodoxEaster(i64),
+    /// N-th weekday of a month; negative `n` counts from the end
+    NthWeekday(u32, Weekday, i8),
+}
+
+impl HolidayRule {
+    fn resolve(self, year: i32) -> Option<NaiveDate> {
+        match self {
+            HolidayRule::Fixed(month, day) => NaiveDate::from_ymd_opt(year, month, day),
+            HolidayRule::Easter(offset) => Some(easter(year)? + Duration::days(offset)),
+            HolidayRule::OrthodoxEaster(offset) => {
+                Some(orthodox_easter(year)? + Duration::days(offset))
+            }
+            HolidayRule::NthWeekday(month, weekday, n) => nth_weekday(year, month, weekday, n),
+        }
+    }
+}
+
+/// Holiday table for a region code (case-insensitive). Unknown regions have none.
+fn region_holidays(region: &str) -> &'static [(&'static str, HolidayRule)] {
+    use HolidayRule::*;
+
+    const US: &[(&str, HolidayRule)] = &[
+        ("New Year's Day", Fixed(1, 1)),
+        ("Martin Luther King Jr. Day", NthWeekday(1, Weekday::Mon, 3)),
+        ("Presidents' Day", NthWeekday(2, Weekday::Mon, 3)),
+        ("Memorial Day", NthWeekday(5, Weekday::Mon, -1)),
+        ("Juneteenth", Fixed(6, 19)),
+        ("Independence Day", Fixed(7, 4)),
+        ("Labor Day", NthWeekday(9, Weekday::Mon, 1)),
+        ("Columbus Day", NthWeekday(10, Weekday::Mon, 2)),
+        ("Veterans Day", Fixed(11, 11)),
+        ("Thanksgiving", NthWeekday(11, Weekday::Thu, 4)),
+        ("Christmas Day", Fixed(12, 25)),
+    ];
+    const GB: &[(&str, HolidayRule)] = &[
+        ("New Year's Day", Fixed(1, 1)),
+        ("Good Friday", Easter(-2)),
+        ("Easter Monday", Easter(1)),
+        ("Early May Bank Holiday", NthWeekday(5, Weekday::Mon, 1)),
+        ("Spring Bank Holiday", NthWeekday(5, Weekday::Mon, -1)),
+        ("Summer Bank Holiday", NthWeekday(8, Weekday::Mon, -1)),
+        ("Christmas Day", Fixed(12, 25)),
+        ("Boxing Day", Fixed(12, 26)),
+    ];
+    const DE: &[(&str, HolidayRule)] = &[
+        ("Neujahr", Fixed(1, 1)),
+        ("Karfreitag", Easter(-2)),
+        ("Ostermontag", Easter(1)),
+        ("Tag der Arbeit", Fixed(5, 1)),
+        ("Christi Himmelfahrt", Easter(39)),
+        ("Pfingstmontag", Easter(50)),
+        ("Tag der Deutschen Einheit", Fixed(10, 3)),
+        ("1. Weihnachtstag", Fixed(12, 25)),
+        ("2. Weihnachtstag", Fixed(12, 26)),
+    ];
+    const FR: &[(&str, HolidayRule)] = &[
+        ("Jour de l'an", Fixed(1, 1)),
+        ("Lundi de Pâques", Easter(1)),
+        ("Fête du Travail", Fixed(5, 1)),
+        ("Victoire 1945", Fixed(5, 8)),
+        ("Ascension", Easter(39)),
+        ("Lundi de Pentecôte", Easter(50)),
+        ("Fête nationale", Fixed(7, 14)),
+        ("Assomption", Fixed(8, 15)),
+        ("Toussaint", Fixed(11, 1)),
+        ("Armistice", Fixed(11, 11)),
+        ("Noël", Fixed(12, 25)),
+    ];
+    const RO: &[(&str, HolidayRule)] = &[
+        ("Anul Nou", Fixed(1, 1)),
+        ("Anul Nou", Fixed(1, 2)),
+        ("Ziua Unirii", Fixed(1, 24)),
+        ("Vinerea Mare", OrthodoxEaster(-2)),
+        ("Paștele", OrthodoxEaster(0)),
+        ("Paștele", OrthodoxEaster(1)),
+        ("Ziua Muncii", Fixed(5, 1)),
+        ("Ziua Copilului", Fixed(6, 1)),
+        ("Rusaliile", OrthodoxEaster(49)),
+        ("Rusaliile", OrthodoxEaster(50)),
+        ("Adormirea Maicii Domnului", Fixed(8, 15)),
+        ("Sfântul Andrei", Fixed(11, 30)),
+        ("Ziua Națională", Fixed(12, 1)),
+        ("Crăciunul", Fixed(12, 25)),
+        ("Crăciunul", Fixed(12, 26)),
+    ];
+
+    match region.to_ascii_lowercase().as_str() {
+        "us" => US,
+        "gb" | "uk" => GB,
+        "de" => DE,
+        "fr" => FR,
+        "ro" => RO,
+        _ => &[],
+    }
+}
+
+/// Western Easter Sunday (anonymous Gregorian algorithm).
+fn easter(year: i32) -> Option<NaiveDate> {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+/// Orthodox Easter Sunday (Meeus Julian algorithm, converted to Gregorian).
+/// The 13-day offset is valid for 1900-2099.
+fn orthodox_easter(year: i32) -> Option<NaiveDate> {
+    let a = year % 4;
+    let b = year % 7;
+    let c = year % 19;
+    let d = (19 * c + 15) % 30;
+    let e = (2 * a + 4 * b - d + 34) % 7;
+    let month = (d + e + 114) / 31;
+    let day = (d + e + 114) % 31 + 1;
+    let julian = NaiveDate::from_ymd_opt(year, month as u32, day as u32)?;
+    Some(julian + Duration::days(13))
+}
+
+fn nth_weekday(year: i32, month: u32, weekday: Weekday, n: i8) -> Option<NaiveDate> {
+    if n > 0 {
+        let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+        let offset = (7 + weekday.num_days_from_monday() as i64
+            - first.weekday().num_days_from_monday() as i64)
+            % 7;
+        let date = first + Duration::days(offset + (n as i64 - 1) * 7);
+        (date.month() == month).then_some(date)
+    } else {
+        let last = last_day_of_month(year, month)?;
+        let offset = (7 + last.weekday().num_days_from_monday() as i64
+            - weekday.num_days_from_monday() as i64)
+            % 7;
+        let date = last - Duration::days(offset + (-(n as i64) - 1) * 7);
+        (date.month() == month).then_some(date)
+    }
+}
+
+fn last_day_of_month(year: i32, month: u32) -> Option<NaiveDate> {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1).map(|d| d - Duration::days(1))
+}
+
+// ============================================================================
+// View
+// ============================================================================
+
+/// Popup size (width, height) for the calendar.
+pub fn popup_size(font_size: f32) -> (u32, u32) {
+    let width = CELL_WIDTH * 7.0 + 24.0;
+    // Header + weekday names + 6 week rows + marker list (4 lines)
+    let height = font_size + 16.0 + CELL_HEIGHT * 7.0 + (font_size + 6.0) * 4.0 + 24.0;
+    (width as u32, height as u32)
+}
+
+/// Render a month grid with holiday/reminder markers.
+///
+/// `month` is any date inside the month to show.
+pub fn view_month<'a, M: Clone + 'a>(
+    month: NaiveDate,
+    today: NaiveDate,
+    locale: Locale,
+    markers: &'a [Marker],
+    on_prev: M,
+    on_next: M,
+) -> Element<'a, M> {
+    let theme = get_theme();
+    let font_size = theme.font_size();
+    let text_color = theme.text();
+    let muted = theme.muted();
+    let accent = theme.accent();
+    let accent2 = theme.accent2();
+    let danger = theme.danger();
+    let hover_bg = theme.hover();
+
+    let nav_button = |label: &'static str, message: M| {
+        button(text(label).size(font_size))
+            .padding([2, 8])
+            .style(move |_theme, status| button::Style {
+                background: match status {
+                    button::Status::Hovered | button::Status::Pressed => Some(hover_bg.into()),
+                    _ => None,
+                },
+                text_color,
+                border: Border {
+                    radius: 4.0.into(),
+                    ..Border::default()
+                },
+                shadow: Default::default(),
+            })
+            .on_press(message)
+    };
+
+    let title = container(text(month.format_localized("%B %Y", locale).to_string()).size(font_size))
+        .width(Length::Fill)
+        .center_x(Length::Fill);
+    let header = row![nav_button("", on_prev), title, nav_button("", on_next)]
+        .align_y(iced::Alignment::Center);
+
+    let weekday_names = Row::from_vec(
+        locale::weekday_initials(locale)
+            .into_iter()
+            .map(|name| {
+                container(text(name).size(font_size - 2.0).color(muted))
+                    .width(Length::Fixed(CELL_WIDTH))
+                    .center_x(Length::Fixed(CELL_WIDTH))
+                    .into()
+            })
+            .collect(),
+    );
+
+    let first = month.with_day(1).unwrap_or(month);
+    let leading = first.weekday().num_days_from_monday() as i64;
+    let grid_start = first - Duration::days(leading);
+
+    let mut weeks = Column::new();
+    for week in 0..6 {
+        let mut days = Row::new();
+        for weekday in 0..7 {
+            let date = grid_start + Duration::days(week * 7 + weekday);
+            let in_month = date.month() == first.month();
+            let marker = markers.iter().find(|m| m.date == date).map(|m| m.kind);
+
+            let color = match (in_month, marker) {
+                (false, _) => muted,
+                (true, Some(MarkerKind::Holiday)) => danger,
+                (true, Some(MarkerKind::Reminder)) => accent2,
+                (true, None) => text_color,
+            };
+            let label = if in_month {
+                date.day().to_string()
+            } else {
+                String::new()
+            };
+            let is_today = date == today;
+
+            days = days.push(
+                container(text(label).size(font_size).color(color))
+                    .width(Length::Fixed(CELL_WIDTH))
+                    .height(Length::Fixed(CELL_HEIGHT))
+                    .center_x(Length::Fixed(CELL_WIDTH))
+                    .center_y(Length::Fixed(CELL_HEIGHT))
+                    .style(move |_theme| container::Style {
+                        border: Border {
+                            color: accent,
+                            width: if is_today { 1.0 } else { 0.0 },
+                            radius: 4.0.into(),
+                        },
+                        ..container::Style::default()
+                    }),
+            );
+        }
+        weeks = weeks.push(days);
+    }
+
+    let marker_lines = Column::from_vec(
+        markers
+            .iter()
+            .map(|m| {
+                let color = match m.kind {
+                    MarkerKind::Holiday => danger,
+                    MarkerKind::Reminder => accent2,
+                };
+                text(format!("{:>2}  {}", m.date.day(), m.label))
+                    .size(font_size - 2.0)
+                    .color(color)
+                    .into()
+            })
+            .collect(),
+    )
+    .spacing(2);
+
+    column![
+        header,
+        weekday_names,
+        weeks,
+        scrollable(marker_lines).height(Length::Fill)
+    ]
+    .spacing(4)
+    .padding(8)
+    .into()
+}
@@ -0,0 +1,90 @@
+//! Carousel of modules sharing one layout slot.
+//!
+//! This component provides:
+//! - One module of a `[[carousels]]` entry at a time, rotating on an interval
+//! - Scrolling over the slot to step through the modules by hand
+//!
+//! The carousel only decides which module is current; the modules keep
+//! their own state and polling, and are rendered by the bar.
+
+use std::time::Duration;
+
+use iced::mouse::ScrollDelta;
+use iced::{Subscription, time};
+
+use crate::config::CarouselConfig;
+use crate::layout::ModuleKind;
+
+#[derive(Debug, Clone)]
+pub struct Carousel {
+    name: String,
+    /// Modules in rotation order (nested carousels and disabled modules are
+    /// left out)
+    modules: Vec<ModuleKind>,
+    interval_secs: u64,
+    index: usize,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Rotation interval elapsed
+    Tick,
+    Scrolled(ScrollDelta),
+}
+
+impl Carousel {
+    pub fn new(config: &CarouselConfig, enabled: impl Fn(&ModuleKind) -> bool) -> Self {
+        let modules = config
+            .modules
+            .iter()
+            .filter(|kind| !matches!(kind, ModuleKind::Carousel(_)) && enabled(kind))
+            .cloned()
+            .collect();
+        Self {
+            name: config.name.clone(),
+            modules,
+            interval_secs: config.interval_secs,
+            index: 0,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Module currently shown.
+    pub fn current(&self) -> Option<&ModuleKind> {
+        self.modules.get(self.index)
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::Tick => self.step(1),
+            Message::Scrolled(delta) => {
+                let y = match delta {
+                    ScrollDelta::Lines { y, .. } | ScrollDelta::Pixels { y, .. } => y,
+                };
+                if y > 0.0 {
+                    self.step(-1);
+                } else if y < 0.0 {
+                    self.step(1);
+                }
+            }
+        }
+    }
+
+    fn step(&mut self, step: isize) {
+        let len = self.modules.len();
+        if len > 0 {
+            self.index = (self.index as isize + step).rem_euclid(len as isize) as usize;
+        }
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        // An interval of 0 only rotates on scroll
+        if self.interval_secs == 0 || self.modules.len() < 2 {
+            return Subscription::none();
+        }
+        time::every(Duration::from_secs(self.interval_secs)).map(|_| Message::Tick)
+    }
+}
@@ -1,47 +1,339 @@
-use chrono::Local;
-use iced::{Element, Subscription, time};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{Datelike, Local, Months, NaiveDate};
+use chrono_tz::Tz;
+use iced::widget::{button, column, container, row, text};
+use iced::{Border, Element, Length, Subscription, Task, time};
 
-use super::tray_widget::tray_text;
+use super::alarm::{self, Alarms};
+use super::calendar::{self, Marker};
+use super::tray_widget::{tray_text_button, with_tooltip};
+use crate::config::{ClockConfig, Config};
+use crate::layout::ModuleKind;
+use crate::locale::{self, Locale};
+use crate::modules::{Action, BarState, Context, Module};
+use crate::theme::get_theme;
+
+/// Format used when the configured one is invalid.
+const CLOCK_FORMAT: &str = "%a %d %b %H:%M";
 
 #[derive(Debug, Clone)]
 pub struct Clock {
     current_time: chrono::DateTime<Local>,
     formatted_buffer: String,
+    /// Validated `clock.format`
+    time_format: String,
+    /// Validated `peek.clock_format` while the bar is peeked
+    peek_format: Option<String>,
+    /// Language of the day and month names
+    locale: Locale,
+    config: ClockConfig,
+    /// First day of the month shown in the calendar popup
+    calendar_month: NaiveDate,
+    /// Holidays and reminders for `calendar_month`
+    calendar_markers: Vec<Marker>,
+    /// Next upcoming holiday/reminder line for the tooltip
+    upcoming: Option<String>,
+    /// Additional timezones (label, zone) from the config
+    timezones: Vec<(String, Tz)>,
+    /// Tooltip contents (full date, world clock and upcoming line)
+    tooltip_text: String,
+    alarms: Alarms,
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
     Tick(chrono::DateTime<Local>),
+    /// User clicked the clock (main opens the calendar popup)
+    Clicked,
+    /// Show the previous month in the calendar
+    PreviousMonth,
+    /// Show the next month in the calendar
+    NextMonth,
+    Alarm(alarm::Message),
 }
 
 impl Default for Clock {
     fn default() -> Self {
-        let now = Local::now();
-        Self {
-            current_time: now,
-            formatted_buffer: now.format("%a %d %b %H:%M").to_string(),
-        }
+        Self::new(ClockConfig::default())
     }
 }
 
 impl Clock {
+    pub fn new(config: ClockConfig) -> Self {
+        let now = Local::now();
+        let today = now.date_naive();
+        let mut clock = Self {
+            current_time: now,
+            formatted_buffer: String::new(),
+            time_format: parse_format(&config),
+            peek_format: None,
+            locale: locale::from_config(config.locale.as_deref()),
+            upcoming: calendar::upcoming_line(&config, today),
+            timezones: parse_timezones(&config),
+            config,
+            calendar_month: today.with_day(1).unwrap_or(today),
+            calendar_markers: Vec::new(),
+            tooltip_text: String::new(),
+            alarms: Alarms::load(),
+        };
+        clock.refresh_markers();
+        clock.refresh_text();
+        clock
+    }
+
+    /// Apply a reloaded clock config.
+    pub fn set_config(&mut self, config: ClockConfig) {
+        self.config = config;
+        self.time_format = parse_format(&self.config);
+        self.locale = locale::from_config(self.config.locale.as_deref());
+        self.upcoming = calendar::upcoming_line(&self.config, self.current_time.date_naive());
+        self.timezones = parse_timezones(&self.config);
+        self.refresh_markers();
+        self.refresh_text();
+    }
+
+    /// Show `format` (with the additional timezones inline) while the bar
+    /// is peeked, or go back to `clock.format` with `None`.
+    pub fn set_peek_format(&mut self, format: Option<&str>) {
+        self.peek_format = format
+            .filter(|format| {
+                let valid = !StrftimeItems::new(format).any(|item| matches!(item, Item::Error));
+                if !valid {
+                    eprintln!("Invalid peek clock format `{}`", format);
+                }
+                valid
+            })
+            .map(|format| self.hours(format));
+        self.refresh_text();
+    }
+
     pub fn update(&mut self, message: Message) {
         match message {
             Message::Tick(time) => {
+                let day_changed = time.date_naive() != self.current_time.date_naive();
                 self.current_time = time;
-                // Reuse buffer - clear() doesn't deallocate capacity
-                self.formatted_buffer.clear();
-                use std::fmt::Write;
-                let _ = write!(&mut self.formatted_buffer, "{}", time.format("%a %d %b %H:%M"));
+
+                if day_changed {
+                    self.upcoming = calendar::upcoming_line(&self.config, time.date_naive());
+                }
+                self.refresh_text();
+                self.alarms.tick(time, &self.config);
+            }
+            Message::Clicked => {
+                // Always open the calendar on the current month
+                let today = self.current_time.date_naive();
+                self.calendar_month = today.with_day(1).unwrap_or(today);
+                self.refresh_markers();
+            }
+            Message::PreviousMonth => {
+                if let Some(month) = self.calendar_month.checked_sub_months(Months::new(1)) {
+                    self.calendar_month = month;
+                    self.refresh_markers();
+                }
             }
+            Message::NextMonth => {
+                if let Some(month) = self.calendar_month.checked_add_months(Months::new(1)) {
+                    self.calendar_month = month;
+                    self.refresh_markers();
+                }
+            }
+            Message::Alarm(msg) => self.alarms.update(msg, &self.config),
+        }
+    }
+
+    /// Rebuild the clock text and the tooltip from the current time.
+    fn refresh_text(&mut self) {
+        use std::fmt::Write;
+        // Reuse buffers - clear() doesn't deallocate capacity
+        self.formatted_buffer.clear();
+        self.tooltip_text.clear();
+        let now = self.current_time;
+        let date = now.format_localized("%A, %-d %B %Y", self.locale);
+        let _ = write!(&mut self.tooltip_text, "{}", date);
+        let format = self.peek_format.as_deref().unwrap_or(&self.time_format);
+        let time = now.format_localized(format, self.locale);
+        let _ = write!(&mut self.formatted_buffer, "{}", time);
+
+        let (inline, tooltip) = (self.hours("%H:%M"), self.hours("%a %H:%M"));
+        for (label, tz) in &self.timezones {
+            let local = now.with_timezone(tz);
+            if self.config.timezones_inline || self.peek_format.is_some() {
+                let inline = local.format_localized(&inline, self.locale);
+                let _ = write!(&mut self.formatted_buffer, "  {} {}", label, inline);
+            } else {
+                let tooltip = local.format_localized(&tooltip, self.locale);
+                let _ = write!(&mut self.tooltip_text, "\n{} {}", label, tooltip);
+            }
+        }
+
+        if let Some(line) = &self.upcoming {
+            self.tooltip_text.push('\n');
+            self.tooltip_text.push_str(line);
+        }
+    }
+
+    /// A time format with the hours of `clock.format_12h`.
+    fn hours(&self, format: &str) -> String {
+        if self.config.format_12h {
+            locale::twelve_hour(format)
+        } else {
+            format.to_string()
         }
     }
 
+    fn refresh_markers(&mut self) {
+        self.calendar_markers = calendar::markers_for_month(
+            &self.config,
+            self.calendar_month.year(),
+            self.calendar_month.month(),
+        );
+    }
+
     pub fn view(&self) -> Element<'_, Message> {
-        tray_text(&self.formatted_buffer)
+        if let Some(label) = self.alarms.ringing() {
+            return self.view_ringing(label);
+        }
+
+        let clock = tray_text_button(&self.formatted_buffer, Message::Clicked);
+        with_tooltip(clock, &self.tooltip_text)
+    }
+
+    /// Flashing alarm indicator with snooze and dismiss buttons.
+    fn view_ringing<'a>(&'a self, label: &'a str) -> Element<'a, Message> {
+        let theme = get_theme();
+        let font_size = theme.font_size();
+        let (background, foreground) = if self.alarms.flash() {
+            (Some(theme.danger().into()), theme.background())
+        } else {
+            (None, theme.danger())
+        };
+        let hover_bg = theme.hover();
+
+        let action = move |label: &'static str, message: Message| {
+            button(text(label).size(font_size))
+                .padding([0, 6])
+                .style(move |_theme, status| button::Style {
+                    background: match status {
+                        button::Status::Hovered | button::Status::Pressed => Some(hover_bg.into()),
+                        _ => None,
+                    },
+                    text_color: foreground,
+                    border: Border {
+                        radius: 2.0.into(),
+                        ..Border::default()
+                    },
+                    shadow: Default::default(),
+                })
+                .on_press(message)
+        };
+
+        let content = row![
+            text(format!("󰀠 {}", label)).size(font_size).color(foreground),
+            action("󰒲", Message::Alarm(alarm::Message::Snooze)),
+            action("✕", Message::Alarm(alarm::Message::Dismiss)),
+        ]
+        .spacing(4)
+        .align_y(iced::Alignment::Center);
+
+        container(content)
+            .center_y(Length::Fill)
+            .padding([0.0, theme.tray_widget_padding()])
+            .style(move |_theme| container::Style {
+                background,
+                border: Border {
+                    radius: 4.0.into(),
+                    ..Border::default()
+                },
+                ..container::Style::default()
+            })
+            .into()
+    }
+
+    /// Render the calendar popup content (month grid and alarms).
+    pub fn view_calendar(&self) -> Element<'_, Message> {
+        let month = calendar::view_month(
+            self.calendar_month,
+            self.current_time.date_naive(),
+            self.locale,
+            &self.calendar_markers,
+            Message::PreviousMonth,
+            Message::NextMonth,
+        );
+        let alarms = self.alarms.view_manager().map(Message::Alarm);
+        column![month, alarms].into()
     }
 
     pub fn subscription(&self) -> Subscription<Message> {
         time::every(std::time::Duration::from_millis(1000)).map(|_| Message::Tick(Local::now()))
     }
 }
+
+impl Module for Clock {
+    type Message = Message;
+
+    fn show(&self, _output: &str) -> Element<'_, Message> {
+        self.view()
+    }
+
+    fn handle(
+        &mut self,
+        message: Message,
+        _context: &Context<'_>,
+        actions: &mut Vec<Action>,
+    ) -> Task<Message> {
+        if matches!(message, Message::Clicked) {
+            actions.push(Action::ToggleCalendar);
+        }
+        self.update(message);
+        Task::none()
+    }
+
+    fn watch(&self, bar: &BarState<'_>) -> Subscription<Message> {
+        if bar.config.modules.is_enabled(&ModuleKind::Clock) {
+            self.subscription()
+        } else {
+            Subscription::none()
+        }
+    }
+
+    fn reconfigure(&mut self, config: &Config) {
+        self.set_config(config.clock.clone());
+    }
+}
+
+/// Popup size (width, height) for the calendar with the alarm manager.
+pub fn popup_size(font_size: f32) -> (u32, u32) {
+    let (width, height) = calendar::popup_size(font_size);
+    (width, height + Alarms::manager_height(font_size) as u32)
+}
+
+/// The configured clock format (or the default if it has invalid
+/// specifiers) with the configured hours.
+fn parse_format(config: &ClockConfig) -> String {
+    let format = if StrftimeItems::new(&config.format).any(|item| matches!(item, Item::Error)) {
+        eprintln!("Invalid clock format `{}`, using the default", config.format);
+        CLOCK_FORMAT
+    } else {
+        &config.format
+    };
+    if config.format_12h {
+        locale::twelve_hour(format)
+    } else {
+        format.to_string()
+    }
+}
+
+/// Parse the configured timezones, skipping (and reporting) invalid names.
+fn parse_timezones(config: &ClockConfig) -> Vec<(String, Tz)> {
+    config
+        .timezones
+        .iter()
+        .filter_map(|zone| match zone.tz.parse::<Tz>() {
+            Ok(tz) => Some((zone.label.clone(), tz)),
+            Err(e) => {
+                eprintln!("Invalid timezone `{}`: {}", zone.tz, e);
+                None
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,156 @@
+//! Cursor theme and size switcher.
+//!
+//! This component provides:
+//! - The current cursor size, with the theme in a tooltip
+//! - Click to switch to the next preset from `[cursor]`, right-click for the
+//!   previous one; presets are applied with `hyprctl setcursor`
+//!
+//! Hyprland has no way to query the cursor, so the widget starts from
+//! `$XCURSOR_THEME`/`$XCURSOR_SIZE` and only knows about changes made here.
+
+use iced::widget::mouse_area;
+use iced::{Element, Task};
+
+use super::tray_widget::{tray_text_button, with_tooltip};
+use crate::config::{Config, CursorConfig, CursorPreset};
+use crate::modules::{Action, Context, Module};
+
+#[derive(Debug, Clone)]
+pub struct Cursor {
+    config: CursorConfig,
+    /// Theme and size currently applied
+    current: Option<CursorPreset>,
+    /// Error of the last `hyprctl setcursor`, shown in the tooltip
+    error: Option<String>,
+    display_text: String,
+    tooltip_text: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Next,
+    Previous,
+    Applied(CursorPreset, Result<(), String>),
+}
+
+impl Cursor {
+    pub fn new(config: CursorConfig) -> Self {
+        let current = match (std::env::var("XCURSOR_THEME"), std::env::var("XCURSOR_SIZE")) {
+            (Ok(theme), Ok(size)) => size.parse().ok().map(|size| CursorPreset { theme, size }),
+            _ => None,
+        };
+        let mut cursor = Self {
+            config,
+            current,
+            error: None,
+            display_text: String::new(),
+            tooltip_text: String::new(),
+        };
+        cursor.update_display();
+        cursor
+    }
+
+    /// Apply a reloaded cursor config.
+    pub fn set_config(&mut self, config: CursorConfig) {
+        self.config = config;
+        self.update_display();
+    }
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::Next => self.apply(1),
+            Message::Previous => self.apply(-1),
+            Message::Applied(preset, result) => {
+                match result {
+                    Ok(()) => {
+                        self.current = Some(preset);
+                        self.error = None;
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to set cursor: {}", e);
+                        self.error = Some(e);
+                    }
+                }
+                self.update_display();
+                Task::none()
+            }
+        }
+    }
+
+    /// Switch to the preset `step` places from the current one (the first
+    /// preset when the current cursor is not a preset).
+    fn apply(&self, step: isize) -> Task<Message> {
+        let presets = &self.config.presets;
+        if presets.is_empty() {
+            return Task::none();
+        }
+        let index = match presets.iter().position(|p| Some(p) == self.current.as_ref()) {
+            Some(index) => (index as isize + step).rem_euclid(presets.len() as isize) as usize,
+            None => 0,
+        };
+        let preset = presets[index].clone();
+        Task::perform(set_cursor(preset.clone()), move |result| {
+            Message::Applied(preset.clone(), result)
+        })
+    }
+
+    fn update_display(&mut self) {
+        self.display_text = match &self.current {
+            Some(current) => format!("󰇀 {}", current.size),
+            None => "󰇀".to_string(),
+        };
+
+        self.tooltip_text = match &self.current {
+            Some(current) => format!("Cursor: {} ({}px)", current.theme, current.size),
+            None => "Cursor: unknown".to_string(),
+        };
+        if let Some(e) = &self.error {
+            self.tooltip_text.push_str(&format!("\nFailed to switch: {}", e));
+        }
+        if self.config.presets.len() > 1 {
+            self.tooltip_text
+                .push_str("\nClick for the next preset, right-click for the previous one");
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let content = mouse_area(tray_text_button(&self.display_text, Message::Next))
+            .on_right_press(Message::Previous);
+
+        with_tooltip(content.into(), &self.tooltip_text)
+    }
+}
+
+impl Module for Cursor {
+    type Message = Message;
+
+    fn show(&self, _output: &str) -> Element<'_, Message> {
+        self.view()
+    }
+
+    fn handle(
+        &mut self,
+        message: Message,
+        _context: &Context<'_>,
+        _actions: &mut Vec<Action>,
+    ) -> Task<Message> {
+        self.update(message)
+    }
+
+    fn reconfigure(&mut self, config: &Config) {
+        self.set_config(config.cursor.clone());
+    }
+}
+
+async fn set_cursor(preset: CursorPreset) -> Result<(), String> {
+    let output = tokio::process::Command::new("hyprctl")
+        .args(["setcursor", &preset.theme, &preset.size.to_string()])
+        .output()
+        .await
+        .map_err(|e| format!("hyprctl: {}", e))?;
+    // hyprctl exits successfully even when the command is rejected
+    match String::from_utf8_lossy(&output.stdout).trim() {
+        "ok" => Ok(()),
+        reply => Err(reply.to_string()),
+    }
+}
@@ -0,0 +1,60 @@
+//! Pseudo-modules that group the widgets around them: `separator` draws a
+//! divider, `spacer` leaves a gap and `label:<text>` shows fixed text.
+//! They have no state and take no input.
+
+use iced::widget::{Space, container, text};
+use iced::{Color, Element, Length};
+
+use crate::config::{SeparatorConfig, SpacerConfig};
+use crate::theme::get_theme;
+
+#[derive(Debug, Clone, Default)]
+pub struct Decorations {
+    separator: String,
+    /// Parsed `separator.color`; the theme's muted color if unset or invalid
+    separator_color: Option<Color>,
+    spacer_width: f32,
+}
+
+impl Decorations {
+    pub fn new(separator: &SeparatorConfig, spacer: &SpacerConfig) -> Self {
+        Self {
+            separator: separator.text.clone(),
+            separator_color: separator
+                .color
+                .as_deref()
+                .and_then(|color| crate::color::parse(color).ok()),
+            spacer_width: spacer.width.max(0.0),
+        }
+    }
+
+    pub fn view_separator<'a, M: 'a>(&'a self) -> Element<'a, M> {
+        let theme = get_theme();
+        container(
+            text(self.separator.as_str())
+                .font(theme.font())
+                .size(theme.font_size())
+                .color(self.separator_color.unwrap_or(theme.muted())),
+        )
+        .center_y(Length::Fill)
+        .into()
+    }
+
+    pub fn view_spacer<'a, M: 'a>(&self) -> Element<'a, M> {
+        Space::with_width(self.spacer_width).into()
+    }
+
+    /// Text of a `label:<text>` entry, like [`tray_text`](super::tray_widget::tray_text).
+    pub fn view_label<'a, M: 'a>(&self, content: &str) -> Element<'a, M> {
+        let theme = get_theme();
+        container(
+            text(content.to_string())
+                .font(theme.font())
+                .size(theme.font_size())
+                .color(theme.text()),
+        )
+        .center_y(Length::Fill)
+        .padding([0.0, theme.tray_widget_padding()])
+        .into()
+    }
+}
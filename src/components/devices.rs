@@ -0,0 +1,353 @@
+//! Attached device monitor.
+//!
+//! This component provides:
+//! - A count of attached devices of interest (USB storage, external input
+//!   devices, webcams), with the device names in a tooltip
+//! - Short connect/disconnect toasts, driven by `udevadm monitor`
+
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+use iced::futures::{SinkExt, Stream};
+use iced::{Element, Subscription, Task, stream, time};
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+use super::tray_widget::{tray_text, with_tooltip};
+use crate::config::{Config, DeviceClass, DevicesConfig};
+use crate::layout::ModuleKind;
+use crate::modules::{Action, BarState, Context, Module};
+
+/// Seconds to wait before restarting `udevadm monitor` after it exits.
+const RESTART_DELAY_SECS: u64 = 5;
+
+/// Subsystems that can contain devices of interest.
+const SUBSYSTEMS: [&str; 3] = ["block", "input", "video4linux"];
+
+#[derive(Debug, Clone)]
+pub struct Device {
+    class: DeviceClass,
+    label: String,
+}
+
+#[derive(Debug, Clone)]
+struct Toast {
+    text: String,
+    expires_at: Instant,
+}
+
+#[derive(Debug, Clone)]
+pub struct Devices {
+    config: DevicesConfig,
+    /// Attached devices by udev device path
+    attached: HashMap<String, Device>,
+    toast: Option<Toast>,
+    /// No toasts are shown while the session is locked
+    toasts_paused: bool,
+    display_text: String,
+    tooltip_text: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Devices present when the monitor started
+    Snapshot(Vec<(String, Device)>),
+    Added(String, Device),
+    Removed(String),
+    Tick,
+}
+
+impl Devices {
+    pub fn new(config: DevicesConfig) -> Self {
+        let mut devices = Self {
+            config,
+            attached: HashMap::new(),
+            toast: None,
+            toasts_paused: false,
+            display_text: String::new(),
+            tooltip_text: String::new(),
+        };
+        devices.update_display();
+        devices
+    }
+
+    /// Apply a reloaded devices config.
+    pub fn set_config(&mut self, config: DevicesConfig) {
+        self.config = config;
+        self.update_display();
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::Snapshot(devices) => {
+                self.attached = devices.into_iter().collect();
+            }
+            Message::Added(path, device) => {
+                if self.config.classes.contains(&device.class) {
+                    self.show_toast(format!("󰕓 + {}", device.label));
+                }
+                self.attached.insert(path, device);
+            }
+            Message::Removed(path) => {
+                let removed = self.attached.remove(&path);
+                if let Some(device) = removed.filter(|d| self.config.classes.contains(&d.class)) {
+                    self.show_toast(format!("󰕓 − {}", device.label));
+                }
+            }
+            Message::Tick => {
+                if self.toast.as_ref().is_some_and(|t| Instant::now() >= t.expires_at) {
+                    self.toast = None;
+                }
+            }
+        }
+        self.update_display();
+    }
+
+    /// Pause or resume toasts; pausing drops the current one.
+    pub fn set_toasts_paused(&mut self, paused: bool) {
+        self.toasts_paused = paused;
+        if paused {
+            self.toast = None;
+            self.update_display();
+        }
+    }
+
+    fn show_toast(&mut self, text: String) {
+        if self.toasts_paused {
+            return;
+        }
+        self.toast = Some(Toast {
+            text,
+            expires_at: Instant::now() + Duration::from_secs(self.config.toast_secs),
+        });
+    }
+
+    /// Devices of the configured classes, sorted by name.
+    fn watched(&self) -> Vec<&Device> {
+        let mut devices: Vec<_> = self
+            .attached
+            .values()
+            .filter(|d| self.config.classes.contains(&d.class))
+            .collect();
+        devices.sort_by(|a, b| a.label.cmp(&b.label));
+        devices
+    }
+
+    fn update_display(&mut self) {
+        let watched = self.watched();
+        let count = watched.len();
+        let tooltip_text = watched
+            .iter()
+            .map(|d| format!("{} {}", class_icon(d.class), d.label))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        self.tooltip_text = tooltip_text;
+        self.display_text = match &self.toast {
+            Some(toast) => toast.text.clone(),
+            None => format!("󰕓 {}", count),
+        };
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        with_tooltip(tray_text(&self.display_text), &self.tooltip_text)
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        let events = Subscription::run_with_id("udev-devices", udev_watcher());
+        // Only tick while a toast is shown
+        if self.toast.is_none() {
+            return events;
+        }
+        Subscription::batch([
+            events,
+            time::every(Duration::from_secs(1)).map(|_| Message::Tick),
+        ])
+    }
+}
+
+impl Module for Devices {
+    type Message = Message;
+
+    fn show(&self, _output: &str) -> Element<'_, Message> {
+        self.view()
+    }
+
+    fn handle(
+        &mut self,
+        message: Message,
+        _context: &Context<'_>,
+        _actions: &mut Vec<Action>,
+    ) -> Task<Message> {
+        self.update(message);
+        Task::none()
+    }
+
+    // udevadm only runs while the module is in the layout, after the
+    // startup delay
+    fn watch(&self, bar: &BarState<'_>) -> Subscription<Message> {
+        if bar.backends_started && bar.config.shows(&ModuleKind::Devices) {
+            self.subscription()
+        } else {
+            Subscription::none()
+        }
+    }
+
+    fn reconfigure(&mut self, config: &Config) {
+        self.set_config(config.devices.clone());
+    }
+}
+
+fn class_icon(class: DeviceClass) -> &'static str {
+    match class {
+        DeviceClass::UsbStorage => "󰋊",
+        DeviceClass::Input => "󰌌",
+        DeviceClass::Webcam => "󰄀",
+    }
+}
+
+/// Classify a udev device from its properties. Returns `None` for devices
+/// that are not of interest (partitions, internal keyboards, metadata nodes).
+fn classify(props: &HashMap<String, String>) -> Option<Device> {
+    let prop = |key: &str| props.get(key).map(String::as_str);
+
+    let class = match prop("SUBSYSTEM")? {
+        "block" if prop("DEVTYPE") == Some("disk") && prop("ID_BUS") == Some("usb") => {
+            DeviceClass::UsbStorage
+        }
+        // The parent inputN device (event/mouse nodes have a DEVNAME); only
+        // USB (bus 3) and Bluetooth (bus 5) devices are external
+        "input"
+            if prop("DEVNAME").is_none()
+                && prop("PRODUCT").is_some_and(|p| p.starts_with("3/") || p.starts_with("5/")) =>
+        {
+            DeviceClass::Input
+        }
+        "video4linux"
+            if prop("ID_V4L_CAPABILITIES").is_some_and(|caps| caps.contains(":capture:")) =>
+        {
+            DeviceClass::Webcam
+        }
+        _ => return None,
+    };
+
+    let label = match class {
+        DeviceClass::UsbStorage => match (prop("ID_VENDOR"), prop("ID_MODEL")) {
+            (Some(vendor), Some(model)) => format!("{} {}", vendor, model),
+            (None, Some(model)) => model.to_string(),
+            _ => "USB storage".to_string(),
+        },
+        DeviceClass::Input => prop("NAME")
+            .map(|name| name.trim_matches('"').to_string())
+            .unwrap_or_else(|| "Input device".to_string()),
+        DeviceClass::Webcam => prop("ID_V4L_PRODUCT").unwrap_or("Webcam").to_string(),
+    };
+
+    Some(Device {
+        class,
+        label: label.replace('_', " "),
+    })
+}
+
+/// Parse one `KEY=VALUE` property line. Accepts both `udevadm monitor
+/// --property` lines and `udevadm info --export-db` lines (`E: KEY=VALUE`).
+fn parse_property(line: &str) -> Option<(String, String)> {
+    let line = line.strip_prefix("E: ").unwrap_or(line);
+    let (key, value) = line.split_once('=')?;
+    let is_key = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+    is_key.then(|| (key.to_string(), value.to_string()))
+}
+
+/// Split udevadm output into blank-line separated property blocks.
+fn parse_blocks(output: &str) -> Vec<HashMap<String, String>> {
+    output
+        .split("\n\n")
+        .map(|block| block.lines().filter_map(parse_property).collect())
+        .collect()
+}
+
+/// Currently attached devices of interest, from the udev database.
+async fn snapshot() -> Vec<(String, Device)> {
+    let output = match tokio::process::Command::new("udevadm")
+        .args(["info", "--export-db"])
+        .output()
+        .await
+    {
+        Ok(output) => output,
+        Err(e) => {
+            eprintln!("Failed to query udev database: {}", e);
+            return Vec::new();
+        }
+    };
+
+    parse_blocks(&String::from_utf8_lossy(&output.stdout))
+        .into_iter()
+        .filter_map(|props| {
+            let path = props.get("DEVPATH")?.clone();
+            Some((path, classify(&props)?))
+        })
+        .collect()
+}
+
+fn udev_watcher() -> impl Stream<Item = Message> {
+    stream::channel(100, |mut output| async move {
+        loop {
+            // Start monitoring before taking the snapshot so no event is missed
+            let mut args = vec!["monitor", "--udev", "--property"];
+            for subsystem in SUBSYSTEMS {
+                args.push("--subsystem-match");
+                args.push(subsystem);
+            }
+            let child = tokio::process::Command::new("udevadm")
+                .args(&args)
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .kill_on_drop(true)
+                .spawn();
+
+            let mut child = match child {
+                Ok(child) => child,
+                Err(e) => {
+                    eprintln!("Failed to start udevadm monitor: {}", e);
+                    tokio::time::sleep(Duration::from_secs(RESTART_DELAY_SECS)).await;
+                    continue;
+                }
+            };
+
+            let _ = output.send(Message::Snapshot(snapshot().await)).await;
+
+            if let Some(stdout) = child.stdout.take() {
+                let mut lines = BufReader::new(stdout).lines();
+                let mut props = HashMap::new();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    if !line.is_empty() {
+                        if let Some((key, value)) = parse_property(&line) {
+                            props.insert(key, value);
+                        }
+                        continue;
+                    }
+
+                    // End of an event block
+                    let event = std::mem::take(&mut props);
+                    let Some(path) = event.get("DEVPATH").cloned() else {
+                        continue;
+                    };
+                    let message = match event.get("ACTION").map(String::as_str) {
+                        Some("add") => classify(&event).map(|device| Message::Added(path, device)),
+                        Some("remove") => Some(Message::Removed(path)),
+                        _ => None,
+                    };
+                    if let Some(message) = message {
+                        let _ = output.send(message).await;
+                    }
+                }
+            }
+
+            let _ = child.wait().await;
+            tokio::time::sleep(Duration::from_secs(RESTART_DELAY_SECS)).await;
+        }
+    })
+}
@@ -0,0 +1,433 @@
+//! File content widget.
+//!
+//! Watches a file and shows its (templated) contents in the bar - a simple
+//! integration point for scripts that write their status to a file.
+//! Optionally opens a URL or path when clicked.
+//!
+//! With `exec` the widget shows a command's output instead, read like
+//! waybar's `custom/*` modules or an i3bar status command (see
+//! [`script_output`]), so existing scripts work unchanged.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+
+use iced::futures::{SinkExt, Stream};
+use iced::widget::{container, mouse_area, text};
+use iced::{Element, Length, Subscription, Task, stream};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+use super::script_output::{self, Status};
+use super::tray_widget::{tray_text, tray_text_button, with_tooltip};
+use crate::config::{Config, FileWidgetConfig, ReturnType, expand_home};
+use crate::exec::Exec;
+use crate::format;
+use crate::layout::ModuleKind;
+use crate::modules::{Action, BarState, Context, Module};
+use crate::open::open;
+use crate::theme::get_theme;
+
+/// Seconds an "open failed" error stays in the bar.
+const ERROR_SECS: u64 = 5;
+
+#[derive(Debug, Clone)]
+pub struct FileContent {
+    config: FileWidgetConfig,
+    /// Collapsed file contents or the script's status; `None` hides the
+    /// widget
+    status: Option<Status>,
+    display_text: String,
+    /// Error of the last failed click action, shown instead of the contents
+    error: Option<String>,
+    /// Last `exec` failure, logged once
+    script_error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// File was (re)read; `None` if it is missing or unreadable
+    Changed(Option<String>),
+    /// `exec` reported a status
+    Output(Result<Option<Status>, String>),
+    Clicked,
+    Opened(Result<(), String>),
+    ErrorExpired,
+}
+
+impl FileContent {
+    pub fn new(config: FileWidgetConfig) -> Self {
+        Self {
+            config,
+            status: None,
+            display_text: String::new(),
+            error: None,
+            script_error: None,
+        }
+    }
+
+    /// Name used to reference this widget in the layout (`file:<name>`).
+    pub fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    /// Apply a changed `[[file_widgets]]` entry. What the widget shows
+    /// stays unless it reads from somewhere else now, in which case its
+    /// new subscription reads it anew.
+    pub fn set_config(&mut self, config: FileWidgetConfig) {
+        let source = |config: &FileWidgetConfig| {
+            (
+                config.path.clone(),
+                config.exec.clone(),
+                config.interval,
+                config.return_type,
+            )
+        };
+        if source(&config) != source(&self.config) {
+            self.status = None;
+        }
+        self.config = config;
+        self.display_text = render(&self.config, self.status.as_ref());
+    }
+
+    /// What the widget shows for `clammy --state`; null while hidden.
+    pub fn state(&self) -> serde_json::Value {
+        match &self.status {
+            Some(status) if !self.display_text.is_empty() => serde_json::json!({
+                "text": self.display_text,
+                "tooltip": status.tooltip,
+            }),
+            _ => serde_json::Value::Null,
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::Changed(content) => {
+                let content = content.unwrap_or_default();
+                self.status = match self.config.return_type {
+                    // All lines of a file are shown, collapsed into one
+                    ReturnType::Text => Some(Status {
+                        text: content.split_whitespace().collect::<Vec<_>>().join(" "),
+                        ..Status::default()
+                    })
+                    .filter(|status| !status.text.is_empty()),
+                    return_type => script_output::parse_run(&content, return_type),
+                };
+                self.display_text = render(&self.config, self.status.as_ref());
+                Task::none()
+            }
+            Message::Output(Ok(status)) => {
+                self.script_error = None;
+                self.status = status;
+                self.display_text = render(&self.config, self.status.as_ref());
+                Task::none()
+            }
+            Message::Output(Err(e)) => {
+                if self.script_error.as_ref() != Some(&e) {
+                    eprintln!("File widget `{}`: {}", self.config.name, e);
+                }
+                self.script_error = Some(e);
+                self.status = None;
+                self.display_text.clear();
+                Task::none()
+            }
+            Message::Clicked => match &self.config.on_click {
+                Some(target) => {
+                    let content = self
+                        .status
+                        .as_ref()
+                        .map_or("", |status| status.text.as_str());
+                    Task::perform(open(target.replace("{content}", content)), Message::Opened)
+                }
+                None => Task::none(),
+            },
+            Message::Opened(Ok(())) => Task::none(),
+            Message::Opened(Err(e)) => {
+                eprintln!("File widget `{}`: {}", self.config.name, e);
+                self.error = Some(format!("⚠ {}", e));
+                Task::perform(tokio::time::sleep(Duration::from_secs(ERROR_SECS)), |_| {
+                    Message::ErrorExpired
+                })
+            }
+            Message::ErrorExpired => {
+                self.error = None;
+                Task::none()
+            }
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        if let Some(error) = &self.error {
+            let theme = get_theme();
+            let error = text(error.as_str())
+                .font(theme.font())
+                .size(theme.font_size())
+                .color(theme.danger());
+            return container(error)
+                .center_y(Length::Fill)
+                .padding([0.0, theme.tray_widget_padding()])
+                .into();
+        }
+        // Hide while the file is missing or empty
+        let Some(status) = self
+            .status
+            .as_ref()
+            .filter(|_| !self.display_text.is_empty())
+        else {
+            return container(text("")).into();
+        };
+
+        let theme = get_theme();
+        let color = if status.urgent {
+            Some(theme.danger())
+        } else {
+            status.color
+        };
+        let label = match color {
+            // i3bar blocks bring their own color
+            Some(color) => {
+                let label: Element<'_, Message> = container(
+                    text(self.display_text.as_str())
+                        .font(theme.font())
+                        .size(theme.font_size())
+                        .color(color),
+                )
+                .center_y(Length::Fill)
+                .padding([0.0, theme.tray_widget_padding()])
+                .into();
+                if self.config.on_click.is_some() {
+                    mouse_area(label).on_press(Message::Clicked).into()
+                } else {
+                    label
+                }
+            }
+            None if self.config.on_click.is_some() => {
+                tray_text_button(&self.display_text, Message::Clicked)
+            }
+            None => tray_text(&self.display_text),
+        };
+
+        match &status.tooltip {
+            Some(tooltip_text) => with_tooltip(label, tooltip_text),
+            None => label,
+        }
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        let return_type = self.config.return_type;
+        match (&self.config.exec, self.config.interval) {
+            (Some(command), Some(interval)) => Subscription::run_with_id(
+                ("file-exec", command.clone(), interval, return_type),
+                poll(command.clone(), interval.max(1), return_type),
+            ),
+            (Some(command), None) => Subscription::run_with_id(
+                ("file-exec", command.clone(), return_type),
+                follow(command.clone(), return_type),
+            ),
+            (None, _) => {
+                let path = expand_home(&self.config.path);
+                let debounce_ms = self.config.debounce_ms;
+                Subscription::run_with_id(
+                    ("file-content", path.clone(), return_type),
+                    file_watcher(path, debounce_ms),
+                )
+            }
+        }
+    }
+}
+
+impl Module for FileContent {
+    type Message = Message;
+
+    fn show(&self, _output: &str) -> Element<'_, Message> {
+        self.view()
+    }
+
+    fn handle(
+        &mut self,
+        message: Message,
+        _context: &Context<'_>,
+        _actions: &mut Vec<Action>,
+    ) -> Task<Message> {
+        self.update(message)
+    }
+
+    fn watch(&self, bar: &BarState<'_>) -> Subscription<Message> {
+        let kind = ModuleKind::File(self.name().to_string());
+        if bar.config.modules.is_enabled(&kind) {
+            self.subscription()
+        } else {
+            Subscription::none()
+        }
+    }
+
+    fn reconfigure(&mut self, config: &Config) {
+        let entry = config
+            .file_widgets
+            .iter()
+            .find(|widget| widget.name == self.config.name);
+        if let Some(entry) = entry.filter(|entry| **entry != self.config) {
+            self.set_config(entry.clone());
+        }
+    }
+}
+
+/// Apply the format template to the status text, truncated to `max_length`
+/// characters.
+fn render(config: &FileWidgetConfig, status: Option<&Status>) -> String {
+    let Some(status) = status.filter(|status| !status.text.is_empty()) else {
+        return String::new();
+    };
+
+    let content = match config.max_length {
+        Some(max) if status.text.chars().count() > max => {
+            let mut truncated: String = status.text.chars().take(max.saturating_sub(1)).collect();
+            truncated.push('…');
+            truncated
+        }
+        _ => status.text.clone(),
+    };
+    let percentage = status
+        .percentage
+        .map(|percentage| format!("{:.0}", percentage))
+        .unwrap_or_default();
+
+    format::render(
+        &config.format,
+        &[
+            ("content", &content),
+            // waybar's placeholders
+            ("", &content),
+            ("text", &content),
+            (
+                "icon",
+                script_output::icon(&config.format_icons, status.percentage),
+            ),
+            ("percentage", &percentage),
+            ("alt", &status.alt),
+        ],
+    )
+}
+
+/// Run `command` every `interval` seconds and report its status.
+fn poll(command: String, interval: u64, return_type: ReturnType) -> impl Stream<Item = Message> {
+    stream::channel(1, move |mut output| async move {
+        loop {
+            let status = Exec::new(command.as_str())
+                .output()
+                .await
+                .map(|stdout| script_output::parse_run(&stdout, return_type));
+            let _ = output.send(Message::Output(status)).await;
+            tokio::time::sleep(Duration::from_secs(interval)).await;
+        }
+    })
+}
+
+/// Run `command` once and report each status it prints. Like waybar, a
+/// command that exits is not restarted and its last status stays.
+fn follow(command: String, return_type: ReturnType) -> impl Stream<Item = Message> {
+    stream::channel(100, move |mut output| async move {
+        let child = tokio::process::Command::new("sh")
+            .args(["-c", &command])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn();
+
+        match child {
+            Ok(mut child) => {
+                if let Some(stdout) = child.stdout.take() {
+                    let mut lines = BufReader::new(stdout).lines();
+                    while let Ok(Some(line)) = lines.next_line().await {
+                        let status = script_output::parse_line(&line, return_type);
+                        // The i3bar header and brackets carry no status
+                        if status.is_some() || return_type != ReturnType::I3bar {
+                            let _ = output.send(Message::Output(Ok(status))).await;
+                        }
+                    }
+                }
+                match child.wait().await {
+                    Ok(status) if status.success() => {}
+                    Ok(status) => {
+                        let e = format!("`{}` exited ({})", command, status);
+                        let _ = output.send(Message::Output(Err(e))).await;
+                    }
+                    Err(e) => {
+                        let _ = output.send(Message::Output(Err(e.to_string()))).await;
+                    }
+                }
+            }
+            Err(e) => {
+                let _ = output
+                    .send(Message::Output(Err(format!("sh: {}", e))))
+                    .await;
+            }
+        }
+
+        std::future::pending::<()>().await;
+    })
+}
+
+fn read(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path).ok()
+}
+
+fn file_watcher(path: PathBuf, debounce_ms: u64) -> impl Stream<Item = Message> {
+    stream::channel(100, move |mut output| async move {
+        // Initial contents
+        let _ = output.send(Message::Changed(read(&path))).await;
+
+        let watch_path = path
+            .parent()
+            .map(|p| p.to_path_buf())
+            .unwrap_or(path.clone());
+        let file_name = path.file_name().map(|n| n.to_os_string());
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel::<Event>(10);
+
+        let mut watcher: RecommendedWatcher = match notify::recommended_watcher(move |res| {
+            if let Ok(event) = res {
+                let _ = tx.blocking_send(event);
+            }
+        }) {
+            Ok(w) => w,
+            Err(e) => {
+                eprintln!("Failed to create watcher for {}: {}", path.display(), e);
+                std::future::pending::<()>().await;
+                return;
+            }
+        };
+
+        // Watch the parent directory so the file can be created or replaced
+        if let Err(e) = watcher.watch(&watch_path, RecursiveMode::NonRecursive) {
+            eprintln!("Failed to watch {}: {}", watch_path.display(), e);
+        }
+
+        while let Some(event) = rx.recv().await {
+            if !matches!(
+                event.kind,
+                EventKind::Modify(_) | EventKind::Create(_) | EventKind::Remove(_)
+            ) {
+                continue;
+            }
+
+            let is_our_file = event
+                .paths
+                .iter()
+                .any(|p| p.file_name().map(|n| n.to_os_string()) == file_name);
+            if !is_our_file {
+                continue;
+            }
+
+            // Debounce: wait, then drop events that arrived in the meantime
+            tokio::time::sleep(tokio::time::Duration::from_millis(debounce_ms)).await;
+            while rx.try_recv().is_ok() {}
+
+            let _ = output.send(Message::Changed(read(&path))).await;
+        }
+
+        std::future::pending::<()>().await;
+    })
+}
@@ -0,0 +1,271 @@
+//! Pomodoro-style focus timer.
+//!
+//! This component provides:
+//! - Focus / break countdown, started and stopped by clicking
+//! - Optional strict mode that moves windows of distracting classes to a
+//!   special workspace during focus sessions and restores them on break
+
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use hyprland::data::Clients;
+use hyprland::dispatch::{Dispatch, DispatchType, WindowIdentifier, WorkspaceIdentifierWithSpecial};
+use hyprland::shared::{Address, HyprData, WorkspaceId};
+use iced::{Element, Subscription, Task, time};
+
+use super::tray_widget::tray_text_button;
+use crate::config::{Config, FocusTimerConfig};
+use crate::layout::ModuleKind;
+use crate::modules::{Action, BarState, Context, Module};
+
+/// Special workspace that distracting windows are parked on during focus.
+const FOCUS_WORKSPACE: &str = "clammy-focus";
+
+/// Seconds between strict-mode sweeps for newly opened windows.
+const SWEEP_INTERVAL_SECS: u64 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Idle,
+    Focus { ends_at: Instant },
+    Break { ends_at: Instant },
+}
+
+#[derive(Debug, Clone)]
+pub struct FocusTimer {
+    config: FocusTimerConfig,
+    phase: Phase,
+    /// Windows parked by strict mode: (address, original workspace)
+    parked_windows: Vec<(Address, WorkspaceId)>,
+    display_text: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Start a focus session, or stop the running session
+    Toggle,
+    Tick,
+    /// Strict mode moved these windows away
+    #[doc(hidden)]
+    WindowsParked(Vec<(Address, WorkspaceId)>),
+    /// Parked windows were moved back
+    #[doc(hidden)]
+    WindowsRestored,
+}
+
+impl FocusTimer {
+    pub fn new(config: FocusTimerConfig) -> Self {
+        let mut timer = Self {
+            config,
+            phase: Phase::Idle,
+            parked_windows: Vec::new(),
+            display_text: String::new(),
+        };
+        timer.update_display();
+        timer
+    }
+
+    /// Apply a reloaded focus timer config. A running session keeps its end time.
+    pub fn set_config(&mut self, config: FocusTimerConfig) {
+        self.config = config;
+    }
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::Toggle => {
+                let task = match self.phase {
+                    Phase::Idle => self.start_focus(),
+                    _ => {
+                        self.phase = Phase::Idle;
+                        self.restore_windows()
+                    }
+                };
+                self.update_display();
+                task
+            }
+            Message::Tick => {
+                let now = Instant::now();
+                let task = match self.phase {
+                    Phase::Focus { ends_at } if now >= ends_at => {
+                        notify("Focus session complete", "Time for a break");
+                        self.phase = Phase::Break {
+                            ends_at: now + minutes(self.config.break_minutes),
+                        };
+                        self.restore_windows()
+                    }
+                    Phase::Focus { ends_at } => {
+                        // Catch distracting windows opened during the session
+                        let remaining = ends_at.saturating_duration_since(now).as_secs();
+                        if self.config.strict && remaining % SWEEP_INTERVAL_SECS == 0 {
+                            self.park_windows()
+                        } else {
+                            Task::none()
+                        }
+                    }
+                    Phase::Break { ends_at } if now >= ends_at => {
+                        notify("Break is over", "Click the timer to start another session");
+                        self.phase = Phase::Idle;
+                        Task::none()
+                    }
+                    _ => Task::none(),
+                };
+                self.update_display();
+                task
+            }
+            Message::WindowsParked(windows) => {
+                for window in windows {
+                    if !self.parked_windows.iter().any(|(addr, _)| *addr == window.0) {
+                        self.parked_windows.push(window);
+                    }
+                }
+                // Session may have ended while the sweep was running
+                if !matches!(self.phase, Phase::Focus { .. }) {
+                    return self.restore_windows();
+                }
+                Task::none()
+            }
+            Message::WindowsRestored => Task::none(),
+        }
+    }
+
+    fn start_focus(&mut self) -> Task<Message> {
+        self.phase = Phase::Focus {
+            ends_at: Instant::now() + minutes(self.config.focus_minutes),
+        };
+        if self.config.strict {
+            self.park_windows()
+        } else {
+            Task::none()
+        }
+    }
+
+    fn park_windows(&self) -> Task<Message> {
+        let classes = self.config.blocked_classes.clone();
+        Task::perform(park_distracting_windows(classes), Message::WindowsParked)
+    }
+
+    fn restore_windows(&mut self) -> Task<Message> {
+        if self.parked_windows.is_empty() {
+            return Task::none();
+        }
+        let windows = std::mem::take(&mut self.parked_windows);
+        Task::perform(restore_parked_windows(windows), |_| Message::WindowsRestored)
+    }
+
+    fn update_display(&mut self) {
+        use std::fmt::Write;
+        self.display_text.clear();
+        let now = Instant::now();
+        match self.phase {
+            Phase::Idle => self.display_text.push('󰔛'), // nf-md-timer_outline
+            Phase::Focus { ends_at } => {
+                let secs = ends_at.saturating_duration_since(now).as_secs();
+                let _ = write!(&mut self.display_text, "󰔟 {:02}:{:02}", secs / 60, secs % 60);
+            }
+            Phase::Break { ends_at } => {
+                let secs = ends_at.saturating_duration_since(now).as_secs();
+                let _ = write!(&mut self.display_text, "󰅶 {:02}:{:02}", secs / 60, secs % 60);
+            }
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        tray_text_button(&self.display_text, Message::Toggle)
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        // Only tick while a session is running
+        match self.phase {
+            Phase::Idle => Subscription::none(),
+            _ => time::every(Duration::from_secs(1)).map(|_| Message::Tick),
+        }
+    }
+}
+
+impl Module for FocusTimer {
+    type Message = Message;
+
+    fn show(&self, _output: &str) -> Element<'_, Message> {
+        self.view()
+    }
+
+    fn handle(
+        &mut self,
+        message: Message,
+        _context: &Context<'_>,
+        _actions: &mut Vec<Action>,
+    ) -> Task<Message> {
+        self.update(message)
+    }
+
+    fn watch(&self, bar: &BarState<'_>) -> Subscription<Message> {
+        if bar.config.modules.is_enabled(&ModuleKind::FocusTimer) {
+            self.subscription()
+        } else {
+            Subscription::none()
+        }
+    }
+
+    fn reconfigure(&mut self, config: &Config) {
+        self.set_config(config.focus_timer.clone());
+    }
+}
+
+fn minutes(value: u32) -> Duration {
+    Duration::from_secs(u64::from(value) * 60)
+}
+
+fn notify(summary: &str, body: &str) {
+    let _ = Command::new("notify-send")
+        .args(["--app-name=clammy", summary, body])
+        .spawn();
+}
+
+/// Move all windows whose class matches one of `classes` to the focus
+/// special workspace. Returns the moved windows with their original workspace.
+async fn park_distracting_windows(classes: Vec<String>) -> Vec<(Address, WorkspaceId)> {
+    let clients = match Clients::get_async().await {
+        Ok(clients) => clients,
+        Err(e) => {
+            eprintln!("Failed to fetch clients: {:?}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut parked = Vec::new();
+    for client in clients {
+        // Skip windows already parked (special workspaces have negative IDs)
+        if client.workspace.id < 0 {
+            continue;
+        }
+        let is_blocked = classes
+            .iter()
+            .any(|class| class.eq_ignore_ascii_case(&client.class));
+        if !is_blocked {
+            continue;
+        }
+
+        let dispatch = DispatchType::MoveToWorkspaceSilent(
+            WorkspaceIdentifierWithSpecial::Special(Some(FOCUS_WORKSPACE)),
+            Some(WindowIdentifier::Address(client.address.clone())),
+        );
+        match Dispatch::call_async(dispatch).await {
+            Ok(()) => parked.push((client.address, client.workspace.id)),
+            Err(e) => eprintln!("Failed to park window {}: {:?}", client.address, e),
+        }
+    }
+    parked
+}
+
+/// Move parked windows back to the workspaces they came from.
+async fn restore_parked_windows(windows: Vec<(Address, WorkspaceId)>) {
+    for (address, workspace_id) in windows {
+        let dispatch = DispatchType::MoveToWorkspaceSilent(
+            WorkspaceIdentifierWithSpecial::Id(workspace_id),
+            Some(WindowIdentifier::Address(address.clone())),
+        );
+        if let Err(e) = Dispatch::call_async(dispatch).await {
+            eprintln!("Failed to restore window {}: {:?}", address, e);
+        }
+    }
+}
@@ -0,0 +1,261 @@
+//! Remote host status widget.
+//!
+//! This component provides:
+//! - How many of the configured hosts are up, checked periodically
+//! - Click for a popup with the status and latency of every host
+//!
+//! A host is checked either by connecting to a TCP port or by running
+//! `true` over SSH in batch mode (keys or an agent only, never a password
+//! prompt). All hosts are checked at the same time.
+
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+use iced::{Element, Subscription, Task, time};
+
+use super::system_tray::menu::MenuItem;
+use super::tray_widget::{tray_text_button, with_tooltip};
+use crate::config::{Config, HostConfig, HostProbe, HostsConfig};
+use crate::layout::ModuleKind;
+use crate::modules::{Action, BarState, Context, Lookup, MenuOwner, Module};
+
+/// Menu id of the "Check now" entry.
+const CHECK_NOW_ID: i32 = -1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostStatus {
+    name: String,
+    /// Round trip of the check, or why it failed
+    result: Result<Duration, String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Hosts {
+    config: HostsConfig,
+    /// `None` until the first check finished
+    statuses: Option<Vec<HostStatus>>,
+    display_text: String,
+    tooltip_text: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Refresh,
+    Checked(Vec<HostStatus>),
+    /// Widget clicked; opens the host list
+    Clicked,
+    /// Host list entry chosen
+    Selected(i32),
+}
+
+impl Hosts {
+    pub fn new(config: HostsConfig) -> Self {
+        let mut hosts = Self {
+            config,
+            statuses: None,
+            display_text: String::new(),
+            tooltip_text: String::new(),
+        };
+        hosts.update_display();
+        hosts
+    }
+
+    /// Apply a reloaded hosts config.
+    pub fn set_config(&mut self, config: HostsConfig) {
+        self.config = config;
+        self.update_display();
+    }
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::Refresh => {
+                if self.config.targets.is_empty() {
+                    return Task::none();
+                }
+                let targets = self.config.targets.clone();
+                let timeout = Duration::from_secs(self.config.timeout_secs.max(1));
+                Task::perform(check_all(targets, timeout), Message::Checked)
+            }
+            Message::Checked(statuses) => {
+                self.statuses = Some(statuses);
+                self.update_display();
+                Task::none()
+            }
+            // The bar opens the popup with `menu_items`
+            Message::Clicked => Task::none(),
+            Message::Selected(CHECK_NOW_ID) => Task::done(Message::Refresh),
+            Message::Selected(_) => Task::none(),
+        }
+    }
+
+    fn update_display(&mut self) {
+        if self.config.targets.is_empty() {
+            self.display_text = "󰒍 –".to_string();
+            self.tooltip_text = "No hosts configured".to_string();
+            return;
+        }
+        let Some(statuses) = &self.statuses else {
+            self.display_text = "󰒍 …".to_string();
+            self.tooltip_text = "Checking hosts…".to_string();
+            return;
+        };
+
+        let up = statuses
+            .iter()
+            .filter(|status| status.result.is_ok())
+            .count();
+        let down: Vec<&str> = statuses
+            .iter()
+            .filter(|status| status.result.is_err())
+            .map(|status| status.name.as_str())
+            .collect();
+        self.display_text = format!("󰒍 {}/{}", up, statuses.len());
+
+        self.tooltip_text = if down.is_empty() {
+            format!("All {} hosts up", statuses.len())
+        } else {
+            format!("{} up, down: {}", up, down.join(", "))
+        };
+        self.tooltip_text.push_str("\nClick for details");
+    }
+
+    /// Popup entries: every host with its status, then "Check now".
+    pub fn menu_items(&self) -> Vec<MenuItem> {
+        let entry = |id: i32, label: String, enabled: bool| MenuItem {
+            id,
+            label,
+            enabled,
+            is_separator: false,
+            is_checkable: false,
+            is_radio: false,
+            is_checked: false,
+            icon: None,
+            shortcut: None,
+            submenu: Vec::new(),
+        };
+
+        let mut items: Vec<MenuItem> = match &self.statuses {
+            Some(statuses) => statuses
+                .iter()
+                .enumerate()
+                .map(|(index, status)| {
+                    let label = match &status.result {
+                        Ok(latency) => format!("󰄬 {}  {} ms", status.name, latency.as_millis()),
+                        Err(e) => format!("󰅖 {}  {}", status.name, e),
+                    };
+                    entry(index as i32, label, false)
+                })
+                .collect(),
+            None => vec![entry(0, "Checking hosts…".to_string(), false)],
+        };
+        items.push(MenuItem {
+            is_separator: true,
+            ..entry(0, String::new(), false)
+        });
+        items.push(entry(CHECK_NOW_ID, "Check now".to_string(), true));
+        items
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        with_tooltip(
+            tray_text_button(&self.display_text, Message::Clicked),
+            &self.tooltip_text,
+        )
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        if self.config.targets.is_empty() {
+            return Subscription::none();
+        }
+        time::every(Duration::from_secs(self.config.interval_secs.max(10)))
+            .map(|_| Message::Refresh)
+    }
+}
+
+impl Module for Hosts {
+    type Message = Message;
+
+    fn show(&self, _output: &str) -> Element<'_, Message> {
+        self.view()
+    }
+
+    fn handle(
+        &mut self,
+        message: Message,
+        _context: &Context<'_>,
+        actions: &mut Vec<Action>,
+    ) -> Task<Message> {
+        // Clicking the widget opens the host list
+        if let Message::Clicked = message {
+            actions.push(Action::OpenMenu {
+                owner: MenuOwner::Hosts,
+                items: self.menu_items(),
+            });
+        }
+        self.update(message)
+    }
+
+    // Hosts are only checked while the module is in the layout
+    fn watch(&self, bar: &BarState<'_>) -> Subscription<Message> {
+        if bar.backends_started && bar.config.shows(&ModuleKind::Hosts) {
+            self.subscription()
+        } else {
+            Subscription::none()
+        }
+    }
+
+    fn reconfigure(&mut self, config: &Config) {
+        self.set_config(config.hosts.clone());
+    }
+
+    fn first_lookup(&self) -> Option<(Lookup, Message)> {
+        Some((Lookup::Backend, Message::Refresh))
+    }
+
+    fn menu_message(&self, owner: &MenuOwner, menu_id: i32) -> Option<Message> {
+        (*owner == MenuOwner::Hosts).then_some(Message::Selected(menu_id))
+    }
+}
+
+/// Check all hosts concurrently, in config order.
+async fn check_all(targets: Vec<HostConfig>, timeout: Duration) -> Vec<HostStatus> {
+    let checks = targets.into_iter().map(|target| async move {
+        let started = Instant::now();
+        let result = tokio::time::timeout(timeout, check(&target, timeout))
+            .await
+            .unwrap_or_else(|_| Err("timed out".to_string()))
+            .map(|()| started.elapsed());
+        HostStatus {
+            name: target.name,
+            result,
+        }
+    });
+    iced::futures::future::join_all(checks).await
+}
+
+async fn check(target: &HostConfig, timeout: Duration) -> Result<(), String> {
+    match target.probe {
+        HostProbe::Tcp => tokio::net::TcpStream::connect(target.address.as_str())
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        HostProbe::Ssh => {
+            let status = tokio::process::Command::new("ssh")
+                .args(["-o", "BatchMode=yes", "-o"])
+                .arg(format!("ConnectTimeout={}", timeout.as_secs()))
+                .args(["--", target.address.as_str(), "true"])
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .kill_on_drop(true)
+                .status()
+                .await
+                .map_err(|e| format!("ssh: {}", e))?;
+            if status.success() {
+                Ok(())
+            } else {
+                Err(format!("ssh failed ({})", status))
+            }
+        }
+    }
+}
@@ -0,0 +1,216 @@
+//! Laptop lid and dock state indicator.
+//!
+//! This component provides:
+//! - Lid (open/closed) and dock state from logind, with details in a tooltip
+//! - Configurable hook commands that run when the state changes, e.g. to
+//!   switch the monitor profile when docked; a failing hook is reported in
+//!   the tooltip
+
+use std::time::Duration;
+
+use iced::widget::{container, text};
+use iced::{Element, Subscription, Task, time};
+
+use super::tray_widget::{tray_text, with_tooltip};
+use crate::config::{Config, LidDockConfig};
+use crate::exec::Exec;
+use crate::layout::ModuleKind;
+use crate::modules::{Action, BarState, Context, Module};
+
+/// Seconds between logind state polls.
+const POLL_INTERVAL_SECS: u64 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct State {
+    /// `None` when logind does not report a lid (desktops)
+    lid_closed: Option<bool>,
+    docked: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct LidDock {
+    config: LidDockConfig,
+    /// Last known state; `None` until the first poll completes
+    state: Option<State>,
+    /// Error of the last hook that failed
+    hook_error: Option<String>,
+    display_text: String,
+    tooltip_text: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Tick,
+    StateRead(State),
+    HookFinished(Result<String, String>),
+}
+
+impl LidDock {
+    pub fn new(config: LidDockConfig) -> Self {
+        Self {
+            config,
+            state: None,
+            hook_error: None,
+            display_text: String::new(),
+            tooltip_text: String::new(),
+        }
+    }
+
+    /// Apply a reloaded lid/dock config.
+    pub fn set_config(&mut self, config: LidDockConfig) {
+        self.config = config;
+    }
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::Tick => Task::perform(read_state(), Message::StateRead),
+            Message::StateRead(state) => {
+                // Hooks only run on changes, not for the initial state
+                let hooks = match self.state {
+                    Some(previous) => self.run_hooks(previous, state),
+                    None => Task::none(),
+                };
+                self.state = Some(state);
+                self.update_display();
+                hooks
+            }
+            Message::HookFinished(Ok(_)) => {
+                self.hook_error = None;
+                self.update_display();
+                Task::none()
+            }
+            Message::HookFinished(Err(e)) => {
+                eprintln!("Lid/dock hook failed: {}", e);
+                self.hook_error = Some(e);
+                self.update_display();
+                Task::none()
+            }
+        }
+    }
+
+    fn run_hooks(&self, previous: State, current: State) -> Task<Message> {
+        let mut hooks = Vec::new();
+        if previous.docked != current.docked {
+            hooks.push(if current.docked {
+                &self.config.on_dock
+            } else {
+                &self.config.on_undock
+            });
+        }
+        if previous.lid_closed != current.lid_closed {
+            match current.lid_closed {
+                Some(true) => hooks.push(&self.config.on_lid_close),
+                Some(false) => hooks.push(&self.config.on_lid_open),
+                None => {}
+            }
+        }
+
+        Task::batch(hooks.into_iter().flatten().map(|command| {
+            Task::perform(Exec::new(command.as_str()).output(), Message::HookFinished)
+        }))
+    }
+
+    fn update_display(&mut self) {
+        let Some(state) = self.state else {
+            return;
+        };
+
+        let icon = match (state.docked, state.lid_closed) {
+            (true, Some(true)) => "󰍹", // nf-md-monitor (closed-lid docked)
+            (true, _) => "󰍺",          // nf-md-monitor_multiple
+            (false, _) => "󰌢",         // nf-md-laptop
+        };
+        self.display_text = icon.to_string();
+
+        let lid = match state.lid_closed {
+            Some(true) => "closed",
+            Some(false) => "open",
+            None => "none",
+        };
+        let dock = if state.docked { "docked" } else { "undocked" };
+        self.tooltip_text = format!("Lid: {}\nDock: {}", lid, dock);
+        if let Some(e) = &self.hook_error {
+            self.tooltip_text.push_str(&format!("\nHook failed: {}", e));
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        // Hide until the first state is known
+        if self.state.is_none() {
+            return container(text("")).into();
+        }
+
+        with_tooltip(tray_text(&self.display_text), &self.tooltip_text)
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        time::every(Duration::from_secs(POLL_INTERVAL_SECS)).map(|_| Message::Tick)
+    }
+}
+
+impl Module for LidDock {
+    type Message = Message;
+
+    fn show(&self, _output: &str) -> Element<'_, Message> {
+        self.view()
+    }
+
+    fn handle(
+        &mut self,
+        message: Message,
+        _context: &Context<'_>,
+        _actions: &mut Vec<Action>,
+    ) -> Task<Message> {
+        self.update(message)
+    }
+
+    // Keep polling for hooks even when the indicator is not shown
+    fn watch(&self, bar: &BarState<'_>) -> Subscription<Message> {
+        let wanted = bar.config.shows(&ModuleKind::LidDock) || bar.config.lid_dock.has_hooks();
+        if bar.backends_started && wanted {
+            self.subscription()
+        } else {
+            Subscription::none()
+        }
+    }
+
+    fn reconfigure(&mut self, config: &Config) {
+        self.set_config(config.lid_dock.clone());
+    }
+}
+
+/// Read a boolean property of the logind manager (`b true` / `b false`).
+async fn logind_property(name: &str) -> Option<bool> {
+    let output = tokio::process::Command::new("busctl")
+        .args([
+            "get-property",
+            "org.freedesktop.login1",
+            "/org/freedesktop/login1",
+            "org.freedesktop.login1.Manager",
+            name,
+        ])
+        .output()
+        .await
+        .ok()?;
+    match String::from_utf8_lossy(&output.stdout).trim() {
+        "b true" => Some(true),
+        "b false" => Some(false),
+        _ => None,
+    }
+}
+
+async fn read_state() -> State {
+    // logind reports LidClosed=false on machines without a lid
+    let has_lid = std::fs::read_dir("/proc/acpi/button/lid")
+        .map(|mut entries| entries.next().is_some())
+        .unwrap_or(false);
+
+    let lid_closed = if has_lid {
+        logind_property("LidClosed").await
+    } else {
+        None
+    };
+    let docked = logind_property("Docked").await.unwrap_or(false);
+
+    State { lid_closed, docked }
+}
@@ -0,0 +1,513 @@
+//! Unread mail counter.
+//!
+//! This component provides:
+//! - An unread mail badge from a count command (`notmuch count tag:unread`
+//!   by default), run periodically
+//! - Watch mode for long-running commands that print a new count on each
+//!   line, such as an IMAP IDLE script
+//! - Click to open the mail client
+//! - Right-click for a menu with a Compose entry and, with a list command,
+//!   the unread messages with quick actions (mark read, archive)
+//!
+//! The count is the first number the command prints. A configured secret
+//! is resolved before each run and passed in `$CLAMMY_MAIL_SECRET`; message
+//! actions get the message id in `$CLAMMY_MAIL_ID`.
+
+use std::process::Stdio;
+use std::time::Duration;
+
+use iced::futures::{SinkExt, Stream};
+use iced::widget::{button, container, mouse_area, row, text};
+use iced::{Alignment, Border, Element, Length, Subscription, Task, stream, time};
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+use super::tray_widget::with_tooltip;
+use crate::components::system_tray::menu::MenuItem;
+use crate::config::{Config, MailConfig};
+use crate::exec::Exec;
+use crate::layout::ModuleKind;
+use crate::modules::{Action, BarState, Context, Lookup, MenuOwner, Module};
+use crate::secrets::Secret;
+use crate::theme::get_theme;
+
+/// Largest count shown in the badge before it is abbreviated to `99+`.
+const MAX_BADGE_COUNT: u32 = 99;
+
+/// Seconds to wait before restarting a watch command after it exits.
+const RESTART_DELAY_SECS: u64 = 10;
+
+/// Environment variable holding the resolved secret.
+const SECRET_ENV: &str = "CLAMMY_MAIL_SECRET";
+
+/// Environment variable holding the message id for message actions.
+const ID_ENV: &str = "CLAMMY_MAIL_ID";
+
+/// Messages listed in the right-click menu.
+const MAX_MENU_MESSAGES: usize = 10;
+
+/// Longest sender/subject line in the menu before it is cut off.
+const MAX_MENU_LABEL_CHARS: usize = 50;
+
+/// Menu item id of the Compose entry. Message actions use
+/// `index * 2` (mark read) and `index * 2 + 1` (archive).
+const COMPOSE_ID: i32 = -1;
+
+/// An unread message, as printed by the list command.
+#[derive(Debug, Clone)]
+pub struct MailMessage {
+    id: String,
+    from: String,
+    subject: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Mail {
+    config: MailConfig,
+    /// Unread count of the last successful check
+    count: Option<u32>,
+    /// Error of the last check, shown in the tooltip
+    error: Option<String>,
+    /// Unread messages for the right-click menu
+    messages: Vec<MailMessage>,
+    /// Pre-formatted badge text (empty when there is nothing unread)
+    badge_text: String,
+    tooltip_text: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Check,
+    Counted(Result<u32, String>),
+    /// Open the mail client
+    Clicked,
+    /// Right-click: list the unread messages for the menu
+    MenuRequested,
+    /// Messages for the menu were listed (main opens the menu)
+    Listed(Result<Vec<MailMessage>, String>),
+    /// A menu entry was picked
+    Selected(i32),
+    /// A compose or message action finished
+    ActionFinished(Result<String, String>),
+}
+
+impl Mail {
+    pub fn new(config: MailConfig) -> Self {
+        let mut mail = Self {
+            config,
+            count: None,
+            error: None,
+            messages: Vec::new(),
+            badge_text: String::new(),
+            tooltip_text: String::new(),
+        };
+        mail.update_display();
+        mail
+    }
+
+    /// Apply a reloaded mail config.
+    pub fn set_config(&mut self, config: MailConfig) {
+        self.config = config;
+        self.update_display();
+    }
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            // A watch command reports on its own
+            Message::Check if self.config.watch => Task::none(),
+            Message::Check => {
+                let command = self.config.command.clone();
+                let secret = self.config.secret.clone();
+                Task::perform(count(command, secret), Message::Counted)
+            }
+            Message::Counted(Ok(count)) => {
+                self.count = Some(count);
+                self.error = None;
+                self.update_display();
+                Task::none()
+            }
+            Message::Counted(Err(e)) => {
+                if self.error.as_ref() != Some(&e) {
+                    eprintln!("Failed to count unread mail: {}", e);
+                }
+                self.error = Some(e);
+                self.update_display();
+                Task::none()
+            }
+            Message::Clicked => {
+                let Some(command) = &self.config.on_click else {
+                    return Task::none();
+                };
+                if let Err(e) = Exec::new(command.as_str()).launch() {
+                    eprintln!("Failed to open mail client: {}", e);
+                    self.error = Some(e);
+                    self.update_display();
+                }
+                Task::none()
+            }
+            Message::MenuRequested => match &self.config.list_command {
+                Some(command) => {
+                    let command = command.clone();
+                    let secret = self.config.secret.clone();
+                    Task::perform(list(command, secret), Message::Listed)
+                }
+                None => Task::done(Message::Listed(Ok(Vec::new()))),
+            },
+            Message::Listed(result) => {
+                self.messages = result.unwrap_or_else(|e| {
+                    eprintln!("Failed to list unread mail: {}", e);
+                    Vec::new()
+                });
+                Task::none()
+            }
+            Message::Selected(COMPOSE_ID) => {
+                if let Err(e) = Exec::new(self.config.compose_command.as_str()).launch() {
+                    eprintln!("Failed to compose mail: {}", e);
+                    self.error = Some(e);
+                    self.update_display();
+                }
+                Task::none()
+            }
+            Message::Selected(menu_id) => {
+                let Some((message, command)) = self.message_action(menu_id) else {
+                    return Task::none();
+                };
+                let exec = Exec::new(command).env(ID_ENV, message.id.as_str());
+                let secret = self.config.secret.clone();
+                Task::perform(run_action(exec, secret), Message::ActionFinished)
+            }
+            Message::ActionFinished(Ok(_)) => Task::done(Message::Check),
+            Message::ActionFinished(Err(e)) => {
+                eprintln!("Mail action failed: {}", e);
+                self.error = Some(e);
+                self.update_display();
+                Task::none()
+            }
+        }
+    }
+
+    /// The message and action command behind a menu item id.
+    fn message_action(&self, menu_id: i32) -> Option<(&MailMessage, &str)> {
+        let menu_id = usize::try_from(menu_id).ok()?;
+        let message = self.messages.get(menu_id / 2)?;
+        let command = match menu_id % 2 {
+            0 => self.config.mark_read_command.as_ref(),
+            _ => self.config.archive_command.as_ref(),
+        }?;
+        Some((message, command.as_str()))
+    }
+
+    /// Entries of the right-click menu: Compose, then each listed message
+    /// with its actions.
+    pub fn menu_items(&self) -> Vec<MenuItem> {
+        let entry = |id: i32, label: String| MenuItem {
+            id,
+            label,
+            enabled: true,
+            is_separator: false,
+            is_checkable: false,
+            is_radio: false,
+            is_checked: false,
+            icon: None,
+            shortcut: None,
+            submenu: Vec::new(),
+        };
+        let separator = || MenuItem {
+            is_separator: true,
+            ..entry(0, String::new())
+        };
+
+        let mut items = Vec::new();
+        if !self.config.compose_command.trim().is_empty() {
+            items.push(entry(COMPOSE_ID, "󰏫 Compose".to_string()));
+        }
+        if self.config.list_command.is_none() {
+            return items;
+        }
+
+        if self.messages.is_empty() {
+            if !items.is_empty() {
+                items.push(separator());
+            }
+            items.push(MenuItem {
+                enabled: false,
+                ..entry(0, "No unread mail".to_string())
+            });
+            return items;
+        }
+
+        let actions = [
+            (&self.config.mark_read_command, "Mark read"),
+            (&self.config.archive_command, "Archive"),
+        ];
+        for (index, message) in self.messages.iter().enumerate() {
+            if !items.is_empty() {
+                items.push(separator());
+            }
+            let mut header = format!("{} — {}", message.from, message.subject);
+            if header.chars().count() > MAX_MENU_LABEL_CHARS {
+                header = header.chars().take(MAX_MENU_LABEL_CHARS).collect();
+                header.push('…');
+            }
+            items.push(MenuItem {
+                enabled: false,
+                ..entry(0, header)
+            });
+            for (slot, (command, label)) in actions.iter().enumerate() {
+                if command.is_some() {
+                    let id = (index * 2 + slot) as i32;
+                    items.push(entry(id, format!("    {}", label)));
+                }
+            }
+        }
+        items
+    }
+
+    fn update_display(&mut self) {
+        self.badge_text = match self.count {
+            None | Some(0) => String::new(),
+            Some(n) if n > MAX_BADGE_COUNT => format!("{}+", MAX_BADGE_COUNT),
+            Some(n) => n.to_string(),
+        };
+
+        self.tooltip_text = match (self.count, &self.error) {
+            (_, Some(e)) => format!("Unread mail unavailable: {}", e),
+            (Some(0), None) => "No unread mail".to_string(),
+            (Some(1), None) => "1 unread message".to_string(),
+            (Some(n), None) => format!("{} unread messages", n),
+            (None, None) => "Checking mail…".to_string(),
+        };
+        if self.config.on_click.is_some() {
+            self.tooltip_text
+                .push_str("\nClick to open the mail client");
+        }
+        if self.config.list_command.is_some() {
+            self.tooltip_text
+                .push_str("\nRight-click for quick actions");
+        } else if !self.config.compose_command.trim().is_empty() {
+            self.tooltip_text.push_str("\nRight-click to compose");
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let theme = get_theme();
+        let hover_bg = theme.hover();
+        let font = theme.font();
+        let font_size = theme.font_size();
+        // A failing command leaves the last count visible, but dimmed
+        let text_color = if self.error.is_some() {
+            theme.muted()
+        } else {
+            theme.text()
+        };
+
+        let mut content = row![text("󰇮").font(font).size(font_size)]
+            .spacing(4)
+            .align_y(Alignment::Center);
+
+        if !self.badge_text.is_empty() {
+            let (badge_bg, badge_fg) = (theme.accent(), theme.background());
+            let badge = container(
+                text(&self.badge_text)
+                    .font(font)
+                    .size(font_size * 0.75)
+                    .color(badge_fg),
+            )
+            .padding([0, 5])
+            .style(move |_theme| container::Style {
+                background: Some(badge_bg.into()),
+                border: Border {
+                    radius: 8.0.into(),
+                    ..Border::default()
+                },
+                ..container::Style::default()
+            });
+            content = content.push(badge);
+        }
+
+        let content = button(container(content).center_y(Length::Fill))
+            .padding([0.0, theme.tray_widget_padding()])
+            .height(Length::Fill)
+            .style(move |_theme, status| button::Style {
+                background: match status {
+                    button::Status::Hovered | button::Status::Pressed => Some(hover_bg.into()),
+                    _ => None,
+                },
+                border: Border {
+                    radius: 2.0.into(),
+                    ..Border::default()
+                },
+                text_color,
+                shadow: Default::default(),
+            })
+            .on_press_maybe(self.config.on_click.as_ref().map(|_| Message::Clicked));
+        let content = mouse_area(content).on_right_press(Message::MenuRequested);
+
+        with_tooltip(content.into(), &self.tooltip_text)
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        if self.config.watch {
+            // Keyed by the command, so a changed command restarts the watcher
+            let command = self.config.command.clone();
+            let secret = self.config.secret.clone();
+            return Subscription::run_with_id(
+                ("mail-watch", command.clone(), secret.clone()),
+                watcher(command, secret),
+            );
+        }
+        time::every(Duration::from_secs(self.config.interval_secs.max(10))).map(|_| Message::Check)
+    }
+}
+
+impl Module for Mail {
+    type Message = Message;
+
+    fn show(&self, _output: &str) -> Element<'_, Message> {
+        self.view()
+    }
+
+    fn handle(
+        &mut self,
+        message: Message,
+        _context: &Context<'_>,
+        actions: &mut Vec<Action>,
+    ) -> Task<Message> {
+        let listed = matches!(message, Message::Listed(_));
+        let task = self.update(message);
+        if listed {
+            let items = self.menu_items();
+            if !items.is_empty() {
+                actions.push(Action::OpenMenu {
+                    owner: MenuOwner::Mail,
+                    items,
+                });
+            }
+        }
+        task
+    }
+
+    fn watch(&self, bar: &BarState<'_>) -> Subscription<Message> {
+        if bar.backends_started && bar.config.shows(&ModuleKind::Mail) {
+            self.subscription()
+        } else {
+            Subscription::none()
+        }
+    }
+
+    fn reconfigure(&mut self, config: &Config) {
+        self.set_config(config.mail.clone());
+    }
+
+    fn first_lookup(&self) -> Option<(Lookup, Message)> {
+        Some((Lookup::Backend, Message::Check))
+    }
+
+    fn menu_message(&self, owner: &MenuOwner, menu_id: i32) -> Option<Message> {
+        (*owner == MenuOwner::Mail).then_some(Message::Selected(menu_id))
+    }
+}
+
+/// Run the count command once.
+async fn count(command: String, secret: Option<Secret>) -> Result<u32, String> {
+    let mut exec = Exec::new(command.as_str());
+    if let Some(secret) = secret {
+        exec = exec.env(SECRET_ENV, secret.resolve().await?);
+    }
+    let output = exec.output().await?;
+    parse_count(&output).ok_or_else(|| format!("`{}` printed no count", command))
+}
+
+/// List unread messages for the menu.
+async fn list(command: String, secret: Option<Secret>) -> Result<Vec<MailMessage>, String> {
+    let mut exec = Exec::new(command.as_str());
+    if let Some(secret) = secret {
+        exec = exec.env(SECRET_ENV, secret.resolve().await?);
+    }
+    let output = exec.output().await?;
+    Ok(output
+        .lines()
+        .filter_map(parse_message)
+        .take(MAX_MENU_MESSAGES)
+        .collect())
+}
+
+/// Parse a `<id>\t<from>\t<subject>` line.
+fn parse_message(line: &str) -> Option<MailMessage> {
+    let mut fields = line.splitn(3, '\t');
+    let id = fields.next()?.trim();
+    if id.is_empty() {
+        return None;
+    }
+    Some(MailMessage {
+        id: id.to_string(),
+        from: fields.next().unwrap_or("").trim().to_string(),
+        subject: fields.next().unwrap_or("").trim().to_string(),
+    })
+}
+
+/// Run a message action, with the secret if one is configured.
+async fn run_action(mut exec: Exec, secret: Option<Secret>) -> Result<String, String> {
+    if let Some(secret) = secret {
+        exec = exec.env(SECRET_ENV, secret.resolve().await?);
+    }
+    exec.output().await
+}
+
+/// First number in the command output.
+fn parse_count(output: &str) -> Option<u32> {
+    output.split_whitespace().find_map(|word| word.parse().ok())
+}
+
+/// Follow a long-running count command, one count per line. The command is
+/// restarted if it exits.
+fn watcher(command: String, secret: Option<Secret>) -> impl Stream<Item = Message> {
+    stream::channel(100, move |mut output| async move {
+        loop {
+            let mut sh = tokio::process::Command::new("sh");
+            sh.args(["-c", &command]).stdin(Stdio::null());
+            if let Some(secret) = &secret {
+                match secret.resolve().await {
+                    Ok(secret) => {
+                        sh.env(SECRET_ENV, secret);
+                    }
+                    Err(e) => {
+                        let _ = output.send(Message::Counted(Err(e))).await;
+                        tokio::time::sleep(Duration::from_secs(RESTART_DELAY_SECS)).await;
+                        continue;
+                    }
+                }
+            }
+            let child = sh
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .kill_on_drop(true)
+                .spawn();
+
+            match child {
+                Ok(mut child) => {
+                    if let Some(stdout) = child.stdout.take() {
+                        let mut lines = BufReader::new(stdout).lines();
+                        while let Ok(Some(line)) = lines.next_line().await {
+                            if let Some(count) = parse_count(&line) {
+                                let _ = output.send(Message::Counted(Ok(count))).await;
+                            }
+                        }
+                    }
+                    let status = child.wait().await;
+                    let e = match status {
+                        Ok(status) => format!("`{}` exited ({})", command, status),
+                        Err(e) => e.to_string(),
+                    };
+                    let _ = output.send(Message::Counted(Err(e))).await;
+                }
+                Err(e) => {
+                    let _ = output
+                        .send(Message::Counted(Err(format!("sh: {}", e))))
+                        .await;
+                }
+            }
+
+            tokio::time::sleep(Duration::from_secs(RESTART_DELAY_SECS)).await;
+        }
+    })
+}
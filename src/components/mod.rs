@@ -1,8 +1,26 @@
+pub mod alarm;
 pub mod battery;
+pub mod calendar;
+pub mod carousel;
 pub mod clock;
+pub mod cursor;
+pub mod decoration;
+pub mod devices;
+pub mod file_content;
+pub mod focus_timer;
+pub mod hosts;
+pub mod lid_dock;
+pub mod mail;
 pub mod notification_toggle;
+pub mod power;
+pub mod public_ip;
+pub mod script_output;
+pub mod swap;
 pub mod system_tray;
+pub mod tiling_layout;
+pub mod timer;
 pub mod tray_widget;
 pub mod volume;
+pub mod vrr;
 pub mod window_title;
 pub mod workspaces;
@@ -1,23 +1,163 @@
-use iced::widget::{button, text};
-use iced::{Border, Element, Subscription, Task};
-use std::process::Command;
+//! Notification center toggle.
+//!
+//! This component provides:
+//! - A button that toggles the swaync notification panel
+//! - An unread-count badge and a muted bell while do-not-disturb is enabled,
+//!   driven by swaync's D-Bus state via `swaync-client --subscribe`, started
+//!   whenever swaync appears on the session bus
+//! - Do-not-disturb toggle (right click) and scheduled quiet hours that
+//!   enable do-not-disturb automatically
 
+use chrono::{Local, NaiveTime};
+use iced::futures::{SinkExt, Stream, StreamExt};
+use iced::widget::{button, container, image, mouse_area, row, text};
+use iced::{Alignment, Border, Element, Length, Subscription, Task, stream, time};
+use serde::Deserialize;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use zbus::fdo::{DBusProxy, NameOwnerChangedStream};
+use zbus::names::BusName;
+
+use crate::config::{Config, NotificationsConfig, QuietHoursConfig};
+use crate::icons::Icon;
+use crate::layout::ModuleKind;
+use crate::modules::{Action, BarState, Context, Module};
+use crate::sound::SoundEvent;
 use crate::theme::get_theme;
 
+const ICON: Icon = Icon {
+    glyph: "󰂚",
+    unicode: "○",
+    name: "notifications",
+};
+const UNREAD_ICON: Icon = Icon {
+    glyph: "󰂞",
+    unicode: "●",
+    name: "notifications-new",
+};
+const DND_ICON: Icon = Icon {
+    glyph: "󰂛",
+    unicode: "◌",
+    name: "notifications-disabled",
+};
+
+/// Seconds to wait before restarting `swaync-client` after it exits
+/// (e.g. swaync not running yet or restarted). Doubles while it keeps
+/// failing, up to [`MAX_RECONNECT_DELAY_SECS`].
+const RECONNECT_DELAY_SECS: u64 = 5;
+
+const MAX_RECONNECT_DELAY_SECS: u64 = 300;
+
+/// Name swaync owns on the session bus while it runs.
+const SWAYNC_BUS_NAME: &str = "org.erikreider.swaync.cc";
+
+/// Largest count shown in the badge before it is abbreviated to `99+`.
+const MAX_BADGE_COUNT: u32 = 99;
+
+/// Seconds between quiet hours checks.
+const QUIET_HOURS_CHECK_SECS: u64 = 30;
+
 #[derive(Debug, Clone, Default)]
-pub struct NotificationToggle;
+pub struct NotificationToggle {
+    config: NotificationsConfig,
+    /// Number of notifications in the notification center
+    count: u32,
+    /// Whether do-not-disturb is enabled
+    dnd: bool,
+    /// Pre-formatted badge text (empty when there is nothing unread)
+    badge_text: String,
+    /// Whether the current time was inside quiet hours at the last check
+    in_quiet_hours: bool,
+    /// DND was enabled by quiet hours (and should be disabled when they end)
+    quiet_hours_dnd: bool,
+}
 
 #[derive(Debug, Clone)]
 pub enum Message {
     Toggle,
     Toggled,
+    /// User toggled do-not-disturb
+    ToggleDnd,
+    /// Periodic quiet hours check
+    QuietHoursTick,
+    /// swaync reported a new notification count / DND state
+    StateChanged { count: u32, dnd: bool },
+}
+
+/// One line of `swaync-client --subscribe` output.
+#[derive(Debug, Deserialize)]
+struct SwayncState {
+    #[serde(default)]
+    count: u32,
+    #[serde(default)]
+    dnd: bool,
 }
 
 impl NotificationToggle {
+    pub fn new(config: NotificationsConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    /// Apply a reloaded notifications config. Takes effect on the next check.
+    pub fn set_config(&mut self, config: NotificationsConfig) {
+        self.config = config;
+    }
+
+    /// Number of notifications in the notification center.
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Whether do-not-disturb is enabled.
+    pub fn dnd(&self) -> bool {
+        self.dnd
+    }
+
     pub fn update(&mut self, message: Message) -> Task<Message> {
         match message {
             Message::Toggle => Task::perform(Self::toggle_panel(), |_| Message::Toggled),
             Message::Toggled => Task::none(),
+            Message::ToggleDnd => {
+                // A manual toggle overrides the schedule until the next transition
+                self.quiet_hours_dnd = false;
+                self.set_dnd(!self.dnd);
+                Task::none()
+            }
+            Message::QuietHoursTick => {
+                let now = Local::now().time();
+                let in_quiet_hours = self
+                    .config
+                    .quiet_hours
+                    .iter()
+                    .any(|range| is_within(range, now));
+                if in_quiet_hours == self.in_quiet_hours {
+                    return Task::none();
+                }
+                self.in_quiet_hours = in_quiet_hours;
+
+                if in_quiet_hours && !self.dnd {
+                    self.quiet_hours_dnd = true;
+                    self.set_dnd(true);
+                } else if !in_quiet_hours && self.quiet_hours_dnd {
+                    self.quiet_hours_dnd = false;
+                    self.set_dnd(false);
+                }
+                Task::none()
+            }
+            Message::StateChanged { count, dnd } => {
+                self.count = count;
+                self.dnd = dnd;
+                self.badge_text = match count {
+                    0 => String::new(),
+                    n if n > MAX_BADGE_COUNT => format!("{}+", MAX_BADGE_COUNT),
+                    n => n.to_string(),
+                };
+                Task::none()
+            }
         }
     }
 
@@ -25,15 +165,65 @@ impl NotificationToggle {
         let _ = Command::new("swaync-client").arg("--toggle-panel").spawn();
     }
 
+    /// Enable or disable do-not-disturb. The state is updated right away;
+    /// swaync confirms it through the subscription.
+    fn set_dnd(&mut self, enabled: bool) {
+        self.dnd = enabled;
+        let flag = if enabled { "--dnd-on" } else { "--dnd-off" };
+        let _ = Command::new("swaync-client").arg(flag).spawn();
+    }
+
     pub fn view(&self) -> Element<'_, Message> {
         let theme = get_theme();
         let hover_bg = theme.hover();
         let text_color = theme.text();
+        let font = theme.font();
         let font_size = theme.font_size();
 
-        // Nerd Font bell icon
-        button(text("󰂚").size(font_size))
+        // Bell icons: muted bell for DND, badged bell for unread
+        let icon = if self.dnd {
+            DND_ICON
+        } else if self.count > 0 {
+            UNREAD_ICON
+        } else {
+            ICON
+        }
+        .show(&ModuleKind::NotificationToggle);
+
+        let mut content = row![].spacing(4).align_y(Alignment::Center);
+        if let Some(handle) = icon.image {
+            content = content.push(image(handle).height(font_size * 1.2));
+        } else if !icon.text.is_empty() {
+            content = content.push(text(icon.text).font(font).size(font_size));
+        }
+
+        // Keep the count visible while DND is on, but without drawing attention
+        if !self.badge_text.is_empty() {
+            let (badge_bg, badge_fg) = if self.dnd {
+                (theme.muted(), theme.background())
+            } else {
+                (theme.accent(), theme.background())
+            };
+            let badge = text(&self.badge_text)
+                .font(font)
+                .size(font_size * 0.75)
+                .color(badge_fg);
+            let badge = container(badge)
+                .padding([0, 5])
+                .style(move |_theme| container::Style {
+                    background: Some(badge_bg.into()),
+                    border: Border {
+                        radius: 8.0.into(),
+                        ..Border::default()
+                    },
+                    ..container::Style::default()
+                });
+            content = content.push(badge);
+        }
+
+        let toggle = button(container(content).center_y(Length::Fill))
             .padding([0, 8])
+            .height(Length::Fill)
             .style(move |_theme, status| {
                 let bg = match status {
                     button::Status::Hovered => Some(hover_bg.into()),
@@ -49,11 +239,168 @@ impl NotificationToggle {
                     shadow: Default::default(),
                 }
             })
-            .on_press(Message::Toggle)
-            .into()
+            .on_press(Message::Toggle);
+
+        mouse_area(toggle).on_right_press(Message::ToggleDnd).into()
     }
 
     pub fn subscription(&self) -> Subscription<Message> {
-        Subscription::none()
+        let state = Subscription::run_with_id("swaync-state", swaync_watcher());
+        if self.config.quiet_hours.is_empty() {
+            return state;
+        }
+        Subscription::batch([
+            state,
+            time::every(Duration::from_secs(QUIET_HOURS_CHECK_SECS))
+                .map(|_| Message::QuietHoursTick),
+        ])
+    }
+}
+
+impl Module for NotificationToggle {
+    type Message = Message;
+
+    fn show(&self, _output: &str) -> Element<'_, Message> {
+        self.view()
+    }
+
+    fn handle(
+        &mut self,
+        message: Message,
+        _context: &Context<'_>,
+        actions: &mut Vec<Action>,
+    ) -> Task<Message> {
+        if let Message::StateChanged { count, .. } = message
+            && count > self.count()
+        {
+            actions.push(Action::PlaySound(SoundEvent::Notification));
+        }
+        self.update(message)
+    }
+
+    fn watch(&self, bar: &BarState<'_>) -> Subscription<Message> {
+        let enabled = bar
+            .config
+            .modules
+            .is_enabled(&ModuleKind::NotificationToggle);
+        if bar.backends_started && enabled {
+            self.subscription()
+        } else {
+            Subscription::none()
+        }
+    }
+
+    fn reconfigure(&mut self, config: &Config) {
+        self.set_config(config.notifications.clone());
+    }
+}
+
+/// Whether `time` falls inside a quiet hours range. Ranges whose end is
+/// before their start span midnight. Unparseable ranges never match.
+fn is_within(range: &QuietHoursConfig, time: NaiveTime) -> bool {
+    let (Ok(start), Ok(end)) = (
+        NaiveTime::parse_from_str(&range.start, "%H:%M"),
+        NaiveTime::parse_from_str(&range.end, "%H:%M"),
+    ) else {
+        return false;
+    };
+    if start <= end {
+        start <= time && time < end
+    } else {
+        time >= start || time < end
+    }
+}
+
+/// Follow swaync's state through `swaync-client --subscribe`, which prints
+/// one JSON object per change. The client is started once swaync owns its
+/// name on the session bus, and restarted with a growing delay while it
+/// keeps failing. Without a session bus it is retried on the delay alone.
+fn swaync_watcher() -> impl Stream<Item = Message> {
+    stream::channel(100, |mut output| async move {
+        let mut bus = match watch_swaync().await {
+            Ok(bus) => Some(bus),
+            Err(e) => {
+                eprintln!("Failed to watch for swaync on D-Bus: {}", e);
+                None
+            }
+        };
+        let mut delay = RECONNECT_DELAY_SECS;
+        let mut reported = false;
+        loop {
+            if let Some((dbus, changes)) = &mut bus {
+                wait_for_swaync(dbus, changes).await;
+            }
+
+            let child = tokio::process::Command::new("swaync-client")
+                .arg("--subscribe")
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .kill_on_drop(true)
+                .spawn();
+
+            let mut received = false;
+            match child {
+                Ok(mut child) => {
+                    if let Some(stdout) = child.stdout.take() {
+                        let mut lines = BufReader::new(stdout).lines();
+                        while let Ok(Some(line)) = lines.next_line().await {
+                            let Ok(state) = serde_json::from_str::<SwayncState>(&line) else {
+                                continue;
+                            };
+                            received = true;
+                            let _ = output
+                                .send(Message::StateChanged {
+                                    count: state.count,
+                                    dnd: state.dnd,
+                                })
+                                .await;
+                        }
+                    }
+                    let _ = child.wait().await;
+                }
+                // Only once until it starts, it is retried for good
+                Err(e) if !reported => {
+                    eprintln!("Failed to start swaync-client: {}", e);
+                    reported = true;
+                }
+                Err(_) => {}
+            }
+
+            // A client that worked was cut off by swaync going away, which
+            // is no reason to wait longer for the next one
+            delay = if received {
+                reported = false;
+                RECONNECT_DELAY_SECS
+            } else {
+                (delay * 2).min(MAX_RECONNECT_DELAY_SECS)
+            };
+            tokio::time::sleep(Duration::from_secs(delay)).await;
+        }
+    })
+}
+
+/// The bus daemon and the changes of the owner of swaync's name.
+async fn watch_swaync() -> zbus::Result<(DBusProxy<'static>, NameOwnerChangedStream)> {
+    let connection = zbus::Connection::session().await?;
+    let dbus = DBusProxy::new(&connection).await?;
+    let changes = dbus
+        .receive_name_owner_changed_with_args(&[(0, SWAYNC_BUS_NAME)])
+        .await?;
+    Ok((dbus, changes))
+}
+
+/// Wait until swaync owns its name on the session bus. Returns right away
+/// when it does, or when the bus can't tell.
+async fn wait_for_swaync(dbus: &DBusProxy<'_>, changes: &mut NameOwnerChangedStream) {
+    let Ok(name) = BusName::try_from(SWAYNC_BUS_NAME) else {
+        return;
+    };
+    if dbus.name_has_owner(name).await.unwrap_or(true) {
+        return;
+    }
+    while let Some(change) = changes.next().await {
+        if change.args().is_ok_and(|args| args.new_owner().is_some()) {
+            return;
+        }
     }
 }
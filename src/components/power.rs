@@ -0,0 +1,316 @@
+//! Power menu.
+//!
+//! This component provides:
+//! - A power button that opens a menu with lock, log out, suspend, reboot
+//!   and shut down
+//! - A confirmation popup for log out, reboot and shut down, optionally
+//!   listing windows that may hold unsaved work
+
+use std::collections::HashMap;
+use std::process::Command;
+
+use hyprland::data::Clients;
+use hyprland::shared::HyprData;
+use iced::widget::{button, column, container, row, text};
+use iced::{Border, Element, Length, Task};
+
+use super::system_tray::menu::MenuItem;
+use super::tray_widget::tray_text_button;
+use crate::config::{Config, PowerConfig};
+use crate::modules::{self, Context, MenuOwner, Module};
+use crate::theme::get_theme;
+
+/// Confirmation popup width in pixels.
+const CONFIRM_WIDTH: u32 = 360;
+
+/// Most warning lines listed in the confirmation popup.
+const MAX_WARNINGS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Lock,
+    Logout,
+    Suspend,
+    Reboot,
+    Shutdown,
+}
+
+impl Action {
+    const ALL: [Action; 5] = [
+        Action::Lock,
+        Action::Logout,
+        Action::Suspend,
+        Action::Reboot,
+        Action::Shutdown,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Action::Lock => "Lock",
+            Action::Logout => "Log out",
+            Action::Suspend => "Suspend",
+            Action::Reboot => "Reboot",
+            Action::Shutdown => "Shut down",
+        }
+    }
+
+    /// Program and arguments that perform the action.
+    fn command(self) -> (&'static str, &'static [&'static str]) {
+        match self {
+            Action::Lock => ("loginctl", &["lock-session"]),
+            Action::Logout => ("hyprctl", &["dispatch", "exit"]),
+            Action::Suspend => ("systemctl", &["suspend"]),
+            Action::Reboot => ("systemctl", &["reboot"]),
+            Action::Shutdown => ("systemctl", &["poweroff"]),
+        }
+    }
+
+    /// Actions that end the session and need confirmation.
+    fn needs_confirmation(self) -> bool {
+        matches!(self, Action::Logout | Action::Reboot | Action::Shutdown)
+    }
+
+    fn from_menu_id(id: i32) -> Option<Action> {
+        Action::ALL.get(usize::try_from(id).ok()?).copied()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Power {
+    config: PowerConfig,
+    /// Action waiting for confirmation, with unsaved-work warnings
+    pending: Option<(Action, Vec<String>)>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// User clicked the power button (main opens the menu)
+    Clicked,
+    /// Menu entry chosen
+    Selected(i32),
+    /// Unsaved-work check finished; main opens the confirmation popup
+    ConfirmationReady(Action, Vec<String>),
+    Confirm,
+    Cancel,
+}
+
+impl Power {
+    pub fn new(config: PowerConfig) -> Self {
+        Self {
+            config,
+            pending: None,
+        }
+    }
+
+    /// Apply a reloaded power config.
+    pub fn set_config(&mut self, config: PowerConfig) {
+        self.config = config;
+    }
+
+    /// Entries of the power menu.
+    pub fn menu_items(&self) -> Vec<MenuItem> {
+        Action::ALL
+            .iter()
+            .enumerate()
+            .map(|(index, action)| MenuItem {
+                id: index as i32,
+                label: action.label().to_string(),
+                enabled: true,
+                is_separator: false,
+                is_checkable: false,
+                is_radio: false,
+                is_checked: false,
+                icon: None,
+                shortcut: None,
+                submenu: Vec::new(),
+            })
+            .collect()
+    }
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::Clicked => Task::none(),
+            Message::Selected(id) => {
+                let Some(action) = Action::from_menu_id(id) else {
+                    return Task::none();
+                };
+                if !action.needs_confirmation() {
+                    run(action);
+                    return Task::none();
+                }
+                if !self.config.check_unsaved {
+                    return Task::done(Message::ConfirmationReady(action, Vec::new()));
+                }
+                let config = self.config.clone();
+                Task::perform(unsaved_work_warnings(config), move |warnings| {
+                    Message::ConfirmationReady(action, warnings)
+                })
+            }
+            Message::ConfirmationReady(action, warnings) => {
+                self.pending = Some((action, warnings));
+                Task::none()
+            }
+            Message::Confirm => {
+                if let Some((action, _)) = self.pending.take() {
+                    run(action);
+                }
+                Task::none()
+            }
+            Message::Cancel => {
+                self.pending = None;
+                Task::none()
+            }
+        }
+    }
+
+    /// Size of the confirmation popup content for the pending action.
+    pub fn confirm_size(&self) -> (u32, u32) {
+        let font_size = get_theme().font_size();
+        let line = font_size + 8.0;
+        let warnings = self.pending.as_ref().map_or(0, |(_, w)| w.len().min(MAX_WARNINGS));
+        // Title, buttons and padding, plus a heading and a line per warning
+        let mut height = line * 2.0 + 40.0;
+        if warnings > 0 {
+            height += line * (warnings + 1) as f32;
+        }
+        (CONFIRM_WIDTH, height as u32)
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        tray_text_button("⏻", Message::Clicked)
+    }
+
+    /// Render the confirmation popup content.
+    pub fn view_confirm(&self) -> Element<'_, Message> {
+        let Some((action, warnings)) = &self.pending else {
+            return container(text("")).into();
+        };
+
+        let theme = get_theme();
+        let font_size = theme.font_size();
+        let text_color = theme.text();
+        let hover = theme.hover();
+        let danger = theme.danger();
+        let muted = theme.muted();
+
+        let mut content = column![text(format!("{}?", action.label())).size(font_size)].spacing(6);
+
+        if !warnings.is_empty() {
+            content = content.push(
+                text("Possibly unsaved work:")
+                    .size(font_size)
+                    .color(danger),
+            );
+            for warning in warnings.iter().take(MAX_WARNINGS) {
+                content = content.push(text(warning.as_str()).size(font_size).color(muted));
+            }
+        }
+
+        let action_button = |label: &'static str, message: Message, color| {
+            button(text(label).size(font_size))
+                .padding([4, 12])
+                .on_press(message)
+                .style(move |_theme, status| button::Style {
+                    background: match status {
+                        button::Status::Hovered | button::Status::Pressed => Some(hover.into()),
+                        _ => None,
+                    },
+                    text_color: color,
+                    border: Border {
+                        color,
+                        width: 1.0,
+                        radius: 4.0.into(),
+                    },
+                    shadow: Default::default(),
+                })
+        };
+
+        let buttons = row![
+            action_button("Cancel", Message::Cancel, text_color),
+            action_button(action.label(), Message::Confirm, danger),
+        ]
+        .spacing(8);
+
+        content = content.push(container(buttons).width(Length::Fill).align_right(Length::Fill));
+
+        container(content).padding(8).width(Length::Fill).into()
+    }
+}
+
+impl Module for Power {
+    type Message = Message;
+
+    fn show(&self, _output: &str) -> Element<'_, Message> {
+        self.view()
+    }
+
+    fn handle(
+        &mut self,
+        message: Message,
+        _context: &Context<'_>,
+        actions: &mut Vec<modules::Action>,
+    ) -> Task<Message> {
+        match &message {
+            Message::Clicked => actions.push(modules::Action::OpenMenu {
+                owner: MenuOwner::Power,
+                items: self.menu_items(),
+            }),
+            Message::Confirm | Message::Cancel => actions.push(modules::Action::ClosePowerConfirm),
+            Message::ConfirmationReady(..) => actions.push(modules::Action::OpenPowerConfirm),
+            _ => {}
+        }
+        self.update(message)
+    }
+
+    fn reconfigure(&mut self, config: &Config) {
+        self.set_config(config.power.clone());
+    }
+
+    fn menu_message(&self, owner: &MenuOwner, menu_id: i32) -> Option<Message> {
+        (*owner == MenuOwner::Power).then_some(Message::Selected(menu_id))
+    }
+}
+
+fn run(action: Action) {
+    let (program, args) = action.command();
+    if let Err(e) = Command::new(program).args(args).spawn() {
+        eprintln!("Failed to run {}: {}", action.label(), e);
+    }
+}
+
+/// List windows that may hold unsaved work: titles with an unsaved marker,
+/// and applications with many open windows.
+async fn unsaved_work_warnings(config: PowerConfig) -> Vec<String> {
+    let clients = match Clients::get_async().await {
+        Ok(clients) => clients,
+        Err(e) => {
+            eprintln!("Failed to fetch clients: {:?}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut warnings = Vec::new();
+    let mut windows_per_class: HashMap<String, usize> = HashMap::new();
+    for client in clients {
+        let title = client.title.trim();
+        let unsaved = config
+            .unsaved_markers
+            .iter()
+            .any(|marker| title.starts_with(marker.as_str()) || title.ends_with(marker.as_str()));
+        if unsaved {
+            warnings.push(format!("{}: {}", client.class, title));
+        }
+        *windows_per_class.entry(client.class).or_default() += 1;
+    }
+
+    let mut busy: Vec<_> = windows_per_class
+        .into_iter()
+        .filter(|(_, count)| *count >= config.many_windows)
+        .collect();
+    busy.sort();
+    warnings.extend(
+        busy.into_iter()
+            .map(|(class, count)| format!("{}: {} windows", class, count)),
+    );
+    warnings
+}
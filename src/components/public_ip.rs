@@ -0,0 +1,265 @@
+//! Public IP widget.
+//!
+//! This component provides:
+//! - The public IP address, or the country flag, resolved periodically from
+//!   a configurable endpoint
+//! - Click to copy the address to the clipboard (`wl-copy`)
+//! - Right-click to refresh
+//!
+//! The endpoint may answer with the plain address or with JSON containing
+//! `ip` and optionally `country` (ISO 3166 alpha-2), like ipinfo.io. An
+//! optional API token is sent as a bearer token, passed to `curl` on stdin
+//! so it does not show up in the process list.
+
+use std::io::Write as _;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use iced::widget::mouse_area;
+use iced::{Element, Subscription, Task, time};
+use serde::Deserialize;
+use tokio::io::AsyncWriteExt;
+
+use super::tray_widget::{tray_text_button, with_tooltip};
+use crate::config::{Config, PublicIpConfig, PublicIpDisplay};
+use crate::layout::ModuleKind;
+use crate::modules::{Action, BarState, Context, Lookup, Module};
+use crate::secrets::Secret;
+
+/// Seconds before `curl` gives up on the endpoint.
+const REQUEST_TIMEOUT_SECS: &str = "10";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IpInfo {
+    ip: String,
+    country: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IpResponse {
+    ip: String,
+    #[serde(default)]
+    country: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PublicIp {
+    config: PublicIpConfig,
+    info: Option<IpInfo>,
+    /// Error of the last lookup, shown in the tooltip
+    error: Option<String>,
+    display_text: String,
+    tooltip_text: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Refresh,
+    Resolved(Result<IpInfo, String>),
+    /// Copy the address to the clipboard
+    Copy,
+}
+
+impl PublicIp {
+    pub fn new(config: PublicIpConfig) -> Self {
+        let mut public_ip = Self {
+            config,
+            info: None,
+            error: None,
+            display_text: String::new(),
+            tooltip_text: String::new(),
+        };
+        public_ip.update_display();
+        public_ip
+    }
+
+    /// Apply a reloaded public IP config.
+    pub fn set_config(&mut self, config: PublicIpConfig) {
+        self.config = config;
+        self.update_display();
+    }
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::Refresh => {
+                let endpoint = self.config.endpoint.clone();
+                let token = self.config.token.clone();
+                Task::perform(resolve(endpoint, token), Message::Resolved)
+            }
+            Message::Resolved(Ok(info)) => {
+                self.info = Some(info);
+                self.error = None;
+                self.update_display();
+                Task::none()
+            }
+            Message::Resolved(Err(e)) => {
+                eprintln!("Failed to resolve public IP: {}", e);
+                self.error = Some(e);
+                self.update_display();
+                Task::none()
+            }
+            Message::Copy => {
+                if let Some(info) = &self.info {
+                    copy_to_clipboard(&info.ip);
+                }
+                Task::none()
+            }
+        }
+    }
+
+    fn update_display(&mut self) {
+        let Some(info) = &self.info else {
+            self.display_text = "󰩠 …".to_string();
+            self.tooltip_text = match &self.error {
+                Some(e) => format!("Public IP unavailable: {}", e),
+                None => "Resolving public IP…".to_string(),
+            };
+            return;
+        };
+
+        let flag = info.country.as_deref().and_then(country_flag);
+        self.display_text = match (self.config.display, flag) {
+            (PublicIpDisplay::Flag, Some(flag)) => flag,
+            _ => format!("󰩠 {}", info.ip),
+        };
+
+        self.tooltip_text = match &info.country {
+            Some(country) => format!("{} ({})", info.ip, country),
+            None => info.ip.clone(),
+        };
+        if let Some(e) = &self.error {
+            self.tooltip_text
+                .push_str(&format!("\nLast refresh failed: {}", e));
+        }
+        self.tooltip_text
+            .push_str("\nClick to copy, right-click to refresh");
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let content = mouse_area(tray_text_button(&self.display_text, Message::Copy))
+            .on_right_press(Message::Refresh);
+
+        with_tooltip(content.into(), &self.tooltip_text)
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        time::every(Duration::from_secs(self.config.interval_secs.max(60)))
+            .map(|_| Message::Refresh)
+    }
+}
+
+impl Module for PublicIp {
+    type Message = Message;
+
+    fn show(&self, _output: &str) -> Element<'_, Message> {
+        self.view()
+    }
+
+    fn handle(
+        &mut self,
+        message: Message,
+        _context: &Context<'_>,
+        _actions: &mut Vec<Action>,
+    ) -> Task<Message> {
+        self.update(message)
+    }
+
+    // Only look up the address while the module is in the layout
+    fn watch(&self, bar: &BarState<'_>) -> Subscription<Message> {
+        if bar.backends_started && bar.config.shows(&ModuleKind::PublicIp) {
+            self.subscription()
+        } else {
+            Subscription::none()
+        }
+    }
+
+    fn reconfigure(&mut self, config: &Config) {
+        self.set_config(config.public_ip.clone());
+    }
+
+    fn first_lookup(&self) -> Option<(Lookup, Message)> {
+        Some((Lookup::Backend, Message::Refresh))
+    }
+}
+
+/// Fetch the endpoint with `curl` and parse the address.
+async fn resolve(endpoint: String, token: Option<Secret>) -> Result<IpInfo, String> {
+    let header = match token {
+        Some(token) => Some(format!("Authorization: Bearer {}", token.resolve().await?)),
+        None => None,
+    };
+
+    let mut command = tokio::process::Command::new("curl");
+    command
+        .args(["--silent", "--show-error", "--fail", "--max-time"])
+        .arg(REQUEST_TIMEOUT_SECS)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if header.is_some() {
+        // Read the header from stdin
+        command.args(["--header", "@-"]);
+    }
+    let mut child = command
+        .arg(&endpoint)
+        .spawn()
+        .map_err(|e| format!("curl: {}", e))?;
+
+    // Dropping stdin afterwards closes it
+    if let (Some(mut stdin), Some(header)) = (child.stdin.take(), header) {
+        let _ = stdin.write_all(header.as_bytes()).await;
+    }
+    let output = child
+        .wait_with_output()
+        .await
+        .map_err(|e| format!("curl: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(stderr.trim().to_string());
+    }
+    parse_response(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parse a plain-text or JSON endpoint response.
+fn parse_response(body: &str) -> Result<IpInfo, String> {
+    let body = body.trim();
+    if body.starts_with('{') {
+        let response: IpResponse =
+            serde_json::from_str(body).map_err(|e| format!("invalid response: {}", e))?;
+        return Ok(IpInfo {
+            ip: response.ip,
+            country: response.country,
+        });
+    }
+    match body.parse::<std::net::IpAddr>() {
+        Ok(ip) => Ok(IpInfo {
+            ip: ip.to_string(),
+            country: None,
+        }),
+        Err(_) => Err("response is not an IP address".to_string()),
+    }
+}
+
+/// Flag emoji for an ISO 3166 alpha-2 country code.
+fn country_flag(code: &str) -> Option<String> {
+    if code.len() != 2 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    code.to_ascii_uppercase()
+        .chars()
+        .map(|c| char::from_u32(0x1F1E6 + (c as u32 - 'A' as u32)))
+        .collect()
+}
+
+fn copy_to_clipboard(value: &str) {
+    let child = Command::new("wl-copy").stdin(Stdio::piped()).spawn();
+    match child {
+        Ok(mut child) => {
+            if let Some(mut stdin) = child.stdin.take() {
+                let _ = stdin.write_all(value.as_bytes());
+            }
+        }
+        Err(e) => eprintln!("Failed to run wl-copy: {}", e),
+    }
+}
@@ -0,0 +1,194 @@
+//! Output of custom widget scripts, so waybar and i3bar scripts can be
+//! reused unchanged.
+//!
+//! Output is read according to the widget's `return_type`:
+//! - `text`: waybar's plain output; the first line is shown, the second is
+//!   the tooltip
+//! - `json`: waybar's `{"text", "tooltip", "percentage", "alt"}` objects
+//! - `i3bar`: the i3bar protocol, a `{"version": 1}` header followed by an
+//!   endless JSON array with one array of blocks per status
+//!
+//! Scripts that keep running print one status per line.
+
+use iced::Color;
+use serde::Deserialize;
+
+use crate::color;
+use crate::config::ReturnType;
+
+/// What a script reported.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Status {
+    pub text: String,
+    pub tooltip: Option<String>,
+    /// 0-100, picks the `{icon}`
+    pub percentage: Option<f64>,
+    /// waybar's `alt`, for `{alt}`
+    pub alt: String,
+    /// i3bar block color
+    pub color: Option<Color>,
+    /// i3bar `urgent`, shown in the danger color
+    pub urgent: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct WaybarJson {
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    tooltip: Option<String>,
+    #[serde(default)]
+    percentage: Option<f64>,
+    #[serde(default)]
+    alt: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct I3barBlock {
+    #[serde(default)]
+    full_text: String,
+    #[serde(default)]
+    color: Option<String>,
+    #[serde(default)]
+    urgent: bool,
+}
+
+/// The status in the whole output of one run. `None` hides the widget.
+pub fn parse_run(output: &str, return_type: ReturnType) -> Option<Status> {
+    match return_type {
+        ReturnType::Text => {
+            let mut lines = output.lines();
+            let text = lines.next()?.trim();
+            if text.is_empty() {
+                return None;
+            }
+            Some(Status {
+                text: text.to_string(),
+                tooltip: lines
+                    .next()
+                    .map(str::trim)
+                    .filter(|tooltip| !tooltip.is_empty())
+                    .map(str::to_string),
+                ..Status::default()
+            })
+        }
+        // The object may be pretty-printed over several lines
+        ReturnType::Json => parse_json(output),
+        // A run prints the header and one or more statuses; the last counts
+        ReturnType::I3bar => output
+            .lines()
+            .rev()
+            .find_map(|line| parse_line(line, return_type)),
+    }
+}
+
+/// The status in one line of a script that keeps running. Lines without a
+/// status (e.g. the i3bar header) give `None`.
+pub fn parse_line(line: &str, return_type: ReturnType) -> Option<Status> {
+    match return_type {
+        ReturnType::Text => parse_run(line, return_type),
+        ReturnType::Json => parse_json(line),
+        ReturnType::I3bar => {
+            // Statuses are elements of one endless array: `[`, `[...]`,
+            // `,[...]`, sometimes with the opening bracket on the first one
+            let line = line.trim().trim_start_matches(',').trim_end_matches(',');
+            let line = if line.starts_with("[[") {
+                &line[1..]
+            } else {
+                line
+            };
+            if !line.starts_with('[') || line == "[" {
+                return None;
+            }
+            let blocks: Vec<I3barBlock> = serde_json::from_str(line).ok()?;
+            let text = blocks
+                .iter()
+                .map(|block| block.full_text.trim())
+                .filter(|text| !text.is_empty())
+                .collect::<Vec<_>>()
+                .join("  ");
+            Some(Status {
+                text,
+                color: blocks
+                    .iter()
+                    .find_map(|block| block.color.as_deref())
+                    .and_then(|value| color::parse(value).ok()),
+                urgent: blocks.iter().any(|block| block.urgent),
+                ..Status::default()
+            })
+        }
+    }
+}
+
+fn parse_json(output: &str) -> Option<Status> {
+    let json: WaybarJson = serde_json::from_str(output.trim()).ok()?;
+    if json.text.trim().is_empty() {
+        return None;
+    }
+    Some(Status {
+        text: json.text.trim().to_string(),
+        tooltip: json.tooltip.filter(|tooltip| !tooltip.is_empty()),
+        percentage: json.percentage,
+        alt: json.alt.unwrap_or_default(),
+        ..Status::default()
+    })
+}
+
+/// The icon for a percentage, spreading `icons` evenly over 0-100.
+pub fn icon(icons: &[String], percentage: Option<f64>) -> &str {
+    let Some(last) = icons.len().checked_sub(1) else {
+        return "";
+    };
+    let percentage = percentage.unwrap_or(0.0).clamp(0.0, 100.0);
+    let index = (percentage / 100.0 * icons.len() as f64) as usize;
+    &icons[index.min(last)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_output_has_tooltip_on_second_line() {
+        let status = parse_run("  42%  \nDisk usage\n", ReturnType::Text).unwrap();
+        assert_eq!(status.text, "42%");
+        assert_eq!(status.tooltip.as_deref(), Some("Disk usage"));
+        assert_eq!(parse_run("\n", ReturnType::Text), None);
+    }
+
+    #[test]
+    fn json_output_may_span_lines() {
+        let output = "{\n  \"text\": \"up\",\n  \"percentage\": 75,\n  \"alt\": \"wifi\"\n}\n";
+        let status = parse_run(output, ReturnType::Json).unwrap();
+        assert_eq!(status.text, "up");
+        assert_eq!(status.percentage, Some(75.0));
+        assert_eq!(status.alt, "wifi");
+        assert_eq!(parse_run("{\"text\": \"\"}", ReturnType::Json), None);
+    }
+
+    #[test]
+    fn i3bar_stream_takes_last_status() {
+        let output = "{\"version\": 1}\n[\n[{\"full_text\": \"a\"}]\n,[{\"full_text\": \"b\", \"color\": \"#ff0000\"}, {\"full_text\": \"c\", \"urgent\": true}]\n";
+        let status = parse_run(output, ReturnType::I3bar).unwrap();
+        assert_eq!(status.text, "b  c");
+        assert_eq!(status.color, Some(Color::from_rgb8(0xff, 0, 0)));
+        assert!(status.urgent);
+        assert_eq!(parse_line("{\"version\": 1}", ReturnType::I3bar), None);
+        assert_eq!(parse_line("[", ReturnType::I3bar), None);
+        assert_eq!(
+            parse_line("[[{\"full_text\": \"first\"}],", ReturnType::I3bar)
+                .map(|status| status.text),
+            Some("first".to_string())
+        );
+    }
+
+    #[test]
+    fn icons_spread_over_percentage() {
+        let icons: Vec<String> = ["low", "mid", "high"].map(String::from).to_vec();
+        assert_eq!(icon(&icons, Some(0.0)), "low");
+        assert_eq!(icon(&icons, Some(50.0)), "mid");
+        assert_eq!(icon(&icons, Some(100.0)), "high");
+        assert_eq!(icon(&icons, Some(250.0)), "high");
+        assert_eq!(icon(&[], Some(50.0)), "");
+    }
+}
@@ -0,0 +1,240 @@
+//! Swap usage widget.
+//!
+//! This component provides:
+//! - Swap utilization in percent, colored by configurable thresholds
+//! - Per-device usage in a tooltip, with the compression ratio of zram
+//!   devices
+//!
+//! Hidden when no swap is configured.
+
+use std::fmt::Write;
+use std::fs;
+use std::time::Duration;
+
+use iced::widget::{container, text};
+use iced::{Element, Length, Subscription, Task, time};
+
+use super::tray_widget::with_tooltip;
+use crate::config::{Config, SwapConfig};
+use crate::format::NumberFormat;
+use crate::layout::ModuleKind;
+use crate::modules::{Action, BarState, Context, Lookup, Module};
+use crate::theme::get_theme;
+
+/// Seconds between swap polls.
+const POLL_INTERVAL_SECS: u64 = 5;
+
+/// Seconds between swap polls while the pointer is over the widget.
+const HOVER_INTERVAL_SECS: u64 = 1;
+
+/// One active swap device or file from `/proc/swaps`.
+#[derive(Debug, Clone)]
+struct SwapDevice {
+    name: String,
+    size_kib: u64,
+    used_kib: u64,
+    /// Uncompressed / compressed size, for zram devices holding data
+    compression_ratio: Option<f32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Swap {
+    config: SwapConfig,
+    /// `[format]` defaults with the swap overrides applied
+    format: NumberFormat,
+    /// `None` when no swap is configured
+    percent: Option<u8>,
+    display_text: String,
+    tooltip_text: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Tick,
+}
+
+impl Swap {
+    pub fn new(config: SwapConfig, format: &NumberFormat) -> Self {
+        let mut swap = Self {
+            format: format.with(&config.format),
+            config,
+            percent: None,
+            display_text: String::new(),
+            tooltip_text: String::new(),
+        };
+        swap.refresh();
+        swap
+    }
+
+    /// Apply a reloaded swap config and format defaults.
+    pub fn set_config(&mut self, config: SwapConfig, format: &NumberFormat) {
+        self.format = format.with(&config.format);
+        self.config = config;
+        self.refresh();
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::Tick => self.refresh(),
+        }
+    }
+
+    fn refresh(&mut self) {
+        let devices = read_swaps();
+        let size: u64 = devices.iter().map(|d| d.size_kib).sum();
+        let used: u64 = devices.iter().map(|d| d.used_kib).sum();
+
+        if size == 0 {
+            self.percent = None;
+            return;
+        }
+        let percent = (used * 100 / size).min(100) as u8;
+        self.percent = Some(percent);
+
+        let exact_percent = (used as f64 * 100.0 / size as f64).min(100.0);
+        self.display_text.clear();
+        let _ = write!(
+            &mut self.display_text,
+            "󰓡 {}",
+            self.format.percent(exact_percent)
+        );
+
+        let kib = |kib: u64| self.format.bytes(kib * 1024);
+        self.tooltip_text.clear();
+        let _ = write!(
+            &mut self.tooltip_text,
+            "Swap: {} / {}",
+            kib(used),
+            kib(size)
+        );
+        for device in &devices {
+            let _ = write!(
+                &mut self.tooltip_text,
+                "\n{}: {} / {}",
+                device.name,
+                kib(device.used_kib),
+                kib(device.size_kib)
+            );
+            if let Some(ratio) = device.compression_ratio {
+                let _ = write!(&mut self.tooltip_text, " ({:.1}x)", ratio);
+            }
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let Some(percent) = self.percent else {
+            return container(text("")).into();
+        };
+
+        let theme = get_theme();
+        let color = if percent >= self.config.critical_percent {
+            theme.danger()
+        } else if percent >= self.config.warning_percent {
+            theme.warning()
+        } else {
+            theme.text()
+        };
+        let content = container(
+            text(self.display_text.as_str())
+                .font(theme.font())
+                .size(theme.font_size())
+                .color(color),
+        )
+        .center_y(Length::Fill)
+        .padding([0.0, theme.tray_widget_padding()]);
+
+        with_tooltip(content.into(), &self.tooltip_text)
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        time::every(Duration::from_secs(POLL_INTERVAL_SECS)).map(|_| Message::Tick)
+    }
+
+    /// Faster polling while the pointer is over the widget, so the
+    /// per-device usage in the tooltip stays current.
+    pub fn hover_subscription(&self) -> Subscription<Message> {
+        time::every(Duration::from_secs(HOVER_INTERVAL_SECS)).map(|_| Message::Tick)
+    }
+}
+
+impl Module for Swap {
+    type Message = Message;
+
+    fn show(&self, _output: &str) -> Element<'_, Message> {
+        self.view()
+    }
+
+    fn handle(
+        &mut self,
+        message: Message,
+        _context: &Context<'_>,
+        _actions: &mut Vec<Action>,
+    ) -> Task<Message> {
+        self.update(message);
+        Task::none()
+    }
+
+    fn watch(&self, bar: &BarState<'_>) -> Subscription<Message> {
+        if bar.visible && bar.config.shows(&ModuleKind::Swap) {
+            self.subscription()
+        } else {
+            Subscription::none()
+        }
+    }
+
+    fn has_live_tooltip(&self) -> bool {
+        true
+    }
+
+    fn watch_hovered(&self) -> Subscription<Message> {
+        self.hover_subscription()
+    }
+
+    fn reconfigure(&mut self, config: &Config) {
+        self.set_config(config.swap.clone(), &config.format);
+    }
+
+    fn first_lookup(&self) -> Option<(Lookup, Message)> {
+        Some((Lookup::Display, Message::Tick))
+    }
+}
+
+/// Read active swap devices from `/proc/swaps` (sizes in KiB).
+fn read_swaps() -> Vec<SwapDevice> {
+    let Ok(content) = fs::read_to_string("/proc/swaps") else {
+        return Vec::new();
+    };
+
+    content
+        .lines()
+        .skip(1) // Header
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let path = fields.next()?;
+            let _kind = fields.next()?;
+            let size_kib = fields.next()?.parse().ok()?;
+            let used_kib = fields.next()?.parse().ok()?;
+            let name = path.rsplit('/').next().unwrap_or(path).to_string();
+            let compression_ratio = name
+                .starts_with("zram")
+                .then(|| zram_compression_ratio(&name))
+                .flatten();
+            Some(SwapDevice {
+                name,
+                size_kib,
+                used_kib,
+                compression_ratio,
+            })
+        })
+        .collect()
+}
+
+/// Compression ratio of a zram device from its `mm_stat`
+/// (`orig_data_size compr_data_size ...`).
+fn zram_compression_ratio(name: &str) -> Option<f32> {
+    let stat = fs::read_to_string(format!("/sys/block/{}/mm_stat", name)).ok()?;
+    let mut fields = stat.split_whitespace();
+    let orig: f32 = fields.next()?.parse().ok()?;
+    let compressed: f32 = fields.next()?.parse().ok()?;
+    (compressed > 0.0).then(|| orig / compressed)
+}
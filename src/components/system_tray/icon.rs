@@ -14,6 +14,13 @@ use system_tray::item::{IconPixmap, StatusNotifierItem};
 /// Default icon size for the tray (in pixels).
 pub const ICON_SIZE: u16 = 22;
 
+/// Largest side kept of pixmap icons: [`ICON_SIZE`] on outputs scaled up
+/// to 2x. Larger pixmaps (some apps send 256x256) are scaled down.
+const MAX_PIXMAP_SIZE: usize = ICON_SIZE as usize * 2;
+
+/// Largest PNG menu icon kept; bigger ones are left out.
+const MAX_PNG_BYTES: usize = 64 * 1024;
+
 /// Cache for icon path lookups to avoid repeated filesystem checks.
 /// Key: (theme_path, icon_name), Value: resolved path or None
 static ICON_CACHE: RwLock<Option<HashMap<(String, String), Option<PathBuf>>>> = RwLock::new(None);
@@ -41,18 +48,41 @@ fn get_or_init_cache() -> &'static RwLock<Option<HashMap<(String, String), Optio
 /// 2. Icon name with custom theme path (cached)
 /// 3. Icon name via freedesktop lookup
 pub fn resolve_icon(item: &StatusNotifierItem) -> Option<image::Handle> {
+    resolve(
+        item.icon_pixmap.as_deref(),
+        item.icon_name.as_deref(),
+        item.icon_theme_path.as_deref(),
+    )
+}
+
+/// Resolve the icon of an SNI item's tooltip, in the same order as
+/// [`resolve_icon`].
+pub fn resolve_tooltip_icon(item: &StatusNotifierItem) -> Option<image::Handle> {
+    let tooltip = item.tool_tip.as_ref()?;
+    resolve(
+        Some(&tooltip.icon_data),
+        Some(&tooltip.icon_name),
+        item.icon_theme_path.as_deref(),
+    )
+}
+
+fn resolve(
+    pixmaps: Option<&[IconPixmap]>,
+    icon_name: Option<&str>,
+    theme_path: Option<&str>,
+) -> Option<image::Handle> {
     // Priority 1: Try icon pixmap (raw ARGB32 data)
-    if let Some(pixmaps) = &item.icon_pixmap {
+    if let Some(pixmaps) = pixmaps {
         if let Some(handle) = pixmap_to_handle(pixmaps) {
             return Some(handle);
         }
     }
 
     // Priority 2 & 3: Try icon name
-    if let Some(icon_name) = &item.icon_name {
+    if let Some(icon_name) = icon_name {
         if !icon_name.is_empty() {
             // Check custom theme path first
-            if let Some(theme_path) = &item.icon_theme_path {
+            if let Some(theme_path) = theme_path {
                 if !theme_path.is_empty() {
                     if let Some(path) = find_icon_in_path_cached(theme_path, icon_name) {
                         return Some(image::Handle::from_path(path));
@@ -70,26 +100,78 @@ pub fn resolve_icon(item: &StatusNotifierItem) -> Option<image::Handle> {
     None
 }
 
+/// Resolve the icon of a DBusMenu item: its PNG data, else its name via
+/// freedesktop lookup.
+pub fn resolve_menu_icon(png: Option<&[u8]>, icon_name: Option<&str>) -> Option<image::Handle> {
+    if let Some(png) = png.filter(|png| !png.is_empty() && png.len() <= MAX_PNG_BYTES) {
+        return Some(image::Handle::from_bytes(png.to_vec()));
+    }
+    icon_name
+        .filter(|name| !name.is_empty())
+        .and_then(lookup_freedesktop_icon)
+        .map(image::Handle::from_path)
+}
+
 /// Convert SNI ARGB32 pixmap data to an Iced RGBA image handle.
 fn pixmap_to_handle(pixmaps: &[IconPixmap]) -> Option<image::Handle> {
-    // Find the best size (closest to ICON_SIZE)
+    // Find the best size (closest to the largest size kept)
     let pixmap = pixmaps
         .iter()
         .filter(|p| p.width > 0 && p.height > 0)
-        .min_by_key(|p| (p.width - ICON_SIZE as i32).abs())?;
+        .min_by_key(|p| (p.width - MAX_PIXMAP_SIZE as i32).abs())?;
 
     if pixmap.pixels.is_empty() {
         return None;
     }
 
     // Convert ARGB32 (network byte order) to RGBA
-    let rgba = argb32_to_rgba(&pixmap.pixels, pixmap.width as usize, pixmap.height as usize);
+    let (width, height) = (pixmap.width as usize, pixmap.height as usize);
+    let rgba = argb32_to_rgba(&pixmap.pixels, width, height);
+    let (rgba, width, height) = downscale(rgba, width, height, MAX_PIXMAP_SIZE);
 
-    Some(image::Handle::from_rgba(
-        pixmap.width as u32,
-        pixmap.height as u32,
-        rgba,
-    ))
+    Some(image::Handle::from_rgba(width as u32, height as u32, rgba))
+}
+
+/// Scale RGBA pixels down so neither side exceeds `max`, keeping the aspect
+/// ratio. Each pixel averages the pixels it covers, weighted by alpha so
+/// transparent pixels do not darken the edges.
+fn downscale(rgba: Vec<u8>, width: usize, height: usize, max: usize) -> (Vec<u8>, usize, usize) {
+    if width <= max && height <= max {
+        return (rgba, width, height);
+    }
+    let longest = width.max(height);
+    let new_width = (width * max / longest).max(1);
+    let new_height = (height * max / longest).max(1);
+
+    // Source pixels covered by pixel `index` of `new` along a side of `old`
+    let span = |index: usize, new: usize, old: usize| {
+        let start = index * old / new;
+        start..((index + 1) * old / new).max(start + 1)
+    };
+    let mut scaled = Vec::with_capacity(new_width * new_height * 4);
+    for y in 0..new_height {
+        let rows = span(y, new_height, height);
+        for x in 0..new_width {
+            let columns = span(x, new_width, width);
+            let count = (rows.len() * columns.len()) as u64;
+            let mut sums = [0u64; 4];
+            for source_y in rows.clone() {
+                for source_x in columns.clone() {
+                    let pixel = &rgba[(source_y * width + source_x) * 4..][..4];
+                    let alpha = u64::from(pixel[3]);
+                    for channel in 0..3 {
+                        sums[channel] += u64::from(pixel[channel]) * alpha;
+                    }
+                    sums[3] += alpha;
+                }
+            }
+            for channel in 0..3 {
+                scaled.push(sums[channel].checked_div(sums[3]).unwrap_or(0) as u8);
+            }
+            scaled.push((sums[3] / count) as u8);
+        }
+    }
+    (scaled, new_width, new_height)
 }
 
 /// Convert ARGB32 (big-endian/network byte order) to RGBA.
@@ -132,7 +214,7 @@ fn lookup_freedesktop_icon(_name: &str) -> Option<PathBuf> {
 }
 
 /// Find an icon in a custom theme path with caching.
-fn find_icon_in_path_cached(theme_path: &str, icon_name: &str) -> Option<PathBuf> {
+pub fn find_icon_in_path_cached(theme_path: &str, icon_name: &str) -> Option<PathBuf> {
     let cache = get_or_init_cache();
     let key = (theme_path.to_string(), icon_name.to_string());
 
@@ -199,3 +281,37 @@ fn find_icon_in_path(theme_path: &str, icon_name: &str) -> Option<PathBuf> {
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_argb_to_rgba() {
+        // Opaque red, half-transparent green
+        let argb = [0xff, 0xff, 0x00, 0x00, 0x80, 0x00, 0xff, 0x00];
+        assert_eq!(
+            argb32_to_rgba(&argb, 2, 1),
+            [0xff, 0x00, 0x00, 0xff, 0x00, 0xff, 0x00, 0x80]
+        );
+    }
+
+    #[test]
+    fn downscales_large_pixmaps() {
+        // 4x2: left half opaque white, right half transparent
+        let mut rgba = Vec::new();
+        for _ in 0..2 {
+            rgba.extend([[0xff; 4], [0xff; 4], [0; 4], [0; 4]].concat());
+        }
+        let (scaled, width, height) = downscale(rgba.clone(), 4, 2, 2);
+        assert_eq!((width, height), (2, 1));
+        assert_eq!(scaled, [0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0]);
+
+        assert_eq!(downscale(rgba.clone(), 4, 2, 4), (rgba, 4, 2));
+    }
+
+    #[test]
+    fn short_pixel_data_is_transparent() {
+        assert_eq!(argb32_to_rgba(&[0xff; 4], 2, 1), [0; 8]);
+    }
+}
@@ -2,10 +2,12 @@
 //!
 //! Converts SNI TrayMenu structures into a simplified format for Iced rendering.
 
-use iced::widget::{button, column, container, row, text, Space};
+use iced::widget::{button, column, container, image, row, text, Space};
 use iced::{Border, Color, Element, Length};
 use system_tray::menu::{MenuItem as SniMenuItem, MenuType, ToggleState, TrayMenu};
 
+use super::icon;
+
 /// Maximum menu nesting depth to prevent stack overflow and memory exhaustion
 const MAX_MENU_DEPTH: usize = 5;
 
@@ -22,8 +24,14 @@ pub struct MenuItem {
     pub is_separator: bool,
     /// Whether this item can be checked
     pub is_checkable: bool,
+    /// Whether checking this item unchecks the others of its group
+    pub is_radio: bool,
     /// Whether this item is currently checked
     pub is_checked: bool,
+    /// Icon shown in front of the label
+    pub icon: Option<image::Handle>,
+    /// Keyboard shortcut the application binds, like `Ctrl+S`
+    pub shortcut: Option<String>,
     /// Nested submenu items
     pub submenu: Vec<MenuItem>,
 }
@@ -36,11 +44,13 @@ pub fn convert_menu(menu: &TrayMenu) -> Vec<MenuItem> {
 /// Convert a single SNI menu item to our simplified format.
 fn convert_menu_item(item: &SniMenuItem, depth: usize) -> MenuItem {
     let is_separator = matches!(item.menu_type, MenuType::Separator);
-    let is_checked = matches!(item.toggle_state, ToggleState::On);
     let is_checkable = !matches!(
         item.toggle_type,
         system_tray::menu::ToggleType::CannotBeToggled
     );
+    // Items that cannot be toggled report a default toggle state of `On`
+    let is_checked = is_checkable && matches!(item.toggle_state, ToggleState::On);
+    let is_radio = matches!(item.toggle_type, system_tray::menu::ToggleType::Radio);
 
     // Clean label: remove underscore access key markers (like _File -> File)
     let label = item
@@ -62,15 +72,58 @@ fn convert_menu_item(item: &SniMenuItem, depth: usize) -> MenuItem {
         enabled: item.enabled,
         is_separator,
         is_checkable,
+        is_radio,
         is_checked,
+        icon: icon::resolve_menu_icon(item.icon_data.as_deref(), item.icon_name.as_deref()),
+        shortcut: item.shortcut.as_deref().and_then(shortcut_label),
         submenu,
     }
 }
 
-/// Calculate the estimated height of the menu based on items and font size.
+impl MenuItem {
+    /// Check box or radio button in front of a checkable label.
+    pub fn toggle_glyph(&self) -> Option<&'static str> {
+        match (self.is_checkable, self.is_radio, self.is_checked) {
+            (false, _, _) => None,
+            (true, true, true) => Some("◉"),
+            (true, true, false) => Some("○"),
+            (true, false, true) => Some("☑"),
+            (true, false, false) => Some("☐"),
+        }
+    }
+
+    /// Whether this is a radio item another item of its group is chosen
+    /// over, drawn greyed out.
+    pub fn is_alternative(&self) -> bool {
+        self.is_radio && !self.is_checked
+    }
+}
+
+/// Readable form of a DBusMenu shortcut: key presses joined with `, `,
+/// each as modifiers and key joined with `+`.
+fn shortcut_label(presses: &[Vec<String>]) -> Option<String> {
+    let presses: Vec<String> = presses
+        .iter()
+        .filter(|keys| !keys.is_empty())
+        .map(|keys| {
+            keys.iter()
+                .map(|key| match key.as_str() {
+                    "Control" => "Ctrl",
+                    key => key,
+                })
+                .collect::<Vec<_>>()
+                .join("+")
+        })
+        .collect();
+    (!presses.is_empty()).then(|| presses.join(", "))
+}
+
+/// Calculate the estimated height of the menu based on items and font size,
+/// with every submenu expanded.
 pub fn calculate_height(items: &[MenuItem], font_size: f32) -> f32 {
     let mut height = 0.0;
     for item in items {
+        height += calculate_height(&item.submenu, font_size);
         if item.is_separator {
             // 1px height + 4px top padding + 4px bottom padding
             height += 9.0;
@@ -83,6 +136,48 @@ pub fn calculate_height(items: &[MenuItem], font_size: f32) -> f32 {
     height
 }
 
+/// IDs of the items keyboard navigation stops at, top to bottom: enabled
+/// items with a label, and those of the submenus `expanded` says are open.
+pub fn navigable_ids(items: &[MenuItem], expanded: &impl Fn(i32) -> bool) -> Vec<i32> {
+    let mut ids = Vec::new();
+    for item in items {
+        if item.is_separator || item.label.is_empty() {
+            continue;
+        }
+        if item.enabled {
+            ids.push(item.id);
+        }
+        if !item.submenu.is_empty() && expanded(item.id) {
+            ids.extend(navigable_ids(&item.submenu, expanded));
+        }
+    }
+    ids
+}
+
+/// The ID after `current` in `ids` (before it when going back), wrapping
+/// around; the first (or last) one without a current item.
+pub fn step(ids: &[i32], current: Option<i32>, forward: bool) -> Option<i32> {
+    let position = current.and_then(|current| ids.iter().position(|id| *id == current));
+    let next = match (position, forward) {
+        (None, true) => 0,
+        (None, false) => ids.len().checked_sub(1)?,
+        (Some(position), true) => (position + 1) % ids.len(),
+        (Some(position), false) => (position + ids.len() - 1) % ids.len(),
+    };
+    ids.get(next).copied()
+}
+
+/// The item with an ID, looking into submenus.
+pub fn find(items: &[MenuItem], id: i32) -> Option<&MenuItem> {
+    items.iter().find_map(|item| {
+        if item.id == id {
+            Some(item)
+        } else {
+            find(&item.submenu, id)
+        }
+    })
+}
+
 /// Message type for menu interactions.
 #[derive(Debug, Clone)]
 pub enum MenuMessage {
@@ -148,12 +243,9 @@ where
             .into();
     }
 
-    let check_mark: Element<'_, M> = if item.is_checkable {
-        text(if item.is_checked { "" } else { "  " })
-            .size(12)
-            .into()
-    } else {
-        Space::new(0, 0).into()
+    let check_mark: Element<'_, M> = match item.toggle_glyph() {
+        Some(glyph) => text(glyph).size(12).into(),
+        None => Space::new(0, 0).into(),
     };
 
     let content = row![check_mark, text(&item.label).size(13),]
@@ -197,3 +289,169 @@ fn menu_item_style(status: button::Status, enabled: bool) -> button::Style {
         shadow: Default::default(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use system_tray::menu::ToggleType;
+
+    fn sni_item(id: i32, label: &str) -> SniMenuItem {
+        SniMenuItem {
+            id,
+            label: Some(label.to_string()),
+            enabled: true,
+            visible: true,
+            ..SniMenuItem::default()
+        }
+    }
+
+    /// One line per item, indented by depth, for comparing whole menus.
+    fn outline(items: &[MenuItem], depth: usize, lines: &mut Vec<String>) {
+        for item in items {
+            let kind = if item.is_separator {
+                "separator"
+            } else if item.is_radio {
+                if item.is_checked { "chosen" } else { "alternative" }
+            } else if item.is_checked {
+                "checked"
+            } else if item.is_checkable {
+                "unchecked"
+            } else {
+                "plain"
+            };
+            let state = if item.enabled { "" } else { " disabled" };
+            lines.push(format!(
+                "{}{} {:?} {}{}",
+                "  ".repeat(depth),
+                item.id,
+                item.label,
+                kind,
+                state
+            ));
+            outline(&item.submenu, depth + 1, lines);
+        }
+    }
+
+    #[test]
+    fn converts_menu() {
+        let mut recent = sni_item(2, "_Recent");
+        recent.submenu = vec![sni_item(3, "notes.txt")];
+        let mut wrap = sni_item(5, "_Wrap lines");
+        wrap.toggle_type = ToggleType::Checkmark;
+        wrap.toggle_state = ToggleState::On;
+        let mut quit = sni_item(6, "_Quit");
+        quit.enabled = false;
+        let mut zoom = sni_item(7, "Zoom");
+        zoom.submenu = [(8, "100%", ToggleState::On), (9, "200%", ToggleState::Off)]
+            .into_iter()
+            .map(|(id, label, state)| SniMenuItem {
+                toggle_type: ToggleType::Radio,
+                toggle_state: state,
+                ..sni_item(id, label)
+            })
+            .collect();
+        let menu = TrayMenu {
+            id: 0,
+            submenus: vec![
+                sni_item(1, "_Open"),
+                recent,
+                SniMenuItem {
+                    id: 4,
+                    menu_type: MenuType::Separator,
+                    ..SniMenuItem::default()
+                },
+                wrap,
+                quit,
+                zoom,
+            ],
+        };
+
+        let mut lines = Vec::new();
+        outline(&convert_menu(&menu), 0, &mut lines);
+        assert_eq!(
+            lines,
+            [
+                "1 \"Open\" plain",
+                "2 \"Recent\" plain",
+                "  3 \"notes.txt\" plain",
+                "4 \"\" separator disabled",
+                "5 \"Wrap lines\" checked",
+                "6 \"Quit\" plain disabled",
+                "7 \"Zoom\" plain",
+                "  8 \"100%\" chosen",
+                "  9 \"200%\" alternative",
+            ]
+        );
+    }
+
+    #[test]
+    fn converts_shortcuts() {
+        let mut save = sni_item(1, "_Save");
+        save.shortcut = Some(vec![vec!["Control".to_string(), "S".to_string()]]);
+        let mut quit = sni_item(2, "_Quit");
+        quit.shortcut = Some(vec![
+            vec!["Control".to_string(), "Q".to_string()],
+            vec!["Alt".to_string(), "X".to_string()],
+        ]);
+        let mut other = sni_item(3, "Other");
+        other.shortcut = Some(vec![vec![]]);
+        let menu = TrayMenu {
+            id: 0,
+            submenus: vec![save, quit, other],
+        };
+
+        let shortcuts: Vec<_> = convert_menu(&menu)
+            .into_iter()
+            .map(|item| item.shortcut)
+            .collect();
+        assert_eq!(
+            shortcuts,
+            [
+                Some("Ctrl+S".to_string()),
+                Some("Ctrl+Q, Alt+X".to_string()),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn navigates_open_items() {
+        let mut recent = sni_item(2, "_Recent");
+        recent.submenu = vec![sni_item(3, "notes.txt")];
+        let mut quit = sni_item(5, "_Quit");
+        quit.enabled = false;
+        let menu = TrayMenu {
+            id: 0,
+            submenus: vec![sni_item(1, "_Open"), recent, sni_item(4, ""), quit],
+        };
+        let items = convert_menu(&menu);
+
+        assert_eq!(navigable_ids(&items, &|_| false), [1, 2]);
+        let ids = navigable_ids(&items, &|id| id == 2);
+        assert_eq!(ids, [1, 2, 3]);
+        assert_eq!(step(&ids, None, true), Some(1));
+        assert_eq!(step(&ids, None, false), Some(3));
+        assert_eq!(step(&ids, Some(3), true), Some(1));
+        assert_eq!(step(&ids, Some(1), false), Some(3));
+        assert_eq!(step(&[], None, false), None);
+        assert_eq!(find(&items, 3).map(|item| item.label.as_str()), Some("notes.txt"));
+    }
+
+    #[test]
+    fn truncates_deep_menus() {
+        let mut item = sni_item(0, "leaf");
+        for id in 1..=MAX_MENU_DEPTH as i32 + 2 {
+            let mut parent = sni_item(id, "level");
+            parent.submenu = vec![item];
+            item = parent;
+        }
+        let menu = TrayMenu {
+            id: 0,
+            submenus: vec![item],
+        };
+
+        let mut lines = Vec::new();
+        outline(&convert_menu(&menu), 0, &mut lines);
+        assert_eq!(lines.len(), MAX_MENU_DEPTH + 1);
+    }
+}
@@ -5,8 +5,8 @@
 //! - Right-click context menus
 //! - Custom status indicator API
 
-mod icon;
+pub mod icon;
 pub mod menu;
 mod tray;
 
-pub use tray::{Message, SystemTray};
+pub use tray::{SystemTray, icon_id};
@@ -2,41 +2,131 @@
 //!
 //! Implements the StatusNotifierItem (SNI) protocol host for displaying
 //! application tray icons and handling their interactions.
+//!
+//! No separate StatusNotifierWatcher is needed: the `system-tray` client
+//! serves its own (over zbus) and queues for the
+//! `org.kde.StatusNotifierWatcher` name, so it takes over whenever no other
+//! bar or daemon owns it, also when that one exits later. A client that
+//! cannot be created or stops is retried with backoff.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Duration;
 
+use iced::futures::channel::mpsc::Sender;
 use iced::futures::SinkExt;
 use iced::stream;
-use iced::widget::{button, container, image, text, tooltip, Row};
-use iced::{Border, Color, Element, Length, Subscription, Task};
-use std::future;
-use system_tray::client::ActivateRequest;
+use iced::widget::{button, column, container, image, row, text, Row};
+use iced::{font, Border, Color, Element, Font, Length, Subscription, Task};
+use system_tray::client::{ActivateRequest, Client, Event, UpdateEvent};
+use system_tray::item::StatusNotifierItem;
+use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::mpsc;
 
 use super::icon::{self, ICON_SIZE};
 use super::menu::{self, MenuItem};
+use crate::components::tray_widget::{with_tooltip, with_tooltip_content};
+use crate::config::{Config, SystemTrayConfig, TrayIndicatorConfig, expand_home};
+use crate::diagnostics;
+use crate::exec::Exec;
+use crate::layout::ModuleKind;
+use crate::modules::{Action, BarState, Context, MenuOwner, Module};
 use crate::theme::get_theme;
 
 // ============================================================================
 // Types
 // ============================================================================
 
+/// Icons per row of the overflow popup.
+const OVERFLOW_COLUMNS: usize = 4;
+
+/// First and longest delay before creating the tray client again.
+const RETRY_MIN: Duration = Duration::from_secs(1);
+const RETRY_MAX: Duration = Duration::from_secs(60);
+
+type TraySender = Sender<Message>;
+
 /// Internal representation of a tray item's state.
 #[derive(Debug, Clone)]
 struct TrayItemState {
     /// Unique identifier (D-Bus address)
     address: String,
+    /// Application-chosen id, stable across restarts
+    id: String,
     /// Human-readable title
     title: Option<String>,
     /// Cached icon handle for rendering
     icon_handle: Option<image::Handle>,
+    /// Tooltip the item provides, shown instead of the title
+    tooltip: Option<ItemTooltip>,
+    /// Object path of the item's DBusMenu
+    menu_path: Option<String>,
     /// Associated menu items
     menu_items: Vec<MenuItem>,
     /// Whether item only supports menu (no primary action)
     item_is_menu: bool,
 }
 
+/// The SNI `ToolTip` of an item: an icon, a title and a longer text.
+#[derive(Debug, Clone)]
+pub struct ItemTooltip {
+    icon: Option<image::Handle>,
+    title: String,
+    /// Plain text; the markup some items use is stripped
+    description: String,
+}
+
+impl ItemTooltip {
+    /// The tooltip of an item, if it has one with any text.
+    fn from_item(item: &StatusNotifierItem) -> Option<Self> {
+        let tooltip = item.tool_tip.as_ref()?;
+        let title = plain_text(&tooltip.title);
+        let description = plain_text(&tooltip.description);
+        if title.is_empty() && description.is_empty() {
+            return None;
+        }
+        Some(Self {
+            icon: icon::resolve_tooltip_icon(item),
+            title,
+            description,
+        })
+    }
+}
+
+/// `markup` without tags and with the common entities decoded; line breaks
+/// (`<br>`) become newlines.
+fn plain_text(markup: &str) -> String {
+    let mut plain = String::with_capacity(markup.len());
+    let mut rest = markup;
+    while let Some(start) = rest.find('<') {
+        plain.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('>') else {
+            rest = &rest[start..];
+            break;
+        };
+        let tag = rest[start + 1..start + end].trim().to_ascii_lowercase();
+        if tag.trim_end_matches('/').trim_end() == "br" {
+            plain.push('\n');
+        }
+        rest = &rest[start + end + 1..];
+    }
+    plain.push_str(rest);
+    plain
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+        .trim()
+        .to_string()
+}
+
+/// Widget ID of the icon of an item, to find where it is in the bar.
+pub fn icon_id(address: &str) -> container::Id {
+    container::Id::new(format!("tray-icon-{}", address))
+}
+
 /// Custom status indicator (not from SNI).
 #[derive(Debug, Clone)]
 pub struct CustomIndicator {
@@ -46,6 +136,22 @@ pub struct CustomIndicator {
     pub icon: image::Handle,
     /// Tooltip text
     pub tooltip: String,
+    /// Command run on click
+    pub on_click: Option<String>,
+    /// Whether the indicator is drawn; false while its status command fails
+    pub shown: bool,
+}
+
+impl CustomIndicator {
+    fn from_config(config: &TrayIndicatorConfig) -> Self {
+        Self {
+            id: config.name.clone(),
+            icon: image::Handle::from_path(expand_home(&config.icon)),
+            tooltip: config.tooltip.clone(),
+            on_click: config.on_click.clone(),
+            shown: true,
+        }
+    }
 }
 
 /// The main SystemTray component state.
@@ -58,6 +164,10 @@ pub struct SystemTray {
     open_menu: Option<String>,
     /// Channel sender for activation requests
     activate_tx: Option<mpsc::Sender<ActivateRequest>>,
+    /// Screen position sent with activation requests (the click position)
+    activation_position: (i32, i32),
+    /// Hidden and pinned items
+    config: SystemTrayConfig,
 }
 
 /// Messages that the SystemTray component can handle.
@@ -66,8 +176,11 @@ pub enum Message {
     /// SNI item was added
     ItemAdded {
         address: String,
+        id: String,
         title: Option<String>,
         icon_handle: Option<image::Handle>,
+        tooltip: Option<ItemTooltip>,
+        menu_path: Option<String>,
         item_is_menu: bool,
     },
     /// SNI item was updated
@@ -76,6 +189,11 @@ pub enum Message {
         title: Option<String>,
         icon_handle: Option<image::Handle>,
     },
+    /// SNI item tooltip was set, changed or removed
+    TooltipUpdated {
+        address: String,
+        tooltip: Option<ItemTooltip>,
+    },
     /// SNI item menu was updated
     MenuUpdated {
         address: String,
@@ -85,6 +203,15 @@ pub enum Message {
     ItemRemoved(String),
     /// User left-clicked on a tray icon
     ItemClicked(String),
+    /// User clicked the chevron in front of the items that do not fit
+    OverflowRequested,
+    /// User clicked a custom indicator
+    IndicatorClicked(String),
+    /// Status command of a custom indicator ran: its output, or why it failed
+    IndicatorStatus {
+        id: String,
+        status: Result<String, String>,
+    },
     /// User right-clicked on a tray icon
     ItemRightClicked(String),
     /// User clicked a menu item
@@ -108,6 +235,27 @@ impl Default for SystemTray {
             custom_indicators: Vec::new(),
             open_menu: None,
             activate_tx: None,
+            activation_position: (0, 0),
+            config: SystemTrayConfig::default(),
+        }
+    }
+}
+
+impl TrayItemState {
+    /// Whether `name` from the config is the id or title of this item.
+    fn is_named(&self, name: &str) -> bool {
+        self.id.eq_ignore_ascii_case(name)
+            || self
+                .title
+                .as_deref()
+                .is_some_and(|title| title.eq_ignore_ascii_case(name))
+    }
+
+    /// Title, or id for untitled items, to sort by.
+    fn sort_name(&self) -> String {
+        match self.title.as_deref() {
+            Some(title) if !title.is_empty() => title.to_lowercase(),
+            _ => self.id.to_lowercase(),
         }
     }
 }
@@ -123,14 +271,112 @@ impl std::fmt::Debug for SystemTray {
 }
 
 impl SystemTray {
-    /// Add a custom status indicator to the tray.
-    pub fn add_custom_indicator(&mut self, indicator: CustomIndicator) {
-        self.custom_indicators.push(indicator);
+    pub fn new(config: SystemTrayConfig) -> Self {
+        let mut tray = Self::default();
+        tray.set_config(config);
+        tray
+    }
+
+    /// Apply a reloaded `[tray]` config.
+    pub fn set_config(&mut self, config: SystemTrayConfig) {
+        let previous = std::mem::take(&mut self.custom_indicators);
+        self.custom_indicators = config
+            .indicators
+            .iter()
+            .map(|indicator_config| {
+                let mut indicator = CustomIndicator::from_config(indicator_config);
+                // Keep what the status command last reported; it only runs
+                // again after its interval
+                if indicator_config.status.is_some()
+                    && let Some(old) = previous.iter().find(|old| old.id == indicator.id)
+                {
+                    indicator.shown = old.shown;
+                    indicator.tooltip = old.tooltip.clone();
+                }
+                indicator
+            })
+            .collect();
+        self.config = config;
+    }
+
+    /// Items in the order they are drawn, without the hidden ones: the
+    /// pinned ones first, the others by title. Ties fall back to the
+    /// address so the order does not change between renders.
+    fn shown_items(&self) -> Vec<&TrayItemState> {
+        let mut items: Vec<&TrayItemState> = self
+            .items
+            .values()
+            .filter(|item| !self.config.hidden.iter().any(|name| item.is_named(name)))
+            .collect();
+        items.sort_by_cached_key(|item| {
+            let pinned = self
+                .config
+                .pinned
+                .iter()
+                .position(|name| item.is_named(name))
+                .unwrap_or(usize::MAX);
+            (pinned, item.sort_name(), item.address.clone())
+        });
+        items
+    }
+
+    /// Shown items split into those drawn in the bar and those behind the
+    /// overflow chevron.
+    fn split_items(&self) -> (Vec<&TrayItemState>, Vec<&TrayItemState>) {
+        let mut items = self.shown_items();
+        let overflow = match self.config.max_items {
+            Some(max) if items.len() > max => items.split_off(max),
+            _ => Vec::new(),
+        };
+        (items, overflow)
+    }
+
+    /// Size of the overflow popup.
+    pub fn overflow_size(&self) -> (u32, u32) {
+        let count = self.split_items().1.len();
+        let columns = count.clamp(1, OVERFLOW_COLUMNS);
+        let rows = count.div_ceil(OVERFLOW_COLUMNS).max(1);
+        // Icon, button padding and spacing
+        let tile = ICON_SIZE as f32 + 12.0;
+        (
+            (tile * columns as f32 + 16.0) as u32,
+            (tile * rows as f32 + 16.0) as u32,
+        )
+    }
+
+    /// Tray items for `clammy --state`, ordered by address.
+    pub fn state(&self) -> serde_json::Value {
+        let mut items: Vec<&TrayItemState> = self.items.values().collect();
+        items.sort_by(|a, b| a.address.cmp(&b.address));
+        let items: Vec<_> = items
+            .into_iter()
+            .map(|item| {
+                serde_json::json!({
+                    "address": item.address,
+                    "id": item.id,
+                    "title": item.title,
+                    "item_is_menu": item.item_is_menu,
+                })
+            })
+            .collect();
+        let indicators: Vec<_> = self
+            .custom_indicators
+            .iter()
+            .map(|indicator| {
+                serde_json::json!({
+                    "id": indicator.id,
+                    "tooltip": indicator.tooltip,
+                    "shown": indicator.shown,
+                })
+            })
+            .collect();
+        serde_json::json!({ "items": items, "indicators": indicators })
     }
 
-    /// Remove a custom status indicator by ID.
-    pub fn remove_custom_indicator(&mut self, id: &str) {
-        self.custom_indicators.retain(|i| i.id != id);
+    /// Set the screen position passed to items on activation, so they can
+    /// place their windows near the click.
+    pub fn set_activation_position(&mut self, position: (i32, i32)) {
+        self.activation_position = position;
     }
 
     /// Get menu items for a tray item by address.
@@ -138,6 +384,16 @@ impl SystemTray {
         self.items.get(address).map(|item| item.menu_items.clone())
     }
 
+    /// Object path of an item's menu, `/MenuBar` (the common one) when the
+    /// item does not say.
+    fn menu_path(&self, address: &str) -> String {
+        self.items
+            .get(address)
+            .and_then(|item| item.menu_path.clone())
+            .filter(|path| !path.is_empty())
+            .unwrap_or_else(|| "/MenuBar".to_string())
+    }
+
     /// Check if an item has menu items or is menu-only.
     pub fn has_menu(&self, address: &str) -> bool {
         self.items
@@ -156,16 +412,22 @@ impl SystemTray {
 
             Message::ItemAdded {
                 address,
+                id,
                 title,
                 icon_handle,
+                tooltip,
+                menu_path,
                 item_is_menu,
             } => {
                 self.items.insert(
                     address.clone(),
                     TrayItemState {
                         address,
+                        id,
                         title,
                         icon_handle,
+                        tooltip,
+                        menu_path,
                         menu_items: Vec::new(),
                         item_is_menu,
                     },
@@ -189,6 +451,13 @@ impl SystemTray {
                 Task::none()
             }
 
+            Message::TooltipUpdated { address, tooltip } => {
+                if let Some(item) = self.items.get_mut(&address) {
+                    item.tooltip = tooltip;
+                }
+                Task::none()
+            }
+
             Message::MenuUpdated {
                 address,
                 menu_items,
@@ -211,14 +480,11 @@ impl SystemTray {
                 // Send activation request (menu handling is done by main.rs)
                 if let Some(tx) = &self.activate_tx {
                     let tx = tx.clone();
+                    let (x, y) = self.activation_position;
                     Task::perform(
                         async move {
                             let _ = tx
-                                .send(ActivateRequest::Default {
-                                    address,
-                                    x: 0,
-                                    y: 0,
-                                })
+                                .send(ActivateRequest::Default { address, x, y })
                                 .await;
                         },
                         |_| Message::ActivationComplete,
@@ -242,12 +508,13 @@ impl SystemTray {
 
                 if let Some(tx) = &self.activate_tx {
                     let tx = tx.clone();
+                    let menu_path = self.menu_path(&address);
                     Task::perform(
                         async move {
                             let _ = tx
                                 .send(ActivateRequest::MenuItem {
                                     address,
-                                    menu_path: "/MenuBar".to_string(),
+                                    menu_path,
                                     submenu_id: menu_id,
                                 })
                                 .await;
@@ -264,27 +531,62 @@ impl SystemTray {
                 Task::none()
             }
 
-            Message::ActivationComplete => Task::none(),
+            Message::IndicatorClicked(id) => {
+                let command = self
+                    .custom_indicators
+                    .iter()
+                    .find(|indicator| indicator.id == id)
+                    .and_then(|indicator| indicator.on_click.clone());
+                if let Some(command) = command
+                    && let Err(e) = Exec::new(command).launch()
+                {
+                    eprintln!("Failed to run tray indicator command: {}", e);
+                }
+                Task::none()
+            }
+
+            Message::IndicatorStatus { id, status } => {
+                let configured = self
+                    .config
+                    .indicators
+                    .iter()
+                    .find(|config| config.name == id);
+                let indicator = self
+                    .custom_indicators
+                    .iter_mut()
+                    .find(|indicator| indicator.id == id);
+                if let (Some(config), Some(indicator)) = (configured, indicator) {
+                    indicator.shown = status.is_ok();
+                    indicator.tooltip = match status.as_deref().map(|out| out.lines().next()) {
+                        Ok(Some(line)) if !line.trim().is_empty() => line.trim().to_string(),
+                        _ => config.tooltip.clone(),
+                    };
+                }
+                Task::none()
+            }
+
+            Message::OverflowRequested | Message::ActivationComplete => Task::none(),
         }
     }
 
     /// Render the system tray component.
     pub fn view(&self) -> Element<'_, Message> {
+        let (items, overflow) = self.split_items();
         // Pre-allocate a single Vec for all icons
-        let total_items = self.items.len() + self.custom_indicators.len();
+        let total_items = items.len() + self.custom_indicators.len() + 1;
         let mut all_icons = Vec::with_capacity(total_items);
 
         // Add SNI icons
-        all_icons.extend(
-            self.items
-                .values()
-                .map(|item| self.render_tray_item(item))
-        );
+        all_icons.extend(items.into_iter().map(|item| self.render_tray_item(item)));
+        if !overflow.is_empty() {
+            all_icons.push(self.render_overflow_chevron(overflow.len()));
+        }
 
         // Add custom indicators
         all_icons.extend(
             self.custom_indicators
                 .iter()
+                .filter(|ind| ind.shown)
                 .map(|ind| self.render_custom_indicator(ind))
         );
 
@@ -300,6 +602,50 @@ impl SystemTray {
             .into()
     }
 
+    /// Render the overflow popup: a grid of the items that do not fit in
+    /// the bar.
+    pub fn view_overflow(&self) -> Element<'_, Message> {
+        let (_, overflow) = self.split_items();
+        let mut rows = Vec::new();
+        let mut icons = overflow
+            .into_iter()
+            .map(|item| self.render_tray_item(item))
+            .peekable();
+        while icons.peek().is_some() {
+            let icon_row: Vec<_> = icons.by_ref().take(OVERFLOW_COLUMNS).collect();
+            rows.push(Row::from_vec(icon_row).spacing(4).into());
+        }
+
+        container(column(rows).spacing(4))
+            .padding(4)
+            .width(Length::Fill)
+            .into()
+    }
+
+    /// Render the chevron opening the overflow popup.
+    fn render_overflow_chevron(&self, hidden: usize) -> Element<'_, Message> {
+        let theme = get_theme();
+        let hover_bg = theme.hover();
+        let text_color = theme.text();
+        let btn = button(text("▾").size(theme.font_size()))
+            .padding([4, 6])
+            .style(move |_theme, status| button::Style {
+                background: match status {
+                    button::Status::Hovered | button::Status::Pressed => Some(hover_bg.into()),
+                    _ => None,
+                },
+                border: Border {
+                    radius: 4.0.into(),
+                    ..Border::default()
+                },
+                text_color,
+                shadow: Default::default(),
+            })
+            .on_press(Message::OverflowRequested);
+        let tip = text(format!("{} more", hidden)).size(theme.font_size());
+        with_tooltip_content(btn.into(), tip.into())
+    }
+
     /// Render a single tray item.
     fn render_tray_item<'a>(&'a self, item: &'a TrayItemState) -> Element<'a, Message> {
         let icon_size = Length::Fixed(ICON_SIZE as f32);
@@ -354,12 +700,44 @@ impl SystemTray {
                 }
             })
             .on_press(Message::ItemClicked(address));
+        let btn: Element<'_, Message> = container(btn).id(icon_id(&item.address)).into();
 
-        // Wrap with tooltip showing title
-        if let Some(title) = &item.title {
-            tooltip(btn, title.as_str(), tooltip::Position::Bottom).into()
-        } else {
-            btn.into()
+        // Wrap with the item's own tooltip, else one showing the title
+        match &item.tooltip {
+            Some(tip) => with_tooltip_content(btn, Self::render_tooltip(tip)),
+            None => with_tooltip(btn, item.title.as_deref().unwrap_or("")),
+        }
+    }
+
+    /// Render the SNI tooltip of an item: the icon next to a bold title,
+    /// with the description below.
+    fn render_tooltip(tip: &ItemTooltip) -> Element<'_, Message> {
+        let theme = get_theme();
+        let font_size = theme.font_size();
+        let mut lines = column![].spacing(2);
+        if !tip.title.is_empty() {
+            let bold = Font {
+                weight: font::Weight::Bold,
+                ..theme.font()
+            };
+            lines = lines.push(text(tip.title.as_str()).size(font_size).font(bold));
+        }
+        if !tip.description.is_empty() {
+            lines = lines.push(
+                text(tip.description.as_str())
+                    .size(font_size * 0.9)
+                    .color(theme.muted()),
+            );
+        }
+        match &tip.icon {
+            Some(handle) => {
+                let icon_size = Length::Fixed(ICON_SIZE as f32);
+                row![image(handle.clone()).width(icon_size).height(icon_size), lines]
+                    .spacing(8)
+                    .align_y(iced::Alignment::Center)
+                    .into()
+            }
+            None => lines.into(),
         }
     }
 
@@ -373,7 +751,7 @@ impl SystemTray {
             .height(icon_size)
             .into();
 
-        let btn = button(icon_element)
+        let mut btn = button(icon_element)
             .padding(2)
             .style(move |_theme, _status| button::Style {
                 background: None,
@@ -381,30 +759,113 @@ impl SystemTray {
                 text_color,
                 shadow: Default::default(),
             });
+        if indicator.on_click.is_some() {
+            btn = btn.on_press(Message::IndicatorClicked(indicator.id.clone()));
+        }
 
-        tooltip(btn, indicator.tooltip.as_str(), tooltip::Position::Bottom).into()
+        with_tooltip(btn.into(), indicator.tooltip.as_str())
     }
 
-    /// Subscribe to system tray events.
+    /// Subscribe to system tray events and the status commands of custom
+    /// indicators.
     pub fn subscription(&self) -> Subscription<Message> {
-        Subscription::run_with_id("system-tray-events", stream::channel(100, run_tray_client))
+        let events =
+            Subscription::run_with_id("system-tray-events", stream::channel(100, run_tray_client));
+        let statuses = self.config.indicators.iter().filter_map(|indicator| {
+            let command = indicator.status.clone()?;
+            let interval = indicator.interval_secs.max(1);
+            Some(Subscription::run_with_id(
+                ("tray-indicator", indicator.name.clone(), command.clone(), interval),
+                poll_indicator(indicator.name.clone(), command, interval),
+            ))
+        });
+        Subscription::batch(std::iter::once(events).chain(statuses))
     }
 }
 
-/// Run the system tray client and forward events to messages.
-async fn run_tray_client(mut output: iced::futures::channel::mpsc::Sender<Message>) {
-    use system_tray::client::{Client, Event, UpdateEvent};
-
-    // Create the SNI client
-    let client = match Client::new().await {
-        Ok(c) => Arc::new(c),
-        Err(e) => {
-            eprintln!("Failed to create system-tray client: {:?}", e);
-            future::pending::<()>().await;
-            return;
+impl Module for SystemTray {
+    type Message = Message;
+
+    fn show(&self, _output: &str) -> Element<'_, Message> {
+        self.view()
+    }
+
+    fn handle(
+        &mut self,
+        message: Message,
+        context: &Context<'_>,
+        actions: &mut Vec<Action>,
+    ) -> Task<Message> {
+        match &message {
+            Message::OverflowRequested => actions.push(Action::ToggleTrayOverflow),
+            // Clicking an item in the overflow popup closes it, and opens
+            // the item's menu if it has one
+            Message::ItemClicked(address) => {
+                actions.push(Action::CloseTrayOverflow);
+                self.set_activation_position(context.click_position);
+                let items = self.get_menu_items(address);
+                if let Some(items) = items.filter(|items| !items.is_empty()) {
+                    actions.push(Action::OpenMenu {
+                        owner: MenuOwner::Tray(address.clone()),
+                        items,
+                    });
+                    return Task::none();
+                }
+            }
+            _ => {}
         }
-    };
+        self.update(message)
+    }
+
+    // The status notifier host only runs while the tray is in the layout
+    fn watch(&self, bar: &BarState<'_>) -> Subscription<Message> {
+        if bar.backends_started && bar.config.shows(&ModuleKind::SystemTray) {
+            self.subscription()
+        } else {
+            Subscription::none()
+        }
+    }
+
+    fn reconfigure(&mut self, config: &Config) {
+        self.set_config(config.system_tray.clone());
+    }
+
+    fn menu_message(&self, owner: &MenuOwner, menu_id: i32) -> Option<Message> {
+        match owner {
+            MenuOwner::Tray(address) => Some(Message::MenuItemClicked {
+                address: address.clone(),
+                menu_id,
+            }),
+            _ => None,
+        }
+    }
+}
 
+/// Run the system tray client and forward events to messages. The client
+/// is created again, with growing delays, when it cannot be created (no
+/// session bus yet) or its events stop.
+async fn run_tray_client(mut output: TraySender) {
+    let mut delay = RETRY_MIN;
+    loop {
+        match Client::new().await {
+            Ok(client) => {
+                delay = RETRY_MIN;
+                run_session(Arc::new(client), &mut output).await;
+                eprintln!("System tray client stopped, reconnecting");
+            }
+            Err(e) => {
+                eprintln!("Failed to create system-tray client: {:?}", e);
+                diagnostics::error(&format!("system-tray client: {:?}", e));
+            }
+        }
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(RETRY_MAX);
+    }
+}
+
+/// Forward the items and events of one client until its events stop, then
+/// remove its items again.
+async fn run_session(client: Arc<Client>, output: &mut TraySender) {
     // Create channel for activation requests
     let (activate_tx, mut activate_rx) = mpsc::channel::<ActivateRequest>(32);
 
@@ -425,25 +886,19 @@ async fn run_tray_client(mut output: iced::futures::channel::mpsc::Sender<Messag
             .iter()
             .map(|(addr, (item, menu))| {
                 (
+                    item_added(addr.clone(), item),
                     addr.clone(),
-                    item.title.clone(),
-                    icon::resolve_icon(item),
-                    item.item_is_menu,
-                    menu.as_ref().map(|m| menu::convert_menu(m)),
+                    menu.as_ref().map(menu::convert_menu),
                 )
             })
             .collect()
     };
 
-    for (address, title, icon_handle, item_is_menu, menu_items_opt) in initial_items_data {
-        let _ = output
-            .send(Message::ItemAdded {
-                address: address.clone(),
-                title,
-                icon_handle,
-                item_is_menu,
-            })
-            .await;
+    // Items announced to the component, removed again when the session ends
+    let mut addresses = HashSet::new();
+    for (added, address, menu_items_opt) in initial_items_data {
+        addresses.insert(address.clone());
+        let _ = output.send(added).await;
 
         // If there's an initial menu, send that too
         if let Some(menu_items) = menu_items_opt {
@@ -462,6 +917,7 @@ async fn run_tray_client(mut output: iced::futures::channel::mpsc::Sender<Messag
         while let Some(request) = activate_rx.recv().await {
             if let Err(e) = client_for_activate.activate(request).await {
                 eprintln!("Activation error: {:?}", e);
+                diagnostics::error(&format!("tray activation: {:?}", e));
             }
         }
     });
@@ -471,15 +927,8 @@ async fn run_tray_client(mut output: iced::futures::channel::mpsc::Sender<Messag
         match rx.recv().await {
             Ok(event) => match event {
                 Event::Add(address, item) => {
-                    let icon_handle = icon::resolve_icon(&item);
-                    let _ = output
-                        .send(Message::ItemAdded {
-                            address,
-                            title: item.title.clone(),
-                            icon_handle,
-                            item_is_menu: item.item_is_menu,
-                        })
-                        .await;
+                    addresses.insert(address.clone());
+                    let _ = output.send(item_added(address, &item)).await;
                 }
                 Event::Update(address, update) => match update {
                     UpdateEvent::Menu(menu) => {
@@ -500,21 +949,343 @@ async fn run_tray_client(mut output: iced::futures::channel::mpsc::Sender<Messag
                             })
                             .await;
                     }
-                    _ => {
-                        // For icon updates, we'd need to re-fetch the full item
-                        // For now, we'll skip these
+                    UpdateEvent::Icon { .. } => {
+                        // The client has already applied the update to its
+                        // items; resolve the icon again from the stored item
+                        let icon_handle = {
+                            let items_guard = client.items();
+                            let guard = items_guard.lock().unwrap();
+                            guard
+                                .get(&address)
+                                .and_then(|(item, _)| icon::resolve_icon(item))
+                        };
+                        if icon_handle.is_some() {
+                            let _ = output
+                                .send(Message::ItemUpdated {
+                                    address,
+                                    title: None,
+                                    icon_handle,
+                                })
+                                .await;
+                        }
                     }
+                    UpdateEvent::Tooltip(_) => {
+                        // Resolved from the stored item, like the icon
+                        let tooltip = {
+                            let items_guard = client.items();
+                            let guard = items_guard.lock().unwrap();
+                            guard
+                                .get(&address)
+                                .and_then(|(item, _)| ItemTooltip::from_item(item))
+                        };
+                        let _ = output
+                            .send(Message::TooltipUpdated { address, tooltip })
+                            .await;
+                    }
+                    _ => {}
                 },
                 Event::Remove(address) => {
+                    addresses.remove(&address);
                     let _ = output.send(Message::ItemRemoved(address)).await;
                 }
             },
-            Err(e) => {
+            // Missed events are gone; the ones after them still apply
+            Err(RecvError::Lagged(missed)) => {
+                eprintln!("System tray events lagged, missed {}", missed);
+            }
+            Err(e @ RecvError::Closed) => {
                 eprintln!("System tray event error: {:?}", e);
+                diagnostics::error(&format!("system tray events: {:?}", e));
                 break;
             }
         }
     }
 
-    future::pending::<()>().await;
+    for address in addresses {
+        let _ = output.send(Message::ItemRemoved(address)).await;
+    }
+}
+
+/// Run the status command of a custom indicator every `interval` seconds.
+fn poll_indicator(
+    id: String,
+    command: String,
+    interval: u64,
+) -> impl iced::futures::Stream<Item = Message> {
+    stream::channel(1, move |mut output| async move {
+        loop {
+            let status = Exec::new(command.as_str()).output().await;
+            let _ = output
+                .send(Message::IndicatorStatus {
+                    id: id.clone(),
+                    status,
+                })
+                .await;
+            tokio::time::sleep(Duration::from_secs(interval)).await;
+        }
+    })
+}
+
+/// The message announcing a new item.
+fn item_added(address: String, item: &StatusNotifierItem) -> Message {
+    Message::ItemAdded {
+        address,
+        id: item.id.clone(),
+        title: item.title.clone(),
+        icon_handle: icon::resolve_icon(item),
+        tooltip: ItemTooltip::from_item(item),
+        menu_path: item.menu.clone(),
+        item_is_menu: item.item_is_menu,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::drive;
+
+    fn added(address: &str, title: &str) -> Message {
+        Message::ItemAdded {
+            address: address.to_string(),
+            id: title.to_lowercase(),
+            title: Some(title.to_string()),
+            icon_handle: None,
+            tooltip: None,
+            menu_path: None,
+            item_is_menu: false,
+        }
+    }
+
+    fn menu_item(id: i32, label: &str) -> MenuItem {
+        MenuItem {
+            id,
+            label: label.to_string(),
+            enabled: true,
+            is_separator: false,
+            is_checkable: false,
+            is_radio: false,
+            is_checked: false,
+            icon: None,
+            shortcut: None,
+            submenu: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn follows_sni_events() {
+        let mut tray = SystemTray::default();
+        drive(
+            &mut tray,
+            [
+                added(":1.42", "Network"),
+                added(":1.7", "Chat"),
+                Message::ItemUpdated {
+                    address: ":1.7".to_string(),
+                    title: Some("Chat (3)".to_string()),
+                    icon_handle: None,
+                },
+                Message::MenuUpdated {
+                    address: ":1.42".to_string(),
+                    menu_items: vec![menu_item(1, "Disconnect")],
+                },
+                // Events for unknown items are ignored
+                Message::ItemUpdated {
+                    address: ":1.99".to_string(),
+                    title: Some("Ghost".to_string()),
+                    icon_handle: None,
+                },
+            ],
+            SystemTray::update,
+        );
+        assert_eq!(
+            tray.state(),
+            serde_json::json!({
+                "items": [
+                    {
+                        "address": ":1.42",
+                        "id": "network",
+                        "title": "Network",
+                        "item_is_menu": false,
+                    },
+                    {
+                        "address": ":1.7",
+                        "id": "chat",
+                        "title": "Chat (3)",
+                        "item_is_menu": false,
+                    },
+                ],
+                "indicators": [],
+            })
+        );
+        assert!(tray.has_menu(":1.42"));
+        assert!(!tray.has_menu(":1.7"));
+    }
+
+    #[test]
+    fn strips_tooltip_markup() {
+        assert_eq!(
+            plain_text("<b>3 updates</b><br/>Tom &amp; Jerry &lt;3 "),
+            "3 updates\nTom & Jerry <3"
+        );
+        assert_eq!(plain_text("a < b"), "a < b");
+    }
+
+    #[test]
+    fn hides_pins_and_sorts_items() {
+        let mut tray = SystemTray::new(SystemTrayConfig {
+            hidden: vec!["NETWORK".to_string()],
+            pinned: vec!["steam".to_string(), "Chat".to_string()],
+            ..SystemTrayConfig::default()
+        });
+        drive(
+            &mut tray,
+            [
+                added(":1.1", "Network"),
+                added(":1.2", "Chat"),
+                added(":1.3", "keepass"),
+                added(":1.4", "Discord"),
+                added(":1.5", "Steam"),
+            ],
+            SystemTray::update,
+        );
+        let order: Vec<&str> = tray
+            .shown_items()
+            .iter()
+            .map(|item| item.address.as_str())
+            .collect();
+        assert_eq!(order, [":1.5", ":1.2", ":1.4", ":1.3"]);
+    }
+
+    #[test]
+    fn moves_extra_items_to_the_overflow() {
+        let mut tray = SystemTray::new(SystemTrayConfig {
+            max_items: Some(2),
+            ..SystemTrayConfig::default()
+        });
+        drive(
+            &mut tray,
+            [added(":1.1", "Chat"), added(":1.2", "Network")],
+            SystemTray::update,
+        );
+        assert!(tray.split_items().1.is_empty());
+
+        drive(
+            &mut tray,
+            [added(":1.3", "Discord"), added(":1.4", "Steam")],
+            SystemTray::update,
+        );
+        let addresses = |items: Vec<&TrayItemState>| -> Vec<String> {
+            items.iter().map(|item| item.address.clone()).collect()
+        };
+        let (shown, overflow) = tray.split_items();
+        assert_eq!(addresses(shown), [":1.1", ":1.3"]);
+        assert_eq!(addresses(overflow), [":1.2", ":1.4"]);
+    }
+
+    #[test]
+    fn follows_indicator_status() {
+        let indicator = TrayIndicatorConfig {
+            name: "vpn".to_string(),
+            icon: "~/.icons/vpn.png".to_string(),
+            tooltip: "VPN".to_string(),
+            on_click: None,
+            status: Some("vpn-status".to_string()),
+            interval_secs: 10,
+        };
+        let mut tray = SystemTray::new(SystemTrayConfig {
+            indicators: vec![indicator],
+            ..SystemTrayConfig::default()
+        });
+        let status = |status: Result<&str, &str>| Message::IndicatorStatus {
+            id: "vpn".to_string(),
+            status: status.map(str::to_string).map_err(str::to_string),
+        };
+        let shown = |tray: &SystemTray| tray.state()["indicators"][0].clone();
+
+        drive(&mut tray, [status(Err("not connected"))], SystemTray::update);
+        assert_eq!(
+            shown(&tray),
+            serde_json::json!({ "id": "vpn", "tooltip": "VPN", "shown": false })
+        );
+        drive(&mut tray, [status(Ok("VPN: home\n"))], SystemTray::update);
+        assert_eq!(
+            shown(&tray),
+            serde_json::json!({ "id": "vpn", "tooltip": "VPN: home", "shown": true })
+        );
+        drive(&mut tray, [status(Ok(""))], SystemTray::update);
+        assert_eq!(shown(&tray)["tooltip"], "VPN");
+    }
+
+    #[test]
+    fn activates_menus_at_their_own_path() {
+        let mut tray = SystemTray::default();
+        drive(
+            &mut tray,
+            [
+                added(":1.1", "Network"),
+                Message::ItemAdded {
+                    address: ":1.2".to_string(),
+                    id: "chat".to_string(),
+                    title: None,
+                    icon_handle: None,
+                    tooltip: None,
+                    menu_path: Some("/StatusNotifierItem/menu".to_string()),
+                    item_is_menu: true,
+                },
+            ],
+            SystemTray::update,
+        );
+        assert_eq!(tray.menu_path(":1.1"), "/MenuBar");
+        assert_eq!(tray.menu_path(":1.2"), "/StatusNotifierItem/menu");
+    }
+
+    #[test]
+    fn removing_an_item_closes_its_menu() {
+        let mut tray = SystemTray::default();
+        drive(
+            &mut tray,
+            [
+                added(":1.42", "Network"),
+                Message::ItemRightClicked(":1.42".to_string()),
+            ],
+            SystemTray::update,
+        );
+        assert_eq!(tray.open_menu.as_deref(), Some(":1.42"));
+
+        drive(
+            &mut tray,
+            [Message::ItemRemoved(":1.42".to_string())],
+            SystemTray::update,
+        );
+        assert_eq!(tray.open_menu, None);
+        assert_eq!(tray.state()["items"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn reloads_keep_indicator_status() {
+        let config = |tooltip: &str| -> SystemTrayConfig {
+            let content = format!(
+                "[[indicator]]\nname = \"vpn\"\nicon = \"vpn.png\"\nstatus = \"vpn-up\"\n\
+                 [[indicator]]\nname = \"mic\"\nicon = \"mic.png\"\ntooltip = \"{tooltip}\""
+            );
+            toml::from_str(&content).unwrap()
+        };
+        let mut tray = SystemTray::new(config("Muted"));
+        drive(
+            &mut tray,
+            [Message::IndicatorStatus {
+                id: "vpn".to_string(),
+                status: Err("exit status: 1".to_string()),
+            }],
+            SystemTray::update,
+        );
+
+        tray.set_config(config("Mic off"));
+        let shown: Vec<_> = tray
+            .custom_indicators
+            .iter()
+            .map(|indicator| (indicator.id.as_str(), indicator.shown, indicator.tooltip.as_str()))
+            .collect();
+        assert_eq!(shown, [("vpn", false, ""), ("mic", true, "Mic off")]);
+    }
 }
@@ -0,0 +1,178 @@
+//! Tiling layout indicator.
+//!
+//! This component provides:
+//! - The current Hyprland layout (dwindle/master), with the master
+//!   orientation while the master layout is active
+//! - Click to cycle the layout, right-click to cycle the master orientation
+//!
+//! Both are Hyprland options (`general:layout`, `master:orientation`), so
+//! changes made by a Hyprland config reload show up too.
+
+use hyprland::keyword::Keyword;
+use iced::widget::{container, mouse_area, text};
+use iced::{Element, Subscription, Task};
+
+use super::tray_widget::{tray_text_button, with_tooltip};
+use crate::hyprland_events::HyprlandSubscription;
+use crate::layout::ModuleKind;
+use crate::modules::{Action, BarState, Context, Lookup, Module};
+
+/// Layouts cycled through on click.
+const LAYOUTS: [&str; 2] = ["dwindle", "master"];
+
+/// Master orientations cycled through on right-click, in Hyprland's
+/// `orientationnext` order.
+const ORIENTATIONS: [&str; 5] = ["left", "top", "right", "bottom", "center"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayoutState {
+    layout: String,
+    orientation: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TilingLayout {
+    /// `None` until the options are read
+    state: Option<LayoutState>,
+    display_text: String,
+    tooltip_text: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Refresh,
+    Loaded(Option<LayoutState>),
+    CycleLayout,
+    CycleOrientation,
+}
+
+impl TilingLayout {
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::Refresh => Task::perform(read_state(), Message::Loaded),
+            Message::Loaded(state) => {
+                self.state = state;
+                self.update_display();
+                Task::none()
+            }
+            Message::CycleLayout => {
+                let Some(state) = &self.state else {
+                    return Task::none();
+                };
+                let next = next_in(&LAYOUTS, &state.layout);
+                Task::perform(set_option("general:layout", next), |_| Message::Refresh)
+            }
+            Message::CycleOrientation => match &self.state {
+                Some(state) if state.layout == "master" => {
+                    let next = next_in(&ORIENTATIONS, &state.orientation);
+                    Task::perform(set_option("master:orientation", next), |_| Message::Refresh)
+                }
+                _ => Task::none(),
+            },
+        }
+    }
+
+    fn update_display(&mut self) {
+        let Some(state) = &self.state else {
+            return;
+        };
+
+        self.display_text = match state.layout.as_str() {
+            "master" => {
+                let arrow = match state.orientation.as_str() {
+                    "top" => "↑",
+                    "right" => "→",
+                    "bottom" => "↓",
+                    "center" => "↔",
+                    _ => "←",
+                };
+                format!("󰕰 master {}", arrow)
+            }
+            layout => format!("󰕰 {}", layout),
+        };
+
+        self.tooltip_text = if state.layout == "master" {
+            format!(
+                "Layout: master ({})\nClick to switch layout, right-click to rotate",
+                state.orientation
+            )
+        } else {
+            format!("Layout: {}\nClick to switch layout", state.layout)
+        };
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        // Hide until the layout is known
+        if self.state.is_none() {
+            return container(text("")).into();
+        }
+
+        let content = mouse_area(tray_text_button(&self.display_text, Message::CycleLayout))
+            .on_right_press(Message::CycleOrientation);
+
+        with_tooltip(content.into(), &self.tooltip_text)
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        HyprlandSubscription::new("hyprland-tiling-layout-events")
+            .on_config_reloaded(|| Message::Refresh)
+            .build()
+    }
+}
+
+impl Module for TilingLayout {
+    type Message = Message;
+
+    fn show(&self, _output: &str) -> Element<'_, Message> {
+        self.view()
+    }
+
+    fn handle(
+        &mut self,
+        message: Message,
+        _context: &Context<'_>,
+        _actions: &mut Vec<Action>,
+    ) -> Task<Message> {
+        self.update(message)
+    }
+
+    fn watch(&self, bar: &BarState<'_>) -> Subscription<Message> {
+        if bar.config.shows(&ModuleKind::TilingLayout) {
+            self.subscription()
+        } else {
+            Subscription::none()
+        }
+    }
+
+    fn first_lookup(&self) -> Option<(Lookup, Message)> {
+        Some((Lookup::Startup, Message::Refresh))
+    }
+}
+
+/// The entry after `current`, wrapping around (the first one for unknown
+/// values).
+fn next_in<'a>(values: &[&'a str], current: &str) -> &'a str {
+    let index = values.iter().position(|value| *value == current);
+    values[index.map_or(0, |index| (index + 1) % values.len())]
+}
+
+async fn read_state() -> Option<LayoutState> {
+    let layout = Keyword::get_async("general:layout").await;
+    let orientation = Keyword::get_async("master:orientation").await;
+    match (layout, orientation) {
+        (Ok(layout), Ok(orientation)) => Some(LayoutState {
+            layout: layout.value.to_string(),
+            orientation: orientation.value.to_string(),
+        }),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("Failed to read Hyprland layout: {:?}", e);
+            None
+        }
+    }
+}
+
+async fn set_option(key: &'static str, value: &'static str) {
+    if let Err(e) = Keyword::set_async(key, value).await {
+        eprintln!("Failed to set {}: {:?}", key, e);
+    }
+}
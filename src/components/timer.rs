@@ -0,0 +1,232 @@
+//! Countdown timer and stopwatch.
+//!
+//! This component provides:
+//! - Countdowns ("tea timer") started from config-defined presets
+//! - A stopwatch
+//! - A desktop notification when a countdown completes
+//!
+//! Clicking the widget opens a menu with the presets, the stopwatch and stop.
+
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use iced::{Element, Subscription, Task, time};
+
+use super::system_tray::menu::MenuItem;
+use super::tray_widget::tray_text_button;
+use crate::config::{Config, TimerConfig};
+use crate::layout::ModuleKind;
+use crate::modules::{Action, BarState, Context, MenuOwner, Module};
+use crate::sound::SoundEvent;
+
+/// Menu ID of the "start stopwatch" entry (presets use their index).
+const STOPWATCH_ID: i32 = -1;
+/// Menu ID of the "stop" entry.
+const STOP_ID: i32 = -2;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Mode {
+    Idle,
+    Countdown { label: String, ends_at: Instant },
+    Stopwatch { started_at: Instant },
+}
+
+#[derive(Debug, Clone)]
+pub struct Timer {
+    config: TimerConfig,
+    mode: Mode,
+    display_text: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// User clicked the widget (main opens the menu)
+    Clicked,
+    /// Menu entry chosen
+    Selected(i32),
+    Tick,
+    /// A countdown ran out (main plays the finish sound)
+    Finished,
+}
+
+impl Timer {
+    pub fn new(config: TimerConfig) -> Self {
+        let mut timer = Self {
+            config,
+            mode: Mode::Idle,
+            display_text: String::new(),
+        };
+        timer.update_display();
+        timer
+    }
+
+    /// Apply a reloaded timer config. A running timer keeps going.
+    pub fn set_config(&mut self, config: TimerConfig) {
+        self.config = config;
+    }
+
+    /// Entries of the timer menu.
+    pub fn menu_items(&self) -> Vec<MenuItem> {
+        let entry = |id: i32, label: String| MenuItem {
+            id,
+            label,
+            enabled: true,
+            is_separator: false,
+            is_checkable: false,
+            is_radio: false,
+            is_checked: false,
+            icon: None,
+            shortcut: None,
+            submenu: Vec::new(),
+        };
+
+        let mut items: Vec<MenuItem> = self
+            .config
+            .presets
+            .iter()
+            .enumerate()
+            .map(|(index, preset)| {
+                entry(
+                    index as i32,
+                    format!("{} ({} min)", preset.label, preset.minutes),
+                )
+            })
+            .collect();
+        items.push(MenuItem {
+            is_separator: true,
+            ..entry(0, String::new())
+        });
+        items.push(entry(STOPWATCH_ID, "Start stopwatch".to_string()));
+        if self.mode != Mode::Idle {
+            items.push(entry(STOP_ID, "Stop".to_string()));
+        }
+        items
+    }
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        let mut task = Task::none();
+        match message {
+            Message::Clicked | Message::Finished => {}
+            Message::Selected(STOPWATCH_ID) => {
+                self.mode = Mode::Stopwatch {
+                    started_at: Instant::now(),
+                };
+            }
+            Message::Selected(STOP_ID) => self.mode = Mode::Idle,
+            Message::Selected(id) => {
+                let preset = usize::try_from(id)
+                    .ok()
+                    .and_then(|index| self.config.presets.get(index));
+                if let Some(preset) = preset {
+                    self.mode = Mode::Countdown {
+                        label: preset.label.clone(),
+                        ends_at: Instant::now()
+                            + Duration::from_secs(u64::from(preset.minutes) * 60),
+                    };
+                }
+            }
+            Message::Tick => {
+                let finished = match &self.mode {
+                    Mode::Countdown { label, ends_at } if Instant::now() >= *ends_at => {
+                        Some(label.clone())
+                    }
+                    _ => None,
+                };
+                if let Some(label) = finished {
+                    notify(&label);
+                    self.mode = Mode::Idle;
+                    task = Task::done(Message::Finished);
+                }
+            }
+        }
+        self.update_display();
+        task
+    }
+
+    fn update_display(&mut self) {
+        use std::fmt::Write;
+        self.display_text.clear();
+        let now = Instant::now();
+        match &self.mode {
+            Mode::Idle => self.display_text.push('󱎫'), // nf-md-timer_outline
+            Mode::Countdown { ends_at, .. } => {
+                let secs = ends_at.saturating_duration_since(now).as_secs();
+                let _ = write!(&mut self.display_text, "󰔛 {}", format_duration(secs));
+            }
+            Mode::Stopwatch { started_at } => {
+                let secs = now.duration_since(*started_at).as_secs();
+                let _ = write!(&mut self.display_text, "󱦟 {}", format_duration(secs));
+            }
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        tray_text_button(&self.display_text, Message::Clicked)
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        // Only tick while running
+        match self.mode {
+            Mode::Idle => Subscription::none(),
+            _ => time::every(Duration::from_secs(1)).map(|_| Message::Tick),
+        }
+    }
+}
+
+impl Module for Timer {
+    type Message = Message;
+
+    fn show(&self, _output: &str) -> Element<'_, Message> {
+        self.view()
+    }
+
+    fn handle(
+        &mut self,
+        message: Message,
+        _context: &Context<'_>,
+        actions: &mut Vec<Action>,
+    ) -> Task<Message> {
+        let clicked = matches!(message, Message::Clicked);
+        if matches!(message, Message::Finished) {
+            actions.push(Action::PlaySound(SoundEvent::TimerFinished));
+        }
+        let task = self.update(message);
+        if clicked {
+            actions.push(Action::OpenMenu {
+                owner: MenuOwner::Timer,
+                items: self.menu_items(),
+            });
+        }
+        task
+    }
+
+    fn watch(&self, bar: &BarState<'_>) -> Subscription<Message> {
+        if bar.config.modules.is_enabled(&ModuleKind::Timer) {
+            self.subscription()
+        } else {
+            Subscription::none()
+        }
+    }
+
+    fn reconfigure(&mut self, config: &Config) {
+        self.set_config(config.timer.clone());
+    }
+
+    fn menu_message(&self, owner: &MenuOwner, menu_id: i32) -> Option<Message> {
+        (*owner == MenuOwner::Timer).then_some(Message::Selected(menu_id))
+    }
+}
+
+/// Format seconds as MM:SS, or H:MM:SS from an hour on.
+fn format_duration(secs: u64) -> String {
+    match secs / 3600 {
+        0 => format!("{:02}:{:02}", secs / 60, secs % 60),
+        hours => format!("{}:{:02}:{:02}", hours, secs / 60 % 60, secs % 60),
+    }
+}
+
+fn notify(label: &str) {
+    let _ = Command::new("notify-send")
+        .args(["--app-name=clammy", "Timer finished", label])
+        .spawn();
+}
@@ -1,22 +1,200 @@
 //! Shared tray widget helpers for consistent styling across components.
 
-use iced::widget::{container, text};
-use iced::{Element, Length};
+use iced::mouse::ScrollDelta;
+use iced::widget::{button, container, image, mouse_area, row, text, tooltip};
+use iced::{Alignment, Border, Color, Element, Length};
 
+use crate::config::ModuleActions;
+use crate::layout::ModuleKind;
 use crate::theme::get_theme;
 
 /// Creates a styled text widget for use in the tray area (right section).
-/// Applies consistent font size, text color, padding, and vertical centering.
+/// Applies consistent font, font size, text color, padding, and vertical
+/// centering.
 pub fn tray_text<'a, M: 'a>(content: &'a str) -> Element<'a, M> {
+    tray_icon_text(None, content)
+}
+
+/// [`tray_text`] with an image icon in front of the text, for
+/// `theme.icons = "image"`.
+pub fn tray_icon_text<'a, M: 'a>(icon: Option<&image::Handle>, content: &'a str) -> Element<'a, M> {
+    tray_icon_text_colored(icon, content, get_theme().text())
+}
+
+/// [`tray_icon_text`] in another color than the theme's text color, e.g.
+/// for warnings.
+pub fn tray_icon_text_colored<'a, M: 'a>(
+    icon: Option<&image::Handle>,
+    content: &'a str,
+    color: Color,
+) -> Element<'a, M> {
+    let theme = get_theme();
+    container(label(icon, content, color))
+        .center_y(Length::Fill)
+        .padding([0.0, theme.tray_widget_padding()])
+        .into()
+}
+
+/// Text in the theme's font and size, after an image icon sized to the
+/// text if there is one.
+fn label<'a, M: 'a>(
+    icon: Option<&image::Handle>,
+    content: &'a str,
+    color: Color,
+) -> Element<'a, M> {
     let theme = get_theme();
     let text_widget = text(content)
+        .font(theme.font())
         .size(theme.font_size())
-        .style(|theme: &iced::Theme| iced::widget::text::Style {
-            color: Some(theme.palette().text),
+        .color(color);
+    match icon {
+        Some(icon) => row![
+            image(icon.clone()).height(theme.font_size() * 1.2),
+            text_widget
+        ]
+        .spacing(4)
+        .align_y(Alignment::Center)
+        .into(),
+        None => text_widget.into(),
+    }
+}
+
+/// Creates a clickable tray text widget with a hover background.
+/// Uses the same font, font size and padding as [`tray_text`].
+pub fn tray_text_button<'a, M: Clone + 'a>(content: &'a str, on_press: M) -> Element<'a, M> {
+    tray_icon_text_button(None, content, on_press)
+}
+
+/// [`tray_text_button`] with an image icon in front of the text.
+pub fn tray_icon_text_button<'a, M: Clone + 'a>(
+    icon: Option<&image::Handle>,
+    content: &'a str,
+    on_press: M,
+) -> Element<'a, M> {
+    let theme = get_theme();
+    let hover_bg = theme.hover();
+    let text_color = theme.text();
+
+    button(container(label(icon, content, text_color)).center_y(Length::Fill))
+        .padding([0.0, theme.tray_widget_padding()])
+        .height(Length::Fill)
+        .style(move |_theme, status| {
+            let bg = match status {
+                button::Status::Hovered | button::Status::Pressed => Some(hover_bg.into()),
+                _ => None,
+            };
+            button::Style {
+                background: bg,
+                border: Border {
+                    radius: 2.0.into(),
+                    ..Border::default()
+                },
+                text_color,
+                shadow: Default::default(),
+            }
+        })
+        .on_press(on_press)
+        .into()
+}
+
+/// Shows `tip` in a framed tooltip below the widget on hover. Nothing is
+/// shown for an empty tip or with `tooltips = false`.
+pub fn with_tooltip<'a, M: 'a>(content: Element<'a, M>, tip: &'a str) -> Element<'a, M> {
+    if tip.is_empty() {
+        return content;
+    }
+    with_tooltip_content(content, text(tip).size(get_theme().font_size()).into())
+}
+
+/// [`with_tooltip`] with any widget as the tip, framed the same way.
+pub fn with_tooltip_content<'a, M: 'a>(
+    content: Element<'a, M>,
+    tip: Element<'a, M>,
+) -> Element<'a, M> {
+    let theme = get_theme();
+    if !theme.tooltips() {
+        return content;
+    }
+    let surface = theme.surface();
+    let border = theme.border();
+    tooltip(content, tip, tooltip::Position::Bottom)
+        .padding(6)
+        .style(move |_theme| container::Style {
+            background: Some(surface.into()),
+            border: Border {
+                color: border,
+                width: 1.0,
+                radius: 4.0.into(),
+            },
+            ..container::Style::default()
+        })
+        .into()
+}
+
+/// Wraps a module with its configured click and scroll commands. `run`
+/// turns a command into the message that runs it; scrolling in a direction
+/// without a command produces an empty one.
+pub fn with_actions<'a, M: Clone + 'a>(
+    content: Element<'a, M>,
+    actions: &ModuleActions,
+    run: impl Fn(String) -> M + 'a,
+) -> Element<'a, M> {
+    if *actions == ModuleActions::default() {
+        return content;
+    }
+
+    let mut area = mouse_area(content);
+    if let Some(command) = &actions.on_click {
+        area = area.on_press(run(command.clone()));
+    }
+    if let Some(command) = &actions.on_click_right {
+        area = area.on_right_press(run(command.clone()));
+    }
+    if let Some(command) = &actions.on_click_middle {
+        area = area.on_middle_press(run(command.clone()));
+    }
+    if actions.on_scroll_up.is_some() || actions.on_scroll_down.is_some() {
+        let up = actions.on_scroll_up.clone().unwrap_or_default();
+        let down = actions.on_scroll_down.clone().unwrap_or_default();
+        area = area.on_scroll(move |delta| {
+            let y = match delta {
+                ScrollDelta::Lines { y, .. } | ScrollDelta::Pixels { y, .. } => y,
+            };
+            run(if y > 0.0 { up.clone() } else { down.clone() })
         });
+    }
+    area.into()
+}
 
-    container(text_widget)
+/// Wraps a module in the background and border of its `[theme.<module>]`
+/// overrides; text color and padding are applied through [`get_theme`].
+pub fn with_component_style<'a, M: 'a>(
+    content: Element<'a, M>,
+    kind: &ModuleKind,
+) -> Element<'a, M> {
+    let theme = get_theme();
+    let Some(style) = theme
+        .component(kind)
+        .filter(|style| style.background.is_some() || style.border.is_some())
+        .cloned()
+    else {
+        return content;
+    };
+
+    container(content)
         .center_y(Length::Fill)
-        .padding([0.0, theme.tray_widget_padding()])
+        .style(move |_theme| container::Style {
+            background: style.background.map(Into::into),
+            border: Border {
+                color: style.border.unwrap_or(Color::TRANSPARENT),
+                width: if style.border.is_some() {
+                    style.border_width
+                } else {
+                    0.0
+                },
+                radius: style.border_radius.into(),
+            },
+            ..container::Style::default()
+        })
         .into()
 }
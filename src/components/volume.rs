@@ -1,34 +1,72 @@
 use iced::{Element, Subscription, Task, time};
 use std::process::Command;
 
-use super::tray_widget::tray_text;
+use super::system_tray::menu::MenuItem;
+use super::tray_widget::{tray_icon_text_button, with_tooltip};
+use crate::config::{Config, VolumeConfig};
+use crate::format::{self, NumberFormat};
+use crate::icons::{Icon, Shown};
+use crate::layout::ModuleKind;
+use crate::modules::{Action, BarState, Context, Lookup, MenuOwner, Module};
+use crate::theme::get_theme;
 
 #[derive(Debug, Clone)]
 pub struct Volume {
+    config: VolumeConfig,
     percentage: u8,
     muted: bool,
+    format: NumberFormat,
+    icon: Shown,
     display_text: String,
+    /// Name of the default output device, shown in the tooltip
+    device: String,
+}
+
+/// A PipeWire audio output device.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sink {
+    /// PipeWire object ID (used by `wpctl set-default`)
+    pub id: u32,
+    /// Human-readable device name
+    pub name: String,
+    /// Whether this is the current default output
+    pub is_default: bool,
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
     Tick,
+    /// User clicked the volume widget (main opens the sink popup)
+    Clicked,
+    /// Make the given sink the default output device
+    SetDefaultSink(u32),
+    /// Name of the default output device was looked up
+    DeviceFound(String),
 }
 
-impl Default for Volume {
-    fn default() -> Self {
+impl Volume {
+    pub fn new(config: VolumeConfig, format: &NumberFormat) -> Self {
         let (percentage, muted) = read_volume_info();
         let mut volume = Self {
+            config,
             percentage,
             muted,
+            format: format.clone(),
+            icon: Shown::default(),
             display_text: String::new(),
+            device: String::new(),
         };
         volume.update_display();
         volume
     }
-}
 
-impl Volume {
+    /// Apply a reloaded volume config and format defaults.
+    pub fn set_config(&mut self, config: VolumeConfig, format: &NumberFormat) {
+        self.config = config;
+        self.format = format.clone();
+        self.update_display();
+    }
+
     pub fn update(&mut self, message: Message) -> Task<Message> {
         match message {
             Message::Tick => {
@@ -36,31 +74,58 @@ impl Volume {
                 self.percentage = percentage;
                 self.muted = muted;
                 self.update_display();
+                self.refresh_device()
+            }
+            Message::Clicked => Task::none(),
+            Message::SetDefaultSink(id) => Task::perform(set_default_sink(id), |_| Message::Tick),
+            Message::DeviceFound(device) => {
+                self.device = device;
                 Task::none()
             }
         }
     }
 
+    /// Look up the default output device for the tooltip. Skipped with
+    /// tooltips off, as it runs `wpctl status`.
+    fn refresh_device(&self) -> Task<Message> {
+        if !get_theme().tooltips() {
+            return Task::none();
+        }
+        Task::perform(default_sink_name(), Message::DeviceFound)
+    }
+
     fn update_display(&mut self) {
-        self.display_text.clear();
-        let icon = self.get_icon();
-        use std::fmt::Write;
-        let _ = write!(&mut self.display_text, "{} {}%", icon, self.percentage);
+        let percent = self.format.percent(self.percentage as f64);
+        self.icon = self.get_icon().show(&ModuleKind::Volume);
+        self.display_text = format::render(
+            &self.config.format,
+            &[("icon", self.icon.text), ("percent", &percent)],
+        );
     }
 
-    fn get_icon(&self) -> &'static str {
-        if self.muted {
-            return "󰝟"; // nf-md-volume_off
-        }
-        match self.percentage {
-            66..=100 => "󰕾", // nf-md-volume_high
-            33..=65 => "󰖀",  // nf-md-volume_medium
-            _ => "󰕿",        // nf-md-volume_low
+    fn get_icon(&self) -> Icon {
+        let (glyph, unicode, name) = match (self.muted, self.percentage) {
+            (true, _) => ("󰝟", "♪✕", "audio-volume-muted"), // nf-md-volume_off
+            (false, 66..) => ("󰕾", "♫", "audio-volume-high"), // nf-md-volume_high
+            (false, 33..) => ("󰖀", "♪", "audio-volume-medium"), // nf-md-volume_medium
+            (false, _) => ("󰕿", "♪", "audio-volume-low"),   // nf-md-volume_low
+        };
+        Icon {
+            glyph,
+            unicode,
+            name,
         }
     }
 
     pub fn view(&self) -> Element<'_, Message> {
-        tray_text(&self.display_text)
+        with_tooltip(
+            tray_icon_text_button(
+                self.icon.image.as_ref(),
+                &self.display_text,
+                Message::Clicked,
+            ),
+            &self.device,
+        )
     }
 
     pub fn subscription(&self) -> Subscription<Message> {
@@ -69,6 +134,74 @@ impl Volume {
     }
 }
 
+impl Module for Volume {
+    type Message = Message;
+
+    fn show(&self, _output: &str) -> Element<'_, Message> {
+        self.view()
+    }
+
+    fn handle(
+        &mut self,
+        message: Message,
+        _context: &Context<'_>,
+        actions: &mut Vec<Action>,
+    ) -> Task<Message> {
+        // Clicking the widget opens the output device switcher
+        if let Message::Clicked = message {
+            let items = sink_menu_items(&list_sinks());
+            if !items.is_empty() {
+                actions.push(Action::OpenMenu {
+                    owner: MenuOwner::AudioSinks,
+                    items,
+                });
+                return Task::none();
+            }
+        }
+        self.update(message)
+    }
+
+    // Display-only polling is suspended while the bar is covered or hidden
+    fn watch(&self, bar: &BarState<'_>) -> Subscription<Message> {
+        if bar.visible && bar.config.modules.is_enabled(&ModuleKind::Volume) {
+            self.subscription()
+        } else {
+            Subscription::none()
+        }
+    }
+
+    fn reconfigure(&mut self, config: &Config) {
+        self.set_config(config.volume.clone(), &config.format);
+    }
+
+    fn first_lookup(&self) -> Option<(Lookup, Message)> {
+        Some((Lookup::Display, Message::Tick))
+    }
+
+    fn menu_message(&self, owner: &MenuOwner, menu_id: i32) -> Option<Message> {
+        (*owner == MenuOwner::AudioSinks).then_some(Message::SetDefaultSink(menu_id as u32))
+    }
+}
+
+/// Convert audio sinks into popup menu items, checking the default sink.
+fn sink_menu_items(sinks: &[Sink]) -> Vec<MenuItem> {
+    sinks
+        .iter()
+        .map(|sink| MenuItem {
+            id: sink.id as i32,
+            label: sink.name.clone(),
+            enabled: true,
+            is_separator: false,
+            is_checkable: true,
+            is_radio: true,
+            is_checked: sink.is_default,
+            icon: None,
+            shortcut: None,
+            submenu: Vec::new(),
+        })
+        .collect()
+}
+
 fn read_volume_info() -> (u8, bool) {
     let output = Command::new("wpctl")
         .args(["get-volume", "@DEFAULT_AUDIO_SINK@"])
@@ -78,13 +211,13 @@ fn read_volume_info() -> (u8, bool) {
         Ok(output) => {
             let stdout = String::from_utf8_lossy(&output.stdout);
             // Expected format: "Volume: 0.45" or "Volume: 0.45 [MUTED]"
-            
+
             let muted = stdout.contains("[MUTED]");
-            
+
             // Extract the float value
             if let Some(vol_str) = stdout.split_whitespace().nth(1) {
                 if let Ok(vol_float) = vol_str.parse::<f32>() {
-                     return ((vol_float * 100.0) as u8, muted);
+                    return ((vol_float * 100.0) as u8, muted);
                 }
             }
             (0, false)
@@ -92,3 +225,102 @@ fn read_volume_info() -> (u8, bool) {
         Err(_) => (0, false), // Fail gracefully
     }
 }
+
+/// Name of the default sink, or an empty string if there is none.
+async fn default_sink_name() -> String {
+    tokio::task::spawn_blocking(list_sinks)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .find(|sink| sink.is_default)
+        .map(|sink| sink.name)
+        .unwrap_or_default()
+}
+
+async fn set_default_sink(id: u32) {
+    if let Err(e) = tokio::process::Command::new("wpctl")
+        .args(["set-default", &id.to_string()])
+        .status()
+        .await
+    {
+        eprintln!("Failed to set default sink {}: {}", id, e);
+    }
+}
+
+/// List PipeWire sinks by parsing the `Audio` section of `wpctl status`.
+pub fn list_sinks() -> Vec<Sink> {
+    let output = match Command::new("wpctl").arg("status").output() {
+        Ok(output) => output,
+        Err(e) => {
+            eprintln!("Failed to run wpctl status: {}", e);
+            return Vec::new();
+        }
+    };
+
+    parse_sinks(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parse sinks from `wpctl status` output.
+///
+/// Expected format (tree characters vary):
+/// ```text
+/// Audio
+///  ├─ Sinks:
+///  │  *   46. Built-in Audio Analog Stereo        [vol: 0.40]
+///  │      52. HDMI Output                          [vol: 1.00]
+///  │
+/// ```
+fn parse_sinks(status: &str) -> Vec<Sink> {
+    let mut sinks = Vec::new();
+    let mut in_audio = false;
+    let mut in_sinks = false;
+
+    for line in status.lines() {
+        // Top-level sections (Audio, Video, Settings) start at column 0
+        if !line.starts_with(char::is_whitespace) && !line.is_empty() {
+            in_audio = line.trim() == "Audio";
+            in_sinks = false;
+            continue;
+        }
+        if !in_audio {
+            continue;
+        }
+
+        // Strip tree-drawing characters
+        let entry = line.trim_start_matches(|c: char| c.is_whitespace() || "│├└─".contains(c));
+
+        if entry.ends_with(':') {
+            in_sinks = entry == "Sinks:";
+            continue;
+        }
+        if !in_sinks {
+            continue;
+        }
+        if entry.is_empty() {
+            // Blank tree line ends the list
+            in_sinks = false;
+            continue;
+        }
+
+        let is_default = entry.starts_with('*');
+        let entry = entry.trim_start_matches('*').trim_start();
+
+        let Some((id, rest)) = entry.split_once('.') else {
+            continue;
+        };
+        let Ok(id) = id.trim().parse::<u32>() else {
+            continue;
+        };
+
+        // Drop the trailing "[vol: ...]" annotation
+        let name = rest.split('[').next().unwrap_or(rest).trim().to_string();
+
+        sinks.push(Sink {
+            id,
+            name,
+            is_default,
+        });
+    }
+
+    sinks
+}
@@ -0,0 +1,210 @@
+//! VRR and tearing indicator.
+//!
+//! This component provides:
+//! - Whether variable refresh rate is active on the focused monitor, with
+//!   the `misc:vrr` mode
+//! - Whether tearing is allowed (`general:allow_tearing`)
+//! - Click to toggle VRR, right-click to toggle tearing
+//!
+//! The monitor's VRR state is what Hyprland reports as active, so a mode
+//! that only applies to fullscreen windows shows as off until a window goes
+//! fullscreen. Tearing additionally needs the `immediate` window rule,
+//! which Hyprland does not report per window.
+
+use hyprland::data::Monitor;
+use hyprland::keyword::{Keyword, OptionValue};
+use hyprland::shared::HyprDataActive;
+use iced::widget::{container, mouse_area, text};
+use iced::{Element, Subscription, Task};
+
+use super::tray_widget::{tray_text_button, with_tooltip};
+use crate::hyprland_events::HyprlandSubscription;
+use crate::layout::ModuleKind;
+use crate::modules::{Action, BarState, Context, Lookup, Module};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncState {
+    /// `misc:vrr`: 0 off, 1 on, 2 fullscreen only, 3 fullscreen games and
+    /// video only
+    vrr_mode: i64,
+    allow_tearing: bool,
+    monitor: String,
+    /// VRR currently active on the focused monitor
+    monitor_vrr: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Vrr {
+    /// `None` until the state is read
+    state: Option<SyncState>,
+    /// Mode restored when VRR is toggled back on
+    on_mode: i64,
+    display_text: String,
+    tooltip_text: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Refresh,
+    Loaded(Option<SyncState>),
+    ToggleVrr,
+    ToggleTearing,
+}
+
+impl Default for Vrr {
+    fn default() -> Self {
+        Self {
+            state: None,
+            on_mode: 1,
+            display_text: String::new(),
+            tooltip_text: String::new(),
+        }
+    }
+}
+
+impl Vrr {
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::Refresh => Task::perform(read_state(), Message::Loaded),
+            Message::Loaded(state) => {
+                if let Some(state) = state.as_ref().filter(|state| state.vrr_mode != 0) {
+                    self.on_mode = state.vrr_mode;
+                }
+                self.state = state;
+                self.update_display();
+                Task::none()
+            }
+            Message::ToggleVrr => {
+                let Some(state) = &self.state else {
+                    return Task::none();
+                };
+                let mode = if state.vrr_mode == 0 { self.on_mode } else { 0 };
+                Task::perform(set_option("misc:vrr", mode), |_| Message::Refresh)
+            }
+            Message::ToggleTearing => {
+                let Some(state) = &self.state else {
+                    return Task::none();
+                };
+                let value = i64::from(!state.allow_tearing);
+                Task::perform(set_option("general:allow_tearing", value), |_| {
+                    Message::Refresh
+                })
+            }
+        }
+    }
+
+    fn update_display(&mut self) {
+        let Some(state) = &self.state else {
+            return;
+        };
+
+        let vrr = if state.monitor_vrr { "VRR" } else { "vrr" };
+        let tearing = if state.allow_tearing { " 󱐋" } else { "" };
+        self.display_text = format!("󰍹 {}{}", vrr, tearing);
+
+        let mode = match state.vrr_mode {
+            0 => "off",
+            1 => "on",
+            2 => "fullscreen only",
+            3 => "fullscreen games and video only",
+            _ => "unknown mode",
+        };
+        self.tooltip_text = format!(
+            "VRR on {}: {} ({})\nTearing: {}\nClick to toggle VRR, right-click to toggle tearing",
+            state.monitor,
+            if state.monitor_vrr {
+                "active"
+            } else {
+                "inactive"
+            },
+            mode,
+            if state.allow_tearing {
+                "allowed for windows with the immediate rule"
+            } else {
+                "off"
+            },
+        );
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        // Hide until the state is known
+        if self.state.is_none() {
+            return container(text("")).into();
+        }
+
+        let content = mouse_area(tray_text_button(&self.display_text, Message::ToggleVrr))
+            .on_right_press(Message::ToggleTearing);
+
+        with_tooltip(content.into(), &self.tooltip_text)
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        // Focus and fullscreen changes can move VRR on or off
+        HyprlandSubscription::new("hyprland-vrr-events")
+            .on_active_window(|_| Message::Refresh)
+            .on_fullscreen_changed(|_| Message::Refresh)
+            .on_config_reloaded(|| Message::Refresh)
+            .build()
+    }
+}
+
+impl Module for Vrr {
+    type Message = Message;
+
+    fn show(&self, _output: &str) -> Element<'_, Message> {
+        self.view()
+    }
+
+    fn handle(
+        &mut self,
+        message: Message,
+        _context: &Context<'_>,
+        _actions: &mut Vec<Action>,
+    ) -> Task<Message> {
+        self.update(message)
+    }
+
+    fn watch(&self, bar: &BarState<'_>) -> Subscription<Message> {
+        if bar.config.shows(&ModuleKind::Vrr) {
+            self.subscription()
+        } else {
+            Subscription::none()
+        }
+    }
+
+    fn first_lookup(&self) -> Option<(Lookup, Message)> {
+        Some((Lookup::Startup, Message::Refresh))
+    }
+}
+
+async fn read_state() -> Option<SyncState> {
+    let vrr_mode = Keyword::get_async("misc:vrr").await;
+    let allow_tearing = Keyword::get_async("general:allow_tearing").await;
+    let monitor = Monitor::get_active_async().await;
+    match (vrr_mode, allow_tearing, monitor) {
+        (Ok(vrr_mode), Ok(allow_tearing), Ok(monitor)) => Some(SyncState {
+            vrr_mode: int_value(&vrr_mode.value),
+            allow_tearing: int_value(&allow_tearing.value) != 0,
+            monitor: monitor.name,
+            monitor_vrr: monitor.vrr,
+        }),
+        (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
+            eprintln!("Failed to read Hyprland VRR state: {:?}", e);
+            None
+        }
+    }
+}
+
+fn int_value(value: &OptionValue) -> i64 {
+    match value {
+        OptionValue::Int(value) => *value,
+        OptionValue::Float(value) => *value as i64,
+        OptionValue::String(value) => value.trim().parse().unwrap_or(0),
+    }
+}
+
+async fn set_option(key: &'static str, value: i64) {
+    if let Err(e) = Keyword::set_async(key, value).await {
+        eprintln!("Failed to set {}: {:?}", key, e);
+    }
+}
@@ -1,19 +1,52 @@
-use iced::widget::text;
-use iced::{Element, Subscription};
+//! Active window title.
+//!
+//! This component provides:
+//! - The class and title of the focused window
+//! - Click to pick a window from all workspaces (alt-tab style) and focus it
 
+use hyprland::data::Clients;
+use hyprland::dispatch::{Dispatch, DispatchType, WindowIdentifier};
+use hyprland::shared::{Address, HyprData};
+use iced::widget::{mouse_area, text};
+use iced::{Element, Subscription, Task};
+
+use super::tray_widget::with_tooltip;
+use crate::components::system_tray::menu::MenuItem;
 use crate::hyprland_events::HyprlandSubscription;
+use crate::layout::ModuleKind;
+use crate::modules::{Action, BarState, Context, MenuOwner, Module};
 use crate::theme::get_theme;
 
+/// Longest window title shown in the picker before it is cut off.
+const MAX_PICKER_TITLE_CHARS: usize = 60;
+
+/// An open window, as listed in the picker.
+#[derive(Debug, Clone)]
+pub struct WindowEntry {
+    address: Address,
+    class: String,
+    title: String,
+    workspace: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct WindowTitle {
     title: Option<String>,
     class: Option<String>,
     display_text: String,  // Cached display string
+    /// Windows listed in the open picker, indexed by menu item id
+    picker: Vec<WindowEntry>,
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
     ActiveWindowChanged(Option<String>, Option<String>), // (title, class)
+    /// Open the window picker
+    Clicked,
+    /// Windows for the picker were listed (main opens the menu)
+    WindowsListed(Vec<WindowEntry>),
+    /// A window was picked (index into the listed windows)
+    Selected(i32),
 }
 
 impl Default for WindowTitle {
@@ -22,12 +55,13 @@ impl Default for WindowTitle {
             title: None,
             class: None,
             display_text: String::new(),
+            picker: Vec::new(),
         }
     }
 }
 
 impl WindowTitle {
-    pub fn update(&mut self, message: Message) {
+    pub fn update(&mut self, message: Message) -> Task<Message> {
         match message {
             Message::ActiveWindowChanged(title, class) => {
                 self.title = title;
@@ -39,20 +73,90 @@ impl WindowTitle {
                     use std::fmt::Write;
                     let _ = write!(&mut self.display_text, "{} - {}", c, t);
                 }
+                Task::none()
+            }
+            Message::Clicked => Task::perform(list_windows(), Message::WindowsListed),
+            Message::WindowsListed(windows) => {
+                self.picker = windows;
+                Task::none()
+            }
+            Message::Selected(index) => {
+                let Some(window) = usize::try_from(index).ok().and_then(|i| self.picker.get(i))
+                else {
+                    return Task::none();
+                };
+                // The title follows through the active window event
+                Task::future(focus_window(window.address.clone())).discard()
             }
         }
     }
 
+    /// The active window for `clammy --state`; `null` without one.
+    pub fn state(&self) -> serde_json::Value {
+        match (&self.title, &self.class) {
+            (None, None) => serde_json::Value::Null,
+            (title, class) => serde_json::json!({ "title": title, "class": class }),
+        }
+    }
+
+    /// Picker entries for the listed windows, with a separator between
+    /// workspaces.
+    pub fn menu_items(&self) -> Vec<MenuItem> {
+        let entry = |id: i32, label: String| MenuItem {
+            id,
+            label,
+            enabled: true,
+            is_separator: false,
+            is_checkable: false,
+            is_radio: false,
+            is_checked: false,
+            icon: None,
+            shortcut: None,
+            submenu: Vec::new(),
+        };
+
+        let mut items = Vec::new();
+        let mut workspace = None;
+        for (index, window) in self.picker.iter().enumerate() {
+            if workspace.is_some_and(|ws| ws != window.workspace.as_str()) {
+                items.push(MenuItem {
+                    is_separator: true,
+                    ..entry(-1, String::new())
+                });
+            }
+            workspace = Some(window.workspace.as_str());
+
+            let mut title: String = window.title.chars().take(MAX_PICKER_TITLE_CHARS).collect();
+            if title.len() < window.title.len() {
+                title.push('…');
+            }
+            items.push(entry(
+                index as i32,
+                format!(
+                    "{}  {} {} — {}",
+                    window.workspace,
+                    class_icon(&window.class),
+                    window.class,
+                    title
+                ),
+            ));
+        }
+        items
+    }
+
     pub fn view(&self) -> Element<'_, Message> {
-        let font_size = get_theme().font_size();
-        text(&self.display_text)
-            .size(font_size)
+        let theme = get_theme();
+        let title = text(&self.display_text)
+            .font(theme.font())
+            .size(theme.font_size())
             .style(|theme: &iced::Theme| {
                 text::Style {
                     color: Some(theme.palette().text),
                 }
-            })
-            .into()
+            });
+        let content = mouse_area(title).on_press(Message::Clicked);
+        // The bar may cut a long title off; the tooltip has all of it
+        with_tooltip(content.into(), self.title.as_deref().unwrap_or_default())
     }
 
     pub fn subscription(&self) -> Subscription<Message> {
@@ -64,3 +168,101 @@ impl WindowTitle {
             .build()
     }
 }
+
+impl Module for WindowTitle {
+    type Message = Message;
+
+    fn show(&self, _output: &str) -> Element<'_, Message> {
+        self.view()
+    }
+
+    fn handle(
+        &mut self,
+        message: Message,
+        _context: &Context<'_>,
+        actions: &mut Vec<Action>,
+    ) -> Task<Message> {
+        let listed = matches!(message, Message::WindowsListed(_));
+        let task = self.update(message);
+        if listed {
+            let items = self.menu_items();
+            if !items.is_empty() {
+                actions.push(Action::OpenMenu {
+                    owner: MenuOwner::Windows,
+                    items,
+                });
+            }
+        }
+        task
+    }
+
+    fn watch(&self, bar: &BarState<'_>) -> Subscription<Message> {
+        if bar.config.modules.is_enabled(&ModuleKind::WindowTitle) {
+            self.subscription()
+        } else {
+            Subscription::none()
+        }
+    }
+
+    fn menu_message(&self, owner: &MenuOwner, menu_id: i32) -> Option<Message> {
+        (*owner == MenuOwner::Windows).then_some(Message::Selected(menu_id))
+    }
+}
+
+/// Nerd Font icon for common window classes.
+fn class_icon(class: &str) -> &'static str {
+    let class = class.to_ascii_lowercase();
+    match class.as_str() {
+        c if c.contains("firefox") || c.contains("librewolf") => "󰈹",
+        c if c.contains("chrom") || c.contains("brave") => "",
+        c if c.contains("kitty")
+            || c.contains("alacritty")
+            || c.contains("foot")
+            || c.contains("wezterm")
+            || c.contains("ghostty") =>
+        {
+            ""
+        }
+        c if c.contains("code") || c.contains("codium") => "󰨞",
+        c if c.contains("discord") || c.contains("vesktop") => "󰙯",
+        c if c.contains("spotify") => "󰓇",
+        c if c.contains("thunar") || c.contains("nautilus") || c.contains("dolphin") => "󰉋",
+        c if c.contains("thunderbird") => "󰇮",
+        c if c.contains("steam") => "󰓓",
+        _ => "󰖯",
+    }
+}
+
+/// All mapped windows on regular workspaces, ordered by workspace.
+async fn list_windows() -> Vec<WindowEntry> {
+    let clients = match Clients::get_async().await {
+        Ok(clients) => clients,
+        Err(e) => {
+            eprintln!("Failed to fetch clients: {:?}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut clients: Vec<_> = clients
+        .into_iter()
+        // Special workspaces have negative IDs
+        .filter(|client| client.mapped && client.workspace.id > 0)
+        .collect();
+    clients.sort_by_key(|client| (client.workspace.id, client.focus_history_id));
+    clients
+        .into_iter()
+        .map(|client| WindowEntry {
+            address: client.address,
+            class: client.class,
+            title: client.title,
+            workspace: client.workspace.name,
+        })
+        .collect()
+}
+
+async fn focus_window(address: Address) {
+    let dispatch = DispatchType::FocusWindow(WindowIdentifier::Address(address.clone()));
+    if let Err(e) = Dispatch::call_async(dispatch).await {
+        eprintln!("Failed to focus window {}: {:?}", address, e);
+    }
+}
@@ -1,18 +1,46 @@
 //! Workspaces component for displaying and managing Hyprland workspaces.
 //!
 //! This component provides:
-//! - Real-time workspace list display
+//! - Real-time workspace list display, optionally limited to the
+//!   workspaces around the active one
 //! - Active workspace highlighting
-//! - Click-to-switch functionality
+//! - Optional icons of the windows on each workspace, found through the
+//!   shared [`crate::app_icons`] lookup
+//! - Click-to-switch functionality, and scrolling to the next or previous
+//!   workspace
+//! - Special workspaces (scratchpads) after the numbered ones, toggled on
+//!   click
 //! - Automatic updates via Hyprland event subscription
+//! - Menu (right-click a workspace) to move the focused window there,
+//!   rename the workspace in the overview popup or close its windows
+//! - Overview popup (right-click next to the workspaces) with thumbnails of
+//!   each workspace, captured with `grim` (screencopy) while the workspace
+//!   is shown and cached in memory
+//! - Optional back/forward buttons through the recently used workspaces of
+//!   this session, like a browser history
 
-use hyprland::data::{Workspace, Workspaces as HyprWorkspaces};
-use hyprland::dispatch::{Dispatch, DispatchType, WorkspaceIdentifierWithSpecial};
+use std::collections::{HashMap, VecDeque};
+use std::ops::Range;
+use std::time::{Duration, Instant};
+
+use hyprland::data::{Clients, Monitors, Workspace, Workspaces as HyprWorkspaces};
+use hyprland::dispatch::{
+    Dispatch, DispatchType, WindowIdentifier, WorkspaceIdentifierWithSpecial,
+};
 use hyprland::shared::{HyprData, HyprDataActive, WorkspaceId};
-use iced::widget::{Row, button, container, row, stack, text};
-use iced::{Border, Element, Length, Subscription, Task};
+use iced::widget::{
+    Column, Row, button, column, container, image, mouse_area, row, stack, text, text_input,
+};
+use iced::mouse::ScrollDelta;
+use iced::{Border, Element, Length, Rectangle, Subscription, Task};
 
+use crate::animation::Animation;
+use crate::app_icons;
+use crate::components::system_tray::menu::MenuItem;
+use crate::config::{AnimationsConfig, Config, WorkspacesConfig};
 use crate::hyprland_events::HyprlandSubscription;
+use crate::layout::ModuleKind;
+use crate::modules::{Action, BarState, Context, Lookup, MenuOwner, Module};
 use crate::theme::get_theme;
 
 // ============================================================================
@@ -26,10 +54,11 @@ const BUTTON_PADDING_H: f32 = 8.0;
 /// Text size for workspace labels
 const TEXT_SIZE: f32 = 13.0;
 
-/// Approximate text width for single-digit workspace IDs
+/// Approximate text width for single-digit workspace IDs, for the
+/// indicator until the buttons are measured
 const TEXT_WIDTH_APPROX: f32 = 8.0;
 
-/// Total width of each workspace button (text + horizontal padding)
+/// Estimated width of each workspace button (text + horizontal padding)
 const BUTTON_WIDTH: f32 = TEXT_WIDTH_APPROX + (BUTTON_PADDING_H * 2.0);
 
 /// Spacing between workspace buttons
@@ -38,6 +67,54 @@ const BUTTON_SPACING: f32 = 4.0;
 /// Row padding (horizontal)
 const ROW_PADDING: f32 = 3.0;
 
+/// Wait after a workspace switch before capturing it, so the compositor's
+/// switch animation has finished
+const PREVIEW_DELAY: Duration = Duration::from_millis(600);
+
+/// Output scale of preview captures
+const PREVIEW_CAPTURE_SCALE: &str = "0.2";
+
+/// Size of a preview thumbnail in the overview popup
+const PREVIEW_WIDTH: f32 = 192.0;
+const PREVIEW_HEIGHT: f32 = 108.0;
+
+/// Thumbnails per row in the overview popup
+const OVERVIEW_COLUMNS: usize = 3;
+
+/// Workspaces remembered for back navigation
+const MAX_HISTORY: usize = 20;
+
+/// Size of window icons in workspace buttons; as tall as the label, so
+/// the indicator still fits
+const APP_ICON_SIZE: f32 = TEXT_SIZE;
+
+/// Spacing between the label and window icons of a workspace button
+const APP_ICON_SPACING: f32 = 4.0;
+
+/// Window icons shown per workspace
+const MAX_APP_ICONS: usize = 4;
+
+/// Label of the buttons standing in for workspaces beyond `max_shown`
+const ELLIPSIS: &str = "…";
+
+/// Width of the rename form in the overview popup
+const RENAME_WIDTH: f32 = 240.0;
+
+/// Menu ID of "Move focused window here" in the workspace menu.
+const MOVE_WINDOW_ID: i32 = 0;
+
+/// Menu ID of "Rename…" in the workspace menu.
+pub const RENAME_ID: i32 = 1;
+
+/// Menu ID of "Close all windows" in the workspace menu.
+const CLOSE_WINDOWS_ID: i32 = 2;
+
+/// Menu ID of "Overview" in the workspace menu.
+pub const OVERVIEW_ID: i32 = 3;
+
+/// Marks special workspace buttons
+const SPECIAL_ICON: &str = "✦";
+
 // ============================================================================
 // Types
 // ============================================================================
@@ -45,14 +122,52 @@ const ROW_PADDING: f32 = 3.0;
 /// The main Workspaces component state.
 #[derive(Debug, Clone)]
 pub struct Workspaces {
-    /// List of all available workspaces
+    /// List of all available workspaces, without the special ones
     workspaces: Vec<WorkspaceInfo>,
+    /// Special workspaces, by name
+    specials: Vec<SpecialWorkspace>,
     /// ID of the currently active workspace
     active_workspace_id: Option<WorkspaceId>,
     /// ID of the previous workspace (for animation)
     previous_workspace_id: Option<WorkspaceId>,
-    /// Animation progress (0.0 = old workspace, 1.0 = new workspace)
-    animation_progress: f32,
+    /// Indicator slide from the old workspace (0.0) to the new one (1.0)
+    animation: Animation,
+    /// `[animations]` settings for new slides
+    animations: AnimationsConfig,
+    /// Capture workspace previews for the overview
+    previews_enabled: bool,
+    /// Last captured preview of each workspace
+    previews: HashMap<WorkspaceId, image::Handle>,
+    /// Show the back/forward buttons
+    history_enabled: bool,
+    /// Recently used workspaces, most recent last
+    back: VecDeque<WorkspaceId>,
+    /// Workspaces left with the back button, most recent last
+    forward: Vec<WorkspaceId>,
+    /// Workspace a back/forward switch is heading to; reaching it does not
+    /// count as a new visit
+    navigating_to: Option<WorkspaceId>,
+    /// Switch workspaces on scroll
+    scroll_enabled: bool,
+    /// Scroll past workspaces without windows
+    scroll_skip_empty: bool,
+    /// Scroll from the last workspace to the first and back
+    scroll_wrap: bool,
+    /// Leave out workspaces without windows, except the active one
+    hide_empty: bool,
+    /// Show the icons of the windows on each workspace
+    app_icons: bool,
+    /// Most workspace buttons, around the active workspace
+    max_shown: Option<usize>,
+    /// Outputs with a bar, whose buttons are measured
+    outputs: Vec<String>,
+    /// Laid out bounds of the workspace buttons on each output, relative
+    /// to the button row; the indicator uses estimates for buttons not
+    /// measured yet
+    measured: HashMap<String, HashMap<WorkspaceId, Rectangle>>,
+    /// Workspace being renamed and the name typed so far; the overview
+    /// popup shows the rename form meanwhile
+    renaming: Option<(WorkspaceId, String)>,
 }
 
 /// Simplified workspace information.
@@ -63,6 +178,40 @@ pub(crate) struct WorkspaceInfo {
     pub(crate) monitor: String,
     pub(crate) windows: u16,
     id_string: String,  // Cached for rendering
+    icons: Vec<image::Handle>, // Of the windows' applications, if enabled
+}
+
+/// A special workspace (scratchpad).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SpecialWorkspace {
+    /// Name without the `special:` prefix; empty for the unnamed one
+    pub(crate) name: String,
+    pub(crate) windows: u16,
+    /// Shown on top of a monitor's workspace
+    pub(crate) shown: bool,
+    label: String, // Cached for rendering
+}
+
+impl SpecialWorkspace {
+    /// The special workspace Hyprland calls `full_name`, e.g. `special:term`.
+    fn new(full_name: &str, windows: u16, shown: bool) -> Self {
+        let name = full_name
+            .strip_prefix("special")
+            .map(|rest| rest.trim_start_matches(':'))
+            .unwrap_or(full_name)
+            .to_string();
+        let label = if name.is_empty() {
+            SPECIAL_ICON.to_string()
+        } else {
+            format!("{} {}", SPECIAL_ICON, name)
+        };
+        Self {
+            name,
+            windows,
+            shown,
+            label,
+        }
+    }
 }
 
 /// Messages that the Workspaces component can handle.
@@ -74,16 +223,45 @@ pub enum Message {
     #[doc(hidden)]
     WorkspacesUpdated {
         workspaces: Vec<WorkspaceInfo>,
+        specials: Vec<SpecialWorkspace>,
         active_id: Option<WorkspaceId>,
     },
     /// User clicked on a workspace to switch to it
     WorkspaceClicked(WorkspaceId),
+    /// User clicked on a special workspace to show or hide it
+    SpecialClicked(String),
     /// Workspace switch operation completed
     #[doc(hidden)]
     WorkspaceSwitched,
-    /// Animation tick for border transition
+    /// User right-clicked the workspaces (main opens the overview popup)
+    OverviewRequested,
+    /// User right-clicked a workspace (main opens its menu)
+    ContextRequested(WorkspaceId),
+    /// Workspace menu entry chosen
+    Selected(WorkspaceId, i32),
+    /// Text typed into the rename form
+    RenameInput(String),
+    /// Rename form submitted; an empty name resets the workspace's name
+    RenameSubmitted,
+    /// Go to the previously used workspace
+    Back,
+    /// Undo a [`Message::Back`]
+    Forward,
+    /// User scrolled on the workspaces
+    Scrolled(ScrollDelta),
+    /// Capture a preview of the active workspace, if it is still the given one
+    #[doc(hidden)]
+    CapturePreview(WorkspaceId),
+    /// Preview capture finished
+    #[doc(hidden)]
+    PreviewCaptured(WorkspaceId, Option<image::Handle>),
+    /// Bounds of the button row (`None` workspace) and of the workspace
+    /// buttons on an output, as laid out
     #[doc(hidden)]
-    AnimationTick,
+    Measured(String, Vec<(Option<WorkspaceId>, Option<Rectangle>)>),
+    /// Frame redraw while the border transition is running
+    #[doc(hidden)]
+    AnimationFrame(Instant),
 }
 
 // ============================================================================
@@ -94,42 +272,298 @@ impl Default for Workspaces {
     fn default() -> Self {
         Self {
             workspaces: Vec::new(),
+            specials: Vec::new(),
             active_workspace_id: None,
             previous_workspace_id: None,
-            animation_progress: 1.0, // Start fully transitioned
+            animation: Animation::finished(),
+            animations: AnimationsConfig::default(),
+            previews_enabled: false,
+            previews: HashMap::new(),
+            history_enabled: false,
+            back: VecDeque::new(),
+            forward: Vec::new(),
+            navigating_to: None,
+            scroll_enabled: true,
+            scroll_skip_empty: false,
+            scroll_wrap: false,
+            hide_empty: false,
+            app_icons: false,
+            max_shown: None,
+            outputs: Vec::new(),
+            measured: HashMap::new(),
+            renaming: None,
         }
     }
 }
 
 impl Workspaces {
+    /// Enable or disable preview capturing; disabling drops cached previews.
+    pub fn set_previews(&mut self, enabled: bool) {
+        self.previews_enabled = enabled;
+        if !enabled {
+            self.previews.clear();
+        }
+    }
+
+    /// Apply the `[animations]` settings to the next indicator slide.
+    pub fn set_animations(&mut self, animations: AnimationsConfig) {
+        self.animations = animations;
+    }
+
+    /// Show or hide the back/forward buttons. The history is kept either way.
+    pub fn set_history(&mut self, enabled: bool) {
+        self.history_enabled = enabled;
+    }
+
+    /// Apply the scroll settings of `[workspaces]`.
+    pub fn set_scroll(&mut self, config: &WorkspacesConfig) {
+        self.scroll_enabled = config.scroll;
+        self.scroll_skip_empty = config.scroll_skip_empty;
+        self.scroll_wrap = config.scroll_wrap;
+    }
+
+    /// Show or hide workspaces without windows.
+    pub fn set_hide_empty(&mut self, hide_empty: bool) {
+        self.hide_empty = hide_empty;
+    }
+
+    /// Show or hide window icons in the workspace buttons, from the next
+    /// refresh on.
+    pub fn set_app_icons(&mut self, enabled: bool) {
+        self.app_icons = enabled;
+    }
+
+    /// Limit the workspace buttons; `None` shows all.
+    pub fn set_max_shown(&mut self, max_shown: Option<usize>) {
+        self.max_shown = max_shown;
+    }
+
+    /// Workspaces shown in the bar and the overview.
+    fn visible_workspaces(&self) -> impl Iterator<Item = &WorkspaceInfo> {
+        self.workspaces.iter().filter(|workspace| {
+            !self.hide_empty
+                || workspace.windows > 0
+                || Some(workspace.id) == self.active_workspace_id
+        })
+    }
+
+    /// Capture the active workspace now, e.g. when the overview opens.
+    pub fn capture_active(&self) -> Task<Message> {
+        match self.active_workspace_id {
+            Some(id) if self.previews_enabled => Task::done(Message::CapturePreview(id)),
+            _ => Task::none(),
+        }
+    }
+
+    /// Workspaces for `clammy --state`.
+    pub fn state(&self) -> serde_json::Value {
+        let workspaces: Vec<_> = self
+            .workspaces
+            .iter()
+            .map(|workspace| {
+                serde_json::json!({
+                    "id": workspace.id,
+                    "name": workspace.name,
+                    "monitor": workspace.monitor,
+                    "windows": workspace.windows,
+                    "active": Some(workspace.id) == self.active_workspace_id,
+                })
+            })
+            .collect();
+        let specials: Vec<_> = self
+            .specials
+            .iter()
+            .map(|special| {
+                serde_json::json!({
+                    "name": special.name,
+                    "windows": special.windows,
+                    "shown": special.shown,
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "active": self.active_workspace_id,
+            "workspaces": workspaces,
+            "special": specials,
+        })
+    }
+
     /// Update the component state based on received messages.
     pub fn update(&mut self, message: Message) -> Task<Message> {
         match message {
             Message::Refresh => {
                 // Fetch workspace data asynchronously
-                Task::perform(Self::fetch_workspace_data(), |result| {
+                Task::perform(Self::fetch_workspace_data(self.app_icons), |result| {
                     Message::WorkspacesUpdated {
                         workspaces: result.0,
-                        active_id: result.1,
+                        specials: result.1,
+                        active_id: result.2,
                     }
                 })
             }
 
             Message::WorkspacesUpdated {
                 workspaces,
+                specials,
                 active_id,
             } => {
                 self.workspaces = workspaces;
+                self.specials = specials;
+                // Drop previews of workspaces that no longer exist
+                let existing = &self.workspaces;
+                self.previews
+                    .retain(|id, _| existing.iter().any(|w| w.id == *id));
 
                 // Check if workspace changed to start animation
                 if active_id != self.active_workspace_id {
+                    self.record_visit(self.active_workspace_id, active_id);
                     self.previous_workspace_id = self.active_workspace_id;
                     self.active_workspace_id = active_id;
-                    self.animation_progress = 0.0; // Start animation
+                    self.animation = self.animations.start();
+
+                    // Capture the new workspace once the switch has settled
+                    if let Some(id) = active_id.filter(|_| self.previews_enabled) {
+                        return Task::batch([
+                            self.measure_buttons(),
+                            Task::perform(tokio::time::sleep(PREVIEW_DELAY), move |_| {
+                                Message::CapturePreview(id)
+                            }),
+                        ]);
+                    }
                 } else {
                     self.active_workspace_id = active_id;
                 }
 
+                // Labels, window icons and the shown buttons may have changed
+                self.measure_buttons()
+            }
+
+            Message::Measured(output, bounds) => {
+                let row = bounds
+                    .iter()
+                    .find(|(id, _)| id.is_none())
+                    .and_then(|(_, bounds)| *bounds);
+                let Some(row) = row else {
+                    return Task::none();
+                };
+                let measured = bounds
+                    .into_iter()
+                    .filter_map(|(id, bounds)| Some((id?, bounds?)))
+                    .map(|(id, bounds)| {
+                        let relative = Rectangle {
+                            x: bounds.x - row.x,
+                            y: bounds.y - row.y,
+                            ..bounds
+                        };
+                        (id, relative)
+                    })
+                    .collect();
+                self.measured.insert(output, measured);
+                Task::none()
+            }
+
+            Message::OverviewRequested
+            | Message::ContextRequested(_)
+            | Message::Selected(_, OVERVIEW_ID) => {
+                self.renaming = None;
+                Task::none()
+            }
+
+            Message::Selected(id, MOVE_WINDOW_ID) => {
+                let dispatch = DispatchType::MoveToWorkspaceSilent(
+                    WorkspaceIdentifierWithSpecial::Id(id),
+                    None,
+                );
+                Task::perform(Self::dispatch(dispatch), |_| Message::WorkspaceSwitched)
+            }
+
+            Message::Selected(id, RENAME_ID) => {
+                let name = self
+                    .workspaces
+                    .iter()
+                    .find(|workspace| workspace.id == id)
+                    .map(|workspace| workspace.name.clone())
+                    .unwrap_or_default();
+                self.renaming = Some((id, name));
+                Task::none()
+            }
+
+            Message::Selected(id, CLOSE_WINDOWS_ID) => {
+                Task::perform(Self::close_windows(id), |_| Message::WorkspaceSwitched)
+            }
+
+            Message::Selected(..) => Task::none(),
+
+            Message::RenameInput(input) => {
+                if let Some((_, name)) = &mut self.renaming {
+                    *name = input;
+                }
+                Task::none()
+            }
+
+            Message::RenameSubmitted => {
+                let Some((id, name)) = self.renaming.take() else {
+                    return Task::none();
+                };
+                Task::perform(Self::rename_workspace(id, name), |_| {
+                    Message::WorkspaceSwitched
+                })
+            }
+
+            Message::Back => {
+                let (Some(current), Some(target)) = (self.active_workspace_id, self.pop_back())
+                else {
+                    return Task::none();
+                };
+                self.forward.push(current);
+                self.navigate_to(target)
+            }
+
+            Message::Forward => {
+                let (Some(current), Some(target)) = (self.active_workspace_id, self.pop_forward())
+                else {
+                    return Task::none();
+                };
+                self.push_back(current);
+                self.navigate_to(target)
+            }
+
+            Message::Scrolled(delta) => {
+                let y = match delta {
+                    ScrollDelta::Lines { y, .. } | ScrollDelta::Pixels { y, .. } => y,
+                };
+                if y == 0.0 {
+                    return Task::none();
+                }
+                match self.scroll_target(y > 0.0) {
+                    Some(target) => Task::perform(Self::switch_workspace(target), |_| {
+                        Message::WorkspaceSwitched
+                    }),
+                    None => Task::none(),
+                }
+            }
+
+            Message::CapturePreview(id) => {
+                if self.active_workspace_id != Some(id) {
+                    return Task::none();
+                }
+                let Some(monitor) = self
+                    .workspaces
+                    .iter()
+                    .find(|w| w.id == id)
+                    .map(|w| w.monitor.clone())
+                else {
+                    return Task::none();
+                };
+                Task::perform(capture_preview(id, monitor), move |handle| {
+                    Message::PreviewCaptured(id, handle)
+                })
+            }
+
+            Message::PreviewCaptured(id, handle) => {
+                if let Some(handle) = handle.filter(|_| self.previews_enabled) {
+                    self.previews.insert(id, handle);
+                }
                 Task::none()
             }
 
@@ -140,29 +574,108 @@ impl Workspaces {
                 })
             }
 
+            Message::SpecialClicked(name) => {
+                Task::perform(Self::toggle_special(name), |_| Message::WorkspaceSwitched)
+            }
+
             Message::WorkspaceSwitched => {
                 // Refresh workspace list after switching
                 Task::done(Message::Refresh)
             }
 
-            Message::AnimationTick => {
-                if self.animation_progress < 1.0 {
-                    // Increment animation progress (smooth out over ~200ms at 60fps)
-                    self.animation_progress = (self.animation_progress + 0.15).min(1.0);
-
-                    // Clear previous workspace when animation completes
-                    if self.animation_progress >= 1.0 {
-                        self.previous_workspace_id = None;
-                    }
+            Message::AnimationFrame(now) => {
+                self.animation.advance(now);
+                // Clear previous workspace when animation completes
+                if !self.animation.is_running() {
+                    self.previous_workspace_id = None;
                 }
                 Task::none()
             }
         }
     }
 
+    /// Record leaving `from` for `to` in the history, unless the switch
+    /// came from the back/forward buttons.
+    fn record_visit(&mut self, from: Option<WorkspaceId>, to: Option<WorkspaceId>) {
+        if self.navigating_to.take().is_some_and(|target| Some(target) == to) {
+            return;
+        }
+        if let Some(from) = from {
+            self.push_back(from);
+            self.forward.clear();
+        }
+    }
+
+    fn push_back(&mut self, id: WorkspaceId) {
+        if self.back.back() != Some(&id) {
+            self.back.push_back(id);
+        }
+        if self.back.len() > MAX_HISTORY {
+            self.back.pop_front();
+        }
+    }
+
+    /// Most recent workspace in the back history that still exists.
+    fn pop_back(&mut self) -> Option<WorkspaceId> {
+        while let Some(id) = self.back.pop_back() {
+            if self.exists(id) {
+                return Some(id);
+            }
+        }
+        None
+    }
+
+    /// Most recent workspace in the forward history that still exists.
+    fn pop_forward(&mut self) -> Option<WorkspaceId> {
+        while let Some(id) = self.forward.pop() {
+            if self.exists(id) {
+                return Some(id);
+            }
+        }
+        None
+    }
+
+    fn exists(&self, id: WorkspaceId) -> bool {
+        self.workspaces.iter().any(|workspace| workspace.id == id)
+    }
+
+    /// Workspace after (or before) the active one, skipping empty ones if
+    /// configured. `None` at either end unless scrolling wraps around.
+    fn scroll_target(&self, forward: bool) -> Option<WorkspaceId> {
+        let current = self.active_workspace_id?;
+        let candidates: Vec<WorkspaceId> = self
+            .visible_workspaces()
+            .filter(|w| w.id == current || !self.scroll_skip_empty || w.windows > 0)
+            .map(|w| w.id)
+            .collect();
+        let index = candidates.iter().position(|id| *id == current)?;
+        let target = if forward {
+            index.checked_add(1).filter(|next| *next < candidates.len())
+        } else {
+            index.checked_sub(1)
+        };
+        let target = match target {
+            Some(target) => target,
+            None if self.scroll_wrap => {
+                if forward {
+                    0
+                } else {
+                    candidates.len() - 1
+                }
+            }
+            None => return None,
+        };
+        Some(candidates[target]).filter(|id| *id != current)
+    }
+
+    fn navigate_to(&mut self, target: WorkspaceId) -> Task<Message> {
+        self.navigating_to = Some(target);
+        Task::perform(Self::switch_workspace(target), |_| Message::WorkspaceSwitched)
+    }
+
     /// Render the workspaces component.
-    pub fn view(&self) -> Element<'_, Message> {
-        let workspace_buttons = self.create_workspace_buttons();
+    pub fn view(&self, output: &str) -> Element<'_, Message> {
+        let workspace_buttons = self.create_workspace_buttons(output);
 
         let buttons_content = workspace_buttons
             .spacing(BUTTON_SPACING as u16)
@@ -170,28 +683,226 @@ impl Workspaces {
             .align_y(iced::Alignment::Center);
 
         // Create moving indicator overlay
-        let indicator = self.create_moving_indicator();
+        let indicator = self.create_moving_indicator(output);
 
         // Stack indicator on top of buttons
         let stacked = stack![buttons_content, indicator];
 
-        container(stacked)
+        let content = container(stacked)
+            .id(row_id(output))
             .width(Length::Shrink)
             .height(Length::Fill)
-            .center_y(Length::Fill)
+            .center_y(Length::Fill);
+
+        let mut workspaces = mouse_area(content).on_right_press(Message::OverviewRequested);
+        if self.scroll_enabled {
+            workspaces = workspaces.on_scroll(Message::Scrolled);
+        }
+        if !self.history_enabled {
+            return workspaces.into();
+        }
+
+        row![
+            self.history_button("‹", self.back.len(), Message::Back),
+            workspaces,
+            self.history_button("›", self.forward.len(), Message::Forward),
+        ]
+        .align_y(iced::Alignment::Center)
+        .into()
+    }
+
+    /// Back or forward button, labelled with the history depth.
+    fn history_button(&self, arrow: &str, depth: usize, message: Message) -> Element<'_, Message> {
+        let theme = get_theme();
+        let (text_color, muted, hover_bg) = (theme.text(), theme.muted(), theme.hover());
+        let label = if depth == 0 {
+            arrow.to_string()
+        } else {
+            format!("{}{}", arrow, depth)
+        };
+
+        button(text(label).font(theme.font()).size(TEXT_SIZE))
+            .padding([BUTTON_PADDING_V as u16, 4])
+            .style(move |_theme, status| button::Style {
+                background: match status {
+                    button::Status::Hovered | button::Status::Pressed => Some(hover_bg.into()),
+                    _ => None,
+                },
+                border: Border {
+                    radius: 4.0.into(),
+                    ..Border::default()
+                },
+                text_color: if depth == 0 { muted } else { text_color },
+                shadow: Default::default(),
+            })
+            .on_press_maybe((depth > 0).then_some(message))
+            .into()
+    }
+
+    /// Entries of the menu of a workspace.
+    pub fn menu_items(&self, id: WorkspaceId) -> Vec<MenuItem> {
+        let has_windows = self
+            .workspaces
+            .iter()
+            .any(|workspace| workspace.id == id && workspace.windows > 0);
+        let entry = |id: i32, label: &str, enabled: bool| MenuItem {
+            id,
+            label: label.to_string(),
+            enabled,
+            is_separator: false,
+            is_checkable: false,
+            is_radio: false,
+            is_checked: false,
+            icon: None,
+            shortcut: None,
+            submenu: Vec::new(),
+        };
+        let separator = MenuItem {
+            is_separator: true,
+            ..entry(0, "", true)
+        };
+
+        vec![
+            entry(
+                MOVE_WINDOW_ID,
+                "Move focused window here",
+                self.active_workspace_id != Some(id),
+            ),
+            entry(RENAME_ID, "Rename…", true),
+            entry(CLOSE_WINDOWS_ID, "Close all windows", has_windows),
+            separator,
+            entry(OVERVIEW_ID, "Overview", true),
+        ]
+    }
+
+    /// Size (width, height) of the overview popup content.
+    pub fn overview_size(&self, font_size: f32) -> (u32, u32) {
+        if self.renaming.is_some() {
+            return (RENAME_WIDTH as u32, (font_size * 2.0 + 36.0) as u32);
+        }
+        let count = self.visible_workspaces().count();
+        let columns = count.clamp(1, OVERVIEW_COLUMNS);
+        let rows = count.div_ceil(OVERVIEW_COLUMNS).max(1);
+        let tile_width = PREVIEW_WIDTH + 12.0;
+        let tile_height = PREVIEW_HEIGHT + font_size + 16.0;
+        (
+            (tile_width * columns as f32 + 24.0) as u32,
+            (tile_height * rows as f32 + 16.0) as u32,
+        )
+    }
+
+    /// Render the overview popup: a grid of workspace thumbnails, or the
+    /// rename form while a workspace is renamed.
+    pub fn view_overview(&self) -> Element<'_, Message> {
+        if let Some((id, name)) = &self.renaming {
+            return Self::view_rename(*id, name);
+        }
+        let theme = get_theme();
+        let font_size = theme.font_size();
+        let text_color = theme.text();
+        let muted = theme.muted();
+        let accent = theme.accent();
+        let hover_bg = theme.hover();
+        let border_color = theme.border();
+
+        let tiles: Vec<Element<'_, Message>> = self
+            .visible_workspaces()
+            .map(|workspace| {
+                let is_active = self.active_workspace_id == Some(workspace.id);
+
+                let preview: Element<'_, Message> = match self.previews.get(&workspace.id) {
+                    Some(handle) => image(handle.clone())
+                        .width(Length::Fixed(PREVIEW_WIDTH))
+                        .height(Length::Fixed(PREVIEW_HEIGHT))
+                        .into(),
+                    None => container(text("󰍹").size(font_size * 2.0).color(muted))
+                        .center_x(Length::Fixed(PREVIEW_WIDTH))
+                        .center_y(Length::Fixed(PREVIEW_HEIGHT))
+                        .style(move |_theme| container::Style {
+                            border: Border {
+                                color: border_color,
+                                width: 1.0,
+                                radius: 4.0.into(),
+                            },
+                            ..Default::default()
+                        })
+                        .into(),
+                };
+
+                let label = text(&workspace.name).size(font_size).color(if is_active {
+                    accent
+                } else {
+                    text_color
+                });
+
+                button(
+                    column![preview, label]
+                        .spacing(4)
+                        .align_x(iced::Alignment::Center),
+                )
+                .padding(4)
+                .style(move |_theme, status| button::Style {
+                    background: match status {
+                        button::Status::Hovered | button::Status::Pressed => Some(hover_bg.into()),
+                        _ => None,
+                    },
+                    text_color,
+                    border: Border {
+                        color: accent,
+                        width: if is_active { 1.0 } else { 0.0 },
+                        radius: 4.0.into(),
+                    },
+                    shadow: Default::default(),
+                })
+                .on_press(Message::WorkspaceClicked(workspace.id))
+                .into()
+            })
+            .collect();
+
+        let mut rows = Vec::new();
+        let mut tiles = tiles.into_iter().peekable();
+        while tiles.peek().is_some() {
+            let tile_row: Vec<_> = tiles.by_ref().take(OVERVIEW_COLUMNS).collect();
+            rows.push(Row::from_vec(tile_row).spacing(4).into());
+        }
+
+        container(Column::from_vec(rows).spacing(4))
+            .padding(4)
+            .width(Length::Fill)
             .into()
     }
 
+    /// Form for the new name of a workspace.
+    fn view_rename(id: WorkspaceId, name: &str) -> Element<'_, Message> {
+        let theme = get_theme();
+        let font_size = theme.font_size();
+
+        column![
+            text(format!("Rename workspace {}", id))
+                .size(font_size * 0.85)
+                .color(theme.muted()),
+            text_input(&id.to_string(), name)
+                .on_input(Message::RenameInput)
+                .on_submit(Message::RenameSubmitted)
+                .size(font_size),
+        ]
+        .spacing(4)
+        .padding(8)
+        .width(Length::Fill)
+        .into()
+    }
+
     /// Subscribe to Hyprland workspace events.
     pub fn subscription(&self) -> Subscription<Message> {
         let event_subscription = HyprlandSubscription::new("hyprland-workspace-events")
             .on_any_workspace_event(|| Message::Refresh)
+            // Window counts and icons follow the windows
+            .on_windows_changed(|| Message::Refresh)
             .build();
 
         // Add animation subscription when transition is in progress
-        let animation_subscription = if self.animation_progress < 1.0 {
-            iced::time::every(std::time::Duration::from_millis(16))
-                .map(|_| Message::AnimationTick)
+        let animation_subscription = if self.animation.is_running() {
+            iced::window::frames().map(Message::AnimationFrame)
         } else {
             Subscription::none()
         };
@@ -204,7 +915,31 @@ impl Workspaces {
     // ------------------------------------------------------------------------
 
     /// Fetch workspace data from Hyprland.
-    async fn fetch_workspace_data() -> (Vec<WorkspaceInfo>, Option<WorkspaceId>) {
+    async fn fetch_workspace_data(app_icons: bool) -> (
+        Vec<WorkspaceInfo>,
+        Vec<SpecialWorkspace>,
+        Option<WorkspaceId>,
+    ) {
+        // Special workspaces shown on a monitor; the name is empty for none
+        let shown: Vec<String> = match Monitors::get() {
+            Ok(monitors) => monitors
+                .into_iter()
+                .map(|monitor| monitor.special_workspace.name)
+                .filter(|name| !name.is_empty())
+                .collect(),
+            Err(e) => {
+                eprintln!("Failed to fetch monitors: {:?}", e);
+                Vec::new()
+            }
+        };
+
+        let mut icons = if app_icons {
+            Self::fetch_app_icons()
+        } else {
+            HashMap::new()
+        };
+
+        let mut specials = Vec::new();
         let workspaces = match HyprWorkspaces::get() {
             Ok(ws) => {
                 let mut info: Vec<WorkspaceInfo> = ws
@@ -215,9 +950,21 @@ impl Workspaces {
                         name: w.name,
                         monitor: w.monitor,
                         windows: w.windows,
+                        icons: icons.remove(&w.id).unwrap_or_default(),
                     })
                     .collect();
 
+                // Special workspaces have negative IDs
+                info.retain(|w| {
+                    if w.id >= 0 {
+                        return true;
+                    }
+                    let is_shown = shown.contains(&w.name);
+                    specials.push(SpecialWorkspace::new(&w.name, w.windows, is_shown));
+                    false
+                });
+                specials.sort_by(|a, b| a.name.cmp(&b.name));
+
                 // Sort workspaces by ID for consistent display
                 info.sort_by_key(|w| w.id);
                 info
@@ -236,7 +983,64 @@ impl Workspaces {
             }
         };
 
-        (workspaces, active_id)
+        (workspaces, specials, active_id)
+    }
+
+    /// Icons of the applications with windows on each workspace, one per
+    /// application.
+    fn fetch_app_icons() -> HashMap<WorkspaceId, Vec<image::Handle>> {
+        let clients = match Clients::get() {
+            Ok(clients) => clients,
+            Err(e) => {
+                eprintln!("Failed to fetch windows: {:?}", e);
+                return HashMap::new();
+            }
+        };
+        let mut classes: HashMap<WorkspaceId, Vec<String>> = HashMap::new();
+        for client in clients {
+            let workspace = classes.entry(client.workspace.id).or_default();
+            if workspace.len() < MAX_APP_ICONS && !workspace.contains(&client.class) {
+                workspace.push(client.class);
+            }
+        }
+        classes
+            .into_iter()
+            .map(|(id, classes)| {
+                let icons = classes.iter().filter_map(|class| app_icons::lookup(class));
+                (id, icons.collect())
+            })
+            .collect()
+    }
+
+    /// Run a Hyprland dispatcher, logging failures.
+    async fn dispatch(dispatch: DispatchType<'_>) {
+        if let Err(e) = Dispatch::call_async(dispatch).await {
+            eprintln!("Failed to dispatch to Hyprland: {:?}", e);
+        }
+    }
+
+    /// Close every window on a workspace.
+    async fn close_windows(workspace_id: WorkspaceId) {
+        let clients = match Clients::get_async().await {
+            Ok(clients) => clients,
+            Err(e) => {
+                eprintln!("Failed to fetch windows: {:?}", e);
+                return;
+            }
+        };
+        for client in clients {
+            if client.workspace.id == workspace_id {
+                let window = WindowIdentifier::Address(client.address);
+                Self::dispatch(DispatchType::CloseWindow(window)).await;
+            }
+        }
+    }
+
+    /// Rename a workspace; an empty name resets it to its ID.
+    async fn rename_workspace(workspace_id: WorkspaceId, name: String) {
+        let name = name.trim();
+        let name = (!name.is_empty()).then_some(name);
+        Self::dispatch(DispatchType::RenameWorkspace(workspace_id, name)).await;
     }
 
     /// Switch to a specific workspace.
@@ -248,15 +1052,38 @@ impl Workspaces {
         }
     }
 
-    /// Create workspace button widgets.
-    fn create_workspace_buttons(&self) -> Row<'_, Message> {
-        let buttons = self.workspaces.iter().map(|workspace| {
+    /// Show or hide a special workspace by its name without `special:`.
+    async fn toggle_special(name: String) {
+        // The unnamed special workspace is toggled without a name
+        let target = Some(name.clone()).filter(|name| !name.is_empty());
+        let dispatch = DispatchType::ToggleSpecialWorkspace(target);
+
+        if let Err(e) = Dispatch::call_async(dispatch).await {
+            eprintln!("Failed to toggle special workspace {:?}: {:?}", name, e);
+        }
+    }
+
+    /// Create workspace button widgets, special workspaces last so the
+    /// indicator offsets of the numbered ones stay the same.
+    fn create_workspace_buttons(&self, output: &str) -> Row<'_, Message> {
+        let visible: Vec<&WorkspaceInfo> = self.visible_workspaces().collect();
+        let range = self.button_range();
+        let mut buttons = Vec::new();
+        // "…" ends switch to the next workspace without a button
+        if range.start > 0 {
+            buttons.push(Self::create_ellipsis_button(visible[range.start - 1].id));
+        }
+        for workspace in &visible[range.clone()] {
             let is_active = self.active_workspace_id == Some(workspace.id);
             let is_previous = self.previous_workspace_id == Some(workspace.id);
-            self.create_workspace_button(workspace, is_active, is_previous)
-        });
+            buttons.push(self.create_workspace_button(output, workspace, is_active, is_previous));
+        }
+        if let Some(next) = visible.get(range.end) {
+            buttons.push(Self::create_ellipsis_button(next.id));
+        }
+        buttons.extend(self.specials.iter().map(Self::create_special_button));
 
-        Row::from_vec(buttons.collect())
+        Row::from_vec(buttons)
             .spacing(BUTTON_SPACING as u16)
             .align_y(iced::Alignment::Center)
     }
@@ -264,19 +1091,83 @@ impl Workspaces {
     /// Create a single workspace button.
     fn create_workspace_button<'a>(
         &self,
+        output: &str,
         workspace: &'a WorkspaceInfo,
         is_active: bool,
         is_previous: bool,
     ) -> Element<'a, Message> {
-        let label = text(&workspace.id_string).size(TEXT_SIZE);
-        let animation_progress = self.animation_progress;
+        let label = text(&workspace.id_string)
+            .font(get_theme().font())
+            .size(TEXT_SIZE);
+        let icons = workspace.icons.iter().map(|handle| {
+            image(handle.clone())
+                .width(Length::Fixed(APP_ICON_SIZE))
+                .height(Length::Fixed(APP_ICON_SIZE))
+                .into()
+        });
+        let content = Row::with_children(std::iter::once(label.into()).chain(icons))
+            .spacing(APP_ICON_SPACING as u16)
+            .align_y(iced::Alignment::Center);
+        let animation_progress = self.animation.progress();
 
-        button(label)
+        let workspace_button = button(content)
             .padding([BUTTON_PADDING_V as u16, BUTTON_PADDING_H as u16])
             .style(move |theme: &iced::Theme, status| {
                 Self::workspace_button_style(theme, status, is_active, is_previous, animation_progress)
             })
-            .on_press(Message::WorkspaceClicked(workspace.id))
+            .on_press(Message::WorkspaceClicked(workspace.id));
+        let workspace_button =
+            mouse_area(workspace_button).on_right_press(Message::ContextRequested(workspace.id));
+        container(workspace_button)
+            .id(button_id(output, workspace.id))
+            .into()
+    }
+
+    /// Create a "…" button switching to a workspace without a button.
+    fn create_ellipsis_button<'a>(target: WorkspaceId) -> Element<'a, Message> {
+        let theme = get_theme();
+        let (muted, text_color, hover_bg) = (theme.muted(), theme.text(), theme.hover());
+
+        button(text(ELLIPSIS).font(theme.font()).size(TEXT_SIZE))
+            .padding([BUTTON_PADDING_V as u16, BUTTON_PADDING_H as u16])
+            .style(move |_theme, status| match status {
+                button::Status::Hovered | button::Status::Pressed => button::Style {
+                    background: Some(hover_bg.into()),
+                    text_color,
+                    ..button::Style::default()
+                },
+                _ => button::Style {
+                    text_color: muted,
+                    ..button::Style::default()
+                },
+            })
+            .on_press(Message::WorkspaceClicked(target))
+            .into()
+    }
+
+    /// Create a special workspace button, in the accent color while shown.
+    fn create_special_button(special: &SpecialWorkspace) -> Element<'_, Message> {
+        let theme = get_theme();
+        let (accent, muted, hover_bg) = (theme.accent(), theme.muted(), theme.hover());
+        let label = text(&special.label).font(theme.font()).size(TEXT_SIZE);
+        let shown = special.shown;
+
+        button(label)
+            .padding([BUTTON_PADDING_V as u16, BUTTON_PADDING_H as u16])
+            .style(move |_theme, status| button::Style {
+                background: match status {
+                    button::Status::Hovered | button::Status::Pressed => Some(hover_bg.into()),
+                    _ => None,
+                },
+                text_color: if shown { accent } else { muted },
+                border: Border {
+                    color: accent,
+                    width: if shown { 1.0 } else { 0.0 },
+                    radius: 4.0.into(),
+                },
+                shadow: Default::default(),
+            })
+            .on_press(Message::SpecialClicked(special.name.clone()))
             .into()
     }
 
@@ -313,39 +1204,102 @@ impl Workspaces {
         }
     }
 
-    /// Find the index of a workspace by its ID in the sorted workspace list.
-    fn find_workspace_index(&self, workspace_id: WorkspaceId) -> usize {
-        self.workspaces
-            .iter()
-            .position(|w| w.id == workspace_id)
-            .unwrap_or(0)
+    /// Width of a workspace button: its label, window icons and padding.
+    fn button_width(workspace: &WorkspaceInfo) -> f32 {
+        BUTTON_WIDTH + workspace.icons.len() as f32 * (APP_ICON_SIZE + APP_ICON_SPACING)
+    }
+
+    /// Look up where the workspace buttons were laid out on each bar.
+    fn measure_buttons(&self) -> Task<Message> {
+        let range = self.button_range();
+        Task::batch(self.outputs.iter().map(|output| {
+            let buttons = self
+                .visible_workspaces()
+                .skip(range.start)
+                .take(range.len())
+                .map(|workspace| {
+                    let id = workspace.id;
+                    container::visible_bounds(button_id(output, id))
+                        .map(move |bounds| (Some(id), bounds))
+                });
+            let row = container::visible_bounds(row_id(output)).map(|bounds| (None, bounds));
+            let output = output.clone();
+            Task::batch(std::iter::once(row).chain(buttons))
+                .collect()
+                .map(move |bounds| Message::Measured(output.clone(), bounds))
+        }))
+    }
+
+    /// Indices of the shown workspaces that get a button: all of them,
+    /// or `max_shown` around the active workspace.
+    fn button_range(&self) -> Range<usize> {
+        let count = self.visible_workspaces().count();
+        let Some(max) = self.max_shown.filter(|max| *max > 0 && *max < count) else {
+            return 0..count;
+        };
+        let active = self
+            .active_workspace_id
+            .and_then(|id| self.visible_workspaces().position(|w| w.id == id))
+            .unwrap_or(0);
+        let start = active.saturating_sub(max / 2).min(count - max);
+        start..start + max
+    }
+
+    /// Horizontal offset and width of a workspace's button, if it has one:
+    /// as measured, else estimated from its label and icons.
+    fn button_span(&self, output: &str, workspace_id: WorkspaceId) -> Option<(f32, f32)> {
+        let range = self.button_range();
+        let mut offset = ROW_PADDING;
+        // The "…" button is as wide as a one-digit workspace button
+        if range.start > 0 {
+            offset += BUTTON_WIDTH + BUTTON_SPACING;
+        }
+        for workspace in self.visible_workspaces().skip(range.start).take(range.len()) {
+            let width = Self::button_width(workspace);
+            if workspace.id == workspace_id {
+                let measured = self
+                    .measured
+                    .get(output)
+                    .and_then(|measured| measured.get(&workspace_id));
+                return Some(measured.map_or((offset, width), |bounds| (bounds.x, bounds.width)));
+            }
+            offset += width + BUTTON_SPACING;
+        }
+        None
     }
 
     /// Create the moving border indicator overlay.
-    fn create_moving_indicator(&self) -> Element<'_, Message> {
+    fn create_moving_indicator(&self, output: &str) -> Element<'_, Message> {
         use iced::widget::{horizontal_space, Space};
 
         if let Some(active_id) = self.active_workspace_id {
             let theme = get_theme();
             let accent = theme.accent();
 
-            let active_index = self.find_workspace_index(active_id);
-            let prev_index = self
+            let (active_offset, active_width) = self
+                .button_span(output, active_id)
+                .unwrap_or((ROW_PADDING, BUTTON_WIDTH));
+            // Slide from where the previous workspace's button is, if it has one
+            let (prev_offset, prev_width) = self
                 .previous_workspace_id
-                .map(|id| self.find_workspace_index(id))
-                .unwrap_or(active_index);
+                .and_then(|id| self.button_span(output, id))
+                .unwrap_or((active_offset, active_width));
 
-            // Interpolate position between old and new workspace
-            let interpolated_pos =
-                prev_index as f32 + (active_index as f32 - prev_index as f32) * self.animation_progress;
+            // Interpolate position and width between old and new workspace
+            let eased = self.animation.eased();
+            let offset = prev_offset + (active_offset - prev_offset) * eased;
+            let width = prev_width + (active_width - prev_width) * eased;
 
-            // Calculate horizontal offset using constants
-            let offset = ROW_PADDING + interpolated_pos * (BUTTON_WIDTH + BUTTON_SPACING);
+            let height = self
+                .measured
+                .get(output)
+                .and_then(|measured| measured.get(&active_id))
+                .map_or(TEXT_SIZE, |bounds| bounds.height - BUTTON_PADDING_V * 2.0);
 
             // Create indicator with dimensions matching the button exactly
             let indicator_box = container(Space::new(
-                Length::Fixed(TEXT_WIDTH_APPROX),
-                Length::Fixed(TEXT_SIZE),
+                Length::Fixed(width - BUTTON_PADDING_H * 2.0),
+                Length::Fixed(height),
             ))
             .padding([BUTTON_PADDING_V as u16, BUTTON_PADDING_H as u16])
             .style(move |_theme| container::Style {
@@ -370,3 +1324,397 @@ impl Workspaces {
     }
 
 }
+
+impl Module for Workspaces {
+    type Message = Message;
+
+    fn show(&self, output: &str) -> Element<'_, Message> {
+        self.view(output)
+    }
+
+    fn relayout(&mut self, outputs: &[String]) -> Task<Message> {
+        self.outputs = outputs.to_vec();
+        self.measure_buttons()
+    }
+
+    fn handle(
+        &mut self,
+        message: Message,
+        _context: &Context<'_>,
+        actions: &mut Vec<Action>,
+    ) -> Task<Message> {
+        match message {
+            Message::OverviewRequested | Message::Selected(_, OVERVIEW_ID) => {
+                actions.push(Action::ToggleWorkspaceOverview)
+            }
+            Message::ContextRequested(id) => actions.push(Action::OpenMenu {
+                owner: MenuOwner::Workspace(id),
+                items: self.menu_items(id),
+            }),
+            // The rename form is shown in the overview popup
+            Message::Selected(_, RENAME_ID) => actions.push(Action::OpenWorkspaceOverview),
+            // Switching from the overview or renaming closes it
+            Message::WorkspaceClicked(_) | Message::RenameSubmitted => {
+                actions.push(Action::CloseWorkspaceOverview)
+            }
+            _ => {}
+        }
+        actions.push(Action::WorkspacesChanged);
+        self.update(message)
+    }
+
+    // Workspace events also drive `bar.hide_on_workspaces`
+    fn watch(&self, bar: &BarState<'_>) -> Subscription<Message> {
+        if bar.config.modules.is_enabled(&ModuleKind::Workspaces)
+            || !bar.config.bar.hide_on_workspaces.is_empty()
+        {
+            self.subscription()
+        } else {
+            Subscription::none()
+        }
+    }
+
+    fn reconfigure(&mut self, config: &Config) {
+        self.set_previews(config.workspaces.previews);
+        self.set_history(config.workspaces.history);
+        self.set_scroll(&config.workspaces);
+        self.set_hide_empty(config.workspaces.hide_empty);
+        self.set_app_icons(config.workspaces.app_icons);
+        self.set_max_shown(config.workspaces.max_shown);
+        self.set_animations(config.animations.clone());
+    }
+
+    fn first_lookup(&self) -> Option<(Lookup, Message)> {
+        Some((Lookup::Startup, Message::Refresh))
+    }
+
+    fn menu_message(&self, owner: &MenuOwner, menu_id: i32) -> Option<Message> {
+        match owner {
+            MenuOwner::Workspace(id) => Some(Message::Selected(*id, menu_id)),
+            _ => None,
+        }
+    }
+}
+
+/// Widget ID of the row of workspace buttons on an output's bar.
+fn row_id(output: &str) -> container::Id {
+    container::Id::new(format!("workspaces-row-{}", output))
+}
+
+/// Widget ID of a workspace's button on an output's bar.
+fn button_id(output: &str, id: WorkspaceId) -> container::Id {
+    container::Id::new(format!("workspace-button-{}-{}", output, id))
+}
+
+/// Capture the given monitor with `grim` as a downscaled JPEG.
+async fn capture_preview(id: WorkspaceId, monitor: String) -> Option<image::Handle> {
+    let output = tokio::process::Command::new("grim")
+        .args(["-o", &monitor, "-s", PREVIEW_CAPTURE_SCALE, "-t", "jpeg", "-"])
+        .output()
+        .await;
+    match output {
+        Ok(output) if output.status.success() => Some(image::Handle::from_bytes(output.stdout)),
+        Ok(output) => {
+            eprintln!(
+                "Failed to capture workspace {}: {}",
+                id,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            None
+        }
+        Err(e) => {
+            eprintln!("Failed to run grim: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::drive;
+
+    const OUTPUT: &str = "DP-1";
+
+    fn info(id: WorkspaceId) -> WorkspaceInfo {
+        WorkspaceInfo {
+            id,
+            name: id.to_string(),
+            monitor: "DP-1".to_string(),
+            windows: 1,
+            id_string: id.to_string(),
+            icons: Vec::new(),
+        }
+    }
+
+    /// What Hyprland would report after switching to `active`.
+    fn switched_to(active: WorkspaceId) -> Message {
+        Message::WorkspacesUpdated {
+            workspaces: (1..=3).map(info).collect(),
+            specials: Vec::new(),
+            active_id: Some(active),
+        }
+    }
+
+    #[test]
+    fn back_and_forward_follow_visits() {
+        let mut workspaces = Workspaces::default();
+        drive(
+            &mut workspaces,
+            [switched_to(1), switched_to(2), switched_to(3)],
+            Workspaces::update,
+        );
+        assert_eq!(workspaces.back, [1, 2]);
+
+        // Going back reaches 2 without recording 3 -> 2 as a new visit
+        drive(&mut workspaces, [Message::Back, switched_to(2)], Workspaces::update);
+        assert_eq!(workspaces.active_workspace_id, Some(2));
+        assert_eq!(workspaces.back, [1]);
+        assert_eq!(workspaces.forward, [3]);
+
+        drive(&mut workspaces, [Message::Forward, switched_to(3)], Workspaces::update);
+        assert_eq!(workspaces.back, [1, 2]);
+        assert!(workspaces.forward.is_empty());
+
+        // A new visit drops the forward history
+        drive(
+            &mut workspaces,
+            [Message::Back, switched_to(2), switched_to(1)],
+            Workspaces::update,
+        );
+        assert_eq!(workspaces.back, [1, 2]);
+        assert!(workspaces.forward.is_empty());
+    }
+
+    #[test]
+    fn back_skips_removed_workspaces() {
+        let mut workspaces = Workspaces::default();
+        drive(
+            &mut workspaces,
+            [switched_to(1), switched_to(2), switched_to(3)],
+            Workspaces::update,
+        );
+        drive(
+            &mut workspaces,
+            [
+                Message::WorkspacesUpdated {
+                    workspaces: vec![info(1), info(3)],
+                    specials: Vec::new(),
+                    active_id: Some(3),
+                },
+                Message::Back,
+            ],
+            Workspaces::update,
+        );
+        assert_eq!(workspaces.navigating_to, Some(1));
+    }
+
+    #[test]
+    fn keeps_special_workspaces_apart() {
+        let mut workspaces = Workspaces::default();
+        drive(
+            &mut workspaces,
+            [Message::WorkspacesUpdated {
+                workspaces: vec![info(1), info(2)],
+                specials: vec![
+                    SpecialWorkspace::new("special", 0, false),
+                    SpecialWorkspace::new("special:term", 2, true),
+                ],
+                active_id: Some(2),
+            }],
+            Workspaces::update,
+        );
+        assert_eq!(workspaces.specials[0].name, "");
+        assert_eq!(workspaces.specials[0].label, SPECIAL_ICON);
+        assert_eq!(workspaces.specials[1].name, "term");
+        assert_eq!(workspaces.specials[1].label, format!("{} term", SPECIAL_ICON));
+
+        // The indicator only counts the numbered workspaces
+        assert_eq!(
+            workspaces.button_span(OUTPUT, 2),
+            Some((ROW_PADDING + BUTTON_WIDTH + BUTTON_SPACING, BUTTON_WIDTH))
+        );
+        let state = workspaces.state();
+        assert_eq!(state["workspaces"].as_array().unwrap().len(), 2);
+        assert_eq!(state["special"][1]["shown"], true);
+    }
+
+    #[test]
+    fn scrolls_to_neighbouring_workspaces() {
+        let mut workspaces = Workspaces::default();
+        let mut empty = info(2);
+        empty.windows = 0;
+        drive(
+            &mut workspaces,
+            [Message::WorkspacesUpdated {
+                workspaces: vec![info(1), empty, info(3)],
+                specials: Vec::new(),
+                active_id: Some(3),
+            }],
+            Workspaces::update,
+        );
+        assert_eq!(workspaces.scroll_target(false), Some(2));
+        assert_eq!(workspaces.scroll_target(true), None);
+
+        workspaces.scroll_skip_empty = true;
+        workspaces.scroll_wrap = true;
+        assert_eq!(workspaces.scroll_target(false), Some(1));
+        assert_eq!(workspaces.scroll_target(true), Some(1));
+    }
+
+    #[test]
+    fn hides_empty_workspaces_but_the_active_one() {
+        let mut workspaces = Workspaces::default();
+        workspaces.set_hide_empty(true);
+        let empty = |id| WorkspaceInfo {
+            windows: 0,
+            ..info(id)
+        };
+        drive(
+            &mut workspaces,
+            [Message::WorkspacesUpdated {
+                workspaces: vec![empty(1), info(2), empty(3)],
+                specials: Vec::new(),
+                active_id: Some(3),
+            }],
+            Workspaces::update,
+        );
+        let shown: Vec<_> = workspaces.visible_workspaces().map(|w| w.id).collect();
+        assert_eq!(shown, [2, 3]);
+        assert_eq!(
+            workspaces.button_span(OUTPUT, 3),
+            Some((ROW_PADDING + BUTTON_WIDTH + BUTTON_SPACING, BUTTON_WIDTH))
+        );
+    }
+
+    #[test]
+    fn indicator_spans_window_icons() {
+        let mut workspaces = Workspaces::default();
+        let with_icons = WorkspaceInfo {
+            icons: vec![image::Handle::from_path("a.png"), image::Handle::from_path("b.png")],
+            ..info(1)
+        };
+        drive(
+            &mut workspaces,
+            [Message::WorkspacesUpdated {
+                workspaces: vec![with_icons, info(2)],
+                specials: Vec::new(),
+                active_id: Some(2),
+            }],
+            Workspaces::update,
+        );
+        let wide = BUTTON_WIDTH + 2.0 * (APP_ICON_SIZE + APP_ICON_SPACING);
+        assert_eq!(workspaces.button_span(OUTPUT, 1), Some((ROW_PADDING, wide)));
+        assert_eq!(
+            workspaces.button_span(OUTPUT, 2),
+            Some((ROW_PADDING + wide + BUTTON_SPACING, BUTTON_WIDTH))
+        );
+    }
+
+    #[test]
+    fn renames_from_the_workspace_menu() {
+        let mut workspaces = Workspaces::default();
+        let empty = WorkspaceInfo {
+            windows: 0,
+            ..info(2)
+        };
+        drive(
+            &mut workspaces,
+            [Message::WorkspacesUpdated {
+                workspaces: vec![info(1), empty],
+                specials: Vec::new(),
+                active_id: Some(1),
+            }],
+            Workspaces::update,
+        );
+        let enabled = |id| -> Vec<bool> {
+            workspaces
+                .menu_items(id)
+                .iter()
+                .filter(|item| !item.is_separator)
+                .map(|item| item.enabled)
+                .collect()
+        };
+        // Nothing to move onto the active workspace, nothing to close on an empty one
+        assert_eq!(enabled(1), [false, true, true, true]);
+        assert_eq!(enabled(2), [true, true, false, true]);
+
+        drive(
+            &mut workspaces,
+            [
+                Message::Selected(2, RENAME_ID),
+                Message::RenameInput("web".to_string()),
+            ],
+            Workspaces::update,
+        );
+        assert_eq!(workspaces.renaming, Some((2, "web".to_string())));
+        drive(&mut workspaces, [Message::OverviewRequested], Workspaces::update);
+        assert_eq!(workspaces.renaming, None);
+    }
+
+    #[test]
+    fn limits_buttons_around_the_active_workspace() {
+        let mut workspaces = Workspaces::default();
+        workspaces.set_max_shown(Some(3));
+        drive(
+            &mut workspaces,
+            [Message::WorkspacesUpdated {
+                workspaces: (1..=10).map(info).collect(),
+                specials: Vec::new(),
+                active_id: Some(5),
+            }],
+            Workspaces::update,
+        );
+        assert_eq!(workspaces.button_range(), 3..6);
+        // After the "…" button
+        assert_eq!(
+            workspaces.button_span(OUTPUT, 4),
+            Some((ROW_PADDING + BUTTON_WIDTH + BUTTON_SPACING, BUTTON_WIDTH))
+        );
+        assert_eq!(workspaces.button_span(OUTPUT, 1), None);
+
+        drive(
+            &mut workspaces,
+            [Message::WorkspacesUpdated {
+                workspaces: (1..=10).map(info).collect(),
+                specials: Vec::new(),
+                active_id: Some(10),
+            }],
+            Workspaces::update,
+        );
+        assert_eq!(workspaces.button_range(), 7..10);
+    }
+
+    #[test]
+    fn indicator_follows_measured_buttons() {
+        let bounds = |x, y, width, height| Rectangle::new((x, y).into(), (width, height).into());
+        let mut workspaces = Workspaces::default();
+        drive(
+            &mut workspaces,
+            [
+                Message::WorkspacesUpdated {
+                    workspaces: vec![info(9), info(10)],
+                    specials: Vec::new(),
+                    active_id: Some(10),
+                },
+                Message::Measured(
+                    OUTPUT.to_string(),
+                    vec![
+                        (None, Some(bounds(100.0, 0.0, 60.0, 30.0))),
+                        (Some(9), Some(bounds(103.0, 2.0, 24.0, 26.0))),
+                        (Some(10), Some(bounds(131.0, 2.0, 30.0, 26.0))),
+                    ],
+                ),
+            ],
+            Workspaces::update,
+        );
+        // Two-digit IDs are wider than the estimate
+        assert_eq!(workspaces.button_span(OUTPUT, 10), Some((31.0, 30.0)));
+        assert_eq!(workspaces.button_span(OUTPUT, 9), Some((3.0, 24.0)));
+        // Other bars keep their own measurements
+        assert_eq!(
+            workspaces.button_span("HDMI-A-1", 10),
+            Some((ROW_PADDING + BUTTON_WIDTH + BUTTON_SPACING, BUTTON_WIDTH))
+        );
+    }
+}
@@ -1,58 +1,1417 @@
 use iced::futures::{SinkExt, Stream};
 use iced::stream;
-use iced::Color;
 use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
+use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::format::{FormatOverride, NumberFormat};
+use crate::layout::{LayoutConfig, LayoutStyle, ModuleKind, Section};
+use crate::popup::BAR_HEIGHT;
+use crate::secrets::Secret;
+use crate::animation::{Animation, Easing};
+use crate::appearance::{ColorScheme, ThemeSwitch};
+use crate::migrate::{self, CONFIG_VERSION};
+use crate::profile::{self, PROFILE_FLAG, Profile};
+use crate::icons::IconStyle;
+use crate::theme::{BorderColor, ThemePreset};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
+    // Version of the config layout, set by clammy when it migrates the file
+    // (missing = written before versioning)
+    #[serde(default)]
+    pub version: u32,
+    // Hover tooltips of the widgets (default: true)
+    #[serde(default = "default_true")]
+    pub tooltips: bool,
+    // Named sets of settings for different machines, see `profile`
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+    // The profile merged into this config, if any; not read from the file
+    #[serde(skip)]
+    pub profile: Option<String>,
+    #[serde(default)]
     pub theme: ThemeConfig,
+    #[serde(default)]
+    pub layout: LayoutConfig,
+    #[serde(default)]
+    pub modules: ModulesConfig,
+    // Commands run on clicks and scrolls, by module name, e.g.
+    // `[actions.clock] on_click_middle = "..."`
+    #[serde(default)]
+    pub actions: HashMap<ModuleKind, ModuleActions>,
+    // Number and unit formatting defaults
+    #[serde(default)]
+    pub format: NumberFormat,
+    #[serde(default)]
+    pub clock: ClockConfig,
+    #[serde(default)]
+    pub battery: BatteryConfig,
+    #[serde(default)]
+    pub volume: VolumeConfig,
+    #[serde(default)]
+    pub focus_timer: FocusTimerConfig,
+    #[serde(default)]
+    pub file_widgets: Vec<FileWidgetConfig>,
+    #[serde(default)]
+    pub carousels: Vec<CarouselConfig>,
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+    #[serde(default)]
+    pub devices: DevicesConfig,
+    #[serde(default)]
+    pub lid_dock: LidDockConfig,
+    #[serde(default)]
+    pub power: PowerConfig,
+    #[serde(default)]
+    pub timer: TimerConfig,
+    #[serde(default)]
+    pub swap: SwapConfig,
+    #[serde(default)]
+    pub public_ip: PublicIpConfig,
+    #[serde(default)]
+    pub mail: MailConfig,
+    #[serde(default)]
+    pub hosts: HostsConfig,
+    #[serde(default)]
+    pub separator: SeparatorConfig,
+    #[serde(default)]
+    pub spacer: SpacerConfig,
+    // `[tray]`; `[system_tray]` is read as well
+    #[serde(default, rename = "tray", alias = "system_tray")]
+    pub system_tray: SystemTrayConfig,
+    #[serde(default)]
+    pub workspaces: WorkspacesConfig,
+    #[serde(default)]
+    pub bar: BarConfig,
+    #[serde(default)]
+    pub popups: PopupsConfig,
+    // Per-output overrides, as `[[monitor]]` sections
+    #[serde(default, rename = "monitor")]
+    pub monitors: Vec<MonitorConfig>,
+    #[serde(default)]
+    pub sounds: SoundsConfig,
+    #[serde(default)]
+    pub cursor: CursorConfig,
+    #[serde(default)]
+    pub diagnostics: DiagnosticsConfig,
+    #[serde(default)]
+    pub peek: PeekConfig,
+    #[serde(default)]
+    pub animations: AnimationsConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemeConfig {
+    // Font family, or a list of families of which the first installed one is
+    // used (None = system monospace)
+    #[serde(default)]
+    pub font: Option<FontFamilies>,
+    // Font size in pixels (default: 14)
+    #[serde(default = "default_font_size")]
+    pub font_size: f32,
+    // Spacing between tray widgets in pixels (default: 8)
+    #[serde(default = "default_tray_widget_spacing")]
+    pub tray_widget_spacing: f32,
+    // Horizontal padding inside each tray widget in pixels (default: 8)
+    #[serde(default = "default_tray_widget_padding")]
+    pub tray_widget_padding: f32,
+    // Corner radius of the bar and of popups in pixels (default: 15 and 6)
+    #[serde(default = "default_bar_radius")]
+    pub bar_radius: f32,
+    #[serde(default = "default_popup_radius")]
+    pub popup_radius: f32,
+    // Border width of the bar and popups in pixels (default: 1)
+    #[serde(default = "default_border_width")]
+    pub border_width: f32,
+    // Color of the bar and popup borders: "accent" (default) or "border"
+    #[serde(default)]
+    pub border_color: BorderColor,
+    // Icons of the battery, volume and notification widgets: "nerdfont"
+    // (default), "unicode" for fonts without Nerd Font glyphs, "image" for
+    // PNG icons from icon_dir, or "none"
+    #[serde(default)]
+    pub icons: IconStyle,
+    // Directory of the PNG icons for icons = "image", named like
+    // `battery-good.png` (default: "icons" next to the config)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon_dir: Option<String>,
+
+    // Bundled color scheme the colors below override (default: "tokyo-night")
+    #[serde(default)]
+    pub preset: ThemePreset,
+    // base16 scheme (YAML), pywal `colors.json` or matugen JSON output whose
+    // colors replace the preset's; watched for changes, so colors can follow
+    // the wallpaper ("~/" is expanded, "wal" = ~/.cache/wal/colors.json)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub import: Option<String>,
+
+    // Core palette (used by Iced theme); unset colors come from the preset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+    #[serde(default = "default_background_alpha")]
+    pub background_alpha: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub success: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub danger: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+
+    // Extended colors (used by AppTheme methods)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accent: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accent2: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub info: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub surface: Option<String>,
+    #[serde(default = "default_surface_alpha")]
+    pub surface_alpha: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub border: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub muted: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hover: Option<String>,
+    #[serde(default = "default_hover_alpha")]
+    pub hover_alpha: f32,
+
+    // Switch between `[theme.light]` and `[theme.dark]`: "off" (default),
+    // "portal" (follow the desktop's light/dark setting) or "schedule"
+    #[serde(default)]
+    pub switch: ThemeSwitch,
+    // Start of the light and the dark variant with switch = "schedule"
+    // ("HH:MM", default: "07:00" and "19:00")
+    #[serde(default = "default_light_from")]
+    pub light_from: String,
+    #[serde(default = "default_dark_from")]
+    pub dark_from: String,
+    // Keys replacing those above while the light or dark variant applies
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub light: Option<ThemeVariant>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dark: Option<ThemeVariant>,
+
+    // Style overrides of single modules, as `[theme.<module>]` sections,
+    // e.g. `[theme.clock]` or `[theme."file:status"]`
+    #[serde(flatten)]
+    pub components: HashMap<ModuleKind, ComponentStyle>,
+}
+
+// Style of one module; unset keys keep the theme's look.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ComponentStyle {
+    // Text color (default: the theme's text color)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    // Background behind the module (default: none)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+    // Background transparency (default: 1.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background_alpha: Option<f32>,
+    // Horizontal padding in pixels (default: `tray_widget_padding`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub padding: Option<f32>,
+    // Border color, width and corner radius in pixels (default: none)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub border: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub border_width: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub border_radius: Option<f32>,
+    // Font family or fallback list, and size in pixels (default: the
+    // theme's), e.g. an icon font for glyph-only modules
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font: Option<FontFamilies>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font_size: Option<f32>,
+    // Icon style (default: the theme's `icons`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icons: Option<IconStyle>,
+}
+
+// A font family, or families to try in order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FontFamilies {
+    One(String),
+    List(Vec<String>),
+}
+
+impl FontFamilies {
+    pub fn families(&self) -> &[String] {
+        match self {
+            FontFamilies::One(family) => std::slice::from_ref(family),
+            FontFamilies::List(families) => families,
+        }
+    }
+}
+
+// Preset, import and colors of a light or dark variant; unset keys keep the
+// `[theme]` value.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ThemeVariant {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset: Option<ThemePreset>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub import: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub success: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub danger: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accent: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accent2: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub info: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub surface: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub border: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub muted: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hover: Option<String>,
+}
+
+impl ThemeVariant {
+    /// The color keys and their values.
+    fn colors(&self) -> [(&'static str, &Option<String>); 12] {
+        [
+            ("background", &self.background),
+            ("text", &self.text),
+            ("success", &self.success),
+            ("danger", &self.danger),
+            ("warning", &self.warning),
+            ("accent", &self.accent),
+            ("accent2", &self.accent2),
+            ("info", &self.info),
+            ("surface", &self.surface),
+            ("border", &self.border),
+            ("muted", &self.muted),
+            ("hover", &self.hover),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockConfig {
+    // strftime format of the clock (default: "%a %d %b %H:%M")
+    #[serde(default = "default_clock_format")]
+    pub format: String,
+    // Locale of day and month names and AM/PM, like "de" or "fr_FR.UTF-8"
+    // (default: from $LC_ALL, $LC_TIME or $LANG)
+    #[serde(default)]
+    pub locale: Option<String>,
+    // Hours from 1 to 12 with AM/PM in the clock and world clock
+    #[serde(default, alias = "format-12h")]
+    pub format_12h: bool,
+    // Region code for public holidays in the calendar ("us", "gb", "de", "fr", "ro")
+    #[serde(default)]
+    pub holiday_region: Option<String>,
+    // Show the next holiday/reminder (within 30 days) in the clock tooltip
+    #[serde(default = "default_true")]
+    pub show_upcoming: bool,
+    // Recurring yearly reminders (birthdays, anniversaries)
+    #[serde(default)]
+    pub reminders: Vec<ReminderConfig>,
+    // Additional timezones (world clock)
+    #[serde(default)]
+    pub timezones: Vec<TimezoneConfig>,
+    // Show the additional timezones next to the clock instead of in the tooltip
+    #[serde(default)]
+    pub timezones_inline: bool,
+    // Snooze length for alarms in minutes (default: 9)
+    #[serde(default = "default_snooze_minutes")]
+    pub snooze_minutes: u32,
+    // Shell command that plays the alarm sound
+    #[serde(default)]
+    pub alarm_sound: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatteryConfig {
+    // Placeholders: {icon}, {percent}, {time} (remaining time, empty when
+    // unknown) (default: "{icon} {percent}")
+    #[serde(default = "default_battery_format")]
+    pub format: String,
+    // Charge in percent at or below which the discharging battery is shown
+    // in the warning color (default: 20)
+    #[serde(default = "default_battery_warning_percent")]
+    pub warning_percent: u8,
+    // Charge in percent at or below which the discharging battery is shown
+    // in the danger color (default: 10)
+    #[serde(default = "default_battery_critical_percent")]
+    pub critical_percent: u8,
+    // Fill `{percent}`; false leaves only the icon (and time) (default: true)
+    #[serde(default = "default_true")]
+    pub show_percent: bool,
+}
+
+impl Default for BatteryConfig {
+    fn default() -> Self {
+        Self {
+            format: default_battery_format(),
+            warning_percent: default_battery_warning_percent(),
+            critical_percent: default_battery_critical_percent(),
+            show_percent: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeConfig {
+    // Placeholders: {icon}, {percent} (default: "{icon} {percent}")
+    #[serde(default = "default_volume_format")]
+    pub format: String,
+}
+
+impl Default for VolumeConfig {
+    fn default() -> Self {
+        Self {
+            format: default_volume_format(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimezoneConfig {
+    pub label: String,
+    // IANA timezone name, e.g. "America/New_York"
+    pub tz: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReminderConfig {
+    pub name: String,
+    // Recurring date as "MM-DD"
+    pub date: String,
+}
+
+impl Default for ClockConfig {
+    fn default() -> Self {
+        Self {
+            format: default_clock_format(),
+            locale: None,
+            format_12h: false,
+            holiday_region: None,
+            show_upcoming: default_true(),
+            reminders: Vec::new(),
+            timezones: Vec::new(),
+            timezones_inline: false,
+            snooze_minutes: default_snooze_minutes(),
+            alarm_sound: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FocusTimerConfig {
+    // Length of a focus session in minutes (default: 25)
+    #[serde(default = "default_focus_minutes")]
+    pub focus_minutes: u32,
+    // Length of a break in minutes (default: 5)
+    #[serde(default = "default_break_minutes")]
+    pub break_minutes: u32,
+    // Move distracting windows to a special workspace during focus sessions
+    #[serde(default)]
+    pub strict: bool,
+    // Window classes considered distracting in strict mode
+    #[serde(default)]
+    pub blocked_classes: Vec<String>,
+}
+
+impl Default for FocusTimerConfig {
+    fn default() -> Self {
+        Self {
+            focus_minutes: default_focus_minutes(),
+            break_minutes: default_break_minutes(),
+            strict: false,
+            blocked_classes: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileWidgetConfig {
+    // Name used in the layout as "file:<name>"
+    pub name: String,
+    // File to watch ("~/" is expanded); not needed with `exec`
+    #[serde(default)]
+    pub path: String,
+    // Template; "{content}" is replaced by the file contents. waybar's "{}",
+    // "{text}", "{icon}", "{percentage}" and "{alt}" work too
+    #[serde(default = "default_file_format")]
+    pub format: String,
+    // Truncate contents to this many characters
+    #[serde(default, alias = "max-length")]
+    pub max_length: Option<usize>,
+    // Show the output of this command instead of a file, like waybar's
+    // `custom/*` modules
+    #[serde(default)]
+    pub exec: Option<String>,
+    // Re-run `exec` every this many seconds; unset keeps it running and
+    // shows each status it prints
+    #[serde(default)]
+    pub interval: Option<u64>,
+    // How output is read: "text", waybar's "json" or "i3bar" (default: "text")
+    #[serde(default, alias = "return-type")]
+    pub return_type: ReturnType,
+    // Icons for "{icon}", picked by the JSON `percentage` from low to high
+    #[serde(default, alias = "format-icons")]
+    pub format_icons: Vec<String>,
+    // Wait this long after a change before re-reading (default: 200)
+    #[serde(default = "default_debounce_ms")]
+    pub debounce_ms: u64,
+    // URL or path opened on click; "{content}" is replaced by the file contents
+    #[serde(default)]
+    pub on_click: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReturnType {
+    /// First line shown, second line as tooltip
+    #[default]
+    Text,
+    /// waybar's JSON objects
+    Json,
+    /// The i3bar protocol
+    I3bar,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CarouselConfig {
+    // Name used in the layout as "carousel:<name>"
+    pub name: String,
+    // Modules shown in turn in the carousel's slot
+    pub modules: Vec<ModuleKind>,
+    // Seconds each module is shown; 0 only rotates on scroll (default: 5)
+    #[serde(default = "default_carousel_interval_secs")]
+    pub interval_secs: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotificationsConfig {
+    // Time ranges during which do-not-disturb is enabled automatically
+    #[serde(default)]
+    pub quiet_hours: Vec<QuietHoursConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuietHoursConfig {
+    // Start time as "HH:MM"
+    pub start: String,
+    // End time as "HH:MM"; may be earlier than start to span midnight
+    pub end: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DevicesConfig {
+    // Device classes to watch (default: all)
+    #[serde(default = "default_device_classes")]
+    pub classes: Vec<DeviceClass>,
+    // How long connect/disconnect toasts stay visible in seconds (default: 4)
+    #[serde(default = "default_toast_secs")]
+    pub toast_secs: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceClass {
+    UsbStorage,
+    Input,
+    Webcam,
+}
+
+impl Default for DevicesConfig {
+    fn default() -> Self {
+        Self {
+            classes: default_device_classes(),
+            toast_secs: default_toast_secs(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LidDockConfig {
+    // Shell commands run when the dock/lid state changes
+    #[serde(default)]
+    pub on_dock: Option<String>,
+    #[serde(default)]
+    pub on_undock: Option<String>,
+    #[serde(default)]
+    pub on_lid_close: Option<String>,
+    #[serde(default)]
+    pub on_lid_open: Option<String>,
+}
+
+impl LidDockConfig {
+    pub fn has_hooks(&self) -> bool {
+        self.on_dock.is_some()
+            || self.on_undock.is_some()
+            || self.on_lid_close.is_some()
+            || self.on_lid_open.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerConfig {
+    // Before log out/reboot/shut down, list windows that may hold unsaved work
+    #[serde(default)]
+    pub check_unsaved: bool,
+    // Window title prefixes/suffixes that mark unsaved changes
+    #[serde(default = "default_unsaved_markers")]
+    pub unsaved_markers: Vec<String>,
+    // Warn about applications with at least this many windows (default: 5)
+    #[serde(default = "default_many_windows")]
+    pub many_windows: usize,
+}
+
+impl Default for PowerConfig {
+    fn default() -> Self {
+        Self {
+            check_unsaved: false,
+            unsaved_markers: default_unsaved_markers(),
+            many_windows: default_many_windows(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimerConfig {
+    // Countdowns offered in the timer menu
+    #[serde(default = "default_timer_presets")]
+    pub presets: Vec<TimerPreset>,
+}
+
+impl Default for TimerConfig {
+    fn default() -> Self {
+        Self {
+            presets: default_timer_presets(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimerPreset {
+    pub label: String,
+    pub minutes: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CursorConfig {
+    // Cursor themes and sizes the cursor widget cycles through
+    #[serde(default)]
+    pub presets: Vec<CursorPreset>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CursorPreset {
+    pub theme: String,
+    pub size: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwapConfig {
+    // Usage in percent from which the widget is shown in the warning color (default: 50)
+    #[serde(default = "default_swap_warning_percent")]
+    pub warning_percent: u8,
+    // Usage in percent from which the widget is shown in the danger color (default: 80)
+    #[serde(default = "default_swap_critical_percent")]
+    pub critical_percent: u8,
+    // Overrides of the `[format]` defaults
+    #[serde(default)]
+    pub format: FormatOverride,
+}
+
+impl Default for SwapConfig {
+    fn default() -> Self {
+        Self {
+            warning_percent: default_swap_warning_percent(),
+            critical_percent: default_swap_critical_percent(),
+            format: FormatOverride::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublicIpConfig {
+    // URL answering with the plain address or JSON with "ip" and "country"
+    #[serde(default = "default_public_ip_endpoint")]
+    pub endpoint: String,
+    // What to show in the bar (default: "ip")
+    #[serde(default)]
+    pub display: PublicIpDisplay,
+    // API token sent as a bearer token, e.g. { command = "pass show ipinfo" }
+    #[serde(default)]
+    pub token: Option<Secret>,
+    // Seconds between lookups (default: 600, at least 60)
+    #[serde(default = "default_public_ip_interval_secs")]
+    pub interval_secs: u64,
+}
+
+impl Default for PublicIpConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: default_public_ip_endpoint(),
+            display: PublicIpDisplay::default(),
+            token: None,
+            interval_secs: default_public_ip_interval_secs(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PublicIpDisplay {
+    #[default]
+    Ip,
+    /// Country flag, falling back to the address when the endpoint does not
+    /// report a country
+    Flag,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeparatorConfig {
+    // Text of the `separator` pseudo-module (default: "│")
+    #[serde(default = "default_separator_text")]
+    pub text: String,
+    // Color of the separator (default: the theme's muted color)
+    #[serde(default)]
+    pub color: Option<String>,
+}
+
+impl Default for SeparatorConfig {
+    fn default() -> Self {
+        Self {
+            text: default_separator_text(),
+            color: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpacerConfig {
+    // Width of the gap the `spacer` pseudo-module leaves, in pixels
+    // (default: 12)
+    #[serde(default = "default_spacer_width")]
+    pub width: f32,
+}
+
+impl Default for SpacerConfig {
+    fn default() -> Self {
+        Self {
+            width: default_spacer_width(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SystemTrayConfig {
+    // Items left out of the tray, by SNI id or title as `clammy --state`
+    // lists them (case-insensitive)
+    #[serde(default)]
+    pub hidden: Vec<String>,
+    // Items shown first, in this order; the others follow sorted by title
+    #[serde(default)]
+    pub pinned: Vec<String>,
+    // Items shown in the bar; the others open in a popup from a chevron
+    // after them (default: all)
+    #[serde(default)]
+    pub max_items: Option<usize>,
+    // Own icons shown after the items, as `[[tray.indicator]]`
+    #[serde(default, rename = "indicator")]
+    pub indicators: Vec<TrayIndicatorConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrayIndicatorConfig {
+    // Name of the indicator, as `clammy --state` lists it
+    pub name: String,
+    // Image file drawn as the icon
+    pub icon: String,
+    #[serde(default)]
+    pub tooltip: String,
+    // Command run on click
+    #[serde(default)]
+    pub on_click: Option<String>,
+    // Command run every `interval_secs`; the indicator is shown while it
+    // succeeds, and the first line it prints replaces the tooltip
+    #[serde(default)]
+    pub status: Option<String>,
+    // Seconds between status checks (default: 10)
+    #[serde(default = "default_indicator_interval_secs")]
+    pub interval_secs: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostsConfig {
+    // Hosts to check, as `[[hosts.targets]]`
+    #[serde(default)]
+    pub targets: Vec<HostConfig>,
+    // Seconds between checks (default: 60, at least 10)
+    #[serde(default = "default_hosts_interval_secs")]
+    pub interval_secs: u64,
+    // Seconds before a host counts as down (default: 5)
+    #[serde(default = "default_hosts_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl Default for HostsConfig {
+    fn default() -> Self {
+        Self {
+            targets: Vec::new(),
+            interval_secs: default_hosts_interval_secs(),
+            timeout_secs: default_hosts_timeout_secs(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostConfig {
+    // Name shown in the popup
+    pub name: String,
+    // "host:port" for TCP, or an SSH destination ("user@host", an alias)
+    pub address: String,
+    // How the host is checked (default: "tcp")
+    #[serde(default)]
+    pub probe: HostProbe,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HostProbe {
+    /// Connect to a TCP port
+    #[default]
+    Tcp,
+    /// Run `true` over SSH, without prompting
+    Ssh,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MailConfig {
+    // Shell command printing the unread count
+    #[serde(default = "default_mail_command")]
+    pub command: String,
+    // The command keeps running and prints a new count on each line
+    // (e.g. an IMAP IDLE script) instead of being run periodically
+    #[serde(default)]
+    pub watch: bool,
+    // Credential passed to the command in $CLAMMY_MAIL_SECRET
+    #[serde(default)]
+    pub secret: Option<Secret>,
+    // Seconds between runs when not watching (default: 60, at least 10)
+    #[serde(default = "default_mail_interval_secs")]
+    pub interval_secs: u64,
+    // Shell command run on click, e.g. "thunderbird"
+    #[serde(default)]
+    pub on_click: Option<String>,
+    // Shell command behind the Compose entry of the right-click menu; empty
+    // hides the entry (default: "xdg-open mailto:")
+    #[serde(default = "default_mail_compose_command")]
+    pub compose_command: String,
+    // Shell command listing unread messages for the right-click menu, one
+    // per line as `<id>\t<from>\t<subject>`
+    #[serde(default)]
+    pub list_command: Option<String>,
+    // Per-message actions; the message id is passed in $CLAMMY_MAIL_ID,
+    // e.g. "notmuch tag -unread -- id:$CLAMMY_MAIL_ID"
+    #[serde(default)]
+    pub mark_read_command: Option<String>,
+    #[serde(default)]
+    pub archive_command: Option<String>,
+}
+
+impl Default for MailConfig {
+    fn default() -> Self {
+        Self {
+            command: default_mail_command(),
+            watch: false,
+            secret: None,
+            interval_secs: default_mail_interval_secs(),
+            on_click: None,
+            compose_command: default_mail_compose_command(),
+            list_command: None,
+            mark_read_command: None,
+            archive_command: None,
+        }
+    }
+}
+
+// Module name (as in the layout) -> enabled, e.g. `battery = false`.
+// Modules that are not listed are enabled.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ModulesConfig {
+    toggles: HashMap<ModuleKind, bool>,
+}
+
+impl ModulesConfig {
+    pub fn is_enabled(&self, kind: &ModuleKind) -> bool {
+        self.toggles.get(kind).copied().unwrap_or(true)
+    }
+}
+
+// Shell commands run on clicks and scrolls over a module. Buttons a widget
+// handles itself (e.g. left click on the clock) keep their own behavior.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModuleActions {
+    #[serde(default)]
+    pub on_click: Option<String>,
+    #[serde(default)]
+    pub on_click_right: Option<String>,
+    #[serde(default)]
+    pub on_click_middle: Option<String>,
+    #[serde(default)]
+    pub on_scroll_up: Option<String>,
+    #[serde(default)]
+    pub on_scroll_down: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SoundsConfig {
+    // Play sounds at all (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+    // Sound theme names (e.g. "complete") or paths to sound files; an empty
+    // string turns the sound off
+    #[serde(default = "default_battery_critical_sound")]
+    pub battery_critical: String,
+    #[serde(default = "default_timer_finished_sound")]
+    pub timer_finished: String,
+    #[serde(default = "default_notification_sound")]
+    pub notification: String,
+    // Battery percentage that counts as critical (default: 10)
+    #[serde(default = "default_battery_critical_percent")]
+    pub battery_critical_percent: u8,
+}
+
+impl Default for SoundsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            battery_critical: default_battery_critical_sound(),
+            timer_finished: default_timer_finished_sound(),
+            notification: default_notification_sound(),
+            battery_critical_percent: default_battery_critical_percent(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticsConfig {
+    // Keep recent messages, errors and config reloads in memory for
+    // `clammy --diagnose` (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+    // Number of entries kept (default: 500)
+    #[serde(default = "default_diagnostics_capacity")]
+    pub capacity: usize,
+}
+
+impl Default for DiagnosticsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            capacity: default_diagnostics_capacity(),
+        }
+    }
+}
+
+fn default_diagnostics_capacity() -> usize {
+    500
+}
+
+// Workspace indicator slide and popup slide-down
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnimationsConfig {
+    // Animate at all; off, changes show right away (default: true)
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    // Length of an animation in milliseconds (default: 120)
+    #[serde(default = "default_animation_duration_ms")]
+    pub duration_ms: u64,
+    // "linear", "ease_out", "ease_out_cubic" or "ease_in_out" (default: "ease_out")
+    #[serde(default)]
+    pub easing: Easing,
+}
+
+impl Default for AnimationsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            duration_ms: default_animation_duration_ms(),
+            easing: Easing::default(),
+        }
+    }
+}
+
+impl AnimationsConfig {
+    /// A new animation with these settings, already finished when
+    /// animations are off.
+    pub fn start(&self) -> Animation {
+        if !self.enabled || self.duration_ms == 0 {
+            return Animation::finished();
+        }
+        Animation::new(Duration::from_millis(self.duration_ms), self.easing)
+    }
+}
+
+fn default_animation_duration_ms() -> u64 {
+    120
+}
+
+// What the bar shows while peeked with `clammy --peek`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeekConfig {
+    // Show the bar above windows while it is hidden on the workspace
+    // (default: true)
+    #[serde(default = "default_true")]
+    pub reveal: bool,
+    // strftime format of the clock, e.g. with the full date; additional
+    // timezones are shown next to it (default: "%A %d %B %Y  %H:%M:%S")
+    #[serde(default = "default_peek_clock_format")]
+    pub clock_format: String,
+    // Layout shown instead of the bar's, e.g. with more modules (default:
+    // the bar's layout)
+    #[serde(default)]
+    pub layout: Option<LayoutConfig>,
+    // Stop peeking after this many seconds if the key release never
+    // arrives (default: 10)
+    #[serde(default = "default_peek_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl Default for PeekConfig {
+    fn default() -> Self {
+        Self {
+            reveal: true,
+            clock_format: default_peek_clock_format(),
+            layout: None,
+            timeout_secs: default_peek_timeout_secs(),
+        }
+    }
+}
+
+fn default_peek_clock_format() -> String {
+    "%A %d %B %Y  %H:%M:%S".to_string()
+}
+
+fn default_peek_timeout_secs() -> u64 {
+    10
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspacesConfig {
+    // Show thumbnails in the workspace overview (right-click); each workspace
+    // is captured with grim when it is shown
+    #[serde(default = "default_true")]
+    pub previews: bool,
+    // Show back/forward buttons for recently used workspaces (default: false)
+    #[serde(default)]
+    pub history: bool,
+    // Scroll on the workspaces to switch: up to the next, down to the previous
+    #[serde(default = "default_true")]
+    pub scroll: bool,
+    // Skip workspaces without windows when scrolling (default: false)
+    #[serde(default)]
+    pub scroll_skip_empty: bool,
+    // Scroll from the last workspace to the first and back (default: false)
+    #[serde(default)]
+    pub scroll_wrap: bool,
+    // Leave out workspaces without windows, except the active one (default: false)
+    #[serde(default, alias = "hide-empty")]
+    pub hide_empty: bool,
+    // Show the icons of the windows on each workspace in its button (default: false)
+    #[serde(default)]
+    pub app_icons: bool,
+    // Most workspace buttons; with more workspaces, buttons around the
+    // active one are shown between "…" ends (default: all)
+    #[serde(default)]
+    pub max_shown: Option<usize>,
+}
+
+impl Default for WorkspacesConfig {
+    fn default() -> Self {
+        Self {
+            previews: true,
+            history: false,
+            scroll: true,
+            scroll_skip_empty: false,
+            scroll_wrap: false,
+            hide_empty: false,
+            app_icons: false,
+            max_shown: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BarConfig {
+    // Workspaces (by name or id) on which the bar gets out of the way
+    #[serde(default)]
+    pub hide_on_workspaces: Vec<String>,
+    // What the bar does on those workspaces (default: "hide")
+    #[serde(default)]
+    pub hide_mode: BarHideMode,
+    // Output edge the bar is attached to (default: "top")
+    #[serde(default)]
+    pub position: BarPosition,
+    // Delay before backends that connect to D-Bus or spawn processes (tray,
+    // notifications, devices, network widgets) start, so the bar paints
+    // first at login (default: 1000)
+    #[serde(default = "default_startup_delay_ms")]
+    pub startup_delay_ms: u64,
+    // Blank sensitive widgets (window title, mail, file widgets) and pause
+    // device toasts while the session is locked (default: true)
+    #[serde(default = "default_true")]
+    pub blank_when_locked: bool,
+    // Size of the bar and its popups relative to the output's own scale:
+    // fonts, icons, bar height and workspace indicators grow together
+    // (default: 1.0, 0.5 - 4.0)
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+    // Layer shell namespace of the bar and its popups, which Hyprland's
+    // `layerrule`s match; read on start (default: "clammy")
+    #[serde(default = "default_namespace")]
+    pub namespace: String,
+    // Have Hyprland blur what is behind the bar and popups, for translucent
+    // themes (default: false)
+    #[serde(default)]
+    pub blur: bool,
+}
+
+impl Default for BarConfig {
+    fn default() -> Self {
+        Self {
+            hide_on_workspaces: Vec::new(),
+            hide_mode: BarHideMode::default(),
+            position: BarPosition::default(),
+            startup_delay_ms: default_startup_delay_ms(),
+            blank_when_locked: true,
+            scale: default_scale(),
+            namespace: default_namespace(),
+            blur: false,
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ThemeConfig {
-    // Font (None = system monospace)
+impl BarConfig {
+    /// `scale`, limited to sizes the bar can be drawn at.
+    pub fn scale_factor(&self) -> f32 {
+        if self.scale.is_finite() {
+            self.scale.clamp(0.5, 4.0)
+        } else {
+            1.0
+        }
+    }
+
+    /// `namespace`, or the default if empty (layer shell needs one).
+    pub fn namespace(&self) -> String {
+        match self.namespace.trim() {
+            "" => default_namespace(),
+            namespace => namespace.to_string(),
+        }
+    }
+
+    /// Whether the bar hides on the workspace with the given id and name.
+    pub fn hides_on(&self, id: i32, name: &str) -> bool {
+        self.hide_on_workspaces
+            .iter()
+            .any(|rule| rule == name || rule.parse() == Ok(id))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BarHideMode {
+    /// Slide off the output and release the reserved space
+    #[default]
+    Hide,
+    /// Shrink to a thin strip
+    Minimize,
+}
+
+/// How an open popup shares the screen with tiled windows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PopupSpace {
+    /// Float over the windows
+    #[default]
+    Overlap,
+    /// Grow the bar's reserved space by the popup's height while it is
+    /// open, so tiled windows move out of the way
+    Reserve,
+}
+
+// Per popup type, "overlap" (default) or "reserve"
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PopupsConfig {
+    // Tray, volume, power and other menus
     #[serde(default)]
-    pub font: Option<String>,
-    // Font size in pixels (default: 14)
-    #[serde(default = "default_font_size")]
-    pub font_size: f32,
-    // Spacing between tray widgets in pixels (default: 8)
-    #[serde(default = "default_tray_widget_spacing")]
-    pub tray_widget_spacing: f32,
-    // Horizontal padding inside each tray widget in pixels (default: 8)
-    #[serde(default = "default_tray_widget_padding")]
-    pub tray_widget_padding: f32,
+    pub menu: PopupSpace,
+    #[serde(default)]
+    pub calendar: PopupSpace,
+    #[serde(default)]
+    pub workspace_overview: PopupSpace,
+    #[serde(default)]
+    pub power_confirm: PopupSpace,
+}
 
-    // Core palette (used by Iced theme)
-    pub background: String,
-    pub background_alpha: f32,
-    pub text: String,
-    pub success: String,
-    pub danger: String,
+/// Overrides for the bar on one output.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitorConfig {
+    // Output name, as in `hyprctl monitors` (e.g. "DP-1")
+    pub name: String,
+    // Modules of this bar (default: the top-level layout)
+    #[serde(default)]
+    pub layout: Option<LayoutConfig>,
+    // Bar height in pixels (default: 36)
+    #[serde(default)]
+    pub height: Option<u32>,
+    // Output edge the bar is attached to (default: bar.position)
+    #[serde(default)]
+    pub position: Option<BarPosition>,
+}
 
-    // Extended colors (used by AppTheme methods)
-    pub accent: String,
-    pub accent2: String,
-    pub info: String,
-    pub surface: String,
-    pub surface_alpha: f32,
-    pub border: String,
-    pub muted: String,
-    pub hover: String,
-    pub hover_alpha: f32,
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BarPosition {
+    #[default]
+    Top,
+    /// Popups open upwards
+    Bottom,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
+            version: CONFIG_VERSION,
+            tooltips: true,
+            profiles: BTreeMap::new(),
+            profile: None,
             theme: ThemeConfig::default(),
+            layout: LayoutConfig::default(),
+            modules: ModulesConfig::default(),
+            actions: HashMap::new(),
+            format: NumberFormat::default(),
+            clock: ClockConfig::default(),
+            battery: BatteryConfig::default(),
+            volume: VolumeConfig::default(),
+            focus_timer: FocusTimerConfig::default(),
+            file_widgets: Vec::new(),
+            carousels: Vec::new(),
+            notifications: NotificationsConfig::default(),
+            devices: DevicesConfig::default(),
+            lid_dock: LidDockConfig::default(),
+            power: PowerConfig::default(),
+            timer: TimerConfig::default(),
+            swap: SwapConfig::default(),
+            public_ip: PublicIpConfig::default(),
+            mail: MailConfig::default(),
+            hosts: HostsConfig::default(),
+            separator: SeparatorConfig::default(),
+            spacer: SpacerConfig::default(),
+            system_tray: SystemTrayConfig::default(),
+            workspaces: WorkspacesConfig::default(),
+            bar: BarConfig::default(),
+            popups: PopupsConfig::default(),
+            monitors: Vec::new(),
+            sounds: SoundsConfig::default(),
+            cursor: CursorConfig::default(),
+            diagnostics: DiagnosticsConfig::default(),
+            peek: PeekConfig::default(),
+            animations: AnimationsConfig::default(),
         }
     }
 }
 
+fn default_true() -> bool {
+    true
+}
+
+fn default_separator_text() -> String {
+    "│".to_string()
+}
+
+fn default_spacer_width() -> f32 {
+    12.0
+}
+
+fn default_clock_format() -> String {
+    "%a %d %b %H:%M".to_string()
+}
+
+fn default_battery_format() -> String {
+    "{icon} {percent}".to_string()
+}
+
+fn default_battery_warning_percent() -> u8 {
+    20
+}
+
+fn default_volume_format() -> String {
+    "{icon} {percent}".to_string()
+}
+
+fn default_snooze_minutes() -> u32 {
+    9
+}
+
+fn default_focus_minutes() -> u32 {
+    25
+}
+
+fn default_break_minutes() -> u32 {
+    5
+}
+
+fn default_file_format() -> String {
+    "{content}".to_string()
+}
+
+fn default_debounce_ms() -> u64 {
+    200
+}
+
+fn default_carousel_interval_secs() -> u64 {
+    5
+}
+
+fn default_device_classes() -> Vec<DeviceClass> {
+    vec![DeviceClass::UsbStorage, DeviceClass::Input, DeviceClass::Webcam]
+}
+
+fn default_toast_secs() -> u64 {
+    4
+}
+
+fn default_unsaved_markers() -> Vec<String> {
+    vec!["*".to_string(), "●".to_string()]
+}
+
+fn default_many_windows() -> usize {
+    5
+}
+
+fn default_timer_presets() -> Vec<TimerPreset> {
+    vec![
+        TimerPreset {
+            label: "Tea".to_string(),
+            minutes: 3,
+        },
+        TimerPreset {
+            label: "Pasta".to_string(),
+            minutes: 10,
+        },
+    ]
+}
+
+fn default_swap_warning_percent() -> u8 {
+    50
+}
+
+fn default_swap_critical_percent() -> u8 {
+    80
+}
+
+fn default_public_ip_endpoint() -> String {
+    "https://ipinfo.io/json".to_string()
+}
+
+fn default_public_ip_interval_secs() -> u64 {
+    600
+}
+
+fn default_hosts_interval_secs() -> u64 {
+    60
+}
+
+fn default_indicator_interval_secs() -> u64 {
+    10
+}
+
+fn default_hosts_timeout_secs() -> u64 {
+    5
+}
+
+fn default_battery_critical_sound() -> String {
+    "battery-caution".to_string()
+}
+
+fn default_timer_finished_sound() -> String {
+    "complete".to_string()
+}
+
+fn default_notification_sound() -> String {
+    "message-new-instant".to_string()
+}
+
+fn default_battery_critical_percent() -> u8 {
+    10
+}
+
+fn default_startup_delay_ms() -> u64 {
+    1000
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+fn default_namespace() -> String {
+    "clammy".to_string()
+}
+
+fn default_mail_command() -> String {
+    "notmuch count tag:unread".to_string()
+}
+
+fn default_mail_interval_secs() -> u64 {
+    60
+}
+
+fn default_mail_compose_command() -> String {
+    "xdg-open mailto:".to_string()
+}
+
+fn default_background_alpha() -> f32 {
+    0.85
+}
+
+fn default_surface_alpha() -> f32 {
+    0.94
+}
+
+fn default_hover_alpha() -> f32 {
+    0.5
+}
+
+fn default_light_from() -> String {
+    "07:00".to_string()
+}
+
+fn default_dark_from() -> String {
+    "19:00".to_string()
+}
+
 fn default_font_size() -> f32 {
     14.0
 }
@@ -65,41 +1424,279 @@ fn default_tray_widget_padding() -> f32 {
     8.0
 }
 
+fn default_bar_radius() -> f32 {
+    15.0
+}
+
+fn default_popup_radius() -> f32 {
+    6.0
+}
+
+fn default_border_width() -> f32 {
+    1.0
+}
+
+impl ThemeConfig {
+    /// This theme with the variant for `scheme` applied.
+    pub fn for_scheme(&self, scheme: Option<ColorScheme>) -> ThemeConfig {
+        let variant = match scheme {
+            Some(ColorScheme::Light) => self.light.as_ref(),
+            Some(ColorScheme::Dark) => self.dark.as_ref(),
+            None => None,
+        };
+        let Some(variant) = variant else {
+            return self.clone();
+        };
+        let pick = |variant: &Option<String>, base: &Option<String>| {
+            variant.clone().or_else(|| base.clone())
+        };
+        ThemeConfig {
+            preset: variant.preset.unwrap_or(self.preset),
+            import: pick(&variant.import, &self.import),
+            background: pick(&variant.background, &self.background),
+            text: pick(&variant.text, &self.text),
+            success: pick(&variant.success, &self.success),
+            danger: pick(&variant.danger, &self.danger),
+            warning: pick(&variant.warning, &self.warning),
+            accent: pick(&variant.accent, &self.accent),
+            accent2: pick(&variant.accent2, &self.accent2),
+            info: pick(&variant.info, &self.info),
+            surface: pick(&variant.surface, &self.surface),
+            border: pick(&variant.border, &self.border),
+            muted: pick(&variant.muted, &self.muted),
+            hover: pick(&variant.hover, &self.hover),
+            ..self.clone()
+        }
+    }
+
+    /// The color keys and their values.
+    fn colors(&self) -> [(&'static str, &Option<String>); 12] {
+        [
+            ("background", &self.background),
+            ("text", &self.text),
+            ("success", &self.success),
+            ("danger", &self.danger),
+            ("warning", &self.warning),
+            ("accent", &self.accent),
+            ("accent2", &self.accent2),
+            ("info", &self.info),
+            ("surface", &self.surface),
+            ("border", &self.border),
+            ("muted", &self.muted),
+            ("hover", &self.hover),
+        ]
+    }
+
+    /// The `import` file, with the "wal" shorthand resolved.
+    pub fn import_path(&self) -> Option<PathBuf> {
+        match self.import.as_deref()? {
+            "wal" => Some(dirs::cache_dir()?.join("wal/colors.json")),
+            path => Some(expand_home(path)),
+        }
+    }
+}
+
 impl Default for ThemeConfig {
     fn default() -> Self {
-        // Tokyo Night color scheme
+        // Colors come from the Tokyo Night preset
         Self {
             font: None, // Uses system monospace
             font_size: default_font_size(),
             tray_widget_spacing: default_tray_widget_spacing(),
             tray_widget_padding: default_tray_widget_padding(),
-            background: "#1a1b26".to_string(),
-            background_alpha: 0.85,
-            text: "#c0caf5".to_string(),
-            success: "#9ece6a".to_string(),
-            danger: "#f7768e".to_string(),
-            accent: "#7aa2f7".to_string(),
-            accent2: "#bb9af7".to_string(),
-            info: "#7dcfff".to_string(),
-            surface: "#24283b".to_string(),
-            surface_alpha: 0.94,
-            border: "#414868".to_string(),
-            muted: "#565f89".to_string(),
-            hover: "#414868".to_string(),
-            hover_alpha: 0.5,
+            bar_radius: default_bar_radius(),
+            popup_radius: default_popup_radius(),
+            border_width: default_border_width(),
+            border_color: BorderColor::default(),
+            icons: IconStyle::default(),
+            icon_dir: None,
+            preset: ThemePreset::default(),
+            import: None,
+            background: None,
+            background_alpha: default_background_alpha(),
+            text: None,
+            success: None,
+            danger: None,
+            warning: None,
+            accent: None,
+            accent2: None,
+            info: None,
+            surface: None,
+            surface_alpha: default_surface_alpha(),
+            border: None,
+            muted: None,
+            hover: None,
+            hover_alpha: default_hover_alpha(),
+            switch: ThemeSwitch::default(),
+            light_from: default_light_from(),
+            dark_from: default_dark_from(),
+            light: None,
+            dark: None,
+            components: HashMap::new(),
         }
     }
 }
 
-/// Get the config file path: $XDG_CONFIG_HOME/clammy/config.toml
+/// Argument that picks the config file, e.g. to run a second bar profile.
+pub const CONFIG_FLAG: &str = "--config";
+
+/// Environment variable that picks the config file; `--config` wins.
+pub const CONFIG_ENV: &str = "CLAMMY_CONFIG";
+
+/// Path of the config: `--config <file>`, else `$CLAMMY_CONFIG`, else
+/// `~/.config/clammy/config.toml`.
 pub fn config_path() -> PathBuf {
+    match chosen_config() {
+        Some(path) => path,
+        None => default_config_path(),
+    }
+}
+
+/// The config file unless one is picked: $XDG_CONFIG_HOME/clammy/config.toml
+fn default_config_path() -> PathBuf {
     let config_dir = dirs::config_dir()
         .unwrap_or_else(|| PathBuf::from("~/.config"))
         .join("clammy");
     config_dir.join("config.toml")
 }
 
+/// The config picked with `--config` or `$CLAMMY_CONFIG`, made absolute so
+/// the watcher recognizes it in change events.
+fn chosen_config() -> Option<PathBuf> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let chosen = match args.iter().position(|arg| arg == CONFIG_FLAG) {
+        Some(index) => args.get(index + 1).cloned(),
+        None => std::env::var(CONFIG_ENV).ok().filter(|path| !path.is_empty()),
+    }?;
+    let path = expand_home(&chosen);
+    Some(std::path::absolute(&path).unwrap_or(path))
+}
+
+/// Command-line arguments after the program name, without `--config <file>`
+/// and `--profile <name>`, for the flags that make clammy do something else
+/// than run the bar.
+pub fn cli_args() -> Vec<String> {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    for flag in [CONFIG_FLAG, PROFILE_FLAG] {
+        if let Some(index) = args.iter().position(|arg| arg == flag) {
+            args.drain(index..(index + 2).min(args.len()));
+        }
+    }
+    args
+}
+
+/// Socket `clammy-<name>.sock` in the runtime directory. Bars started with
+/// a config or profile of their own get their own sockets, so they can run
+/// side by side and `clammy --config <file> --peek ...` reaches the right
+/// one.
+pub fn socket_path(name: &str) -> PathBuf {
+    let config = chosen_config().filter(|path| *path != default_config_path());
+    let file = match (config, profile::chosen()) {
+        (None, None) => format!("clammy-{}.sock", name),
+        chosen => {
+            let mut hasher = std::hash::DefaultHasher::new();
+            chosen.hash(&mut hasher);
+            format!("clammy-{}-{:x}.sock", name, hasher.finish())
+        }
+    };
+    dirs::runtime_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join(file)
+}
+
+/// Directory for state that clammy persists itself (alarms, ...).
+pub fn state_dir() -> PathBuf {
+    dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .unwrap_or_else(|| PathBuf::from("~/.local/state"))
+        .join("clammy")
+}
+
+/// Expand a leading `~/` to the home directory.
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
 impl Config {
+    /// Whether the module is in the layout, directly or in a carousel, and
+    /// not disabled in `[modules]`. Disabled modules neither render nor poll.
+    pub fn shows(&self, kind: &ModuleKind) -> bool {
+        let in_layout = |kind: &ModuleKind| self.layouts().any(|layout| layout.contains(kind));
+        let in_carousel = || {
+            self.carousels.iter().any(|carousel| {
+                carousel.modules.contains(kind)
+                    && in_layout(&ModuleKind::Carousel(carousel.name.clone()))
+            })
+        };
+        self.modules.is_enabled(kind) && (in_layout(kind) || in_carousel())
+    }
+
+    /// The top-level layout, every per-output layout and the peek layout.
+    pub fn layouts(&self) -> impl Iterator<Item = &LayoutConfig> {
+        std::iter::once(&self.layout)
+            .chain(
+                self.monitors
+                    .iter()
+                    .filter_map(|monitor| monitor.layout.as_ref()),
+            )
+            .chain(self.peek.layout.as_ref())
+    }
+
+    fn monitor(&self, output: &str) -> Option<&MonitorConfig> {
+        self.monitors.iter().find(|monitor| monitor.name == output)
+    }
+
+    /// Layout of the bar on an output.
+    pub fn layout_for(&self, output: &str) -> &LayoutConfig {
+        self.monitor(output)
+            .and_then(|monitor| monitor.layout.as_ref())
+            .unwrap_or(&self.layout)
+    }
+
+    /// How the sections of the bar on an output are drawn.
+    pub fn layout_style(&self, output: &str) -> LayoutStyle {
+        self.layout_for(output)
+            .style
+            .or(self.layout.style)
+            .unwrap_or_default()
+    }
+
+    /// Layout of the bar on an output, for editing.
+    pub fn layout_for_mut(&mut self, output: &str) -> &mut LayoutConfig {
+        match self
+            .monitors
+            .iter_mut()
+            .find(|monitor| monitor.name == output && monitor.layout.is_some())
+            .and_then(|monitor| monitor.layout.as_mut())
+        {
+            Some(layout) => layout,
+            None => &mut self.layout,
+        }
+    }
+
+    /// Height of the bar on an output, in pixels.
+    pub fn bar_height(&self, output: &str) -> u32 {
+        self.monitor(output)
+            .and_then(|monitor| monitor.height)
+            .unwrap_or(BAR_HEIGHT)
+    }
+
+    /// Height of the bar surface on an output, which is drawn at
+    /// `bar.scale`.
+    pub fn scaled_bar_height(&self, output: &str) -> u32 {
+        (self.bar_height(output) as f32 * self.bar.scale_factor()).round() as u32
+    }
+
+    /// Output edge the bar on an output is attached to.
+    pub fn bar_position(&self, output: &str) -> BarPosition {
+        self.monitor(output)
+            .and_then(|monitor| monitor.position)
+            .unwrap_or(self.bar.position)
+    }
+
     /// Load config from file, creating default if it doesn't exist
     pub fn load() -> Result<Self, ConfigError> {
         let path = config_path();
@@ -118,10 +1715,123 @@ impl Config {
 
         // Read and parse existing config
         let content = fs::read_to_string(&path).map_err(ConfigError::Io)?;
-        let config: Config = toml::from_str(&content).map_err(ConfigError::Parse)?;
+        let (config, migrated) = Config::parse(&content)?;
+
+        // Keep the old file and write the upgraded one in its place
+        if let Some((from, upgraded)) = migrated {
+            let backup = path.with_extension(format!("toml.v{}.bak", from));
+            let written = fs::write(&backup, &content).and_then(|()| {
+                let upgraded = toml::to_string_pretty(&upgraded)
+                    .map_err(|e| std::io::Error::other(e.to_string()))?;
+                fs::write(&path, upgraded)
+            });
+            match written {
+                Ok(()) => eprintln!(
+                    "Migrated config from version {} to {}, the old one is at {}",
+                    from,
+                    CONFIG_VERSION,
+                    backup.display()
+                ),
+                Err(e) => eprintln!("Failed to write migrated config: {}", e),
+            }
+        }
         Ok(config)
     }
 
+    /// Parse a config, migrating it first if it was written for an older
+    /// version. Returns the version it was written for and the upgraded TOML
+    /// when it was migrated.
+    pub fn parse(content: &str) -> Result<(Config, Option<(u32, toml::Table)>), ConfigError> {
+        let mut table: toml::Table = toml::from_str(content).map_err(ConfigError::Parse)?;
+        let migrated = migrate::migrate(&mut table);
+        // The profile only changes what the bar uses, not the written file
+        let mut merged = table.clone();
+        let profile = profile::apply(&mut merged).map_err(ConfigError::Parse)?;
+        let mut config: Config = match (migrated, &profile) {
+            // Parse the text itself, so errors point at its lines
+            (None, None) => toml::from_str(content),
+            _ => merged.try_into(),
+        }
+        .map_err(ConfigError::Parse)?;
+        config.profile = profile;
+        Ok((config, migrated.map(|from| (from, table))))
+    }
+
+    /// Invalid colors of the theme, its variants and the per-module styles,
+    /// as `theme.<key>: <error>`. They fall back to the preset's colors.
+    pub fn color_errors(&self) -> Vec<String> {
+        let theme = &self.theme;
+        let mut colors = Vec::new();
+        let sections = [
+            ("theme", Some(theme.colors())),
+            ("theme.light", theme.light.as_ref().map(ThemeVariant::colors)),
+            ("theme.dark", theme.dark.as_ref().map(ThemeVariant::colors)),
+        ];
+        for (section, keys) in sections {
+            for (key, value) in keys.into_iter().flatten() {
+                colors.push((format!("{}.{}", section, key), value));
+            }
+        }
+        for (kind, style) in &theme.components {
+            for (key, value) in [
+                ("text", &style.text),
+                ("background", &style.background),
+                ("border", &style.border),
+            ] {
+                colors.push((format!("theme.{}.{}", kind, key), value));
+            }
+        }
+        if self.separator.color.is_some() {
+            colors.push(("separator.color".to_string(), &self.separator.color));
+        }
+
+        colors
+            .into_iter()
+            .filter_map(|(key, value)| {
+                let e = crate::color::parse(value.as_deref()?).err()?;
+                Some(format!("{}: {}", key, e))
+            })
+            .collect()
+    }
+
+    /// Modules referred to by name (`file:<name>`, `carousel:<name>`) in the
+    /// layouts, carousels, actions or module styles that are not defined in
+    /// `[[file_widgets]]` or `[[carousels]]`. They show nothing.
+    pub fn reference_errors(&self) -> Vec<String> {
+        let defined = |kind: &ModuleKind| match kind {
+            ModuleKind::File(name) => self.file_widgets.iter().any(|widget| widget.name == *name),
+            ModuleKind::Carousel(name) => {
+                self.carousels.iter().any(|carousel| carousel.name == *name)
+            }
+            _ => true,
+        };
+        let mut references: Vec<(&str, &ModuleKind)> = Vec::new();
+        for layout in self.layouts() {
+            for section in [Section::Left, Section::Center, Section::Right] {
+                references.extend(layout.section(section).iter().map(|kind| ("layout", kind)));
+            }
+        }
+        for carousel in &self.carousels {
+            references.extend(carousel.modules.iter().map(|kind| ("carousels", kind)));
+        }
+        references.extend(self.actions.keys().map(|kind| ("actions", kind)));
+        references.extend(self.theme.components.keys().map(|kind| ("theme", kind)));
+
+        let mut errors: Vec<String> = references
+            .into_iter()
+            .filter(|(_, kind)| !defined(kind))
+            .map(|(section, kind)| {
+                let list = match kind {
+                    ModuleKind::Carousel(_) => "[[carousels]]",
+                    _ => "[[file_widgets]]",
+                };
+                format!("{}: \"{}\" is not defined in {}", section, kind, list)
+            })
+            .collect();
+        errors.dedup();
+        errors
+    }
+
     /// Save config to file
     pub fn save(&self) -> Result<(), ConfigError> {
         let path = config_path();
@@ -135,6 +1845,94 @@ impl Config {
         fs::write(&path, content).map_err(ConfigError::Io)?;
         Ok(())
     }
+
+    /// Write the layout of the bar on an output back to the config file,
+    /// leaving the rest of the file, comments included, as it is.
+    pub fn save_layout(&self, output: &str) -> Result<(), ConfigError> {
+        let path = config_path();
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            // Nothing to keep
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return self.save(),
+            Err(e) => return Err(ConfigError::Io(e)),
+        };
+        let profile_layout = self.profile.as_deref().filter(|name| {
+            self.profiles
+                .get(*name)
+                .is_some_and(|profile| profile.settings.contains_key("layout"))
+        });
+        let table = if self
+            .monitor(output)
+            .is_some_and(|monitor| monitor.layout.is_some())
+        {
+            LayoutTable::Monitor(output)
+        } else if let Some(name) = profile_layout {
+            // The base `[layout]` is what the bar shows without the profile
+            LayoutTable::Profile(name)
+        } else {
+            LayoutTable::Base
+        };
+        let content = edit_layout(&content, table, self.layout_for(output))?;
+        fs::write(&path, content).map_err(ConfigError::Io)
+    }
+}
+
+/// Where in the config file a layout edit is written.
+#[derive(Debug, Clone, Copy)]
+enum LayoutTable<'a> {
+    /// The top-level `[layout]`
+    Base,
+    /// `[profiles.<name>.layout]`
+    Profile(&'a str),
+    /// The `layout` of the `[[monitor]]` entry with the given name
+    Monitor(&'a str),
+}
+
+/// Replace the modules of a layout in config file content.
+fn edit_layout(
+    content: &str,
+    table: LayoutTable<'_>,
+    layout: &LayoutConfig,
+) -> Result<String, ConfigError> {
+    let mut doc: toml_edit::DocumentMut = content.parse().map_err(ConfigError::Edit)?;
+    let table = match table {
+        LayoutTable::Monitor(name) => doc
+            .get_mut("monitor")
+            .and_then(toml_edit::Item::as_array_of_tables_mut)
+            .and_then(|monitors| {
+                monitors.iter_mut().find(|monitor| {
+                    monitor.get("name").and_then(toml_edit::Item::as_str) == Some(name)
+                })
+            })
+            .map(|monitor| &mut monitor["layout"])
+            // Set by a profile, which is not written back
+            .ok_or_else(|| {
+                ConfigError::Io(std::io::Error::other(format!(
+                    "the layout of {} is not in the config file",
+                    name
+                )))
+            })?,
+        LayoutTable::Profile(name) => &mut doc["profiles"][name]["layout"],
+        LayoutTable::Base => &mut doc["layout"],
+    };
+
+    for (key, modules) in [
+        ("left", &layout.left),
+        ("center", &layout.center),
+        ("right", &layout.right),
+    ] {
+        let mut value: toml_edit::Value = modules
+            .iter()
+            .map(ModuleKind::to_string)
+            .collect::<toml_edit::Array>()
+            .into();
+        // Keep a comment after the list
+        if let Some(old) = table.get(key).and_then(toml_edit::Item::as_value) {
+            *value.decor_mut() = old.decor().clone();
+        }
+        table[key] = toml_edit::Item::Value(value);
+    }
+    Ok(doc.to_string())
 }
 
 #[derive(Debug)]
@@ -142,6 +1940,7 @@ pub enum ConfigError {
     Io(std::io::Error),
     Parse(toml::de::Error),
     Serialize(toml::ser::Error),
+    Edit(toml_edit::TomlError),
 }
 
 impl std::fmt::Display for ConfigError {
@@ -150,54 +1949,53 @@ impl std::fmt::Display for ConfigError {
             ConfigError::Io(e) => write!(f, "IO error: {}", e),
             ConfigError::Parse(e) => write!(f, "Parse error: {}", e),
             ConfigError::Serialize(e) => write!(f, "Serialize error: {}", e),
+            ConfigError::Edit(e) => write!(f, "Parse error: {}", e),
         }
     }
 }
 
 impl std::error::Error for ConfigError {}
 
-/// Parse a hex color string (e.g., "#7aa2f7") to iced::Color
-pub fn parse_hex_color(hex: &str) -> Color {
-    let hex = hex.trim_start_matches('#');
-
-    if hex.len() != 6 {
-        return Color::WHITE; // Fallback
-    }
-
-    let r = u8::from_str_radix(&hex[0..2], 16).unwrap_or(255);
-    let g = u8::from_str_radix(&hex[2..4], 16).unwrap_or(255);
-    let b = u8::from_str_radix(&hex[4..6], 16).unwrap_or(255);
-
-    Color::from_rgb8(r, g, b)
-}
-
-/// Parse a hex color string with alpha
-pub fn parse_hex_color_with_alpha(hex: &str, alpha: f32) -> Color {
-    let hex = hex.trim_start_matches('#');
-
-    if hex.len() != 6 {
-        return Color::WHITE; // Fallback
+impl ConfigError {
+    /// One line saying where and what went wrong, without the excerpt of
+    /// the file, for the bar's warning chip.
+    pub fn summary(&self) -> String {
+        match self {
+            ConfigError::Parse(e) => {
+                let text = e.to_string();
+                match text.lines().next() {
+                    Some(first) if first.starts_with("TOML parse error at ") => format!(
+                        "{}: {}",
+                        first.trim_start_matches("TOML parse error at "),
+                        e.message().trim()
+                    ),
+                    _ => e.message().trim().to_string(),
+                }
+            }
+            e => e.to_string(),
+        }
     }
-
-    let r = u8::from_str_radix(&hex[0..2], 16).unwrap_or(255);
-    let g = u8::from_str_radix(&hex[2..4], 16).unwrap_or(255);
-    let b = u8::from_str_radix(&hex[4..6], 16).unwrap_or(255);
-
-    Color::from_rgba8(r, g, b, alpha)
 }
 
 #[derive(Debug, Clone)]
 pub enum ConfigMessage {
-    Reloaded(Config),
+    Reloaded(Box<Config>),
+    /// The changed config could not be loaded; the previous one stays
+    /// active
+    Rejected { error: String, summary: String },
     Error(String),
 }
 
-/// Subscription that watches the config file for changes
-pub fn config_subscription() -> iced::Subscription<ConfigMessage> {
-    iced::Subscription::run(config_watcher)
+/// Subscription that watches the config file, and the theme import file
+/// if there is one, for changes
+pub fn config_subscription(theme_import: Option<PathBuf>) -> iced::Subscription<ConfigMessage> {
+    iced::Subscription::run_with_id(
+        ("config-watcher", theme_import.clone()),
+        config_watcher(theme_import),
+    )
 }
 
-fn config_watcher() -> impl Stream<Item = ConfigMessage> {
+fn config_watcher(theme_import: Option<PathBuf>) -> impl Stream<Item = ConfigMessage> {
     stream::channel(100, |mut output| async move {
         let path = config_path();
         let watch_path = path.parent().map(|p| p.to_path_buf()).unwrap_or(path.clone());
@@ -233,6 +2031,21 @@ fn config_watcher() -> impl Stream<Item = ConfigMessage> {
                 .await;
         }
 
+        // The import file's directory, so editors that replace the file are
+        // noticed too
+        let import_dir = theme_import
+            .as_deref()
+            .and_then(|import| import.parent())
+            .filter(|dir| *dir != watch_path.as_path());
+        if let Some(Err(e)) = import_dir.map(|dir| watcher.watch(dir, RecursiveMode::NonRecursive)) {
+            let _ = output
+                .send(ConfigMessage::Error(format!(
+                    "Failed to watch theme import: {}",
+                    e
+                )))
+                .await;
+        }
+
         // Process file change events
         loop {
             if let Some(event) = rx.recv().await {
@@ -243,10 +2056,8 @@ fn config_watcher() -> impl Stream<Item = ConfigMessage> {
                 ) {
                     // Check if this event is for our config file
                     let is_config_file = event.paths.iter().any(|p| {
-                        p.file_name()
-                            .and_then(|n| n.to_str())
-                            .map(|n| n == "config.toml")
-                            .unwrap_or(false)
+                        p.file_name() == path.file_name()
+                            || theme_import.as_deref() == Some(p.as_path())
                     });
 
                     if is_config_file {
@@ -255,14 +2066,17 @@ fn config_watcher() -> impl Stream<Item = ConfigMessage> {
 
                         match Config::load() {
                             Ok(config) => {
-                                let _ = output.send(ConfigMessage::Reloaded(config)).await;
+                                for e in config.color_errors() {
+                                    let _ = output.send(ConfigMessage::Error(e)).await;
+                                }
+                                let _ = output.send(ConfigMessage::Reloaded(Box::new(config))).await;
                             }
                             Err(e) => {
                                 let _ = output
-                                    .send(ConfigMessage::Error(format!(
-                                        "Failed to reload config: {}",
-                                        e
-                                    )))
+                                    .send(ConfigMessage::Rejected {
+                                        error: e.to_string(),
+                                        summary: e.summary(),
+                                    })
                                     .await;
                             }
                         }
@@ -272,3 +2086,109 @@ fn config_watcher() -> impl Stream<Item = ConfigMessage> {
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RELOADED: &str = r##"
+[theme]
+font = ["Missing Font", "monospace"]
+accent = "#12"
+border_color = "border"
+
+[theme.battery]
+text = "nope"
+font_size = 20
+
+[theme.light]
+accent = "rgb(1, 2, 3)"
+"##;
+
+    #[test]
+    fn reload_reports_invalid_colors() {
+        let config: Config = toml::from_str(RELOADED).unwrap();
+        let mut errors = config.color_errors();
+        errors.sort();
+        assert_eq!(
+            errors,
+            [
+                "theme.accent: invalid color \"#12\"",
+                "theme.battery.text: invalid color \"nope\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn rejected_reload_is_summarized_in_one_line() {
+        let error = Config::parse("[theme]\naccent = \"#fff\"\nfont_size = ").unwrap_err();
+        let summary = error.summary();
+        assert!(summary.starts_with("line 3, column 13: "), "{}", summary);
+        assert!(!summary.contains('\n'));
+    }
+
+    #[test]
+    fn font_is_a_family_or_a_list() {
+        let config: Config = toml::from_str(RELOADED).unwrap();
+        let font = config.theme.font.unwrap();
+        assert_eq!(font.families(), ["Missing Font", "monospace"]);
+
+        let config: Config = toml::from_str("[theme]\nfont = \"Inter\"").unwrap();
+        assert_eq!(config.theme.font.unwrap().families(), ["Inter"]);
+    }
+
+    #[test]
+    fn defaults_survive_a_round_trip() {
+        let config = Config::default();
+        let saved = toml::to_string_pretty(&config).unwrap();
+        let reloaded: Config = toml::from_str(&saved).unwrap();
+        assert_eq!(reloaded.theme.bar_radius, config.theme.bar_radius);
+        assert_eq!(reloaded.theme.border_color, config.theme.border_color);
+        assert!(reloaded.color_errors().is_empty());
+    }
+
+    #[test]
+    fn layout_edits_keep_the_rest_of_the_file() {
+        let content = "# My bar\n[layout]\n# Status on the right\nleft = [\"clock\"] # moved\n\n\
+                       [[monitor]]\nname = \"DP-1\"\nlayout = { left = [\"workspaces\"] }\n";
+        let layout = LayoutConfig {
+            left: vec![ModuleKind::Workspaces, ModuleKind::Clock],
+            ..LayoutConfig::default()
+        };
+
+        let edited = edit_layout(content, LayoutTable::Base, &layout).unwrap();
+        assert!(edited.starts_with("# My bar\n[layout]\n# Status on the right\n"));
+        assert!(edited.contains("left = [\"workspaces\", \"clock\"] # moved\n"));
+        assert!(edited.contains("center = [\"window_title\"]"));
+        let config: Config = toml::from_str(&edited).unwrap();
+        assert_eq!(config.layout.left, layout.left);
+        assert_eq!(config.monitors[0].layout.as_ref().unwrap().left, [ModuleKind::Workspaces]);
+
+        let edited = edit_layout(content, LayoutTable::Monitor("DP-1"), &layout).unwrap();
+        let config: Config = toml::from_str(&edited).unwrap();
+        assert_eq!(config.layout.left, [ModuleKind::Clock]);
+        assert_eq!(config.monitors[0].layout.as_ref().unwrap().left, layout.left);
+
+        assert!(edit_layout(content, LayoutTable::Monitor("HDMI-A-1"), &layout).is_err());
+    }
+
+    #[test]
+    fn layout_edits_stay_in_the_profile() {
+        let content = "[layout]\nleft = [\"clock\"]\n\n\
+                       [profiles.docked]\nwhen = { ac = true }\n\
+                       [profiles.docked.layout]\nleft = [\"workspaces\"]\n";
+        let layout = LayoutConfig {
+            left: vec![ModuleKind::Workspaces, ModuleKind::Clock],
+            ..LayoutConfig::default()
+        };
+
+        let edited = edit_layout(content, LayoutTable::Profile("docked"), &layout).unwrap();
+        let config: Config = toml::from_str(&edited).unwrap();
+        assert_eq!(config.layout.left, [ModuleKind::Clock]);
+        let docked: LayoutConfig = config.profiles["docked"].settings["layout"]
+            .clone()
+            .try_into()
+            .unwrap();
+        assert_eq!(docked.left, layout.left);
+    }
+}
@@ -0,0 +1,287 @@
+//! Commands for the running bar from the command line, e.g. from
+//! compositor binds or scripts.
+//!
+//! `clammy --peek <start|end|toggle>` writes the command to a socket in
+//! `$XDG_RUNTIME_DIR` that the bar listens on, and exits. With Hyprland,
+//! holding a key peeks at the bar:
+//!
+//! ```text
+//! bind = SUPER, SUPER_L, exec, clammy --peek start
+//! bindr = SUPER, SUPER_L, exec, clammy --peek end
+//! ```
+//!
+//! `clammy --edit-layout` enters or leaves layout edit mode, like
+//! right-clicking the bar.
+//!
+//! `clammy --state` prints what the widgets show (workspaces, battery, tray
+//! items, active window, file widgets) as JSON over the same socket; with
+//! `--watch` it prints a line each time that changes, for scripts and other
+//! frontends.
+//!
+//! `clammy --diagnose [file]` writes the bar's recording of recent events
+//! (see [`diagnostics`]) to a file.
+
+use std::io::{BufRead, Read, Write};
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use iced::futures::channel::mpsc::Sender;
+use iced::futures::{SinkExt, Stream};
+use iced::{Subscription, stream};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+use tokio::sync::watch;
+
+use crate::{config, diagnostics};
+
+/// Argument that sends a peek command to the running bar and exits.
+pub const PEEK_FLAG: &str = "--peek";
+
+/// Argument that toggles layout edit mode in the running bar and exits.
+pub const EDIT_LAYOUT_FLAG: &str = "--edit-layout";
+
+/// Argument that prints the running bar's state and exits.
+pub const STATE_FLAG: &str = "--state";
+
+/// Argument of [`STATE_FLAG`] that keeps printing the state as it changes.
+const WATCH_FLAG: &str = "--watch";
+
+/// Argument that dumps the running bar's recording and exits.
+pub const DIAGNOSE_FLAG: &str = "--diagnose";
+
+/// Where the recording goes unless a file is given.
+const DEFAULT_DUMP_FILE: &str = "clammy-diagnostics.txt";
+
+/// The latest published state, as one line of JSON.
+static STATE: LazyLock<watch::Sender<String>> =
+    LazyLock::new(|| watch::Sender::new("{}".to_string()));
+
+/// Whether the state changed while no client listened, so [`STATE`] is out
+/// of date.
+static STATE_STALE: AtomicBool = AtomicBool::new(true);
+
+/// How long a client waits for the bar to bring a stale state up to date.
+const PUBLISH_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A command for the running bar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Peek(PeekCommand),
+    /// Enter or leave layout edit mode
+    EditLayout,
+    /// A state client connected while the state was stale
+    PublishState,
+}
+
+impl Command {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "edit-layout" => Some(Self::EditLayout),
+            _ => PeekCommand::parse(value).map(Self::Peek),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeekCommand {
+    /// The peek key was pressed
+    Start,
+    /// The peek key was released
+    End,
+    /// Peek until toggled again
+    Toggle,
+}
+
+impl PeekCommand {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "peek-start" => Some(Self::Start),
+            "peek-end" => Some(Self::End),
+            "peek-toggle" => Some(Self::Toggle),
+            _ => None,
+        }
+    }
+}
+
+fn socket_path() -> PathBuf {
+    config::socket_path("control")
+}
+
+/// Publish the bar's state to `clammy --state` clients. The state is only
+/// built while a client is connected; otherwise it is marked stale and
+/// built when the next client asks for it.
+pub fn publish_state(state: impl FnOnce() -> serde_json::Value) {
+    if STATE.receiver_count() == 0 {
+        STATE_STALE.store(true, Ordering::Relaxed);
+        return;
+    }
+    STATE_STALE.store(false, Ordering::Relaxed);
+    // Always notify, so a client waiting for a stale state hears back even
+    // if nothing changed; watchers skip repeated lines
+    STATE.send_replace(state().to_string());
+}
+
+/// A receiver of the state, asking the bar to publish it first if it is
+/// stale.
+async fn fresh_state(output: &mut Sender<Command>) -> watch::Receiver<String> {
+    let mut state = STATE.subscribe();
+    if STATE_STALE.load(Ordering::Relaxed) {
+        let _ = output.send(Command::PublishState).await;
+        let _ = tokio::time::timeout(PUBLISH_TIMEOUT, state.changed()).await;
+    }
+    state
+}
+
+/// Commands sent with `clammy --peek` and `--edit-layout`. Also serves
+/// `clammy --state` and `--diagnose`.
+pub fn subscription() -> Subscription<Command> {
+    Subscription::run(listen)
+}
+
+fn listen() -> impl Stream<Item = Command> {
+    stream::channel(10, |mut output| async move {
+        let path = socket_path();
+        // A previous bar may have left its socket behind
+        let _ = std::fs::remove_file(&path);
+        match tokio::net::UnixListener::bind(&path) {
+            Ok(listener) => loop {
+                let Ok((stream, _)) = listener.accept().await else {
+                    continue;
+                };
+                let mut stream = BufReader::new(stream);
+                let mut line = String::new();
+                if stream.read_line(&mut line).await.is_err() {
+                    continue;
+                }
+                match line.trim() {
+                    "state" => {
+                        let state = fresh_state(&mut output).await.borrow().clone();
+                        let _ = write_line(stream.get_mut(), &state).await;
+                    }
+                    // Watchers stay connected, so they get a task of their own
+                    "state-watch" => {
+                        let state = fresh_state(&mut output).await;
+                        tokio::spawn(watch_state(stream.into_inner(), state));
+                    }
+                    // Hanging up without a reply tells the client nothing is recorded
+                    "diagnose" => {
+                        if let Some(text) = diagnostics::dump() {
+                            let _ = stream.get_mut().write_all(text.as_bytes()).await;
+                        }
+                    }
+                    command => {
+                        if let Some(command) = Command::parse(command) {
+                            let _ = output.send(command).await;
+                        }
+                    }
+                }
+            },
+            Err(e) => {
+                eprintln!("Failed to open control socket: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    })
+}
+
+/// Send the state and every change of it until the client hangs up.
+async fn watch_state(mut stream: UnixStream, mut state: watch::Receiver<String>) {
+    let mut last = None;
+    loop {
+        let line = state.borrow_and_update().clone();
+        if last.as_ref() != Some(&line) {
+            if write_line(&mut stream, &line).await.is_err() {
+                return;
+            }
+            last = Some(line);
+        }
+        if state.changed().await.is_err() {
+            return;
+        }
+    }
+}
+
+async fn write_line(stream: &mut UnixStream, line: &str) -> std::io::Result<()> {
+    stream.write_all(line.as_bytes()).await?;
+    stream.write_all(b"\n").await
+}
+
+/// Handle `--peek <start|end|toggle>`, `--edit-layout`, `--state
+/// [--watch]` and `--diagnose [file]`: talk to the running bar and exit.
+/// Returns when clammy was started as the bar.
+pub fn run_if_requested() {
+    let args = config::cli_args();
+    let (command, dump_file) = match args.as_slice() {
+        [flag, action] if flag == PEEK_FLAG => (format!("peek-{}", action), None),
+        [flag, ..] if flag == PEEK_FLAG => (String::new(), None),
+        [flag] if flag == EDIT_LAYOUT_FLAG => ("edit-layout".to_string(), None),
+        [flag] if flag == STATE_FLAG => ("state".to_string(), None),
+        [flag, watch] if flag == STATE_FLAG && watch == WATCH_FLAG => {
+            ("state-watch".to_string(), None)
+        }
+        [flag, ..] if flag == STATE_FLAG => (String::new(), None),
+        [flag] if flag == DIAGNOSE_FLAG => ("diagnose".to_string(), Some(DEFAULT_DUMP_FILE)),
+        [flag, file] if flag == DIAGNOSE_FLAG => ("diagnose".to_string(), Some(file.as_str())),
+        _ => return,
+    };
+
+    let is_state = command.starts_with("state");
+    let result = if !is_state && dump_file.is_none() && Command::parse(&command).is_none() {
+        Err(format!(
+            "usage: clammy {} <start|end|toggle>\n       clammy {}\n       clammy {} [{}]\n       \
+             clammy {} [file]",
+            PEEK_FLAG, EDIT_LAYOUT_FLAG, STATE_FLAG, WATCH_FLAG, DIAGNOSE_FLAG
+        ))
+    } else {
+        std::os::unix::net::UnixStream::connect(socket_path())
+            .and_then(|mut stream| {
+                writeln!(stream, "{}", command)?;
+                Ok(stream)
+            })
+            .map_err(|e| format!("no running bar to talk to ({})", e))
+            .and_then(|stream| match dump_file {
+                Some(file) => write_recording(stream, file),
+                None if is_state => {
+                    print_replies(stream).map_err(|e| format!("no running bar to talk to ({})", e))
+                }
+                // Other commands get no reply
+                None => Ok(()),
+            })
+    };
+
+    match result {
+        Ok(()) => std::process::exit(0),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Write the recording the bar replies with to `file`.
+fn write_recording(mut stream: std::os::unix::net::UnixStream, file: &str) -> Result<(), String> {
+    let mut text = String::new();
+    stream
+        .read_to_string(&mut text)
+        .map_err(|e| format!("reading the recording: {}", e))?;
+    if text.is_empty() {
+        return Err(
+            "the running bar is not recording; set [diagnostics] enabled = true".to_string(),
+        );
+    }
+    std::fs::write(file, text).map_err(|e| format!("{}: {}", file, e))?;
+    println!("Wrote {}", file);
+    Ok(())
+}
+
+/// Print the bar's reply lines as they come, until it hangs up.
+fn print_replies(stream: std::os::unix::net::UnixStream) -> std::io::Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    for line in std::io::BufReader::new(stream).lines() {
+        writeln!(stdout, "{}", line?)?;
+        stdout.flush()?;
+    }
+    Ok(())
+}
@@ -0,0 +1,161 @@
+//! Opt-in recorder of recent bar events for bug reports.
+//!
+//! With `[diagnostics] enabled = true` the bar keeps the last
+//! `capacity` messages (with a short summary of their payload), errors and
+//! config reloads in memory. `clammy --diagnose [file]` asks the running bar
+//! for them over the control socket (see [`control`](crate::control)) and
+//! writes them to a file, so a report like "the tray icon disappeared"
+//! comes with what led up to it.
+//!
+//! Nothing is recorded while disabled.
+
+use std::collections::VecDeque;
+use std::fmt::{self, Write as _};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use chrono::{DateTime, Local};
+
+use crate::config::DiagnosticsConfig;
+
+/// Longest payload summary kept per entry.
+const MAX_SUMMARY_CHARS: usize = 300;
+
+static RECORDER: Mutex<Recorder> = Mutex::new(Recorder {
+    enabled: false,
+    capacity: 0,
+    entries: VecDeque::new(),
+    started: None,
+});
+
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    Message,
+    Error,
+    ConfigReload,
+}
+
+#[derive(Debug)]
+struct Entry {
+    at: DateTime<Local>,
+    kind: Kind,
+    summary: String,
+}
+
+#[derive(Debug)]
+struct Recorder {
+    enabled: bool,
+    capacity: usize,
+    entries: VecDeque<Entry>,
+    started: Option<Instant>,
+}
+
+/// Apply the `[diagnostics]` config; disabling drops the recording.
+pub fn configure(config: &DiagnosticsConfig) {
+    let Ok(mut recorder) = RECORDER.lock() else {
+        return;
+    };
+    recorder.enabled = config.enabled;
+    recorder.capacity = config.capacity.max(1);
+    if !recorder.enabled {
+        recorder.entries.clear();
+    }
+    while recorder.entries.len() > recorder.capacity {
+        recorder.entries.pop_front();
+    }
+    recorder.started.get_or_insert_with(Instant::now);
+}
+
+/// Record a message; `summary` only runs while recording.
+pub fn message(summary: impl FnOnce() -> String) {
+    record(Kind::Message, summary);
+}
+
+/// The variant name of an enum value, taken from its `Debug` output.
+/// Formatting stops before the first field, so large payloads are never
+/// formatted.
+pub fn variant(value: &impl fmt::Debug) -> String {
+    struct UpToFields(String);
+
+    impl fmt::Write for UpToFields {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            match s.find(['(', ' ', '{']) {
+                Some(end) => {
+                    self.0.push_str(&s[..end]);
+                    Err(fmt::Error)
+                }
+                None => {
+                    self.0.push_str(s);
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    let mut name = UpToFields(String::new());
+    let _ = write!(name, "{:?}", value);
+    name.0
+}
+
+/// Record an error, e.g. from a subscription.
+pub fn error(error: &str) {
+    record(Kind::Error, || error.to_string());
+}
+
+/// Record a config reload.
+pub fn config_reloaded() {
+    record(Kind::ConfigReload, String::new);
+}
+
+fn record(kind: Kind, summary: impl FnOnce() -> String) {
+    let Ok(mut recorder) = RECORDER.lock() else {
+        return;
+    };
+    if !recorder.enabled {
+        return;
+    }
+    let mut summary = summary();
+    if let Some((cut, _)) = summary.char_indices().nth(MAX_SUMMARY_CHARS) {
+        summary.truncate(cut);
+        summary.push('…');
+    }
+    if recorder.entries.len() >= recorder.capacity {
+        recorder.entries.pop_front();
+    }
+    recorder.entries.push_back(Entry {
+        at: Local::now(),
+        kind,
+        summary,
+    });
+}
+
+/// The recording as text, oldest entry first, unless recording is
+/// disabled.
+pub fn dump() -> Option<String> {
+    let recorder = RECORDER.lock().ok().filter(|recorder| recorder.enabled)?;
+    let mut text = format!("clammy {} diagnostics\n", env!("CARGO_PKG_VERSION"));
+    if let Some(started) = recorder.started {
+        let _ = writeln!(text, "uptime: {}s", started.elapsed().as_secs());
+    }
+    let _ = writeln!(
+        text,
+        "entries: {} (capacity {})\n",
+        recorder.entries.len(),
+        recorder.capacity
+    );
+    for entry in &recorder.entries {
+        let kind = match entry.kind {
+            Kind::Message => "message",
+            Kind::Error => "ERROR  ",
+            Kind::ConfigReload => "config reloaded",
+        };
+        let _ = writeln!(
+            text,
+            "{} {} {}",
+            entry.at.format("%H:%M:%S%.3f"),
+            kind,
+            entry.summary
+        );
+    }
+    Some(text)
+}
@@ -0,0 +1,170 @@
+//! Running user-configured shell commands.
+//!
+//! Custom widgets, click actions and hooks run their commands through
+//! [`Exec`], which bounds what a misbehaving script can do to the bar:
+//! - At most [`MAX_CONCURRENT`] commands run at once; further runs wait
+//! - A run is killed after its timeout, along with whatever it started
+//! - Captured output is capped at [`MAX_OUTPUT_BYTES`]
+//! - Launching applications is limited to [`MAX_LAUNCHES`] per second
+//! - Commands get no stdin. They inherit the bar's environment, plus the
+//!   variables set with [`Exec::env`]
+//!
+//! Long-running watchers (e.g. `mail.watch`) are meant to keep running and
+//! do not go through here.
+
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::sync::Semaphore;
+
+/// Commands allowed to run at the same time.
+pub const MAX_CONCURRENT: usize = 8;
+
+/// Bytes kept from stdout and stderr each; the rest is discarded.
+pub const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
+/// Applications that may be launched within a second.
+pub const MAX_LAUNCHES: usize = 5;
+
+/// Timeout unless a run sets its own.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+static SLOTS: Semaphore = Semaphore::const_new(MAX_CONCURRENT);
+
+/// Start times of recent launches, for the rate limit.
+static LAUNCHES: Mutex<Vec<Instant>> = Mutex::new(Vec::new());
+
+/// A shell command to run with `sh -c`.
+#[derive(Debug, Clone)]
+pub struct Exec {
+    command: String,
+    timeout: Duration,
+    env: Vec<(String, String)>,
+}
+
+impl Exec {
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+            timeout: DEFAULT_TIMEOUT,
+            env: Vec::new(),
+        }
+    }
+
+    /// Kill the command if it runs longer than this (waiting for a free
+    /// slot does not count).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set an environment variable for the command.
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+
+    /// Run the command and return its (capped) stdout. Errors are short
+    /// messages for the widget: the first line of stderr, or the exit status.
+    pub async fn output(self) -> Result<String, String> {
+        let _permit = SLOTS
+            .acquire()
+            .await
+            .map_err(|_| "command executor closed".to_string())?;
+
+        let mut child = tokio::process::Command::new("sh")
+            .args(["-c", &self.command])
+            .envs(self.env.iter().map(|(key, value)| (key, value)))
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            // A group of its own, so a timeout also reaches pipelines and
+            // background jobs
+            .process_group(0)
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| format!("sh: {}", e))?;
+
+        let group = child.id();
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        let run = async {
+            let (stdout, stderr) = tokio::join!(read_capped(stdout), read_capped(stderr));
+            (child.wait().await, stdout, stderr)
+        };
+
+        let Ok((status, stdout, stderr)) = tokio::time::timeout(self.timeout, run).await else {
+            if let Some(group) = group {
+                kill_group(group);
+            }
+            return Err(format!(
+                "`{}` timed out after {}s",
+                self.command,
+                self.timeout.as_secs()
+            ));
+        };
+        let status = status.map_err(|e| format!("`{}`: {}", self.command, e))?;
+
+        if !status.success() {
+            let stderr = String::from_utf8_lossy(&stderr);
+            return Err(match stderr.lines().next().map(str::trim) {
+                Some(line) if !line.is_empty() => line.to_string(),
+                _ => format!("`{}` failed ({})", self.command, status),
+            });
+        }
+        Ok(String::from_utf8_lossy(&stdout).into_owned())
+    }
+
+    /// Start the command without waiting for it, e.g. to open an
+    /// application. Only a failure to start is reported; the timeout does not
+    /// apply. Needs the Tokio runtime the bar updates in, which reaps it.
+    pub fn launch(self) -> Result<(), String> {
+        if let Ok(mut launches) = LAUNCHES.lock() {
+            let now = Instant::now();
+            launches.retain(|started| now.duration_since(*started) < Duration::from_secs(1));
+            if launches.len() >= MAX_LAUNCHES {
+                return Err(format!("`{}` not started: too many launches", self.command));
+            }
+            launches.push(now);
+        }
+
+        let mut child = tokio::process::Command::new("sh")
+            .args(["-c", &self.command])
+            .envs(self.env.iter().map(|(key, value)| (key, value)))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("sh: {}", e))?;
+
+        // Reap the shell when it exits
+        tokio::spawn(async move { child.wait().await });
+        Ok(())
+    }
+}
+
+/// Kill every process in the group led by `pid`.
+fn kill_group(pid: u32) {
+    // SAFETY: killpg takes no pointers; a group that is gone already only
+    // makes it fail
+    unsafe {
+        libc::killpg(pid as libc::pid_t, libc::SIGKILL);
+    }
+}
+
+/// Read up to [`MAX_OUTPUT_BYTES`] and discard the rest, so the command
+/// does not block on a full pipe.
+async fn read_capped(reader: Option<impl AsyncRead + Unpin>) -> Vec<u8> {
+    let mut buf = Vec::new();
+    let Some(mut reader) = reader else {
+        return buf;
+    };
+    let _ = (&mut reader)
+        .take(MAX_OUTPUT_BYTES as u64)
+        .read_to_end(&mut buf)
+        .await;
+    let _ = tokio::io::copy(&mut reader, &mut tokio::io::sink()).await;
+    buf
+}
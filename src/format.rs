@@ -0,0 +1,176 @@
+//! Number and unit formatting shared by the widgets.
+//!
+//! `[format]` sets the defaults; widgets showing sizes or percentages take
+//! a `format` table of their own that overrides single fields, e.g.
+//! `[swap.format] decimals = 0`.
+//!
+//! Widgets with a format string (e.g. `[battery] format = "{icon} {percent}"`)
+//! fill its placeholders with [`render`].
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnitPrefixes {
+    /// Powers of 1024: KiB, MiB, GiB
+    #[default]
+    Binary,
+    /// Powers of 1000: kB, MB, GB
+    Si,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NumberFormat {
+    // Decimal places of sizes (default: 1)
+    #[serde(default = "default_decimals")]
+    pub decimals: u8,
+    // Decimal places of percentages (default: 0)
+    #[serde(default)]
+    pub percent_decimals: u8,
+    // "binary" (KiB, MiB) or "si" (kB, MB) (default: "binary")
+    #[serde(default)]
+    pub prefixes: UnitPrefixes,
+    // Appended to percentages, e.g. " %" (default: "%")
+    #[serde(default = "default_percent_sign")]
+    pub percent_sign: String,
+    // Between groups of three digits, e.g. "," or " " (default: none)
+    #[serde(default)]
+    pub thousands_separator: String,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self {
+            decimals: default_decimals(),
+            percent_decimals: 0,
+            prefixes: UnitPrefixes::default(),
+            percent_sign: default_percent_sign(),
+            thousands_separator: String::new(),
+        }
+    }
+}
+
+/// Per-widget overrides of [`NumberFormat`]; unset fields keep the
+/// defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FormatOverride {
+    #[serde(default)]
+    pub decimals: Option<u8>,
+    #[serde(default)]
+    pub percent_decimals: Option<u8>,
+    #[serde(default)]
+    pub prefixes: Option<UnitPrefixes>,
+    #[serde(default)]
+    pub percent_sign: Option<String>,
+    #[serde(default)]
+    pub thousands_separator: Option<String>,
+}
+
+fn default_decimals() -> u8 {
+    1
+}
+
+fn default_percent_sign() -> String {
+    "%".to_string()
+}
+
+impl NumberFormat {
+    /// These defaults with a widget's overrides applied.
+    pub fn with(&self, overrides: &FormatOverride) -> Self {
+        Self {
+            decimals: overrides.decimals.unwrap_or(self.decimals),
+            percent_decimals: overrides.percent_decimals.unwrap_or(self.percent_decimals),
+            prefixes: overrides.prefixes.unwrap_or(self.prefixes),
+            percent_sign: overrides
+                .percent_sign
+                .clone()
+                .unwrap_or_else(|| self.percent_sign.clone()),
+            thousands_separator: overrides
+                .thousands_separator
+                .clone()
+                .unwrap_or_else(|| self.thousands_separator.clone()),
+        }
+    }
+
+    /// A number with the given decimal places and thousands separators.
+    pub fn number(&self, value: f64, decimals: u8) -> String {
+        let formatted = format!("{:.*}", decimals as usize, value);
+        if self.thousands_separator.is_empty() {
+            return formatted;
+        }
+
+        let (sign, unsigned) = match formatted.strip_prefix('-') {
+            Some(unsigned) => ("-", unsigned),
+            None => ("", formatted.as_str()),
+        };
+        let (integer, fraction) = match unsigned.find('.') {
+            Some(dot) => unsigned.split_at(dot),
+            None => (unsigned, ""),
+        };
+        let mut grouped = String::from(sign);
+        for (index, digit) in integer.chars().enumerate() {
+            if index > 0 && (integer.len() - index) % 3 == 0 {
+                grouped.push_str(&self.thousands_separator);
+            }
+            grouped.push(digit);
+        }
+        grouped.push_str(fraction);
+        grouped
+    }
+
+    /// A percentage, e.g. `42%`.
+    pub fn percent(&self, value: f64) -> String {
+        format!(
+            "{}{}",
+            self.number(value, self.percent_decimals),
+            self.percent_sign
+        )
+    }
+
+    /// A size in bytes with the largest fitting unit, e.g. `1.5 GiB`.
+    pub fn bytes(&self, bytes: u64) -> String {
+        let (base, units) = match self.prefixes {
+            UnitPrefixes::Binary => (1024.0, ["B", "KiB", "MiB", "GiB", "TiB", "PiB"]),
+            UnitPrefixes::Si => (1000.0, ["B", "kB", "MB", "GB", "TB", "PB"]),
+        };
+        let mut value = bytes as f64;
+        let mut unit = 0;
+        while value >= base && unit < units.len() - 1 {
+            value /= base;
+            unit += 1;
+        }
+        // Whole bytes have no fraction
+        let decimals = if unit == 0 { 0 } else { self.decimals };
+        format!("{} {}", self.number(value, decimals), units[unit])
+    }
+}
+
+/// Fill the `{name}` placeholders of a widget format string. Unknown
+/// placeholders are kept as written, so typos show up in the bar.
+pub fn render(template: &str, values: &[(&str, &str)]) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let placeholder = &rest[start..];
+        let value = placeholder.find('}').and_then(|end| {
+            let name = &placeholder[1..end];
+            values
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| (*value, end))
+        });
+        match value {
+            Some((value, end)) => {
+                output.push_str(value);
+                rest = &placeholder[end + 1..];
+            }
+            None => {
+                output.push('{');
+                rest = &placeholder[1..];
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
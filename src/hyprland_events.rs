@@ -10,6 +10,8 @@ use iced::Subscription;
 use std::future;
 use std::pin::Pin;
 
+use crate::diagnostics;
+
 /// Type alias for the boxed async handler future.
 type BoxedFuture = Pin<Box<dyn std::future::Future<Output = ()> + Send>>;
 
@@ -27,7 +29,12 @@ pub struct HyprlandSubscription<M> {
     workspace_added: Option<Box<dyn Fn() -> M + Send + Sync + 'static>>,
     workspace_deleted: Option<Box<dyn Fn() -> M + Send + Sync + 'static>>,
     workspace_changed: Option<Box<dyn Fn() -> M + Send + Sync + 'static>>,
+    special_changed: Option<Box<dyn Fn() -> M + Send + Sync + 'static>>,
     active_window: Option<Box<dyn Fn(Option<(String, String)>) -> M + Send + Sync + 'static>>,
+    fullscreen_changed: Option<Box<dyn Fn(bool) -> M + Send + Sync + 'static>>,
+    config_reloaded: Option<Box<dyn Fn() -> M + Send + Sync + 'static>>,
+    monitors_changed: Option<Box<dyn Fn() -> M + Send + Sync + 'static>>,
+    windows_changed: Option<Box<dyn Fn() -> M + Send + Sync + 'static>>,
 }
 
 impl<M> HyprlandSubscription<M>
@@ -41,7 +48,12 @@ where
             workspace_added: None,
             workspace_deleted: None,
             workspace_changed: None,
+            special_changed: None,
             active_window: None,
+            fullscreen_changed: None,
+            config_reloaded: None,
+            monitors_changed: None,
+            windows_changed: None,
         }
     }
 
@@ -72,15 +84,26 @@ where
         self
     }
 
+    /// Handle a special workspace being shown or hidden on a monitor.
+    pub fn on_special_changed<F>(mut self, handler: F) -> Self
+    where
+        F: Fn() -> M + Send + Sync + 'static,
+    {
+        self.special_changed = Some(Box::new(handler));
+        self
+    }
+
     /// Handle all workspace events with a single handler.
-    /// Convenience method that sets added, deleted, and changed handlers.
+    /// Convenience method that sets added, deleted, changed and special
+    /// changed handlers.
     pub fn on_any_workspace_event<F>(self, handler: F) -> Self
     where
         F: Fn() -> M + Clone + Send + Sync + 'static,
     {
         self.on_workspace_added(handler.clone())
             .on_workspace_deleted(handler.clone())
-            .on_workspace_changed(handler)
+            .on_workspace_changed(handler.clone())
+            .on_special_changed(handler)
     }
 
     /// Handle active window changed events.
@@ -93,6 +116,43 @@ where
         self
     }
 
+    /// Handle fullscreen state changes of the focused window.
+    /// The handler receives whether fullscreen is now on.
+    pub fn on_fullscreen_changed<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(bool) -> M + Send + Sync + 'static,
+    {
+        self.fullscreen_changed = Some(Box::new(handler));
+        self
+    }
+
+    /// Handle Hyprland config reloads.
+    pub fn on_config_reloaded<F>(mut self, handler: F) -> Self
+    where
+        F: Fn() -> M + Send + Sync + 'static,
+    {
+        self.config_reloaded = Some(Box::new(handler));
+        self
+    }
+
+    /// Handle monitors being added or removed.
+    pub fn on_monitors_changed<F>(mut self, handler: F) -> Self
+    where
+        F: Fn() -> M + Send + Sync + 'static,
+    {
+        self.monitors_changed = Some(Box::new(handler));
+        self
+    }
+
+    /// Handle windows being opened, closed or moved to another workspace.
+    pub fn on_windows_changed<F>(mut self, handler: F) -> Self
+    where
+        F: Fn() -> M + Send + Sync + 'static,
+    {
+        self.windows_changed = Some(Box::new(handler));
+        self
+    }
+
     /// Build the subscription.
     pub fn build(self) -> Subscription<M> {
         let id = self.id;
@@ -100,20 +160,8 @@ where
         Subscription::run_with_id(
             id,
             stream::channel(100, move |output| {
-                let workspace_added = self.workspace_added;
-                let workspace_deleted = self.workspace_deleted;
-                let workspace_changed = self.workspace_changed;
-                let active_window = self.active_window;
-
                 async move {
-                    run_listener(
-                        output,
-                        workspace_added,
-                        workspace_deleted,
-                        workspace_changed,
-                        active_window,
-                    )
-                    .await;
+                    run_listener(output, self).await;
 
                     // Keep subscription alive
                     future::pending::<()>().await;
@@ -124,16 +172,23 @@ where
 }
 
 /// Internal function to run the event listener with configured handlers.
-async fn run_listener<M, S>(
-    output: S,
-    workspace_added: Option<Box<dyn Fn() -> M + Send + Sync + 'static>>,
-    workspace_deleted: Option<Box<dyn Fn() -> M + Send + Sync + 'static>>,
-    workspace_changed: Option<Box<dyn Fn() -> M + Send + Sync + 'static>>,
-    active_window: Option<Box<dyn Fn(Option<(String, String)>) -> M + Send + Sync + 'static>>,
-) where
+async fn run_listener<M, S>(output: S, handlers: HyprlandSubscription<M>)
+where
     M: Clone + Send + 'static,
     S: SinkExt<M> + Clone + Unpin + Send + Sync + 'static,
 {
+    let HyprlandSubscription {
+        workspace_added,
+        workspace_deleted,
+        workspace_changed,
+        special_changed,
+        active_window,
+        fullscreen_changed,
+        config_reloaded,
+        monitors_changed,
+        windows_changed,
+        ..
+    } = handlers;
     let mut listener = AsyncEventListener::new();
 
     // Helper to create workspace event handlers
@@ -173,6 +228,22 @@ async fn run_listener<M, S>(
         output
     );
 
+    if let Some(handler) = special_changed {
+        let handler = std::sync::Arc::new(handler);
+        add_workspace_handler!(
+            listener,
+            add_changed_special_handler,
+            Some(handler.clone()),
+            output
+        );
+        add_workspace_handler!(
+            listener,
+            add_special_removed_handler,
+            Some(handler),
+            output
+        );
+    }
+
     // Active window handler is slightly different - it receives data
     if let Some(handler) = active_window {
         let handler = std::sync::Arc::new(handler);
@@ -188,8 +259,73 @@ async fn run_listener<M, S>(
         });
     }
 
+    if let Some(handler) = fullscreen_changed {
+        let handler = std::sync::Arc::new(handler);
+        let output = output.clone();
+        listener.add_fullscreen_state_changed_handler(move |state| {
+            let handler = handler.clone();
+            let mut output = output.clone();
+            Box::pin(async move {
+                let msg = handler(state);
+                let _ = output.send(msg).await;
+            }) as BoxedFuture
+        });
+    }
+
+    if let Some(handler) = config_reloaded {
+        let handler = std::sync::Arc::new(handler);
+        let output = output.clone();
+        listener.add_config_reloaded_handler(move || {
+            let handler = handler.clone();
+            let mut output = output.clone();
+            Box::pin(async move {
+                let msg = handler();
+                let _ = output.send(msg).await;
+            }) as BoxedFuture
+        });
+    }
+
+    if let Some(handler) = monitors_changed {
+        let handler = std::sync::Arc::new(handler);
+        add_workspace_handler!(
+            listener,
+            add_monitor_added_handler,
+            Some(handler.clone()),
+            output
+        );
+        add_workspace_handler!(
+            listener,
+            add_monitor_removed_handler,
+            Some(handler),
+            output
+        );
+    }
+
+    if let Some(handler) = windows_changed {
+        let handler = std::sync::Arc::new(handler);
+        add_workspace_handler!(
+            listener,
+            add_window_opened_handler,
+            Some(handler.clone()),
+            output
+        );
+        add_workspace_handler!(
+            listener,
+            add_window_closed_handler,
+            Some(handler.clone()),
+            output
+        );
+        add_workspace_handler!(
+            listener,
+            add_window_moved_handler,
+            Some(handler),
+            output
+        );
+    }
+
     // Start listener
     if let Err(e) = listener.start_listener_async().await {
         eprintln!("Hyprland event listener error: {:?}", e);
+        diagnostics::error(&format!("Hyprland event listener: {:?}", e));
     }
 }
@@ -0,0 +1,68 @@
+//! Icons of the battery, volume and notification widgets, in the style
+//! picked with `theme.icons` (or per module in `[theme.<module>]`).
+//!
+//! Nerd Font glyphs need a patched font. `unicode` draws plain Unicode
+//! symbols most fonts have, `image` draws PNG icons named after the
+//! freedesktop icon names (e.g. `battery-good.png`, `audio-volume-high.png`)
+//! from `theme.icon_dir`, and `none` leaves the icon out.
+
+use iced::widget::image;
+use serde::{Deserialize, Serialize};
+
+use crate::components::system_tray::icon::find_icon_in_path_cached;
+use crate::layout::ModuleKind;
+use crate::theme::{get_theme, with_component};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IconStyle {
+    #[default]
+    #[serde(rename = "nerdfont")]
+    NerdFont,
+    Unicode,
+    Image,
+    None,
+}
+
+/// One icon in every style.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Icon {
+    /// Nerd Font glyph
+    pub glyph: &'static str,
+    /// Symbol for fonts without Nerd Font glyphs
+    pub unicode: &'static str,
+    /// Freedesktop icon name, the file name of the image
+    pub name: &'static str,
+}
+
+/// An icon as a widget shows it.
+#[derive(Debug, Clone, Default)]
+pub struct Shown {
+    /// Text for the widget's `{icon}`; empty when drawn as an image
+    pub text: &'static str,
+    /// Image drawn in front of the widget's text
+    pub image: Option<image::Handle>,
+}
+
+impl Icon {
+    /// The icon in the style of a module. A missing image falls back to
+    /// the Unicode symbol.
+    pub fn show(&self, kind: &ModuleKind) -> Shown {
+        let theme = with_component(kind, get_theme);
+        let text = match theme.icons() {
+            IconStyle::NerdFont => self.glyph,
+            IconStyle::Unicode => self.unicode,
+            IconStyle::None => "",
+            IconStyle::Image => match find_icon_in_path_cached(theme.icon_dir(), self.name) {
+                Some(path) => {
+                    return Shown {
+                        text: "",
+                        image: Some(image::Handle::from_path(path)),
+                    };
+                }
+                None => self.unicode,
+            },
+        };
+        Shown { text, image: None }
+    }
+}
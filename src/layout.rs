@@ -0,0 +1,415 @@
+//! Bar layout configuration and the interactive layout editor.
+//!
+//! The layout decides which modules are shown in each bar section and in
+//! which order. The editor lets the user rearrange modules by dragging them
+//! with the mouse; the result is written back to the config file.
+
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// A module that can be placed in the bar.
+///
+/// Serialized as its config name, e.g. `"clock"`, or `"file:status"` and
+/// `"carousel:stats"` for modules that refer to a named instance.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum ModuleKind {
+    Workspaces,
+    WindowTitle,
+    SystemTray,
+    Volume,
+    Battery,
+    Clock,
+    NotificationToggle,
+    FocusTimer,
+    Devices,
+    LidDock,
+    Power,
+    Timer,
+    Swap,
+    PublicIp,
+    Mail,
+    TilingLayout,
+    Cursor,
+    Vrr,
+    Hosts,
+    /// Separator between groups of modules, from `[separator]`
+    Separator,
+    /// Empty gap, from `[spacer]`
+    Spacer,
+    /// Fixed text, as written after `label:`
+    Label(String),
+    /// File content widget, by name from `[[file_widgets]]`
+    File(String),
+    /// Modules rotating in one slot, by name from `[[carousels]]`
+    Carousel(String),
+}
+
+impl std::fmt::Display for ModuleKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ModuleKind::Workspaces => write!(f, "workspaces"),
+            ModuleKind::WindowTitle => write!(f, "window_title"),
+            ModuleKind::SystemTray => write!(f, "system_tray"),
+            ModuleKind::Volume => write!(f, "volume"),
+            ModuleKind::Battery => write!(f, "battery"),
+            ModuleKind::Clock => write!(f, "clock"),
+            ModuleKind::NotificationToggle => write!(f, "notification_toggle"),
+            ModuleKind::FocusTimer => write!(f, "focus_timer"),
+            ModuleKind::Devices => write!(f, "devices"),
+            ModuleKind::LidDock => write!(f, "lid_dock"),
+            ModuleKind::Power => write!(f, "power"),
+            ModuleKind::Timer => write!(f, "timer"),
+            ModuleKind::Swap => write!(f, "swap"),
+            ModuleKind::PublicIp => write!(f, "public_ip"),
+            ModuleKind::Mail => write!(f, "mail"),
+            ModuleKind::TilingLayout => write!(f, "tiling_layout"),
+            ModuleKind::Cursor => write!(f, "cursor"),
+            ModuleKind::Vrr => write!(f, "vrr"),
+            ModuleKind::Hosts => write!(f, "hosts"),
+            ModuleKind::Separator => write!(f, "separator"),
+            ModuleKind::Spacer => write!(f, "spacer"),
+            ModuleKind::Label(text) => write!(f, "label:{}", text),
+            ModuleKind::File(name) => write!(f, "file:{}", name),
+            ModuleKind::Carousel(name) => write!(f, "carousel:{}", name),
+        }
+    }
+}
+
+impl TryFrom<String> for ModuleKind {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if let Some(name) = value.strip_prefix("file:") {
+            return Ok(ModuleKind::File(name.to_string()));
+        }
+        if let Some(name) = value.strip_prefix("carousel:") {
+            return Ok(ModuleKind::Carousel(name.to_string()));
+        }
+        if let Some(text) = value.strip_prefix("label:") {
+            return Ok(ModuleKind::Label(text.to_string()));
+        }
+        match value.as_str() {
+            "workspaces" => Ok(ModuleKind::Workspaces),
+            "window_title" => Ok(ModuleKind::WindowTitle),
+            "system_tray" => Ok(ModuleKind::SystemTray),
+            "volume" => Ok(ModuleKind::Volume),
+            "battery" => Ok(ModuleKind::Battery),
+            "clock" => Ok(ModuleKind::Clock),
+            "notification_toggle" => Ok(ModuleKind::NotificationToggle),
+            "focus_timer" => Ok(ModuleKind::FocusTimer),
+            "devices" => Ok(ModuleKind::Devices),
+            "lid_dock" => Ok(ModuleKind::LidDock),
+            "power" => Ok(ModuleKind::Power),
+            "timer" => Ok(ModuleKind::Timer),
+            "swap" => Ok(ModuleKind::Swap),
+            "public_ip" => Ok(ModuleKind::PublicIp),
+            "mail" => Ok(ModuleKind::Mail),
+            "tiling_layout" => Ok(ModuleKind::TilingLayout),
+            "cursor" => Ok(ModuleKind::Cursor),
+            "vrr" => Ok(ModuleKind::Vrr),
+            "hosts" => Ok(ModuleKind::Hosts),
+            "separator" => Ok(ModuleKind::Separator),
+            "spacer" => Ok(ModuleKind::Spacer),
+            _ => Err(format!("unknown module `{}`", value)),
+        }
+    }
+}
+
+impl From<ModuleKind> for String {
+    fn from(kind: ModuleKind) -> Self {
+        kind.to_string()
+    }
+}
+
+/// One of the three bar sections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Section {
+    Left,
+    Center,
+    Right,
+}
+
+/// Position of a module inside the layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Slot {
+    pub section: Section,
+    pub index: usize,
+}
+
+/// Modules shown in each bar section, in display order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayoutConfig {
+    #[serde(default = "default_left")]
+    pub left: Vec<ModuleKind>,
+    #[serde(default = "default_center")]
+    pub center: Vec<ModuleKind>,
+    #[serde(default = "default_right")]
+    pub right: Vec<ModuleKind>,
+    /// How the sections are drawn; a monitor's layout without one uses the
+    /// top-level one
+    #[serde(default)]
+    pub style: Option<LayoutStyle>,
+}
+
+/// How the bar sections are drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LayoutStyle {
+    /// One bar across the output
+    #[default]
+    Bar,
+    /// Each section in a rounded pill of its own, with gaps between them
+    Islands,
+}
+
+fn default_left() -> Vec<ModuleKind> {
+    vec![ModuleKind::Workspaces]
+}
+
+fn default_center() -> Vec<ModuleKind> {
+    vec![ModuleKind::WindowTitle]
+}
+
+fn default_right() -> Vec<ModuleKind> {
+    vec![
+        ModuleKind::SystemTray,
+        ModuleKind::Volume,
+        ModuleKind::Battery,
+        ModuleKind::Clock,
+        ModuleKind::NotificationToggle,
+    ]
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        Self {
+            left: default_left(),
+            center: default_center(),
+            right: default_right(),
+            style: None,
+        }
+    }
+}
+
+impl LayoutConfig {
+    /// Modules of a section, in display order.
+    pub fn section(&self, section: Section) -> &[ModuleKind] {
+        match section {
+            Section::Left => &self.left,
+            Section::Center => &self.center,
+            Section::Right => &self.right,
+        }
+    }
+
+    /// Whether the module is placed in any section.
+    pub fn contains(&self, kind: &ModuleKind) -> bool {
+        [&self.left, &self.center, &self.right]
+            .iter()
+            .any(|section| section.contains(kind))
+    }
+
+    fn section_mut(&mut self, section: Section) -> &mut Vec<ModuleKind> {
+        match section {
+            Section::Left => &mut self.left,
+            Section::Center => &mut self.center,
+            Section::Right => &mut self.right,
+        }
+    }
+
+    /// Move the module at `from` so that it ends up before the module
+    /// currently at `to` (or at the end when `to.index` is past the end).
+    /// Returns whether the layout changed.
+    pub fn move_module(&mut self, from: Slot, to: Slot) -> bool {
+        if from.index >= self.section(from.section).len() {
+            return false;
+        }
+
+        let mut target = to.index;
+        if from.section == to.section {
+            // Dropping onto itself or right after itself is a no-op
+            if target == from.index || target == from.index + 1 {
+                return false;
+            }
+            // Removing the module shifts everything after it one to the left
+            if target > from.index {
+                target -= 1;
+            }
+        }
+
+        let kind = self.section_mut(from.section).remove(from.index);
+        let dest = self.section_mut(to.section);
+        dest.insert(target.min(dest.len()), kind);
+        true
+    }
+}
+
+// ============================================================================
+// Layout editor
+// ============================================================================
+
+/// Messages that the layout editor can handle.
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Enter or leave edit mode
+    Toggle,
+    /// User grabbed the drag handle of a module
+    Pick(Slot),
+    /// Cursor entered a drop target while dragging
+    Hover(Slot),
+    /// Cursor left a drop target while dragging
+    Leave(Slot),
+    /// Mouse button released over a drop target - drop the dragged module
+    Drop,
+    /// Mouse button released elsewhere - put the dragged module back
+    Cancel,
+}
+
+/// State of the drag-to-reorder edit mode.
+#[derive(Debug, Clone, Default)]
+pub struct LayoutEditor {
+    /// Whether edit mode is active
+    active: bool,
+    /// Module currently being dragged
+    dragging: Option<Slot>,
+    /// Drop target under the cursor
+    hovered: Option<Slot>,
+}
+
+impl LayoutEditor {
+    /// Whether edit mode is active.
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Whether a module is currently being dragged.
+    pub fn is_dragging(&self) -> bool {
+        self.dragging.is_some()
+    }
+
+    /// Module currently being dragged, if any.
+    pub fn dragging(&self) -> Option<Slot> {
+        self.dragging
+    }
+
+    /// Drop target under the cursor while dragging.
+    pub fn drop_target(&self) -> Option<Slot> {
+        self.dragging.and(self.hovered)
+    }
+
+    /// Update editor state. Returns `true` when `layout` was modified and
+    /// should be persisted.
+    pub fn update(&mut self, message: Message, layout: &mut LayoutConfig) -> bool {
+        match message {
+            Message::Toggle => {
+                self.active = !self.active;
+                self.dragging = None;
+                self.hovered = None;
+                false
+            }
+            Message::Pick(slot) => {
+                if self.active {
+                    self.dragging = Some(slot);
+                    self.hovered = Some(slot);
+                }
+                false
+            }
+            Message::Hover(slot) => {
+                if self.dragging.is_some() {
+                    self.hovered = Some(slot);
+                }
+                false
+            }
+            Message::Leave(slot) => {
+                if self.hovered == Some(slot) {
+                    self.hovered = None;
+                }
+                false
+            }
+            Message::Drop => match (self.dragging.take(), self.hovered.take()) {
+                (Some(from), Some(to)) => layout.move_module(from, to),
+                _ => false,
+            },
+            Message::Cancel => {
+                self.dragging = None;
+                self.hovered = None;
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot(section: Section, index: usize) -> Slot {
+        Slot { section, index }
+    }
+
+    #[test]
+    fn moves_modules_within_a_section() {
+        let mut layout = LayoutConfig::default();
+
+        // Clock in front of the tray
+        assert!(layout.move_module(slot(Section::Right, 3), slot(Section::Right, 0)));
+        assert_eq!(layout.right[0], ModuleKind::Clock);
+        assert_eq!(layout.right[1], ModuleKind::SystemTray);
+
+        // Tray to the end, past the last module
+        assert!(layout.move_module(slot(Section::Right, 1), slot(Section::Right, 5)));
+        assert_eq!(layout.right[4], ModuleKind::SystemTray);
+        assert_eq!(layout.right.len(), 5);
+    }
+
+    #[test]
+    fn moves_modules_between_sections() {
+        let mut layout = LayoutConfig::default();
+        assert!(layout.move_module(slot(Section::Right, 3), slot(Section::Left, 0)));
+        assert_eq!(layout.left, [ModuleKind::Clock, ModuleKind::Workspaces]);
+        assert!(!layout.right.contains(&ModuleKind::Clock));
+
+        // Into an empty section, at an index past its end
+        layout.center.clear();
+        assert!(layout.move_module(slot(Section::Left, 0), slot(Section::Center, 3)));
+        assert_eq!(layout.center, [ModuleKind::Clock]);
+    }
+
+    #[test]
+    fn ignores_moves_that_change_nothing() {
+        let mut layout = LayoutConfig::default();
+        // Onto itself, right after itself, and from an empty slot
+        assert!(!layout.move_module(slot(Section::Right, 1), slot(Section::Right, 1)));
+        assert!(!layout.move_module(slot(Section::Right, 1), slot(Section::Right, 2)));
+        assert!(!layout.move_module(slot(Section::Center, 1), slot(Section::Left, 0)));
+        assert_eq!(layout, LayoutConfig::default());
+    }
+
+    #[test]
+    fn drops_only_over_a_target() {
+        let mut layout = LayoutConfig::default();
+        let mut editor = LayoutEditor::default();
+        editor.update(Message::Toggle, &mut layout);
+
+        // Released after leaving the target: nothing moves
+        editor.update(Message::Pick(slot(Section::Right, 3)), &mut layout);
+        editor.update(Message::Hover(slot(Section::Left, 0)), &mut layout);
+        editor.update(Message::Leave(slot(Section::Left, 0)), &mut layout);
+        assert!(!editor.update(Message::Drop, &mut layout));
+        assert!(!editor.is_dragging());
+
+        // Released outside any target
+        editor.update(Message::Pick(slot(Section::Right, 3)), &mut layout);
+        editor.update(Message::Hover(slot(Section::Left, 0)), &mut layout);
+        assert!(!editor.update(Message::Cancel, &mut layout));
+        assert_eq!(layout, LayoutConfig::default());
+
+        editor.update(Message::Pick(slot(Section::Right, 3)), &mut layout);
+        editor.update(Message::Hover(slot(Section::Left, 0)), &mut layout);
+        assert!(editor.update(Message::Drop, &mut layout));
+        assert_eq!(layout.left[0], ModuleKind::Clock);
+    }
+}
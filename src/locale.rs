@@ -0,0 +1,173 @@
+//! Day and month names of the clock and calendar in the user's language,
+//! and the 12-hour clock.
+//!
+//! Names and AM/PM come from the glibc locale data chrono bundles with its
+//! `unstable-locales` feature, by formatting with `format_localized`. The
+//! locale is `clock.locale`, or the one of `$LC_ALL`, `$LC_TIME` or
+//! `$LANG`; unknown locales fall back to POSIX, i.e. English.
+
+use chrono::NaiveDate;
+
+pub use chrono::Locale;
+
+/// The locale of `clock.locale`, or of the environment when unset.
+pub fn from_config(name: Option<&str>) -> Locale {
+    if let Some(name) = name {
+        return find(name).unwrap_or_else(|| {
+            eprintln!("Unknown clock locale `{}`, using English", name);
+            Locale::POSIX
+        });
+    }
+    ["LC_ALL", "LC_TIME", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| find(&value))
+        .unwrap_or(Locale::POSIX)
+}
+
+/// The locale of a name like `de`, `de_DE`, `de_DE.UTF-8` or
+/// `de_DE.UTF-8@euro`.
+fn find(name: &str) -> Option<Locale> {
+    let (name, modifier) = match name.split_once('@') {
+        Some((name, modifier)) => (name, Some(modifier)),
+        None => (name, None),
+    };
+    // The codeset doesn't matter, names are UTF-8 either way
+    let name = name.split('.').next()?;
+    if name == "C" {
+        return Some(Locale::POSIX);
+    }
+    // A bare language is taken as spoken in its namesake country
+    let name = if name.contains('_') {
+        name.to_string()
+    } else {
+        format!(
+            "{}_{}",
+            name.to_ascii_lowercase(),
+            name.to_ascii_uppercase()
+        )
+    };
+    modifier
+        .and_then(|modifier| Locale::try_from(format!("{}@{}", name, modifier).as_str()).ok())
+        .or_else(|| Locale::try_from(name.as_str()).ok())
+}
+
+/// Two-letter weekday names for the calendar header, Monday first.
+pub fn weekday_initials(locale: Locale) -> [String; 7] {
+    // 2024-01-01 was a Monday
+    let monday = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap_or_default();
+    std::array::from_fn(|day| {
+        let date = monday + chrono::Days::new(day as u64);
+        date.format_localized("%a", locale)
+            .to_string()
+            .chars()
+            .take(2)
+            .collect()
+    })
+}
+
+/// `format` with hours from 1 to 12 and AM/PM after the time (unless it
+/// already has `%p` or `%P`).
+pub fn twelve_hour(format: &str) -> String {
+    let pieces: Vec<Piece> = pieces(format).collect();
+    let has_am_pm = pieces
+        .iter()
+        .any(|piece| matches!(piece, Piece::Spec(_, 'p' | 'P')));
+    let mut converted = String::with_capacity(format.len() + 4);
+    // Where AM/PM goes: after the last minutes or seconds of the time
+    let mut am_pm_at = None;
+    let mut in_time = false;
+    for piece in pieces {
+        match piece {
+            Piece::Spec(_, 'H' | 'k') => {
+                converted.push_str("%-I");
+                in_time = true;
+                am_pm_at = Some(converted.len());
+            }
+            Piece::Spec(_, 'R') => {
+                converted.push_str("%-I:%M");
+                am_pm_at = Some(converted.len());
+            }
+            Piece::Spec(_, 'T') => {
+                converted.push_str("%-I:%M:%S");
+                am_pm_at = Some(converted.len());
+            }
+            Piece::Spec(text, 'M' | 'S') if in_time => {
+                converted.push_str(text);
+                am_pm_at = Some(converted.len());
+            }
+            Piece::Spec(text, _) | Piece::Literal(text) => converted.push_str(text),
+        }
+    }
+    if let (Some(at), false) = (am_pm_at, has_am_pm) {
+        converted.insert_str(at, " %p");
+    }
+    converted
+}
+
+/// Part of a strftime format: a specifier with its text and letter, or
+/// text in between.
+enum Piece<'a> {
+    Spec(&'a str, char),
+    Literal(&'a str),
+}
+
+fn pieces(format: &str) -> impl Iterator<Item = Piece<'_>> {
+    let mut rest = format;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let Some(literal) = rest.find('%') else {
+            return Some(Piece::Literal(std::mem::take(&mut rest)));
+        };
+        if literal > 0 {
+            let (text, after) = rest.split_at(literal);
+            rest = after;
+            return Some(Piece::Literal(text));
+        }
+        // Skip padding, width and other modifiers to the letter
+        let letter = rest[1..]
+            .char_indices()
+            .find(|(_, c)| !matches!(c, '-' | '_' | '0'..='9' | '.' | ':' | '#'));
+        let end = match letter {
+            Some((index, c)) => 1 + index + c.len_utf8(),
+            None => rest.len(),
+        };
+        let (text, after) = rest.split_at(end);
+        rest = after;
+        Some(Piece::Spec(text, letter.map_or('%', |(_, c)| c)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_locales() {
+        assert_eq!(find("de_DE.UTF-8"), Some(Locale::de_DE));
+        assert_eq!(find("de_DE.UTF-8@euro"), Some(Locale::de_DE_euro));
+        assert_eq!(find("fr"), Some(Locale::fr_FR));
+        assert_eq!(find("C.UTF-8"), Some(Locale::POSIX));
+        assert_eq!(find("xx_XX"), None);
+
+        let date = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        assert_eq!(
+            date.format_localized("%a %d %b, %A %-d. %B", Locale::de_DE)
+                .to_string(),
+            "Mo 03 Mär, Montag 3. März"
+        );
+        assert_eq!(weekday_initials(Locale::de_DE)[6], "So");
+    }
+
+    #[test]
+    fn converts_to_twelve_hours() {
+        assert_eq!(twelve_hour("%a %d %b %H:%M"), "%a %d %b %-I:%M %p");
+        assert_eq!(twelve_hour("%H:%M:%S (%Z)"), "%-I:%M:%S %p (%Z)");
+        assert_eq!(twelve_hour("%R %d"), "%-I:%M %p %d");
+        assert_eq!(twelve_hour("%I:%M %p"), "%I:%M %p");
+        assert_eq!(twelve_hour("%d %b"), "%d %b");
+    }
+}
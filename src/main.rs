@@ -1,41 +1,82 @@
+mod animation;
+mod app_icons;
+mod appearance;
+mod blur;
+mod check;
+mod color;
 mod components;
 mod config;
+mod control;
+mod diagnostics;
+mod exec;
+mod format;
 mod hyprland_events;
+mod icons;
+mod layout;
+mod locale;
+mod migrate;
+mod modules;
+mod open;
+mod popup;
+mod privileged;
+mod profile;
+mod schema;
+mod secrets;
+mod session;
+mod sound;
 mod styles;
+#[cfg(test)]
+mod testing;
 mod theme;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
 
+use hyprland::data::{Clients, FullscreenMode, Monitors};
+use hyprland::shared::HyprData;
 use iced::event::{self, Event};
 use iced::keyboard::{self, key::Named};
 use iced::border::Radius;
+use iced::mouse;
 use iced::widget::container::Style;
-use iced::widget::{button, column, container, row, scrollable, text};
-use iced::window::Id;
-use iced::{Border, Element, Font, Length, Subscription, Task};
-use iced_layershell::actions::{IcedNewMenuSettings, MenuDirection};
+use iced::widget::{Row, button, column, container, image, mouse_area, row, scrollable, text};
+use iced::window::{self, Id};
+use iced::{Border, Element, Font, Length, Point, Subscription, Task, alignment, time};
+use iced_layershell::actions::{IcedNewMenuSettings, IcedNewPopupSettings};
 use iced_layershell::build_pattern::{MainSettings, daemon};
-use iced_layershell::reexport::{Anchor, Layer};
-use iced_layershell::settings::LayerShellSettings;
+use iced_layershell::reexport::{Layer, NewLayerShellSettings};
+use iced_layershell::settings::{LayerShellSettings, StartMode};
 use iced_layershell::to_layer_message;
 
-use crate::config::{Config, ConfigMessage, config_subscription};
-use crate::theme::{AppTheme, set_global_theme};
-use components::battery;
-use components::clock;
-use components::notification_toggle;
-use components::system_tray;
-use components::volume;
-use components::window_title;
-use components::workspaces;
+use crate::animation::Animation;
+use crate::appearance::{ColorScheme, ThemeSwitch};
+use crate::config::{
+    BarHideMode, BarPosition, Config, ConfigMessage, PopupSpace, config_subscription,
+};
+use crate::hyprland_events::HyprlandSubscription;
+use crate::layout::{LayoutConfig, LayoutEditor, LayoutStyle, ModuleKind, Section, Slot};
+use crate::sound::SoundEvent;
+use crate::popup::{MINIMIZED_BAR_HEIGHT, Placement};
+use crate::theme::{AppTheme, resolve_font, set_global_theme};
+use components::{clock, devices, notification_toggle, power, system_tray, workspaces};
+use modules::{Action, Context, MenuOwner, ModuleMessage, Modules};
 
 pub fn main() -> Result<(), iced_layershell::Error> {
+    // `clammy --set`, `--diagnose`, `--peek`, `--state`, `--check-config`,
+    // `--schema` and the polkit helper exit here
+    check::run_if_requested();
+    schema::run_if_requested();
+    privileged::run_if_requested();
+    control::run_if_requested();
+
     // Load config early to get font setting
     let config = Config::load().unwrap_or_default();
-    let default_font = match &config.theme.font {
-        Some(name) => Font::with_name(Box::leak(name.clone().into_boxed_str())),
-        None => Font::MONOSPACE,
-    };
+    let default_font = config
+        .theme
+        .font
+        .as_ref()
+        .and_then(|font| resolve_font(font.families()))
+        .unwrap_or(Font::MONOSPACE);
 
     daemon(
         StatusBar::namespace,
@@ -45,13 +86,11 @@ pub fn main() -> Result<(), iced_layershell::Error> {
     )
     .subscription(StatusBar::subscription)
     .theme(StatusBar::theme)
+    .scale_factor(StatusBar::scale_factor)
     .settings(MainSettings {
+        // Bars are opened per output once the outputs are known
         layer_settings: LayerShellSettings {
-            anchor: Anchor::Top | Anchor::Left | Anchor::Right,
-            layer: Layer::Top,
-            exclusive_zone: 36,
-            size: Some((0, 36)),
-            margin: (4, 4, 15, 4),
+            start_mode: StartMode::Background,
             ..LayerShellSettings::default()
         },
         default_font,
@@ -62,68 +101,220 @@ pub fn main() -> Result<(), iced_layershell::Error> {
 }
 
 /// Window type identifier
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 enum WindowType {
-    Main,
-    TrayMenu,
+    /// Bar on the output with this name
+    Main(String),
+    PopupMenu,
+    Calendar,
+    PowerConfirm,
+    WorkspaceOverview,
+    TrayOverflow,
+    /// Empty surface reserving space for an open popup
+    Spacer,
+}
+
+impl WindowType {
+    fn is_popup(&self) -> bool {
+        !matches!(self, Self::Main(_) | Self::Spacer)
+    }
 }
 
 /// Animation state for dropdown menus
 #[derive(Debug, Clone)]
 struct PopupAnimationState {
-    /// Progress from 0.0 (closed) to 1.0 (fully open)
-    progress: f32,
+    /// Slide-down progress from closed to fully open
+    animation: Animation,
     /// Total height of menu content
     content_height: f32,
+    /// Gap from the popup origin (the click position) to the bar's edge
+    edge_offset: f32,
+}
+
+impl PopupAnimationState {
+    fn new(animation: Animation, content_height: f32, placement: &Placement) -> Self {
+        Self {
+            animation,
+            content_height,
+            edge_offset: placement.edge_offset(),
+        }
+    }
 }
 
+/// Peeking at the bar with `clammy --peek`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Peek {
+    #[default]
+    Off,
+    /// Until the key is released (or `peek.timeout_secs` passed)
+    Held,
+    /// Until toggled off
+    Latched,
+}
+
+impl Peek {
+    fn next(self, command: control::PeekCommand) -> Self {
+        match (command, self) {
+            (control::PeekCommand::Start, Peek::Off) => Peek::Held,
+            (control::PeekCommand::End, Peek::Held) => Peek::Off,
+            (control::PeekCommand::Toggle, Peek::Off) => Peek::Latched,
+            (control::PeekCommand::Toggle, _) => Peek::Off,
+            // Key repeat, or a release after a toggle
+            (_, peek) => peek,
+        }
+    }
+}
+
+/// How long the bar shows why a config change was rejected.
+const CONFIG_ERROR_TIMEOUT: Duration = Duration::from_secs(15);
+
 struct StatusBar {
     config: Config,
     app_theme: AppTheme,
-    battery: battery::Battery,
-    clock: clock::Clock,
-    volume: volume::Volume,
-    notification_toggle: notification_toggle::NotificationToggle,
-    workspaces: workspaces::Workspaces,
-    window_title: window_title::WindowTitle,
-    system_tray: system_tray::SystemTray,
+    /// State and message routing of all bar modules
+    modules: Modules,
+    /// Drag-to-reorder edit mode state
+    layout_editor: LayoutEditor,
     /// Track window IDs and their types
     windows: HashMap<Id, WindowType>,
     /// Store menu data for popup windows (keyed by popup ID)
-    menu_data: HashMap<Id, (String, Vec<system_tray::menu::MenuItem>)>,
+    menu_data: HashMap<Id, (MenuOwner, Vec<system_tray::menu::MenuItem>)>,
+    /// Expanded submenus of popup menus, by popup and menu item ID
+    expanded_submenus: HashSet<(Id, i32)>,
+    /// Menu item highlighted with the arrow keys, by popup
+    menu_highlight: HashMap<Id, i32>,
     /// Animation state for popup windows
     popup_animations: HashMap<Id, PopupAnimationState>,
+    /// Outputs whose bar is covered by a fullscreen window; the view is
+    /// blank, and display-only polling is suspended while every bar is
+    /// covered or hidden
+    bar_covered: HashSet<String>,
+    /// Outputs whose active workspace is listed in `bar.hide_on_workspaces`
+    bar_hidden: HashSet<String>,
+    /// Enabled outputs by name; each has a bar
+    outputs: HashMap<String, popup::Output>,
+    /// Bars waiting for their surface, opened one at a time
+    bars_to_open: VecDeque<Id>,
+    /// Bar whose surface is being opened
+    opening_bar: Option<Id>,
+    /// Output that had the focus before bars were opened
+    focus_to_restore: Option<String>,
+    /// Output of the bar the cursor was last on
+    hovered_output: Option<String>,
+    /// Startup delay is over and the heavy backends run
+    backends_started: bool,
+    /// Session is locked; sensitive widgets are blanked
+    session_locked: bool,
+    /// Light or dark theme variant in use (`None` with `theme.switch` off)
+    color_scheme: Option<ColorScheme>,
+    /// Spacer surfaces reserving space for open popups, by popup
+    reserved: HashMap<Id, Id>,
+    /// Bar revealed and expanded with `clammy --peek`
+    peek: Peek,
+    /// Counts held peeks, so a timeout only ends the one it was started for
+    peek_generation: u64,
+    /// Why the last change of the config was rejected, shown as a chip in
+    /// the bar, and its generation
+    config_error: Option<(u64, String)>,
+    /// Counts rejected changes, so a timeout only dismisses the chip it was
+    /// started for
+    config_error_generation: u64,
+    /// Click position and output, for placing popups
+    placement: Placement,
 }
 
 #[to_layer_message(multi)]
 #[derive(Debug, Clone)]
 enum Message {
-    Battery(battery::Message),
-    Clock(clock::Message),
-    Volume(volume::Message),
-    NotificationToggle(notification_toggle::Message),
-    Workspaces(workspaces::Message),
-    WindowTitle(window_title::Message),
-    SystemTray(system_tray::Message),
+    /// Message for a bar module
+    Module(ModuleMessage),
+    /// Layout edit mode interaction
+    Layout(layout::Message),
     /// Config file changed - hot reload
     ConfigChanged(ConfigMessage),
-    /// Open a tray menu popup
-    OpenTrayMenu {
-        address: String,
+    /// Open a popup menu
+    OpenMenu {
+        owner: MenuOwner,
         items: Vec<system_tray::menu::MenuItem>,
+        /// Bar-local x to center the menu on (the clicked tray icon);
+        /// the menu opens at the cursor without one
+        center_x: Option<f32>,
     },
     /// Close a popup window
     ClosePopup(Id),
     /// Menu item was clicked in popup
     PopupMenuItemClicked {
         popup_id: Id,
-        address: String,
+        owner: MenuOwner,
         menu_id: i32,
     },
+    /// Expand or collapse a submenu in a popup menu
+    ToggleSubmenu { popup_id: Id, menu_id: i32 },
     /// Global event for keyboard/mouse handling
     IcedEvent(Event),
-    /// Animation tick for popup slide-down
-    PopupAnimationTick,
+    /// Cursor moved over a window
+    CursorMoved(Id, Point),
+    /// Re-query the outputs after a monitor was added or removed
+    RefreshOutputs,
+    /// Outputs were queried (`None` if Hyprland could not be asked)
+    OutputsListed(Option<Vec<(String, popup::Output)>>),
+    /// The output of a bar about to be opened got the focus; carries the
+    /// output that had it before
+    BarOutputFocused(Id, Option<String>),
+    /// A surface was opened and configured
+    SurfaceOpened(Id),
+    /// A surface got a new size
+    SurfaceResized(Id),
+    /// Re-check the active workspace of each monitor, which covers or hides
+    /// its bar
+    CheckMonitors,
+    /// Result of a monitor check (`None` if Hyprland could not be asked)
+    MonitorsChecked(Option<Vec<MonitorState>>),
+    /// Startup delay is over, start the heavy backends
+    StartBackends,
+    /// A `clammy --state` client is waiting for the state
+    PublishState,
+    /// The session was locked or unlocked
+    SessionLocked(bool),
+    /// Re-check the portal setting or schedule of `theme.switch`
+    CheckColorScheme,
+    /// Light or dark variant that applies now
+    ColorSchemeChecked(Option<ColorScheme>),
+    /// Command from `clammy --peek`
+    Peek(control::PeekCommand),
+    /// `peek.timeout_secs` passed for the held peek of that generation
+    PeekTimeout(u64),
+    /// The config error chip of that generation timed out or was clicked
+    DismissConfigError(u64),
+    /// Frame redraw while a popup slide-down is running
+    PopupAnimationFrame(Instant),
+}
+
+impl Message {
+    /// What the diagnostics recording keeps of a message: the variant and
+    /// its small fields, without pixmaps, image handles or typed text.
+    fn summary(&self) -> String {
+        match self {
+            Message::Module(message) => format!("Module {}", message.summary()),
+            Message::Layout(message) => format!("Layout {:?}", message),
+            Message::ConfigChanged(message) => {
+                format!("ConfigChanged {}", diagnostics::variant(message))
+            }
+            Message::OpenMenu { owner, items, .. } => {
+                format!("OpenMenu {:?} with {} items", owner, items.len())
+            }
+            Message::IcedEvent(_) => "IcedEvent".to_string(),
+            Message::OutputsListed(outputs) => format!(
+                "OutputsListed {:?}",
+                outputs
+                    .as_ref()
+                    .map(|outputs| outputs.iter().map(|(name, _)| name).collect::<Vec<_>>())
+            ),
+            // The rest carry IDs, flags and small values only, including the
+            // layer shell requests
+            _ => format!("{:?}", self),
+        }
+    }
 }
 
 impl StatusBar {
@@ -133,163 +324,423 @@ impl StatusBar {
             eprintln!("Failed to load config: {}, using defaults", e);
             Config::default()
         });
-        let app_theme = AppTheme::from_config(&config);
+        for e in config.color_errors() {
+            eprintln!("Config error: {}", e);
+        }
+        let app_theme = AppTheme::from_config(&config, None);
+        diagnostics::configure(&config.diagnostics);
 
         // Set global theme for component access
         set_global_theme(&app_theme);
 
+        let modules = Modules::new(&config);
+        let init_task = modules.init().map(Message::Module);
+
+        let startup_delay = Duration::from_millis(config.bar.startup_delay_ms);
+        let mut placement = Placement::default();
+        placement.set_position(config.bar.position);
+        placement.set_scale(config.bar.scale_factor());
+        let blur_task = if config.bar.blur {
+            Task::future(blur::enable(config.bar.namespace())).discard()
+        } else {
+            Task::none()
+        };
+
         (
             Self {
                 config,
                 app_theme,
-                battery: battery::Battery::default(),
-                clock: clock::Clock::default(),
-                volume: volume::Volume::default(),
-                notification_toggle: notification_toggle::NotificationToggle::default(),
-                workspaces: workspaces::Workspaces::default(),
-                window_title: window_title::WindowTitle::default(),
-                system_tray: system_tray::SystemTray::default(),
+                modules,
+                layout_editor: LayoutEditor::default(),
                 windows: HashMap::new(),
                 menu_data: HashMap::new(),
+                expanded_submenus: HashSet::new(),
+                menu_highlight: HashMap::new(),
                 popup_animations: HashMap::new(),
+                bar_covered: HashSet::new(),
+                bar_hidden: HashSet::new(),
+                outputs: HashMap::new(),
+                bars_to_open: VecDeque::new(),
+                opening_bar: None,
+                focus_to_restore: None,
+                hovered_output: None,
+                backends_started: false,
+                session_locked: false,
+                color_scheme: None,
+                reserved: HashMap::new(),
+                peek: Peek::Off,
+                peek_generation: 0,
+                config_error: None,
+                config_error_generation: 0,
+                placement,
             },
-            Task::done(workspaces::Message::Refresh).map(Message::Workspaces),
+            Task::batch([
+                init_task,
+                Task::done(Message::CheckColorScheme),
+                Task::done(Message::CheckMonitors),
+                Task::done(Message::RefreshOutputs),
+                Task::perform(tokio::time::sleep(startup_delay), |_| Message::StartBackends),
+                blur_task,
+            ]),
         )
     }
 
     fn namespace(&self) -> String {
-        String::from("clammy")
+        self.config.bar.namespace()
     }
 
     fn theme(&self) -> iced::Theme {
         (&self.app_theme).into()
     }
 
+    /// `bar.scale`, on top of the output's own scale. Surface sizes are
+    /// scaled to match, see [`Placement::surface_len`].
+    fn scale_factor(&self, _id: Id) -> f64 {
+        self.config.bar.scale_factor() as f64
+    }
+
     fn remove_id(&mut self, id: Id) {
-        if let Some(window_type) = self.windows.remove(&id) {
-            if matches!(window_type, WindowType::TrayMenu) {
-                self.menu_data.remove(&id);
-                self.popup_animations.remove(&id);
-            }
+        if let Some(window_type) = self.windows.remove(&id)
+            && !matches!(window_type, WindowType::Main(_))
+        {
+            self.menu_data.remove(&id);
+            self.expanded_submenus.retain(|(popup_id, _)| *popup_id != id);
+            self.menu_highlight.remove(&id);
+            self.popup_animations.remove(&id);
         }
     }
 
     fn update(&mut self, message: Message) -> Task<Message> {
+        // Pointer movement and animation frames would flood the recording
+        if !matches!(
+            message,
+            Message::IcedEvent(_) | Message::CursorMoved(..) | Message::PopupAnimationFrame(_)
+        ) {
+            diagnostics::message(|| message.summary());
+        }
+
         match message {
-            Message::Battery(msg) => self.battery.update(msg).map(Message::Battery),
-            Message::Clock(msg) => {
-                self.clock.update(msg);
-                Task::none()
-            }
-            Message::Volume(msg) => self.volume.update(msg).map(Message::Volume),
-            Message::NotificationToggle(msg) => {
-                self.notification_toggle.update(msg).map(Message::NotificationToggle)
-            }
-            Message::Workspaces(msg) => self.workspaces.update(msg).map(Message::Workspaces),
-            Message::WindowTitle(msg) => {
-                self.window_title.update(msg);
-                Task::none()
+            Message::Module(msg) => {
+                let animation_frame = msg.is_animation_frame();
+                let context = Context {
+                    config: &self.config,
+                    click_position: self.placement.screen_point(self.placement.cursor()),
+                };
+                let (task, actions) = self.modules.update(msg, context);
+                let mut tasks = vec![task.map(Message::Module)];
+                for action in actions {
+                    tasks.push(self.perform(action));
+                }
+                // Animation frames only move the drawing along
+                if !animation_frame {
+                    control::publish_state(|| self.modules.state());
+                }
+                Task::batch(tasks)
             }
-            Message::SystemTray(msg) => {
-                // Check if this is a menu open request
-                if let system_tray::Message::ItemClicked(ref address) = msg {
-                    if let Some(items) = self.system_tray.get_menu_items(address) {
-                        if !items.is_empty() {
-                            return Task::done(Message::OpenTrayMenu {
-                                address: address.clone(),
-                                items,
-                            });
-                        }
-                    }
+            Message::Layout(msg) => {
+                // Persist the new layout; the config watcher picks it up again
+                // Edits apply to the layout of the bar being edited
+                let output = self.hovered_output.clone().unwrap_or_default();
+                let layout = self.config.layout_for_mut(&output);
+                if self.layout_editor.update(msg, layout)
+                    && let Err(e) = self.config.save_layout(&output)
+                {
+                    eprintln!("Failed to save layout: {}", e);
                 }
-                self.system_tray.update(msg).map(Message::SystemTray)
+                Task::none()
             }
             Message::ConfigChanged(config_msg) => {
                 match config_msg {
                     ConfigMessage::Reloaded(new_config) => {
-                        self.config = new_config;
-                        self.app_theme.update(&self.config);
+                        let new_config = *new_config;
+                        self.config_error = None;
+                        let hide_mode_changed =
+                            new_config.bar.hide_mode != self.config.bar.hide_mode;
+                        // Per-monitor layouts do not move the bars
+                        let geometry = |config: &Config| -> Vec<_> {
+                            config
+                                .monitors
+                                .iter()
+                                .map(|monitor| {
+                                    (monitor.name.clone(), monitor.height, monitor.position)
+                                })
+                                .collect()
+                        };
+                        let geometry_changed = new_config.bar.position
+                            != self.config.bar.position
+                            || new_config.bar.scale_factor() != self.config.bar.scale_factor()
+                            || geometry(&new_config) != geometry(&self.config);
+                        // Modules new to the layout look up their data now
+                        // instead of staying empty until their next interval
+                        let added_task = self
+                            .modules
+                            .start_added(&self.config, &new_config, self.backends_started)
+                            .map(Message::Module);
+                        let blur_task = if new_config.bar.blur && !self.config.bar.blur {
+                            Task::future(blur::enable(new_config.bar.namespace())).discard()
+                        } else {
+                            Task::none()
+                        };
+                        let old_config = std::mem::replace(&mut self.config, new_config);
+                        diagnostics::configure(&self.config.diagnostics);
+                        diagnostics::config_reloaded();
+                        // Move the bars in place and reopen resized ones; open
+                        // popups were placed for the old edge and height, so
+                        // they close
+                        let position_task = if geometry_changed {
+                            match self.hovered_output.clone() {
+                                Some(output) => self.place_on(&output),
+                                None => self.placement.set_position(self.config.bar.position),
+                            }
+                            self.placement.set_scale(self.config.bar.scale_factor());
+                            Task::batch([
+                                self.apply_bar_position(),
+                                self.apply_bar_hidden(),
+                                self.close_popups(),
+                                self.reopen_resized_bars(&old_config),
+                            ])
+                        } else {
+                            Task::none()
+                        };
+                        self.app_theme.update(&self.config, self.color_scheme);
                         set_global_theme(&self.app_theme);
+                        self.modules.set_config(&self.config);
+                        // Fonts, sizes and labels may have changed
+                        let relayout_task = self.relayout_bars();
+
+                        let unlock_task = if self.config.bar.blank_when_locked {
+                            Task::none()
+                        } else {
+                            self.set_session_locked(false)
+                        };
+
+                        // A new hide mode applies right away to hidden bars;
+                        // new hiding rules once the monitors are checked
+                        let hidden_task = if hide_mode_changed && !self.bar_hidden.is_empty() {
+                            self.apply_bar_hidden()
+                        } else {
+                            Task::none()
+                        };
+                        return Task::batch([
+                            position_task,
+                            added_task,
+                            blur_task,
+                            unlock_task,
+                            relayout_task,
+                            hidden_task,
+                            Task::done(Message::CheckMonitors),
+                            // `theme.switch` may have changed
+                            Task::done(Message::CheckColorScheme),
+                        ]);
+                    }
+                    // Keep the previous config and say why in the bar
+                    ConfigMessage::Rejected { error, summary } => {
+                        eprintln!("Failed to reload config, keeping the previous one: {}", error);
+                        diagnostics::error(&error);
+                        self.config_error_generation += 1;
+                        let generation = self.config_error_generation;
+                        self.config_error = Some((generation, summary));
+                        return Task::perform(tokio::time::sleep(CONFIG_ERROR_TIMEOUT), move |_| {
+                            Message::DismissConfigError(generation)
+                        });
                     }
                     ConfigMessage::Error(e) => {
                         eprintln!("Config error: {}", e);
+                        diagnostics::error(&e);
                     }
                 }
                 Task::none()
             }
-            Message::OpenTrayMenu { address, items } => {
+            Message::DismissConfigError(generation) => {
+                if self.config_error.as_ref().is_some_and(|(g, _)| *g == generation) {
+                    self.config_error = None;
+                }
+                Task::none()
+            }
+            Message::OpenMenu {
+                owner,
+                items,
+                center_x,
+            } => {
                 // Create popup window
                 let id = Id::unique();
 
                 // Calculate menu height
                 let menu_height = system_tray::menu::calculate_height(&items, self.app_theme.font_size()) + 16.0;
-                // Add 18px top offset + 4px connector height
-                let height = menu_height + 22.0;
+                // Add the gap to the bar and the connector height
+                let height = self.placement.surface_height(menu_height);
                 let content_height = menu_height;
 
                 // Store menu data keyed by popup ID
-                self.menu_data.insert(id, (address, items));
-                self.windows.insert(id, WindowType::TrayMenu);
+                let width = menu_width(&items, self.app_theme.font_size());
+                self.menu_data.insert(id, (owner, items));
+                self.windows.insert(id, WindowType::PopupMenu);
 
-                // Initialize animation state - starts at 0.0
+                // Initialize animation state - starts closed
                 self.popup_animations.insert(
                     id,
-                    PopupAnimationState {
-                        progress: 0.0,
+                    PopupAnimationState::new(
+                        self.config.animations.start(),
                         content_height,
-                    },
+                        &self.placement,
+                    ),
                 );
 
-                Task::done(Message::NewMenu {
-                    settings: IcedNewMenuSettings {
-                        size: (200, height.min(400.0) as u32),
-                        direction: MenuDirection::Down,
+                let size = (
+                    self.placement.surface_len(width),
+                    height.min(self.placement.surface_len(400)),
+                );
+                let open = match center_x {
+                    Some(center_x) => Message::NewPopUp {
+                        settings: IcedNewPopupSettings {
+                            size,
+                            position: self.placement.centered_popup_position(center_x, size),
+                        },
+                        id,
                     },
-                    id,
-                })
+                    None => Message::NewMenu {
+                        settings: IcedNewMenuSettings {
+                            size,
+                            direction: self.placement.direction(),
+                        },
+                        id,
+                    },
+                };
+                Task::batch([
+                    Task::done(open),
+                    self.reserve_popup_space(id, self.config.popups.menu, size.1),
+                ])
             }
             Message::ClosePopup(id) => {
                 self.remove_id(id);
-                Task::done(Message::RemoveWindow(id))
+                Task::batch([
+                    Task::done(Message::RemoveWindow(id)),
+                    self.release_popup_space(),
+                ])
             }
             Message::PopupMenuItemClicked {
                 popup_id,
-                address,
+                owner,
                 menu_id,
             } => {
-                // Forward to system tray and close popup
-                let tray_msg = system_tray::Message::MenuItemClicked { address, menu_id };
+                // Forward to the owning component and close popup
                 let close_task = Task::done(Message::ClosePopup(popup_id));
-                let tray_task = self.system_tray.update(tray_msg).map(Message::SystemTray);
-                Task::batch([close_task, tray_task])
+                let owner_task = self.modules.menu_selected(owner, menu_id).map(Message::Module);
+                Task::batch([close_task, owner_task])
+            }
+            Message::ToggleSubmenu { popup_id, menu_id } => {
+                if !self.expanded_submenus.remove(&(popup_id, menu_id)) {
+                    self.expanded_submenus.insert((popup_id, menu_id));
+                }
+                Task::none()
             }
             Message::IcedEvent(event) => {
+                // Drop targets capture their releases, so this one is outside
+                // of them and puts the dragged module back
+                if let Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) = event
+                    && self.layout_editor.is_dragging()
+                {
+                    return Task::done(Message::Layout(layout::Message::Cancel));
+                }
+
                 // Handle ESC key to close any open popup
                 if let Event::Keyboard(keyboard::Event::KeyPressed {
                     key: keyboard::Key::Named(Named::Escape),
                     ..
                 }) = event
                 {
-                    // Find and close any TrayMenu windows
+                    // Leave layout edit mode first
+                    if self.layout_editor.is_active() {
+                        return Task::done(Message::Layout(layout::Message::Toggle));
+                    }
+
+                    // Find and close any popup windows
                     if let Some((&id, _)) = self
                         .windows
                         .iter()
-                        .find(|(_, wt)| matches!(wt, WindowType::TrayMenu))
+                        .find(|(_, wt)| wt.is_popup())
                     {
                         return Task::done(Message::ClosePopup(id));
                     }
                 }
+
+                // Arrow keys and Enter navigate an open popup menu
+                if let Event::Keyboard(keyboard::Event::KeyPressed {
+                    key: keyboard::Key::Named(key),
+                    ..
+                }) = event
+                    && let Some((&popup_id, _)) = self
+                        .windows
+                        .iter()
+                        .find(|(_, wt)| matches!(wt, WindowType::PopupMenu))
+                {
+                    return self.navigate_menu(popup_id, key);
+                }
                 Task::none()
             }
-            Message::PopupAnimationTick => {
-                // Find the first animating popup and advance it
-                if let Some((_, anim)) = self
-                    .popup_animations
-                    .iter_mut()
-                    .find(|(_, a)| a.progress < 1.0)
-                {
-                    // Ease-out quadratic for smoother animation
-                    anim.progress = (anim.progress + 0.15).min(1.0);
+            Message::CursorMoved(id, position) => {
+                // Only positions on a bar anchor popups, on that bar's output
+                if let Some(WindowType::Main(output)) = self.windows.get(&id) {
+                    let output = output.clone();
+                    self.placement.set_cursor(position);
+                    self.place_on(&output);
+                    self.hovered_output = Some(output);
+                }
+                Task::none()
+            }
+            Message::RefreshOutputs => Task::perform(popup::outputs(), Message::OutputsListed),
+            Message::OutputsListed(Some(outputs)) => self.sync_bars(outputs),
+            Message::BarOutputFocused(id, previous) => self.open_bar(id, previous),
+            Message::SurfaceOpened(id) => {
+                if self.opening_bar != Some(id) {
+                    return Task::none();
+                }
+                self.opening_bar = None;
+                Task::batch([self.relayout_bars(), self.open_next_bar()])
+            }
+            Message::SurfaceResized(id) => match self.windows.get(&id) {
+                Some(WindowType::Main(_)) => self.relayout_bars(),
+                _ => Task::none(),
+            },
+            Message::CheckMonitors => Task::perform(monitor_states(), Message::MonitorsChecked),
+            Message::MonitorsChecked(Some(monitors)) => self.update_bar_hidden(monitors),
+            Message::MonitorsChecked(None) => Task::none(),
+            Message::PublishState => {
+                control::publish_state(|| self.modules.state());
+                Task::none()
+            }
+            Message::StartBackends => {
+                self.backends_started = true;
+                self.modules.start_backends(&self.config).map(Message::Module)
+            }
+            Message::SessionLocked(locked) => {
+                if locked != self.session_locked {
+                    self.set_session_locked(locked)
+                } else {
+                    Task::none()
+                }
+            }
+            Message::CheckColorScheme => Task::perform(
+                appearance::current(self.config.theme.clone()),
+                Message::ColorSchemeChecked,
+            ),
+            Message::ColorSchemeChecked(scheme) => {
+                if scheme != self.color_scheme {
+                    self.color_scheme = scheme;
+                    self.app_theme.update(&self.config, scheme);
+                    set_global_theme(&self.app_theme);
+                }
+                Task::none()
+            }
+            Message::Peek(command) => self.set_peek(self.peek.next(command)),
+            Message::PeekTimeout(generation)
+                if generation == self.peek_generation && self.peek == Peek::Held =>
+            {
+                self.set_peek(Peek::Off)
+            }
+            Message::PopupAnimationFrame(now) => {
+                for anim in self.popup_animations.values_mut() {
+                    anim.animation.advance(now);
                 }
                 Task::none()
             }
@@ -297,58 +748,939 @@ impl StatusBar {
         }
     }
 
+    /// Carry out what a module asked for.
+    fn perform(&mut self, action: Action) -> Task<Message> {
+        match action {
+            Action::OpenMenu {
+                owner: MenuOwner::Tray(address),
+                items,
+            } => {
+                // Line the menu up with the clicked icon
+                let owner = MenuOwner::Tray(address.clone());
+                container::visible_bounds(system_tray::icon_id(&address)).map(move |bounds| {
+                    Message::OpenMenu {
+                        owner: owner.clone(),
+                        items: items.clone(),
+                        center_x: bounds.map(|bounds| bounds.center_x()),
+                    }
+                })
+            }
+            Action::OpenMenu { owner, items } => Task::done(Message::OpenMenu {
+                owner,
+                items,
+                center_x: None,
+            }),
+            Action::ToggleCalendar => self.toggle_calendar(),
+            Action::ToggleWorkspaceOverview => self.toggle_workspace_overview(),
+            Action::OpenWorkspaceOverview => self.open_workspace_overview(),
+            Action::CloseWorkspaceOverview => self.close_workspace_overview(),
+            Action::ToggleTrayOverflow => self.toggle_tray_overflow(),
+            Action::CloseTrayOverflow => self.close_tray_overflow(),
+            Action::OpenPowerConfirm => self.open_power_confirm(),
+            Action::ClosePowerConfirm => self.close_power_confirm(),
+            Action::WorkspacesChanged => Task::done(Message::CheckMonitors),
+            Action::PlaySound(event) => {
+                self.play_sound(event);
+                Task::none()
+            }
+        }
+    }
+
+    /// Play the sound for an event, unless do-not-disturb is on.
+    fn play_sound(&self, event: SoundEvent) {
+        let dnd = self
+            .modules
+            .get::<notification_toggle::NotificationToggle>(&ModuleKind::NotificationToggle)
+            .is_some_and(|notifications| notifications.dnd());
+        sound::play(event, &self.config.sounds, dnd);
+    }
+
+    /// Let the modules measure the bars after they were laid out anew.
+    fn relayout_bars(&mut self) -> Task<Message> {
+        let outputs: Vec<String> = self
+            .windows
+            .values()
+            .filter_map(|window| match window {
+                WindowType::Main(output) => Some(output.clone()),
+                _ => None,
+            })
+            .collect();
+        self.modules.relayout(&outputs).map(Message::Module)
+    }
+
+    /// Blank or restore sensitive widgets. Locking also closes open popups.
+    fn set_session_locked(&mut self, locked: bool) -> Task<Message> {
+        self.session_locked = locked;
+        if let Some(devices) = self
+            .modules
+            .get_mut::<devices::Devices>(&ModuleKind::Devices)
+        {
+            devices.set_toasts_paused(locked);
+        }
+        if !locked {
+            return Task::none();
+        }
+        self.close_popups()
+    }
+
+    /// Close all open popups.
+    fn close_popups(&self) -> Task<Message> {
+        let popups: Vec<Id> = self
+            .windows
+            .iter()
+            .filter(|(_, wt)| wt.is_popup())
+            .map(|(&id, _)| id)
+            .collect();
+        Task::batch(popups.into_iter().map(|id| Task::done(Message::ClosePopup(id))))
+    }
+
+    /// Whether the bar on an output is neither covered nor hidden (or
+    /// revealed by peeking).
+    fn bar_visible(&self, output: &str) -> bool {
+        !self.bar_covered.contains(output)
+            && (!self.bar_hidden.contains(output) || self.peek_reveals())
+    }
+
+    /// Whether the bar on any output is visible; until the outputs are
+    /// listed, the bars count as visible.
+    fn any_bar_visible(&self) -> bool {
+        self.outputs.is_empty() || self.outputs.keys().any(|output| self.bar_visible(output))
+    }
+
+    /// Whether peeking shows the bar while it is hidden.
+    fn peek_reveals(&self) -> bool {
+        self.peek != Peek::Off && self.config.peek.reveal
+    }
+
+    /// Start, keep or end peeking. Holding arms a timeout in case the key
+    /// release never arrives.
+    fn set_peek(&mut self, peek: Peek) -> Task<Message> {
+        if peek == self.peek {
+            return Task::none();
+        }
+        let was_visible = self.any_bar_visible();
+        self.peek = peek;
+        self.modules
+            .set_peek((peek != Peek::Off).then_some(&self.config.peek));
+
+        let timeout = if peek == Peek::Held {
+            self.peek_generation += 1;
+            let generation = self.peek_generation;
+            let timeout = Duration::from_secs(self.config.peek.timeout_secs.max(1));
+            Task::perform(tokio::time::sleep(timeout), move |_| {
+                Message::PeekTimeout(generation)
+            })
+        } else {
+            Task::none()
+        };
+        let surface = if !self.bar_hidden.is_empty() {
+            self.apply_bar_hidden()
+        } else {
+            Task::none()
+        };
+        Task::batch([surface, self.catch_up_display(was_visible), timeout])
+    }
+
+    /// Catch up on the polling that was suspended while every bar was
+    /// covered or hidden, once one is visible again.
+    fn catch_up_display(&self, was_visible: bool) -> Task<Message> {
+        if was_visible || !self.any_bar_visible() {
+            return Task::none();
+        }
+        self.modules.catch_up_display().map(Message::Module)
+    }
+
+    /// Cover, hide or restore each bar for the active workspace of its
+    /// monitor.
+    fn update_bar_hidden(&mut self, monitors: Vec<MonitorState>) -> Task<Message> {
+        let covered: HashSet<String> = monitors
+            .iter()
+            .filter(|monitor| monitor.covered)
+            .map(|monitor| monitor.output.clone())
+            .collect();
+        let hidden: HashSet<String> = monitors
+            .into_iter()
+            .filter(|monitor| {
+                self.config
+                    .bar
+                    .hides_on(monitor.workspace_id, &monitor.workspace_name)
+            })
+            .map(|monitor| monitor.output)
+            .collect();
+
+        let was_visible = self.any_bar_visible();
+        let changed: HashSet<String> = self
+            .bar_hidden
+            .symmetric_difference(&hidden)
+            .cloned()
+            .collect();
+        self.bar_covered = covered;
+        self.bar_hidden = hidden;
+        Task::batch([
+            self.apply_bar_hidden_on(|output| changed.contains(output)),
+            self.catch_up_display(was_visible),
+        ])
+    }
+
+    /// Surfaces of the bars on all outputs, with their output names.
+    fn bars(&self) -> Vec<(Id, &str)> {
+        self.windows
+            .iter()
+            .filter_map(|(&id, wt)| match wt {
+                WindowType::Main(output) => Some((id, output.as_str())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Place popups relative to the bar on an output.
+    fn place_on(&mut self, output: &str) {
+        if let Some(geometry) = self.outputs.get(output) {
+            self.placement.set_output(*geometry);
+        }
+        self.placement.set_position(self.config.bar_position(output));
+        self.placement.set_height(self.config.bar_height(output));
+    }
+
+    /// Open bars on new outputs and close the bars of removed ones.
+    fn sync_bars(&mut self, outputs: Vec<(String, popup::Output)>) -> Task<Message> {
+        self.outputs = outputs.into_iter().collect();
+
+        let removed: Vec<Id> = self
+            .windows
+            .iter()
+            .filter(|(_, wt)| {
+                matches!(wt, WindowType::Main(name) if !self.outputs.contains_key(name))
+            })
+            .map(|(&id, _)| id)
+            .collect();
+        let mut tasks: Vec<Task<Message>> =
+            removed.into_iter().map(|id| self.close_bar(id)).collect();
+
+        let mut added: Vec<String> = self
+            .outputs
+            .keys()
+            .filter(|name| {
+                !self
+                    .windows
+                    .values()
+                    .any(|wt| matches!(wt, WindowType::Main(bar) if bar == *name))
+            })
+            .cloned()
+            .collect();
+        added.sort();
+        for name in added {
+            self.queue_bar(name);
+        }
+        tasks.push(self.open_next_bar());
+        Task::batch(tasks)
+    }
+
+    /// Close and reopen the bars whose height changed, since the space a
+    /// surface reserves is fixed when it is created.
+    fn reopen_resized_bars(&mut self, old_config: &Config) -> Task<Message> {
+        let resized: Vec<(Id, String)> = self
+            .bars()
+            .into_iter()
+            .filter(|(_, output)| old_config.scaled_bar_height(output) != self.config.scaled_bar_height(output))
+            .map(|(id, output)| (id, output.to_string()))
+            .collect();
+        if resized.is_empty() {
+            return Task::none();
+        }
+
+        let mut tasks = Vec::new();
+        for (id, output) in resized {
+            tasks.push(self.close_bar(id));
+            self.queue_bar(output);
+        }
+        tasks.push(self.open_next_bar());
+        Task::batch(tasks)
+    }
+
+    /// Close the surface of a bar.
+    fn close_bar(&mut self, id: Id) -> Task<Message> {
+        self.windows.remove(&id);
+        if self.opening_bar == Some(id) {
+            self.opening_bar = None;
+        }
+        Task::done(Message::RemoveWindow(id))
+    }
+
+    /// Queue a bar on an output to be opened.
+    fn queue_bar(&mut self, output: String) {
+        let id = Id::unique();
+        self.windows.insert(id, WindowType::Main(output));
+        self.bars_to_open.push_back(id);
+    }
+
+    /// Open the next queued bar. A layer surface without an output opens
+    /// on the focused one, so its output is focused first, and the output
+    /// that had the focus gets it back once all bars are open.
+    fn open_next_bar(&mut self) -> Task<Message> {
+        if self.opening_bar.is_some() {
+            return Task::none();
+        }
+        while let Some(id) = self.bars_to_open.pop_front() {
+            // The output may have gone away while the bar was queued
+            let Some(WindowType::Main(output)) = self.windows.get(&id) else {
+                continue;
+            };
+            self.opening_bar = Some(id);
+            return Task::perform(popup::focus_output(output.clone()), move |previous| {
+                Message::BarOutputFocused(id, previous)
+            });
+        }
+        match self.focus_to_restore.take() {
+            Some(output) => Task::future(popup::focus_output(output)).discard(),
+            None => Task::none(),
+        }
+    }
+
+    /// Open the surface of a bar whose output has the focus, in its
+    /// position and the current hidden state.
+    fn open_bar(&mut self, id: Id, previous: Option<String>) -> Task<Message> {
+        if self.focus_to_restore.is_none() {
+            self.focus_to_restore = previous;
+        }
+        let Some(WindowType::Main(output)) = self.windows.get(&id) else {
+            self.opening_bar = None;
+            return self.open_next_bar();
+        };
+        // The height is drawn at `bar.scale`
+        let height = self.config.scaled_bar_height(output);
+        let settings = NewLayerShellSettings {
+            size: Some((0, self.bar_surface_height(output))),
+            layer: Layer::Top,
+            anchor: popup::bar_anchor(self.config.bar_position(output)),
+            exclusive_zone: Some(height as i32),
+            margin: Some(self.bar_margin(output)),
+            ..NewLayerShellSettings::default()
+        };
+        Task::done(Message::NewLayerShell { settings, id })
+    }
+
+    /// Reserve space for a popup that was just opened, if its type is
+    /// configured to. The bar's exclusive zone can't grow once created, so
+    /// an empty surface is opened at the bar's edge with the popup's height
+    /// as its own zone, which the compositor adds to the bar's. Like the
+    /// bars, it opens on the focused output, which is the one the popup was
+    /// opened from as long as focus follows the mouse.
+    fn reserve_popup_space(&mut self, id: Id, space: PopupSpace, height: u32) -> Task<Message> {
+        let output = match (space, &self.hovered_output) {
+            (PopupSpace::Reserve, Some(output)) => output.clone(),
+            _ => return self.release_popup_space(),
+        };
+        let spacer = Id::unique();
+        self.windows.insert(spacer, WindowType::Spacer);
+        self.reserved.insert(id, spacer);
+        let settings = NewLayerShellSettings {
+            size: Some((0, 1)),
+            layer: Layer::Top,
+            anchor: popup::bar_anchor(self.config.bar_position(&output)),
+            exclusive_zone: Some(height as i32),
+            events_transparent: true,
+            ..NewLayerShellSettings::default()
+        };
+        Task::batch([
+            self.release_popup_space(),
+            Task::done(Message::NewLayerShell {
+                settings,
+                id: spacer,
+            }),
+        ])
+    }
+
+    /// Close the spacers of popups that are no longer open.
+    fn release_popup_space(&mut self) -> Task<Message> {
+        let closed: Vec<Id> = self
+            .reserved
+            .keys()
+            .filter(|popup| !self.windows.contains_key(popup))
+            .copied()
+            .collect();
+        Task::batch(closed.into_iter().filter_map(|popup| {
+            let spacer = self.reserved.remove(&popup)?;
+            self.windows.remove(&spacer);
+            Some(Task::done(Message::RemoveWindow(spacer)))
+        }))
+    }
+
+    /// Height of the bar surface on an output. Peeking at a hidden bar keeps
+    /// its margin, so no more space is reserved, and stretches the surface
+    /// from past the edge back onto the output far enough to show the whole
+    /// bar above the windows.
+    fn bar_surface_height(&self, output: &str) -> u32 {
+        let height = self.config.scaled_bar_height(output);
+        if !self.bar_hidden.contains(output) || !self.peek_reveals() {
+            return height;
+        }
+        let on_output = match self.config.bar.hide_mode {
+            BarHideMode::Hide => 0,
+            BarHideMode::Minimize => MINIMIZED_BAR_HEIGHT,
+        };
+        2 * height - on_output
+    }
+
+    /// Margins of the bar surface on an output for the current hidden
+    /// state. Its exclusive zone is fixed when the surface is created, but
+    /// the compositor adds the margin at the bar's edge to it, so a negative
+    /// margin slides the bar past the edge and takes the reserved space along.
+    fn bar_margin(&self, output: &str) -> (i32, i32, i32, i32) {
+        let position = self.config.bar_position(output);
+        let height = self.config.scaled_bar_height(output) as i32;
+        let (top, right, bottom, left) = popup::bar_margin(position);
+        let edge = match (self.bar_hidden.contains(output), self.config.bar.hide_mode) {
+            (false, _) => return (top, right, bottom, left),
+            // Entirely off the output, without reserving space
+            (true, BarHideMode::Hide) => -height,
+            // Only a strip at the edge is left
+            (true, BarHideMode::Minimize) => MINIMIZED_BAR_HEIGHT as i32 - height,
+        };
+        match position {
+            BarPosition::Top => (edge, right, bottom, left),
+            BarPosition::Bottom => (top, right, edge, left),
+        }
+    }
+
+    /// Slide the bar surfaces in or out for their current hidden state.
+    fn apply_bar_hidden(&self) -> Task<Message> {
+        self.apply_bar_hidden_on(|_| true)
+    }
+
+    /// Slide the bar surfaces on some outputs in or out for their current
+    /// hidden state.
+    fn apply_bar_hidden_on(&self, slide: impl Fn(&str) -> bool) -> Task<Message> {
+        let bars = self.bars().into_iter().filter(|(_, output)| slide(output));
+        Task::batch(bars.flat_map(|(id, output)| {
+            [
+                Task::done(Message::SizeChange {
+                    id,
+                    size: (0, self.bar_surface_height(output)),
+                }),
+                Task::done(Message::MarginChange {
+                    id,
+                    margin: self.bar_margin(output),
+                }),
+            ]
+        }))
+    }
+
+    /// Move the bar surfaces to their configured output edges.
+    fn apply_bar_position(&self) -> Task<Message> {
+        Task::batch(self.bars().into_iter().flat_map(|(id, output)| {
+            [
+                Task::done(Message::AnchorChange {
+                    id,
+                    anchor: popup::bar_anchor(self.config.bar_position(output)),
+                }),
+                Task::done(Message::MarginChange {
+                    id,
+                    margin: self.bar_margin(output),
+                }),
+            ]
+        }))
+    }
+
+    /// Open the calendar popup, or close it if it is already open.
+    fn toggle_calendar(&mut self) -> Task<Message> {
+        if let Some((&id, _)) = self
+            .windows
+            .iter()
+            .find(|(_, wt)| matches!(wt, WindowType::Calendar))
+        {
+            return Task::done(Message::ClosePopup(id));
+        }
+
+        let id = Id::unique();
+        let (width, content_height) = clock::popup_size(self.app_theme.font_size());
+        self.windows.insert(id, WindowType::Calendar);
+        self.popup_animations.insert(
+            id,
+            PopupAnimationState::new(
+                self.config.animations.start(),
+                content_height as f32,
+                &self.placement,
+            ),
+        );
+
+        // Add the gap to the bar and the connector height
+        let height = self.placement.surface_height(content_height as f32);
+        Task::batch([
+            Task::done(Message::NewMenu {
+                settings: IcedNewMenuSettings {
+                    size: (self.placement.surface_len(width), height),
+                    direction: self.placement.direction(),
+                },
+                id,
+            }),
+            self.reserve_popup_space(id, self.config.popups.calendar, height),
+        ])
+    }
+
+    /// Open the workspace overview popup, or close it if it is already open.
+    fn toggle_workspace_overview(&mut self) -> Task<Message> {
+        if let Some((&id, _)) = self
+            .windows
+            .iter()
+            .find(|(_, wt)| matches!(wt, WindowType::WorkspaceOverview))
+        {
+            return Task::done(Message::ClosePopup(id));
+        }
+        self.open_workspace_overview()
+    }
+
+    /// Open the workspace overview popup unless it is already open.
+    fn open_workspace_overview(&mut self) -> Task<Message> {
+        if self
+            .windows
+            .values()
+            .any(|wt| matches!(wt, WindowType::WorkspaceOverview))
+        {
+            return Task::none();
+        }
+
+        let id = Id::unique();
+        let (width, content_height) = self
+            .modules
+            .get::<workspaces::Workspaces>(&ModuleKind::Workspaces)
+            .map_or((0, 0), |workspaces| {
+                workspaces.overview_size(self.app_theme.font_size())
+            });
+        self.windows.insert(id, WindowType::WorkspaceOverview);
+        self.popup_animations.insert(
+            id,
+            PopupAnimationState::new(
+                self.config.animations.start(),
+                content_height as f32,
+                &self.placement,
+            ),
+        );
+
+        // Add the gap to the bar and the connector height
+        let height = self.placement.surface_height(content_height as f32);
+        Task::batch([
+            self.modules
+                .get::<workspaces::Workspaces>(&ModuleKind::Workspaces)
+                .map_or_else(Task::none, |workspaces| workspaces.capture_active())
+                .map(|msg| Message::Module(ModuleMessage::widget(ModuleKind::Workspaces, msg))),
+            Task::done(Message::NewMenu {
+                settings: IcedNewMenuSettings {
+                    size: (self.placement.surface_len(width), height),
+                    direction: self.placement.direction(),
+                },
+                id,
+            }),
+            self.reserve_popup_space(id, self.config.popups.workspace_overview, height),
+        ])
+    }
+
+    /// Close the workspace overview popup if it is open.
+    fn close_workspace_overview(&self) -> Task<Message> {
+        match self
+            .windows
+            .iter()
+            .find(|(_, wt)| matches!(wt, WindowType::WorkspaceOverview))
+        {
+            Some((&id, _)) => Task::done(Message::ClosePopup(id)),
+            None => Task::none(),
+        }
+    }
+
+    /// Open the popup with the tray items that do not fit in the bar, or
+    /// close it if it is already open.
+    fn toggle_tray_overflow(&mut self) -> Task<Message> {
+        if let Some((&id, _)) = self
+            .windows
+            .iter()
+            .find(|(_, wt)| matches!(wt, WindowType::TrayOverflow))
+        {
+            return Task::done(Message::ClosePopup(id));
+        }
+
+        let id = Id::unique();
+        let (width, content_height) = self
+            .modules
+            .get::<system_tray::SystemTray>(&ModuleKind::SystemTray)
+            .map_or((0, 0), |tray| tray.overflow_size());
+        self.windows.insert(id, WindowType::TrayOverflow);
+        self.popup_animations.insert(
+            id,
+            PopupAnimationState::new(
+                self.config.animations.start(),
+                content_height as f32,
+                &self.placement,
+            ),
+        );
+
+        let height = self.placement.surface_height(content_height as f32);
+        Task::batch([
+            Task::done(Message::NewMenu {
+                settings: IcedNewMenuSettings {
+                    size: (self.placement.surface_len(width), height),
+                    direction: self.placement.direction(),
+                },
+                id,
+            }),
+            self.reserve_popup_space(id, self.config.popups.menu, height),
+        ])
+    }
+
+    /// Close the tray overflow popup if it is open.
+    fn close_tray_overflow(&self) -> Task<Message> {
+        match self
+            .windows
+            .iter()
+            .find(|(_, wt)| matches!(wt, WindowType::TrayOverflow))
+        {
+            Some((&id, _)) => Task::done(Message::ClosePopup(id)),
+            None => Task::none(),
+        }
+    }
+
+    /// Open the power action confirmation popup (replacing an open one).
+    fn open_power_confirm(&mut self) -> Task<Message> {
+        let close_task = self.close_power_confirm();
+
+        let id = Id::unique();
+        let (width, content_height) = self
+            .modules
+            .get::<power::Power>(&ModuleKind::Power)
+            .map_or((0, 0), |power| power.confirm_size());
+        self.windows.insert(id, WindowType::PowerConfirm);
+        self.popup_animations.insert(
+            id,
+            PopupAnimationState::new(
+                self.config.animations.start(),
+                content_height as f32,
+                &self.placement,
+            ),
+        );
+
+        // Add the gap to the bar and the connector height
+        let height = self.placement.surface_height(content_height as f32);
+        Task::batch([
+            close_task,
+            Task::done(Message::NewMenu {
+                settings: IcedNewMenuSettings {
+                    size: (self.placement.surface_len(width), height),
+                    direction: self.placement.direction(),
+                },
+                id,
+            }),
+            self.reserve_popup_space(id, self.config.popups.power_confirm, height),
+        ])
+    }
+
+    /// Close the power confirmation popup if it is open.
+    fn close_power_confirm(&self) -> Task<Message> {
+        match self
+            .windows
+            .iter()
+            .find(|(_, wt)| matches!(wt, WindowType::PowerConfirm))
+        {
+            Some((&id, _)) => Task::done(Message::ClosePopup(id)),
+            None => Task::none(),
+        }
+    }
+
     fn view(&self, id: Id) -> Element<'_, Message> {
         match self.windows.get(&id) {
-            Some(WindowType::TrayMenu) => self.view_tray_menu(id),
-            _ => self.view_main(),
+            Some(WindowType::PopupMenu) => self.view_popup_menu(id),
+            Some(WindowType::Calendar) => self.view_calendar_popup(id),
+            Some(WindowType::TrayOverflow) => {
+                let overflow = self
+                    .modules
+                    .view_popup(
+                        &ModuleKind::SystemTray,
+                        system_tray::SystemTray::view_overflow,
+                    )
+                    .map(Message::Module);
+                self.view_popup_frame(id, overflow)
+            }
+            Some(WindowType::WorkspaceOverview) => {
+                let overview = self
+                    .modules
+                    .view_popup(
+                        &ModuleKind::Workspaces,
+                        workspaces::Workspaces::view_overview,
+                    )
+                    .map(Message::Module);
+                self.view_popup_frame(id, overview)
+            }
+            Some(WindowType::PowerConfirm) => {
+                let confirm = self
+                    .modules
+                    .view_popup(&ModuleKind::Power, power::Power::view_confirm)
+                    .map(Message::Module);
+                self.view_popup_frame(id, confirm)
+            }
+            // Nothing is visible under a fullscreen window or while hidden
+            Some(WindowType::Main(output)) if !self.bar_visible(output) => {
+                container(text("")).into()
+            }
+            Some(WindowType::Main(output)) => self.view_main(output),
+            Some(WindowType::Spacer) | None => container(text("")).into(),
         }
     }
 
-    fn view_main(&self) -> Element<'_, Message> {
-        let left = self.workspaces.view().map(Message::Workspaces);
+    fn view_main(&self, output: &str) -> Element<'_, Message> {
+        let islands = self.config.layout_style(output) == LayoutStyle::Islands;
+        let section = |section| {
+            let modules = self.view_section(output, section);
+            if islands {
+                self.view_island(output, section, modules)
+            } else {
+                modules
+            }
+        };
+        let left = section(Section::Left);
 
-        let middle = container(self.window_title.view().map(Message::WindowTitle))
+        let middle = container(section(Section::Center))
             .width(Length::Fill)
             .center_x(Length::Fill)
             .style(|_theme| Style::default());
 
-        let system_tray = self.system_tray.view().map(Message::SystemTray);
-        let battery = self.battery.view().map(Message::Battery);
-        let clock = self.clock.view().map(Message::Clock);
-        let volume = self.volume.view().map(Message::Volume);
-        let notification_toggle = self.notification_toggle.view().map(Message::NotificationToggle);
-        let right = row![system_tray, volume, battery, clock, notification_toggle]
-            .spacing(self.app_theme.tray_widget_spacing())
-            .align_y(iced::Alignment::Center);
+        let right = section(Section::Right);
 
-        let content = row![left, middle, right,]
+        let content = row![left, middle, self.view_config_error(), right,]
             .padding(5)
             .align_y(iced::Alignment::Center)
             .width(Length::Fill);
 
-        let accent = self.app_theme.accent();
+        // Highlight the bar border while the layout is being edited
+        let editing = self.layout_editor.is_active();
+        let border_color = if editing {
+            self.app_theme.accent2()
+        } else {
+            self.app_theme.frame_border()
+        };
+        let radius = self.app_theme.bar_radius();
+        let border_width = self.app_theme.border_width();
 
-        container(content)
+        let bar = container(content)
             .width(Length::Fill)
             .height(Length::Fill)
             .style(move |theme: &iced::Theme| {
                 let palette = theme.palette();
+                // Islands draw their own background, the gaps stay clear
                 container::Style {
-                    background: Some(palette.primary.into()),
+                    background: (!islands).then(|| palette.primary.into()),
                     border: Border {
-                        radius: 15.0.into(),
-                        width: 1.0.into(),
-                        color: accent,
-                        ..Border::default()
+                        radius: radius.into(),
+                        width: if islands && !editing { 0.0 } else { border_width },
+                        color: border_color,
                     },
                     ..container::Style::default()
                 }
+            });
+
+        // Right-click anywhere on the bar toggles layout edit mode
+        let bar = mouse_area(bar).on_right_press(Message::Layout(layout::Message::Toggle));
+
+        // A peeked surface reaches past the edge; the bar is its part on the
+        // output
+        let edge = match self.config.bar_position(output) {
+            BarPosition::Top => alignment::Vertical::Bottom,
+            BarPosition::Bottom => alignment::Vertical::Top,
+        };
+        container(container(bar).height(self.config.bar_height(output) as f32))
+            .height(Length::Fill)
+            .align_y(edge)
+            .into()
+    }
+
+    /// A section of the bar in a pill of its own, for `layout.style =
+    /// "islands"`. Sections without modules leave no empty pill behind,
+    /// except while editing, so modules can be dropped into them.
+    fn view_island<'a>(
+        &self,
+        output: &str,
+        section: Section,
+        modules: Element<'a, Message>,
+    ) -> Element<'a, Message> {
+        let editing = self.layout_editor.is_active();
+        let shown = self
+            .shown_layout(output)
+            .section(section)
+            .iter()
+            .any(|kind| self.config.modules.is_enabled(kind));
+        if !shown && !editing {
+            return modules;
+        }
+        let border_color = self.app_theme.frame_border();
+        let border_width = self.app_theme.border_width();
+        // Rounded to half the height of the island: the bar without its padding
+        let radius = (self.config.bar_height(output) as f32 - 10.0).max(0.0) / 2.0;
+        container(modules)
+            .padding([0, 6])
+            .height(Length::Fill)
+            .center_y(Length::Fill)
+            .style(move |theme: &iced::Theme| container::Style {
+                background: Some(theme.palette().primary.into()),
+                border: Border {
+                    radius: radius.into(),
+                    width: border_width,
+                    color: border_color,
+                },
+                ..container::Style::default()
             })
             .into()
     }
 
-    fn view_tray_menu(&self, popup_id: Id) -> Element<'_, Message> {
-        let (address, items) = match self.menu_data.get(&popup_id) {
+    /// Chip saying why the last change of the config was rejected; clicking
+    /// it dismisses it.
+    fn view_config_error(&self) -> Element<'_, Message> {
+        let Some((generation, summary)) = &self.config_error else {
+            return row![].into();
+        };
+        let danger = self.app_theme.danger();
+        let chip = container(
+            text(format!("⚠ config not applied: {}", summary))
+                .size(self.app_theme.font_size())
+                .color(danger),
+        )
+        .padding([2, 8])
+        .max_width(480)
+        .style(move |_theme| container::Style {
+            border: Border {
+                radius: 8.0.into(),
+                width: 1.0,
+                color: danger,
+            },
+            ..container::Style::default()
+        });
+        mouse_area(chip)
+            .on_press(Message::DismissConfigError(*generation))
+            .interaction(mouse::Interaction::Pointer)
+            .into()
+    }
+
+    /// Render the modules of one section of the bar on an output, in
+    /// configured order.
+    fn view_section(&self, output: &str, section: Section) -> Element<'_, Message> {
+        let editing = self.layout_editor.is_active();
+        let modules = self.shown_layout(output).section(section);
+
+        let mut items: Vec<Element<'_, Message>> = Vec::with_capacity(modules.len() + 1);
+        for (index, kind) in modules.iter().enumerate() {
+            // Disabled modules keep their slot so edits don't move them
+            if !self.config.modules.is_enabled(kind) {
+                continue;
+            }
+            let module = self.view_module(output, kind);
+            if editing {
+                items.push(self.view_editable_module(module, Slot { section, index }));
+            } else {
+                items.push(module);
+            }
+        }
+
+        // Trailing drop zone so modules can be appended or moved into empty sections
+        if editing {
+            items.push(self.view_drop_zone(Slot {
+                section,
+                index: modules.len(),
+            }));
+        }
+
+        Row::from_vec(items)
+            .spacing(self.app_theme.tray_widget_spacing())
+            .align_y(iced::Alignment::Center)
+            .into()
+    }
+
+    /// The layout the bar on an output shows: the peek layout while peeked,
+    /// else its own one.
+    fn shown_layout(&self, output: &str) -> &LayoutConfig {
+        match &self.config.peek.layout {
+            // Edits apply to the bar's own layout
+            Some(layout) if self.peek != Peek::Off && !self.layout_editor.is_active() => layout,
+            _ => self.config.layout_for(output),
+        }
+    }
+
+    /// Render a single module.
+    fn view_module(&self, output: &str, kind: &ModuleKind) -> Element<'_, Message> {
+        // Window titles, mail and file contents may be visible above the
+        // lock screen, also when shown in a carousel
+        if self.session_locked
+            && matches!(
+                self.modules.shown_kind(kind),
+                ModuleKind::WindowTitle | ModuleKind::Mail | ModuleKind::File(_)
+            )
+        {
+            return text("").into();
+        }
+
+        self.modules.view(kind, output).map(Message::Module)
+    }
+
+    /// Wrap a module with a drag handle and drop target for edit mode.
+    fn view_editable_module<'a>(
+        &self,
+        module: Element<'a, Message>,
+        slot: Slot,
+    ) -> Element<'a, Message> {
+        let accent = self.app_theme.accent();
+        let border = self.app_theme.border();
+        let hover = self.app_theme.hover();
+        let is_target = self.layout_editor.drop_target() == Some(slot);
+        let is_dragged = self.layout_editor.dragging() == Some(slot);
+
+        let handle = mouse_area(
+            container(text("⠿").size(self.app_theme.font_size()))
+                .center_y(Length::Fill)
+                .padding([0, 4]),
+        )
+        .on_press(Message::Layout(layout::Message::Pick(slot)))
+        .interaction(mouse::Interaction::Grab);
+
+        let content = container(row![handle, module].align_y(iced::Alignment::Center))
+            .height(Length::Fill)
+            .style(move |_theme| container::Style {
+                background: is_dragged.then(|| hover.into()),
+                border: Border {
+                    color: if is_target { accent } else { border },
+                    width: if is_target { 2.0 } else { 1.0 },
+                    radius: 4.0.into(),
+                },
+                ..container::Style::default()
+            });
+
+        mouse_area(content)
+            .on_enter(Message::Layout(layout::Message::Hover(slot)))
+            .on_exit(Message::Layout(layout::Message::Leave(slot)))
+            .on_release(Message::Layout(layout::Message::Drop))
+            .into()
+    }
+
+    /// Empty drop target at the end of a section.
+    fn view_drop_zone(&self, slot: Slot) -> Element<'_, Message> {
+        let accent = self.app_theme.accent();
+        let muted = self.app_theme.muted();
+        let is_target = self.layout_editor.drop_target() == Some(slot);
+
+        let zone = container(iced::widget::Space::new(Length::Fixed(24.0), Length::Fill))
+            .height(Length::Fill)
+            .style(move |_theme| container::Style {
+                border: Border {
+                    color: if is_target { accent } else { muted },
+                    width: 1.0,
+                    radius: 4.0.into(),
+                },
+                ..container::Style::default()
+            });
+
+        mouse_area(zone)
+            .on_enter(Message::Layout(layout::Message::Hover(slot)))
+            .on_exit(Message::Layout(layout::Message::Leave(slot)))
+            .on_release(Message::Layout(layout::Message::Drop))
+            .into()
+    }
+
+    fn view_popup_menu(&self, popup_id: Id) -> Element<'_, Message> {
+        let (owner, items) = match self.menu_data.get(&popup_id) {
             Some(data) => data,
             None => {
                 return container(text("Menu not found"))
@@ -358,30 +1690,80 @@ impl StatusBar {
             }
         };
 
-        // Get animation progress (default to 1.0 = fully visible)
-        let (progress, content_height) = self
-            .popup_animations
+        let mut menu_items = Vec::new();
+        self.push_menu_items(popup_id, owner, items, 0, &mut menu_items);
+
+        let menu_column = column(menu_items).spacing(0).width(Length::Fill);
+        let scroll_content = scrollable(menu_column).height(Length::Fill);
+
+        self.view_popup_frame(popup_id, scroll_content.into())
+    }
+
+    /// Move the highlight of a popup menu with the arrow keys, or activate
+    /// the highlighted item (expanding or collapsing a submenu) with Enter.
+    fn navigate_menu(&mut self, popup_id: Id, key: Named) -> Task<Message> {
+        let Some((owner, items)) = self.menu_data.get(&popup_id) else {
+            return Task::none();
+        };
+        let expanded = |menu_id| self.expanded_submenus.contains(&(popup_id, menu_id));
+        let ids = system_tray::menu::navigable_ids(items, &expanded);
+        let current = self
+            .menu_highlight
             .get(&popup_id)
-            .map(|anim| {
-                // Ease-out quadratic for smoother feel
-                let eased = 1.0 - (1.0 - anim.progress).powi(2);
-                (eased, anim.content_height)
-            })
-            .unwrap_or((1.0, 100.0));
+            .copied()
+            .filter(|id| ids.contains(id));
+        match key {
+            Named::ArrowDown | Named::ArrowUp => {
+                let forward = key == Named::ArrowDown;
+                if let Some(menu_id) = system_tray::menu::step(&ids, current, forward) {
+                    self.menu_highlight.insert(popup_id, menu_id);
+                }
+                Task::none()
+            }
+            Named::Enter => {
+                let Some(item) = current.and_then(|id| system_tray::menu::find(items, id)) else {
+                    return Task::none();
+                };
+                Task::done(if item.submenu.is_empty() {
+                    Message::PopupMenuItemClicked {
+                        popup_id,
+                        owner: owner.clone(),
+                        menu_id: item.id,
+                    }
+                } else {
+                    Message::ToggleSubmenu {
+                        popup_id,
+                        menu_id: item.id,
+                    }
+                })
+            }
+            _ => Task::none(),
+        }
+    }
 
+    /// Add the rows of popup menu items, with the items of expanded
+    /// submenus indented below their parent. Clicking a parent expands or
+    /// collapses it instead of activating it.
+    fn push_menu_items<'a>(
+        &self,
+        popup_id: Id,
+        owner: &MenuOwner,
+        items: &'a [system_tray::menu::MenuItem],
+        depth: u16,
+        rows: &mut Vec<Element<'a, Message>>,
+    ) {
         let border_color = self.app_theme.border();
         let hover_color = self.app_theme.hover();
         let text_color = self.app_theme.text();
         let muted_color = self.app_theme.muted();
-        let surface_color = self.app_theme.surface();
-        let accent_color = self.app_theme.accent();
         let font_size = self.app_theme.font_size();
 
-        let menu_items: Vec<Element<'_, Message>> = items
+        for item in items
             .iter()
             .filter(|item| !item.label.is_empty() || item.is_separator)
-            .map(|item| {
-                if item.is_separator {
+        {
+            if item.is_separator {
+                rows.push(
                     container(iced::widget::Space::new(Length::Fill, 1))
                         .style(move |_theme| container::Style {
                             background: Some(border_color.into()),
@@ -389,71 +1771,139 @@ impl StatusBar {
                         })
                         .width(Length::Fill)
                         .padding([4, 0])
-                        .into()
-                } else {
-                    let addr = address.clone();
-                    let item_id = item.id;
-                    let enabled = item.enabled;
+                        .into(),
+                );
+                continue;
+            }
 
-                    let label_widget = if item.is_checkable && item.is_checked {
-                        text(format!(" {}", item.label)).size(font_size)
-                    } else {
-                        text(&item.label).size(font_size)
-                    };
+            let enabled = item.enabled;
+            let alternative = item.is_alternative();
+            let highlighted = self.menu_highlight.get(&popup_id) == Some(&item.id);
+            let has_submenu = !item.submenu.is_empty();
+            let expanded = has_submenu && self.expanded_submenus.contains(&(popup_id, item.id));
+            let label = if has_submenu {
+                format!("{} {}", if expanded { "▾" } else { "▸" }, item.label)
+            } else if let Some(glyph) = item.toggle_glyph() {
+                format!("{} {}", glyph, item.label)
+            } else {
+                item.label.clone()
+            };
 
-                    let mut btn = button(label_widget)
-                        .width(Length::Fill)
-                        .padding([6, 12])
-                        .style(move |_theme, status| {
-                            let bg = if !enabled {
-                                None
-                            } else {
-                                match status {
-                                    button::Status::Hovered | button::Status::Pressed => {
-                                        Some(hover_color.into())
-                                    }
-                                    _ => None,
-                                }
-                            };
-                            button::Style {
-                                background: bg,
-                                text_color: if enabled { text_color } else { muted_color },
-                                border: Border::default(),
-                                shadow: Default::default(),
+            let mut content = row![].spacing(8).align_y(iced::Alignment::Center);
+            if let Some(handle) = &item.icon {
+                content = content.push(
+                    image(handle.clone())
+                        .width(Length::Fixed(font_size))
+                        .height(Length::Fixed(font_size)),
+                );
+            }
+            content = content.push(text(label).size(font_size).width(Length::Fill));
+            if let Some(shortcut) = &item.shortcut {
+                content = content.push(
+                    text(shortcut.as_str())
+                        .size(font_size * 0.85)
+                        .color(muted_color),
+                );
+            }
+
+            let mut btn = button(content)
+                .width(Length::Fill)
+                .padding(iced::Padding {
+                    top: 6.0,
+                    right: 12.0,
+                    bottom: 6.0,
+                    left: 12.0 + 16.0 * f32::from(depth),
+                })
+                .style(move |_theme, status| {
+                    let bg = if !enabled {
+                        None
+                    } else {
+                        match status {
+                            button::Status::Hovered | button::Status::Pressed => {
+                                Some(hover_color.into())
                             }
-                        });
+                            _ if highlighted => Some(hover_color.into()),
+                            _ => None,
+                        }
+                    };
+                    button::Style {
+                        background: bg,
+                        text_color: if enabled && !alternative {
+                            text_color
+                        } else {
+                            muted_color
+                        },
+                        border: Border::default(),
+                        shadow: Default::default(),
+                    }
+                });
 
-                    if enabled {
-                        btn = btn.on_press(Message::PopupMenuItemClicked {
-                            popup_id,
-                            address: addr,
-                            menu_id: item_id,
-                        });
+            if enabled {
+                btn = btn.on_press(if has_submenu {
+                    Message::ToggleSubmenu {
+                        popup_id,
+                        menu_id: item.id,
                     }
+                } else {
+                    Message::PopupMenuItemClicked {
+                        popup_id,
+                        owner: owner.clone(),
+                        menu_id: item.id,
+                    }
+                });
+            }
+            rows.push(btn.into());
 
-                    btn.into()
-                }
-            })
-            .collect();
+            if expanded {
+                self.push_menu_items(popup_id, owner, &item.submenu, depth + 1, rows);
+            }
+        }
+    }
 
-        let menu_column = column(menu_items).spacing(0).width(Length::Fill);
-        let scroll_content = scrollable(menu_column).height(Length::Fill);
+    fn view_calendar_popup(&self, popup_id: Id) -> Element<'_, Message> {
+        let calendar = self
+            .modules
+            .view_popup(&ModuleKind::Clock, clock::Clock::view_calendar)
+            .map(Message::Module);
+        self.view_popup_frame(popup_id, calendar)
+    }
+
+    /// Shared popup chrome: offset spacer, connector tab and the surface
+    /// container, clipped to the current slide-down animation height.
+    fn view_popup_frame<'a>(
+        &self,
+        popup_id: Id,
+        content: Element<'a, Message>,
+    ) -> Element<'a, Message> {
+        // Get animation progress (default to 1.0 = fully visible)
+        let (progress, content_height, edge_offset) = self
+            .popup_animations
+            .get(&popup_id)
+            .map(|anim| (anim.animation.eased(), anim.content_height, anim.edge_offset))
+            .unwrap_or((1.0, 100.0, self.placement.edge_offset()));
+        let opens_up = self.placement.opens_up();
+
+        let surface_color = self.app_theme.surface();
+        let border_color = self.app_theme.frame_border();
+        let radius = self.app_theme.popup_radius();
+        let border_width = self.app_theme.border_width();
 
         // Animated height - clip content by showing only a portion
         let visible_height = (content_height * progress).max(1.0);
 
-        // Small connector tab at top to bridge gap with status bar
+        // Small connector tab on the bar side to bridge the gap with the bar
+        let (tab_top, tab_bottom) = if opens_up { (0.0, 2.0) } else { (2.0, 0.0) };
         let connector = container(iced::widget::Space::new(Length::Fill, 0))
             .width(Length::Fixed(40.0))
-            .height(Length::Fixed(4.0))
+            .height(Length::Fixed(popup::CONNECTOR_HEIGHT))
             .style(move |_theme| container::Style {
-                background: Some(accent_color.into()),
+                background: Some(border_color.into()),
                 border: Border {
                     radius: Radius {
-                        top_left: 2.0,
-                        top_right: 2.0,
-                        bottom_left: 0.0,
-                        bottom_right: 0.0,
+                        top_left: tab_top,
+                        top_right: tab_top,
+                        bottom_left: tab_bottom,
+                        bottom_right: tab_bottom,
                     },
                     ..Border::default()
                 },
@@ -461,7 +1911,7 @@ impl StatusBar {
             });
 
         // Menu content container with clipped height for animation
-        let menu_container = container(scroll_content)
+        let menu_container = container(content)
             .width(Length::Fill)
             .height(Length::Fixed(visible_height))
             .clip(true)
@@ -469,28 +1919,29 @@ impl StatusBar {
             .style(move |_theme| container::Style {
                 background: Some(surface_color.into()),
                 border: Border {
-                    color: accent_color,
-                    width: 1.0,
-                    radius: Radius {
-                        top_left: 6.0,
-                        top_right: 6.0,
-                        bottom_left: 6.0,
-                        bottom_right: 6.0,
-                    },
+                    color: border_color,
+                    width: border_width,
+                    radius: radius.into(),
                 },
                 ..Default::default()
             });
 
-        // Add top spacing to offset from bar center to bar bottom
-        // Bar is 36px, menu appears at center (18px), so add ~18px offset
-        let top_spacer = iced::widget::Space::new(Length::Fill, Length::Fixed(18.0));
+        // Spacing to offset from the click position to the bar edge
+        let edge_spacer = iced::widget::Space::new(Length::Fill, Length::Fixed(edge_offset));
+        let connector = container(connector).width(Length::Fill).center_x(Length::Fill);
 
-        // Stack: spacer, connector, menu
-        let content = column![
-            top_spacer,
-            container(connector).width(Length::Fill).center_x(Length::Fill),
-            menu_container,
-        ]
+        // Stack: spacer, connector, menu - reversed and bottom-aligned when
+        // opening upwards, so the menu grows away from the bar
+        let content = if opens_up {
+            column![
+                iced::widget::Space::new(Length::Fill, Length::Fill),
+                menu_container,
+                connector,
+                edge_spacer,
+            ]
+        } else {
+            column![edge_spacer, connector, menu_container]
+        }
         .spacing(0);
 
         container(content)
@@ -504,26 +1955,139 @@ impl StatusBar {
         let has_animating = self
             .popup_animations
             .values()
-            .any(|anim| anim.progress < 1.0);
+            .any(|anim| anim.animation.is_running());
 
         let animation_subscription = if has_animating {
-            iced::time::every(std::time::Duration::from_millis(16))
-                .map(|_| Message::PopupAnimationTick)
+            window::frames().map(Message::PopupAnimationFrame)
+        } else {
+            Subscription::none()
+        };
+
+        let session_lock_subscription = if self.config.bar.blank_when_locked {
+            session::subscription().map(Message::SessionLocked)
         } else {
             Subscription::none()
         };
 
-        Subscription::batch(vec![
-            self.battery.subscription().map(Message::Battery),
-            self.clock.subscription().map(Message::Clock),
-            self.volume.subscription().map(Message::Volume),
-            self.notification_toggle.subscription().map(Message::NotificationToggle),
-            self.workspaces.subscription().map(Message::Workspaces),
-            self.window_title.subscription().map(Message::WindowTitle),
-            self.system_tray.subscription().map(Message::SystemTray),
-            config_subscription().map(Message::ConfigChanged),
+        let color_scheme_subscription = if self.config.theme.switch != ThemeSwitch::Off {
+            time::every(Duration::from_secs(appearance::POLL_INTERVAL_SECS))
+                .map(|_| Message::CheckColorScheme)
+        } else {
+            Subscription::none()
+        };
+
+        let bar_covered_subscription = HyprlandSubscription::new("hyprland-bar-covered-events")
+            .on_workspace_changed(|| Message::CheckMonitors)
+            .on_fullscreen_changed(|_| Message::CheckMonitors)
+            .build();
+
+        // Bars follow monitor hotplug
+        let outputs_subscription = HyprlandSubscription::new("hyprland-monitor-events")
+            .on_monitors_changed(|| Message::RefreshOutputs)
+            .build();
+
+        Subscription::batch([
+            self.modules
+                .subscription(&self.config, self.any_bar_visible(), self.backends_started)
+                .map(Message::Module),
+            session_lock_subscription,
+            config_subscription(
+                self.config
+                    .theme
+                    .for_scheme(self.color_scheme)
+                    .import_path(),
+            )
+            .map(Message::ConfigChanged),
+            color_scheme_subscription,
+            bar_covered_subscription,
+            outputs_subscription,
             event::listen().map(Message::IcedEvent),
+            event::listen_with(|event, _status, id| match event {
+                Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                    Some(Message::CursorMoved(id, position))
+                }
+                Event::Window(window::Event::Opened { .. }) => Some(Message::SurfaceOpened(id)),
+                Event::Window(window::Event::Resized(_)) => Some(Message::SurfaceResized(id)),
+                _ => None,
+            }),
             animation_subscription,
+            control::subscription().map(|command| match command {
+                control::Command::Peek(command) => Message::Peek(command),
+                control::Command::EditLayout => Message::Layout(layout::Message::Toggle),
+                control::Command::PublishState => Message::PublishState,
+            }),
         ])
     }
-}
\ No newline at end of file
+}
+
+/// Width of a popup menu wide enough for its longest label (200-400px).
+fn menu_width(items: &[system_tray::menu::MenuItem], font_size: f32) -> u32 {
+    // Longest label, counting the indentation of submenu items, the icon and
+    // the shortcut in glyphs
+    fn longest(items: &[system_tray::menu::MenuItem], indent: usize) -> usize {
+        items
+            .iter()
+            .map(|item| {
+                let icon = if item.icon.is_some() { 3 } else { 0 };
+                let shortcut = item
+                    .shortcut
+                    .as_ref()
+                    .map_or(0, |shortcut| shortcut.chars().count() + 3);
+                let own = indent + icon + item.label.chars().count() + shortcut;
+                own.max(longest(&item.submenu, indent + 3))
+            })
+            .max()
+            .unwrap_or(0)
+    }
+    // Approximate monospace glyph width + button padding and check mark
+    let width = longest(items, 0) as f32 * font_size * 0.6 + 48.0;
+    width.clamp(200.0, 400.0) as u32
+}
+
+/// A monitor's active workspace, which covers or hides its bar.
+#[derive(Debug, Clone)]
+struct MonitorState {
+    output: String,
+    workspace_id: i32,
+    workspace_name: String,
+    /// The workspace has a fullscreen window (maximized windows leave the
+    /// bar visible)
+    covered: bool,
+}
+
+/// The active workspace of each monitor.
+async fn monitor_states() -> Option<Vec<MonitorState>> {
+    let monitors = match Monitors::get_async().await {
+        Ok(monitors) => monitors,
+        Err(e) => {
+            eprintln!("Failed to fetch monitors: {:?}", e);
+            return None;
+        }
+    };
+    let fullscreen: HashSet<i32> = Clients::get_async()
+        .await
+        .map(|clients| {
+            clients
+                .into_iter()
+                .filter(|client| {
+                    matches!(
+                        client.fullscreen,
+                        FullscreenMode::Fullscreen | FullscreenMode::MaximizedFullscreen
+                    )
+                })
+                .map(|client| client.workspace.id)
+                .collect()
+        })
+        .unwrap_or_default();
+    Some(
+        monitors
+            .into_iter()
+            .map(|monitor| MonitorState {
+                covered: fullscreen.contains(&monitor.active_workspace.id),
+                output: monitor.name,
+                workspace_id: monitor.active_workspace.id,
+                workspace_name: monitor.active_workspace.name,
+            })
+            .collect(),
+    )
+}
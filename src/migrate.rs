@@ -0,0 +1,91 @@
+//! Upgrades of configs written for older versions of the config layout.
+//!
+//! A migration rewrites the TOML of one version into the next before it is
+//! deserialized, so renamed or restructured keys keep working instead of
+//! failing to parse and dropping the bar to its defaults. `Config::load`
+//! writes the upgraded config back, after keeping the old file as
+//! `config.toml.v<version>.bak`.
+//!
+//! Changing the config layout incompatibly means appending a migration to
+//! [`MIGRATIONS`], which bumps [`CONFIG_VERSION`].
+
+use toml::{Table, Value};
+
+/// Migrations, by the version they upgrade from.
+const MIGRATIONS: &[fn(&mut Table)] = &[unpin_default_colors];
+
+/// Version of the current config layout, written as `version`.
+pub const CONFIG_VERSION: u32 = MIGRATIONS.len() as u32;
+
+/// Upgrade a config to [`CONFIG_VERSION`]. Returns the version it was
+/// written for, or `None` if it is current (or newer).
+pub fn migrate(config: &mut Table) -> Option<u32> {
+    let from = match config.get("version") {
+        Some(version) => u32::try_from(version.as_integer()?).ok()?,
+        // Configs from before versioning
+        None => 0,
+    };
+    if from >= CONFIG_VERSION {
+        return None;
+    }
+    for migration in &MIGRATIONS[from as usize..] {
+        migration(config);
+    }
+    config.insert("version".to_string(), Value::Integer(CONFIG_VERSION.into()));
+    Some(from)
+}
+
+/// Version 0 wrote every theme color out, pinned to Tokyo Night. Drop the
+/// unchanged ones so `preset`, `import` and the light/dark variants apply;
+/// Tokyo Night is the default preset, so the bar looks the same.
+fn unpin_default_colors(config: &mut Table) {
+    const PINNED: &[(&str, &str)] = &[
+        ("background", "#1a1b26"),
+        ("text", "#c0caf5"),
+        ("success", "#9ece6a"),
+        ("danger", "#f7768e"),
+        ("accent", "#7aa2f7"),
+        ("accent2", "#bb9af7"),
+        ("info", "#7dcfff"),
+        ("surface", "#24283b"),
+        ("border", "#414868"),
+        ("muted", "#565f89"),
+        ("hover", "#414868"),
+    ];
+    let Some(Value::Table(theme)) = config.get_mut("theme") else {
+        return;
+    };
+    for (key, pinned) in PINNED {
+        if theme
+            .get(*key)
+            .and_then(Value::as_str)
+            .is_some_and(|color| color.eq_ignore_ascii_case(pinned))
+        {
+            theme.remove(*key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unversioned_configs_lose_pinned_colors() {
+        let mut config: Table = toml::from_str(
+            "[theme]\nbackground = \"#1A1B26\"\naccent = \"#ff0000\"\nfont_size = 16",
+        )
+        .unwrap();
+        assert_eq!(migrate(&mut config), Some(0));
+        assert_eq!(
+            config,
+            toml::from_str(&format!(
+                "version = {}\n[theme]\naccent = \"#ff0000\"\nfont_size = 16",
+                CONFIG_VERSION
+            ))
+            .unwrap()
+        );
+        // Migrating again changes nothing
+        assert_eq!(migrate(&mut config), None);
+    }
+}
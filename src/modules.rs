@@ -0,0 +1,814 @@
+//! Bar modules and their message routing.
+//!
+//! Every widget implements [`Module`] next to its state and is registered
+//! in [`Modules`] under its [`ModuleKind`]. Widget messages travel as a
+//! [`ModuleMessage`] carrying that key and are routed to the registered
+//! widget; the bar only sees the [`Action`]s a widget asks for (opening a
+//! popup, playing a sound) and hands popup menu selections back through
+//! [`Modules::menu_selected`].
+//!
+//! Adding a widget means implementing [`Module`] for it and registering it
+//! in [`Modules::new`]; `StatusBar` does not change unless the widget needs
+//! a new kind of popup.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+
+use hyprland::shared::WorkspaceId;
+use iced::{Element, Subscription, Task};
+
+use crate::components::system_tray::menu::MenuItem;
+use crate::components::{
+    battery, carousel, clock, cursor, decoration, devices, file_content, focus_timer, hosts,
+    lid_dock, mail, notification_toggle, power, public_ip, swap, system_tray, tiling_layout, timer,
+    tray_widget, volume, vrr, window_title, workspaces,
+};
+use crate::config::{Config, ModuleActions, PeekConfig};
+use crate::diagnostics;
+use crate::exec::Exec;
+use crate::layout::ModuleKind;
+use crate::sound::SoundEvent;
+use crate::theme;
+
+/// A bar widget, as routed by [`Modules`].
+pub trait Module: Any {
+    type Message: fmt::Debug + Clone + Send + 'static;
+
+    /// The widget in the bar on `output`.
+    fn show(&self, output: &str) -> Element<'_, Self::Message>;
+
+    /// Handle one of the widget's messages, pushing what it asks of the bar
+    /// to `actions`.
+    fn handle(
+        &mut self,
+        message: Self::Message,
+        context: &Context<'_>,
+        actions: &mut Vec<Action>,
+    ) -> Task<Self::Message>;
+
+    /// Subscriptions that run in the given bar state.
+    fn watch(&self, _bar: &BarState<'_>) -> Subscription<Self::Message> {
+        Subscription::none()
+    }
+
+    /// Whether the tooltip refreshes faster while the pointer is over the
+    /// widget, through [`Module::watch_hovered`].
+    fn has_live_tooltip(&self) -> bool {
+        false
+    }
+
+    fn watch_hovered(&self) -> Subscription<Self::Message> {
+        Subscription::none()
+    }
+
+    /// Apply a reloaded config.
+    fn reconfigure(&mut self, _config: &Config) {}
+
+    /// The bars on `outputs` were laid out anew: opened, resized, or
+    /// redrawn with a reloaded config.
+    fn relayout(&mut self, _outputs: &[String]) -> Task<Self::Message> {
+        Task::none()
+    }
+
+    /// The message of the first lookup, for widgets whose periodic lookups
+    /// only fire after the first interval, and when it runs.
+    fn first_lookup(&self) -> Option<(Lookup, Self::Message)> {
+        None
+    }
+
+    /// The message for an activated item of a popup menu, if the widget
+    /// owns the menu.
+    fn menu_message(&self, _owner: &MenuOwner, _menu_id: i32) -> Option<Self::Message> {
+        None
+    }
+}
+
+/// When a widget's first lookup runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lookup {
+    /// At startup, for Hyprland state read once
+    Startup,
+    /// When the bar shows again, for display-only polling
+    Display,
+    /// Once backends start after the startup delay
+    Backend,
+}
+
+/// Bar state that decides which module subscriptions run.
+pub struct BarState<'a> {
+    pub config: &'a Config,
+    /// Whether the bar is shown, not covered or hidden
+    pub visible: bool,
+    /// Whether the startup delay for backends has passed
+    pub backends_started: bool,
+}
+
+/// State of every bar module.
+pub struct Modules {
+    /// Widgets by the layout entry they render
+    widgets: HashMap<ModuleKind, Box<dyn Routable>>,
+    /// Separators, spacers and labels
+    decorations: decoration::Decorations,
+    /// Carousels, in config order
+    carousels: Vec<carousel::Carousel>,
+    /// Configured click and scroll commands, by module
+    actions: HashMap<ModuleKind, ModuleActions>,
+    /// Module under the pointer, if it has a live tooltip
+    hovered: Option<ModuleKind>,
+}
+
+#[derive(Debug, Clone)]
+pub enum ModuleMessage {
+    /// Message for the widget registered under the given kind
+    Widget(ModuleKind, Routed),
+    /// Message for the carousel with the given name
+    Carousel(String, carousel::Message),
+    /// A configured click or scroll command; empty commands do nothing
+    RunCommand(String),
+    /// Pointer entered (`true`) or left a module with a live tooltip
+    Hovered(ModuleKind, bool),
+}
+
+impl ModuleMessage {
+    /// Route a widget's own message to the widget registered under `kind`.
+    pub fn widget<M: fmt::Debug + Clone + Send + 'static>(kind: ModuleKind, message: M) -> Self {
+        Self::Widget(kind, Routed(Box::new(message)))
+    }
+
+    /// Short description for the diagnostics recording: the widget and its
+    /// message's variant.
+    pub fn summary(&self) -> String {
+        match self {
+            Self::Widget(kind, message) => {
+                format!("{:?} {}", kind, diagnostics::variant(message))
+            }
+            Self::Carousel(name, message) => {
+                format!("Carousel {} {}", name, diagnostics::variant(message))
+            }
+            Self::RunCommand(_) => "RunCommand".to_string(),
+            Self::Hovered(kind, hovered) => format!("Hovered {:?} {}", kind, hovered),
+        }
+    }
+
+    /// A frame of a widget animation; these come at the display's refresh
+    /// rate and change nothing but the drawing.
+    pub fn is_animation_frame(&self) -> bool {
+        match self {
+            Self::Widget(ModuleKind::Workspaces, message) => matches!(
+                message.downcast_ref(),
+                Some(workspaces::Message::AnimationFrame(_))
+            ),
+            _ => false,
+        }
+    }
+}
+
+/// A widget's own message, with its type erased until it reaches the
+/// widget.
+pub struct Routed(Box<dyn AnyMessage>);
+
+trait AnyMessage: Any + Send + fmt::Debug {
+    fn clone_box(&self) -> Box<dyn AnyMessage>;
+}
+
+impl<M: fmt::Debug + Clone + Send + 'static> AnyMessage for M {
+    fn clone_box(&self) -> Box<dyn AnyMessage> {
+        Box::new(self.clone())
+    }
+}
+
+impl Routed {
+    fn downcast<M: 'static>(self) -> Option<M> {
+        let message: Box<dyn Any> = self.0;
+        message.downcast().ok().map(|message| *message)
+    }
+
+    fn downcast_ref<M: 'static>(&self) -> Option<&M> {
+        let message: &dyn Any = self.0.as_ref();
+        message.downcast_ref()
+    }
+}
+
+impl Clone for Routed {
+    fn clone(&self) -> Self {
+        Self(self.0.clone_box())
+    }
+}
+
+impl fmt::Debug for Routed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// [`Module`] with its message type erased, as kept in the registry. The
+/// kind a widget is registered under tags the messages it produces.
+trait Routable {
+    fn view(&self, kind: &ModuleKind, output: &str) -> Element<'_, ModuleMessage>;
+    fn update(
+        &mut self,
+        kind: &ModuleKind,
+        message: Routed,
+        context: &Context<'_>,
+        actions: &mut Vec<Action>,
+    ) -> Task<ModuleMessage>;
+    fn subscription(&self, kind: &ModuleKind, bar: &BarState<'_>) -> Subscription<ModuleMessage>;
+    fn hover_subscription(&self, kind: &ModuleKind) -> Subscription<ModuleMessage>;
+    fn has_live_tooltip(&self) -> bool;
+    fn reconfigure(&mut self, config: &Config);
+    fn relayout(&mut self, kind: &ModuleKind, outputs: &[String]) -> Task<ModuleMessage>;
+    fn first_lookup(&self, kind: &ModuleKind) -> Option<(Lookup, ModuleMessage)>;
+    fn menu_message(
+        &self,
+        kind: &ModuleKind,
+        owner: &MenuOwner,
+        menu_id: i32,
+    ) -> Option<ModuleMessage>;
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<M: Module> Routable for M {
+    fn view(&self, kind: &ModuleKind, output: &str) -> Element<'_, ModuleMessage> {
+        let kind = kind.clone();
+        Module::show(self, output).map(move |message| ModuleMessage::widget(kind.clone(), message))
+    }
+
+    fn update(
+        &mut self,
+        kind: &ModuleKind,
+        message: Routed,
+        context: &Context<'_>,
+        actions: &mut Vec<Action>,
+    ) -> Task<ModuleMessage> {
+        let Some(message) = message.downcast() else {
+            return Task::none();
+        };
+        let kind = kind.clone();
+        Module::handle(self, message, context, actions)
+            .map(move |message| ModuleMessage::widget(kind.clone(), message))
+    }
+
+    fn subscription(&self, kind: &ModuleKind, bar: &BarState<'_>) -> Subscription<ModuleMessage> {
+        Module::watch(self, bar)
+            .with(kind.clone())
+            .map(|(kind, message)| ModuleMessage::widget(kind, message))
+    }
+
+    fn hover_subscription(&self, kind: &ModuleKind) -> Subscription<ModuleMessage> {
+        Module::watch_hovered(self)
+            .with(kind.clone())
+            .map(|(kind, message)| ModuleMessage::widget(kind, message))
+    }
+
+    fn has_live_tooltip(&self) -> bool {
+        Module::has_live_tooltip(self)
+    }
+
+    fn reconfigure(&mut self, config: &Config) {
+        Module::reconfigure(self, config)
+    }
+
+    fn relayout(&mut self, kind: &ModuleKind, outputs: &[String]) -> Task<ModuleMessage> {
+        let kind = kind.clone();
+        Module::relayout(self, outputs)
+            .map(move |message| ModuleMessage::widget(kind.clone(), message))
+    }
+
+    fn first_lookup(&self, kind: &ModuleKind) -> Option<(Lookup, ModuleMessage)> {
+        Module::first_lookup(self)
+            .map(|(when, message)| (when, ModuleMessage::widget(kind.clone(), message)))
+    }
+
+    fn menu_message(
+        &self,
+        kind: &ModuleKind,
+        owner: &MenuOwner,
+        menu_id: i32,
+    ) -> Option<ModuleMessage> {
+        Module::menu_message(self, owner, menu_id)
+            .map(|message| ModuleMessage::widget(kind.clone(), message))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Component that owns a popup menu and receives its item activations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MenuOwner {
+    /// Tray item menu (D-Bus address of the item)
+    Tray(String),
+    /// Audio output device switcher
+    AudioSinks,
+    /// Power menu
+    Power,
+    /// Countdown/stopwatch menu
+    Timer,
+    /// Window picker
+    Windows,
+    /// Remote host list
+    Hosts,
+    /// Mail compose and quick actions
+    Mail,
+    /// Actions on a workspace (its ID)
+    Workspace(WorkspaceId),
+}
+
+/// Something a module asks the bar to do after handling a message.
+#[derive(Debug, Clone)]
+pub enum Action {
+    OpenMenu {
+        owner: MenuOwner,
+        items: Vec<MenuItem>,
+    },
+    ToggleCalendar,
+    ToggleWorkspaceOverview,
+    /// Open the overview popup, e.g. to rename a workspace in it
+    OpenWorkspaceOverview,
+    CloseWorkspaceOverview,
+    ToggleTrayOverflow,
+    CloseTrayOverflow,
+    OpenPowerConfirm,
+    ClosePowerConfirm,
+    /// The active workspace may have changed
+    WorkspacesChanged,
+    PlaySound(SoundEvent),
+}
+
+/// Bar state a module may need while handling a message.
+pub struct Context<'a> {
+    pub config: &'a Config,
+    /// Screen position of the last click on the bar
+    pub click_position: (i32, i32),
+}
+
+impl Modules {
+    pub fn new(config: &Config) -> Self {
+        let mut workspaces = workspaces::Workspaces::default();
+        Module::reconfigure(&mut workspaces, config);
+
+        let mut modules = Self {
+            widgets: HashMap::new(),
+            decorations: decoration::Decorations::new(&config.separator, &config.spacer),
+            carousels: carousels_from_config(config),
+            actions: config.actions.clone(),
+            hovered: None,
+        };
+        modules.register(
+            ModuleKind::Battery,
+            battery::Battery::new(config.battery.clone(), &config.format),
+        );
+        modules.register(ModuleKind::Clock, clock::Clock::new(config.clock.clone()));
+        modules.register(
+            ModuleKind::FocusTimer,
+            focus_timer::FocusTimer::new(config.focus_timer.clone()),
+        );
+        modules.register(
+            ModuleKind::Devices,
+            devices::Devices::new(config.devices.clone()),
+        );
+        modules.register(
+            ModuleKind::LidDock,
+            lid_dock::LidDock::new(config.lid_dock.clone()),
+        );
+        modules.register(ModuleKind::Power, power::Power::new(config.power.clone()));
+        modules.register(ModuleKind::Timer, timer::Timer::new(config.timer.clone()));
+        modules.register(
+            ModuleKind::Swap,
+            swap::Swap::new(config.swap.clone(), &config.format),
+        );
+        modules.register(
+            ModuleKind::PublicIp,
+            public_ip::PublicIp::new(config.public_ip.clone()),
+        );
+        modules.register(ModuleKind::Mail, mail::Mail::new(config.mail.clone()));
+        modules.register(ModuleKind::Hosts, hosts::Hosts::new(config.hosts.clone()));
+        modules.register(
+            ModuleKind::TilingLayout,
+            tiling_layout::TilingLayout::default(),
+        );
+        modules.register(
+            ModuleKind::Cursor,
+            cursor::Cursor::new(config.cursor.clone()),
+        );
+        modules.register(ModuleKind::Vrr, vrr::Vrr::default());
+        modules.register(
+            ModuleKind::Volume,
+            volume::Volume::new(config.volume.clone(), &config.format),
+        );
+        modules.register(
+            ModuleKind::NotificationToggle,
+            notification_toggle::NotificationToggle::new(config.notifications.clone()),
+        );
+        modules.register(ModuleKind::Workspaces, workspaces);
+        modules.register(
+            ModuleKind::WindowTitle,
+            window_title::WindowTitle::default(),
+        );
+        modules.register(
+            ModuleKind::SystemTray,
+            system_tray::SystemTray::new(config.system_tray.clone()),
+        );
+        modules.register_file_widgets(config);
+        modules
+    }
+
+    fn register(&mut self, kind: ModuleKind, widget: impl Module) {
+        self.widgets.insert(kind, Box::new(widget));
+    }
+
+    /// Register a file content widget for every `[[file_widgets]]` entry
+    /// that has none yet.
+    fn register_file_widgets(&mut self, config: &Config) {
+        for widget in &config.file_widgets {
+            let kind = ModuleKind::File(widget.name.clone());
+            if !self.widgets.contains_key(&kind) {
+                self.register(kind, file_content::FileContent::new(widget.clone()));
+            }
+        }
+    }
+
+    /// The widget registered under `kind`, if it is an `M`.
+    pub fn get<M: Module>(&self, kind: &ModuleKind) -> Option<&M> {
+        self.widgets.get(kind)?.as_any().downcast_ref()
+    }
+
+    pub fn get_mut<M: Module>(&mut self, kind: &ModuleKind) -> Option<&mut M> {
+        self.widgets.get_mut(kind)?.as_any_mut().downcast_mut()
+    }
+
+    /// A popup the widget registered under `kind` shows outside the bar,
+    /// with its messages routed back to the widget.
+    pub fn view_popup<'a, M: Module>(
+        &'a self,
+        kind: &ModuleKind,
+        view: impl FnOnce(&'a M) -> Element<'a, M::Message>,
+    ) -> Element<'a, ModuleMessage> {
+        match self.get::<M>(kind) {
+            Some(widget) => {
+                let kind = kind.clone();
+                view(widget).map(move |message| ModuleMessage::widget(kind.clone(), message))
+            }
+            None => iced::widget::text("").into(),
+        }
+    }
+
+    /// First lookups of the widgets for which `runs` holds.
+    fn first_lookups(&self, runs: impl Fn(&ModuleKind, Lookup) -> bool) -> Task<ModuleMessage> {
+        Task::batch(self.widgets.iter().filter_map(|(kind, widget)| {
+            let (when, message) = widget.first_lookup(kind)?;
+            runs(kind, when).then(|| Task::done(message))
+        }))
+    }
+
+    /// Initial refreshes for modules that read Hyprland state once.
+    pub fn init(&self) -> Task<ModuleMessage> {
+        self.first_lookups(|_, when| when == Lookup::Startup)
+    }
+
+    /// First lookups of the backends that start after the startup delay
+    /// (their periodic lookups only fire after the first interval).
+    pub fn start_backends(&self, config: &Config) -> Task<ModuleMessage> {
+        self.first_lookups(|kind, when| when == Lookup::Backend && config.shows(kind))
+    }
+
+    /// First lookups of the modules a reloaded config adds to the layout
+    /// (backends only once they started), which would otherwise stay empty
+    /// until their first interval.
+    pub fn start_added(
+        &self,
+        old: &Config,
+        new: &Config,
+        backends_started: bool,
+    ) -> Task<ModuleMessage> {
+        self.first_lookups(|kind, when| {
+            !old.shows(kind) && new.shows(kind) && (when != Lookup::Backend || backends_started)
+        })
+    }
+
+    /// Catch up on display-only polling that was suspended.
+    pub fn catch_up_display(&self) -> Task<ModuleMessage> {
+        self.first_lookups(|_, when| when == Lookup::Display)
+    }
+
+    /// Apply a reloaded config to every module.
+    pub fn set_config(&mut self, config: &Config) {
+        // File widgets keep what they show unless their entry is gone; their
+        // subscriptions carry on and only report changes
+        self.widgets.retain(|kind, _| match kind {
+            ModuleKind::File(name) => config
+                .file_widgets
+                .iter()
+                .any(|widget| widget.name == *name),
+            _ => true,
+        });
+        for widget in self.widgets.values_mut() {
+            widget.reconfigure(config);
+        }
+        self.register_file_widgets(config);
+        self.decorations = decoration::Decorations::new(&config.separator, &config.spacer);
+        self.carousels = carousels_from_config(config);
+        self.actions = config.actions.clone();
+    }
+
+    /// Let the widgets measure the bars on `outputs` after they were laid
+    /// out anew.
+    pub fn relayout(&mut self, outputs: &[String]) -> Task<ModuleMessage> {
+        Task::batch(
+            self.widgets
+                .iter_mut()
+                .map(|(kind, widget)| widget.relayout(kind, outputs)),
+        )
+    }
+
+    /// Switch the modules with an expanded look (the clock's full date) to
+    /// it while the bar is peeked, with the `[peek]` config, or back with
+    /// `None`.
+    pub fn set_peek(&mut self, peek: Option<&PeekConfig>) {
+        if let Some(clock) = self.get_mut::<clock::Clock>(&ModuleKind::Clock) {
+            clock.set_peek_format(peek.map(|peek| peek.clock_format.as_str()));
+        }
+    }
+
+    /// What the widgets currently show, as served by `clammy --state`.
+    pub fn state(&self) -> serde_json::Value {
+        serde_json::json!({
+            "workspaces": self
+                .get::<workspaces::Workspaces>(&ModuleKind::Workspaces)
+                .map(|workspaces| workspaces.state()),
+            "battery": self
+                .get::<battery::Battery>(&ModuleKind::Battery)
+                .map(|battery| battery.state()),
+            "tray": self
+                .get::<system_tray::SystemTray>(&ModuleKind::SystemTray)
+                .map(|tray| tray.state()),
+            "window": self
+                .get::<window_title::WindowTitle>(&ModuleKind::WindowTitle)
+                .map(|window| window.state()),
+            "files": self
+                .widgets
+                .iter()
+                .filter_map(|(kind, widget)| match kind {
+                    ModuleKind::File(name) => {
+                        let widget: &file_content::FileContent = widget.as_any().downcast_ref()?;
+                        Some((name.clone(), widget.state()))
+                    }
+                    _ => None,
+                })
+                .collect::<serde_json::Map<_, _>>(),
+        })
+    }
+
+    /// Route a message to its module. Returns the module's follow-up task
+    /// and what it asks of the bar.
+    pub fn update(
+        &mut self,
+        message: ModuleMessage,
+        context: Context<'_>,
+    ) -> (Task<ModuleMessage>, Vec<Action>) {
+        let mut actions = Vec::new();
+        let task = match message {
+            ModuleMessage::Widget(kind, message) => match self.widgets.get_mut(&kind) {
+                Some(widget) => widget.update(&kind, message, &context, &mut actions),
+                None => Task::none(),
+            },
+            ModuleMessage::Carousel(name, msg) => {
+                if let Some(carousel) = self.carousels.iter_mut().find(|c| c.name() == name) {
+                    // A module rotated away never sees the pointer leave
+                    if carousel.current() == self.hovered.as_ref() {
+                        self.hovered = None;
+                    }
+                    carousel.update(msg);
+                }
+                Task::none()
+            }
+            ModuleMessage::Hovered(kind, true) => {
+                self.hovered = Some(kind);
+                Task::none()
+            }
+            ModuleMessage::Hovered(kind, false) => {
+                if self.hovered.as_ref() == Some(&kind) {
+                    self.hovered = None;
+                }
+                Task::none()
+            }
+            ModuleMessage::RunCommand(command) if command.trim().is_empty() => Task::none(),
+            ModuleMessage::RunCommand(command) => {
+                if let Err(e) = Exec::new(command).launch() {
+                    eprintln!("Failed to run module command: {}", e);
+                }
+                Task::none()
+            }
+        };
+        (task, actions)
+    }
+
+    /// Forward a popup menu item activation to the module owning the menu.
+    pub fn menu_selected(&mut self, owner: MenuOwner, menu_id: i32) -> Task<ModuleMessage> {
+        self.widgets
+            .iter()
+            .find_map(|(kind, widget)| widget.menu_message(kind, &owner, menu_id))
+            .map_or_else(Task::none, Task::done)
+    }
+
+    /// Render a single module, with its configured click and scroll
+    /// commands. Modules with a live tooltip report when the pointer is
+    /// over them, which speeds up their polling.
+    pub fn view(&self, kind: &ModuleKind, output: &str) -> Element<'_, ModuleMessage> {
+        let mut content = tray_widget::with_component_style(
+            theme::with_component(kind, || self.view_module(kind, output)),
+            kind,
+        );
+        if self
+            .widgets
+            .get(kind)
+            .is_some_and(|widget| widget.has_live_tooltip())
+        {
+            content = iced::widget::mouse_area(content)
+                .on_enter(ModuleMessage::Hovered(kind.clone(), true))
+                .on_exit(ModuleMessage::Hovered(kind.clone(), false))
+                .into();
+        }
+        match self.actions.get(kind) {
+            Some(actions) => tray_widget::with_actions(content, actions, ModuleMessage::RunCommand),
+            None => content,
+        }
+    }
+
+    fn view_module(&self, kind: &ModuleKind, output: &str) -> Element<'_, ModuleMessage> {
+        match kind {
+            ModuleKind::Separator => self.decorations.view_separator(),
+            ModuleKind::Spacer => self.decorations.view_spacer(),
+            ModuleKind::Label(content) => self.decorations.view_label(content),
+            ModuleKind::Carousel(name) => match self.carousels.iter().find(|c| c.name() == name) {
+                Some(carousel) => match carousel.current() {
+                    Some(current) => {
+                        let name = carousel.name().to_string();
+                        iced::widget::mouse_area(self.view(current, output))
+                            .on_scroll(move |delta| {
+                                ModuleMessage::Carousel(
+                                    name.clone(),
+                                    carousel::Message::Scrolled(delta),
+                                )
+                            })
+                            .into()
+                    }
+                    None => iced::widget::text("").into(),
+                },
+                None => iced::widget::text("").into(),
+            },
+            _ => match self.widgets.get(kind) {
+                Some(widget) => widget.view(kind, output),
+                None => iced::widget::text("").into(),
+            },
+        }
+    }
+
+    /// The module actually rendered for a layout entry: the current module
+    /// of a carousel, or the entry itself.
+    pub fn shown_kind<'a>(&'a self, kind: &'a ModuleKind) -> &'a ModuleKind {
+        let current = match kind {
+            ModuleKind::Carousel(name) => self
+                .carousels
+                .iter()
+                .find(|c| c.name() == name)
+                .and_then(|c| c.current()),
+            _ => None,
+        };
+        current.unwrap_or(kind)
+    }
+
+    /// Subscriptions of all modules. Each widget decides from the bar state
+    /// which of its subscriptions run; see [`Module::watch`].
+    pub fn subscription(
+        &self,
+        config: &Config,
+        bar_visible: bool,
+        backends_started: bool,
+    ) -> Subscription<ModuleMessage> {
+        let bar = BarState {
+            config,
+            visible: bar_visible,
+            backends_started,
+        };
+        let widget_subscriptions = self
+            .widgets
+            .iter()
+            .map(|(kind, widget)| widget.subscription(kind, &bar));
+
+        // Rotation pauses while the bar is not visible
+        let carousel_subscriptions = self
+            .carousels
+            .iter()
+            .filter(|carousel| {
+                let kind = ModuleKind::Carousel(carousel.name().to_string());
+                bar_visible && config.shows(&kind)
+            })
+            .map(|carousel| {
+                carousel
+                    .subscription()
+                    .with(carousel.name().to_string())
+                    .map(|(name, msg)| ModuleMessage::Carousel(name, msg))
+            });
+
+        // Tooltips of the module under the pointer refresh faster
+        let hover_subscription = match &self.hovered {
+            Some(kind) if bar_visible && config.modules.is_enabled(kind) => self
+                .widgets
+                .get(kind)
+                .map_or_else(Subscription::none, |widget| widget.hover_subscription(kind)),
+            _ => Subscription::none(),
+        };
+
+        Subscription::batch(
+            widget_subscriptions
+                .chain(carousel_subscriptions)
+                .chain([hover_subscription]),
+        )
+    }
+}
+
+/// Create carousels for every `[[carousels]]` entry, leaving out modules
+/// disabled in `[modules]`.
+fn carousels_from_config(config: &Config) -> Vec<carousel::Carousel> {
+    config
+        .carousels
+        .iter()
+        .map(|carousel| carousel::Carousel::new(carousel, |kind| config.modules.is_enabled(kind)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestBar;
+
+    #[test]
+    fn routes_messages_to_the_widget_of_their_kind() {
+        let mut bar = TestBar::new(Config::default());
+        bar.send(
+            ModuleKind::WindowTitle,
+            [window_title::Message::ActiveWindowChanged(
+                Some("notes.txt".to_string()),
+                Some("gedit".to_string()),
+            )],
+        );
+        assert_eq!(bar.state()["window"]["title"], "notes.txt");
+
+        bar.send(ModuleKind::Clock, [clock::Message::Clicked]);
+        assert!(matches!(bar.actions[..], [Action::ToggleCalendar]));
+
+        // A message of another widget's type is dropped
+        bar.send(ModuleKind::Timer, [clock::Message::Clicked]);
+        assert_eq!(bar.actions.len(), 1);
+    }
+
+    #[test]
+    fn reloads_replace_file_widgets() {
+        let config = |name: &str| -> Config {
+            let content = format!("[[file_widgets]]\nname = \"{name}\"\npath = \"/nonexistent\"");
+            toml::from_str(&content).unwrap()
+        };
+        let text = |bar: &TestBar, name: &str| bar.state()["files"][name]["text"].clone();
+        let registered = |bar: &TestBar, name: &str| {
+            let kind = ModuleKind::File(name.to_string());
+            bar.modules
+                .get::<file_content::FileContent>(&kind)
+                .is_some()
+        };
+
+        let mut bar = TestBar::new(config("cpu"));
+        assert!(registered(&bar, "cpu"));
+        bar.send(
+            ModuleKind::File("cpu".to_string()),
+            [file_content::Message::Changed(Some("42%".to_string()))],
+        );
+
+        // The watcher only reports changes, so an unrelated reload keeps the
+        // contents; a new format applies to them right away
+        let mut reloaded = config("cpu");
+        reloaded.bar.scale = 1.5;
+        bar.reload(reloaded.clone());
+        assert_eq!(text(&bar, "cpu"), "42%");
+        reloaded.file_widgets[0].format = "CPU {content}".to_string();
+        bar.reload(reloaded);
+        assert_eq!(text(&bar, "cpu"), "CPU 42%");
+
+        bar.reload(config("gpu"));
+        assert!(!registered(&bar, "cpu"));
+        assert!(registered(&bar, "gpu"));
+        // Built-in widgets stay registered
+        assert!(
+            bar.modules
+                .get::<clock::Clock>(&ModuleKind::Clock)
+                .is_some()
+        );
+    }
+}
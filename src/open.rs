@@ -0,0 +1,83 @@
+//! Opening URLs and paths with the user's default application.
+//!
+//! Click actions that open a web page or a folder go through [`open`]
+//! instead of spawning a launcher themselves, so every widget gets the same
+//! fallbacks and can report failures in the bar.
+
+use std::process::Stdio;
+use std::time::Duration;
+
+use crate::config::expand_home;
+
+/// How long to wait for the launcher to fail before assuming it worked.
+/// `xdg-open` may keep running while the application starts.
+const LAUNCH_GRACE_MS: u64 = 1500;
+
+/// Open a URL or a path (`~/` is expanded) with the default application.
+///
+/// Uses `xdg-open`, falling back to the XDG desktop portal for URLs when
+/// `xdg-open` is not installed. Returns a short error message for the bar.
+pub async fn open(target: String) -> Result<(), String> {
+    let target = target.trim();
+    if target.is_empty() {
+        return Err("nothing to open".to_string());
+    }
+
+    let is_url = target.contains("://") || target.starts_with("mailto:");
+    let target = if is_url {
+        target.to_string()
+    } else {
+        let path = expand_home(target);
+        if !path.exists() {
+            return Err(format!("{} does not exist", path.display()));
+        }
+        path.to_string_lossy().into_owned()
+    };
+
+    match launch("xdg-open", &[&target]).await {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound && is_url => {
+            let portal_args = [
+                "call",
+                "--session",
+                "--dest",
+                "org.freedesktop.portal.Desktop",
+                "--object-path",
+                "/org/freedesktop/portal/desktop",
+                "--method",
+                "org.freedesktop.portal.OpenURI.OpenURI",
+                "",
+                &target,
+                "{}",
+            ];
+            launch("gdbus", &portal_args)
+                .await
+                .map_err(|e| format!("no launcher available: {}", e))?
+        }
+        result => result.map_err(|e| format!("xdg-open: {}", e))?,
+    }
+    .map_err(|code| format!("failed to open {} (exit {})", target, code))
+}
+
+/// Run a launcher and wait briefly for it to exit. The outer error is a
+/// spawn failure, the inner one a non-zero exit code.
+async fn launch(program: &str, args: &[&str]) -> std::io::Result<Result<(), i32>> {
+    let mut child = tokio::process::Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    match tokio::time::timeout(Duration::from_millis(LAUNCH_GRACE_MS), child.wait()).await {
+        Ok(status) => {
+            let status = status?;
+            Ok(if status.success() {
+                Ok(())
+            } else {
+                Err(status.code().unwrap_or(-1))
+            })
+        }
+        // Still running: the application is starting up
+        Err(_) => Ok(Ok(())),
+    }
+}
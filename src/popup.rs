@@ -0,0 +1,243 @@
+//! Popup placement.
+//!
+//! Popups open at the click position on the bar surface. [`Placement`]
+//! tracks that position together with the bar's output and position, and
+//! converts bar-local coordinates to output and screen coordinates, so
+//! popups line up with the bar's edge on any scale, including fractional
+//! scales on mixed-DPI setups. A bar at the bottom opens its popups upwards.
+//! With a bar on every output, the output is the one of the bar last
+//! hovered.
+
+use hyprland::data::{Monitor, Monitors};
+use hyprland::dispatch::{Dispatch, DispatchType, MonitorIdentifier};
+use hyprland::shared::{HyprData, HyprDataActive};
+use iced::Point;
+use iced_layershell::actions::MenuDirection;
+use iced_layershell::reexport::Anchor;
+
+use crate::config::BarPosition;
+
+/// Default height of the bar surface in logical pixels.
+pub const BAR_HEIGHT: u32 = 36;
+
+/// Height of the strip of the bar left on the output while minimized on a
+/// workspace that hides it.
+pub const MINIMIZED_BAR_HEIGHT: u32 = 4;
+
+/// Bar margins from the output edges (top, right, bottom, left) for a bar
+/// at the top; a bar at the bottom mirrors them.
+pub const BAR_MARGIN: (i32, i32, i32, i32) = (4, 4, 15, 4);
+
+/// Height of the connector tab between the bar and a popup.
+pub const CONNECTOR_HEIGHT: f32 = 4.0;
+
+/// Output a bar is shown on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Output {
+    /// Top-left corner in the compositor's (logical) layout
+    pub origin: Point,
+    /// Logical height
+    pub height: f32,
+    pub scale: f32,
+}
+
+impl Default for Output {
+    fn default() -> Self {
+        Self {
+            origin: Point::ORIGIN,
+            height: 0.0,
+            scale: 1.0,
+        }
+    }
+}
+
+/// Layer shell anchors of the bar surface.
+pub fn bar_anchor(position: BarPosition) -> Anchor {
+    match position {
+        BarPosition::Top => Anchor::Top | Anchor::Left | Anchor::Right,
+        BarPosition::Bottom => Anchor::Bottom | Anchor::Left | Anchor::Right,
+    }
+}
+
+/// Margins of the bar surface (top, right, bottom, left).
+pub fn bar_margin(position: BarPosition) -> (i32, i32, i32, i32) {
+    let (top, right, bottom, left) = BAR_MARGIN;
+    match position {
+        BarPosition::Top => (top, right, bottom, left),
+        BarPosition::Bottom => (bottom, right, top, left),
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Placement {
+    /// Last cursor position on the bar, in bar-local logical coordinates
+    cursor: Point,
+    output: Output,
+    position: BarPosition,
+    /// Height of the bar the cursor is on
+    height: u32,
+    /// `bar.scale`: bar-local coordinates are in pixels of this size
+    scale: f32,
+}
+
+impl Default for Placement {
+    fn default() -> Self {
+        Self {
+            cursor: Point::ORIGIN,
+            output: Output::default(),
+            position: BarPosition::default(),
+            height: BAR_HEIGHT,
+            scale: 1.0,
+        }
+    }
+}
+
+impl Placement {
+    pub fn set_position(&mut self, position: BarPosition) {
+        self.position = position;
+    }
+
+    pub fn set_height(&mut self, height: u32) {
+        self.height = height;
+    }
+
+    pub fn set_cursor(&mut self, position: Point) {
+        self.cursor = position;
+    }
+
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale;
+    }
+
+    pub fn set_output(&mut self, output: Output) {
+        self.output = output;
+    }
+
+    /// Last cursor position on the bar.
+    pub fn cursor(&self) -> Point {
+        self.cursor
+    }
+
+    /// Convert bar-local logical coordinates to logical coordinates relative
+    /// to the output's top-left corner, snapped to whole physical pixels.
+    pub fn output_point(&self, local: Point) -> Point {
+        let (top, _, bottom, left) = bar_margin(self.position);
+        let bar_top = match self.position {
+            BarPosition::Top => top as f32,
+            BarPosition::Bottom => {
+                self.output.height - bottom as f32 - self.height as f32 * self.scale
+            }
+        };
+        Point::new(
+            self.snap(left as f32 + local.x * self.scale, 1.0),
+            self.snap(bar_top + local.y * self.scale, 1.0),
+        )
+    }
+
+    /// Convert bar-local logical coordinates to the compositor's global
+    /// layout, as expected by tray items.
+    pub fn screen_point(&self, local: Point) -> (i32, i32) {
+        let point = self.output_point(local);
+        let origin = self.output.origin;
+        (
+            (origin.x + point.x).round() as i32,
+            (origin.y + point.y).round() as i32,
+        )
+    }
+
+    /// Gap between a popup opened at the cursor and the bar edge it opens
+    /// from (the bottom edge, or the top edge for a bar at the bottom),
+    /// snapped to whole physical pixels.
+    pub fn edge_offset(&self) -> f32 {
+        let gap = match self.position {
+            BarPosition::Top => self.height as f32 - self.cursor.y,
+            BarPosition::Bottom => self.cursor.y,
+        };
+        self.snap(gap.clamp(0.0, self.height as f32), self.scale)
+    }
+
+    /// Top-left corner, in bar surface coordinates, of a popup of `size`
+    /// centered on the bar-local `center_x` (a tray icon) instead of
+    /// starting at the cursor. It opens from the cursor's height like a
+    /// popup at the cursor.
+    pub fn centered_popup_position(&self, center_x: f32, size: (u32, u32)) -> (i32, i32) {
+        let (width, height) = size;
+        let x = (center_x * self.scale - width as f32 / 2.0).max(0.0);
+        let y = match self.position {
+            BarPosition::Top => self.cursor.y * self.scale,
+            BarPosition::Bottom => self.cursor.y * self.scale - height as f32,
+        };
+        (x.round() as i32, y.round() as i32)
+    }
+
+    /// Popup surface height for content of the given height, including the
+    /// gap to the bar and the connector tab.
+    pub fn surface_height(&self, content_height: f32) -> u32 {
+        ((content_height + self.edge_offset() + CONNECTOR_HEIGHT) * self.scale).ceil() as u32
+    }
+
+    /// Surface length for a length of content, which is drawn at
+    /// `bar.scale`.
+    pub fn surface_len(&self, len: u32) -> u32 {
+        (len as f32 * self.scale).ceil() as u32
+    }
+
+    /// Direction popups open in, away from the bar.
+    pub fn direction(&self) -> MenuDirection {
+        match self.position {
+            BarPosition::Top => MenuDirection::Down,
+            BarPosition::Bottom => MenuDirection::Up,
+        }
+    }
+
+    /// Whether popups open upwards, from a bar at the bottom.
+    pub fn opens_up(&self) -> bool {
+        self.position == BarPosition::Bottom
+    }
+
+    /// Round a length in pixels of `scale` logical pixels to whole physical
+    /// pixels of the output, so fractional scales leave no half-pixel gaps.
+    fn snap(&self, value: f32, scale: f32) -> f32 {
+        let scale = (self.output.scale * scale).max(f32::EPSILON);
+        (value * scale).round() / scale
+    }
+}
+
+/// Query the enabled outputs by name. `None` if Hyprland could not be
+/// asked, so a failed query does not remove every bar.
+pub async fn outputs() -> Option<Vec<(String, Output)>> {
+    match Monitors::get_async().await {
+        Ok(monitors) => Some(
+            monitors
+                .into_iter()
+                .filter(|monitor| !monitor.disabled)
+                .map(|monitor| {
+                    let output = Output {
+                        origin: Point::new(monitor.x as f32, monitor.y as f32),
+                        height: monitor.height as f32 / monitor.scale.max(f32::EPSILON),
+                        scale: monitor.scale,
+                    };
+                    (monitor.name, output)
+                })
+                .collect(),
+        ),
+        Err(e) => {
+            eprintln!("Failed to fetch monitors: {:?}", e);
+            None
+        }
+    }
+}
+
+/// Focus an output, so that a layer surface opened without an output
+/// lands on it. Returns the output that had the focus before.
+pub async fn focus_output(name: String) -> Option<String> {
+    let previous = Monitor::get_active_async()
+        .await
+        .ok()
+        .map(|monitor| monitor.name);
+    let dispatch = DispatchType::FocusMonitor(MonitorIdentifier::Name(&name));
+    if let Err(e) = Dispatch::call_async(dispatch).await {
+        eprintln!("Failed to focus monitor {}: {:?}", name, e);
+    }
+    previous
+}
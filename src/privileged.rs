@@ -0,0 +1,138 @@
+//! Writes to root-owned hardware settings.
+//!
+//! Battery charge thresholds, CPU boost, radio kill switches and backlight
+//! brightness live in sysfs files only root may write. Everything that
+//! changes them goes through [`write`] instead of calling `sudo` itself:
+//! - The file is written directly when it is writable, e.g. thanks to a
+//!   udev rule
+//! - Otherwise clammy runs itself under `pkexec` with [`HELPER_FLAG`], so
+//!   polkit asks for authorization (see `docs/io.github.clammy.policy`)
+//!
+//! Only the files in [`ALLOWED_PATHS`] and short alphanumeric values are
+//! accepted, and the helper checks them again as root, so authorizing the
+//! helper never allows writing arbitrary files.
+//!
+//! `clammy --set <path> <value>` does the same from the command line, e.g.
+//! for click actions.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::process::{Command, Stdio};
+
+use crate::config::cli_args;
+
+/// Argument that makes clammy perform one write as root and exit.
+pub const HELPER_FLAG: &str = "--privileged-write";
+
+/// Argument that makes clammy perform one write as the user and exit.
+pub const SET_FLAG: &str = "--set";
+
+/// Writable files; `*` matches a single path component.
+pub const ALLOWED_PATHS: &[&str] = &[
+    "/sys/class/power_supply/*/charge_control_start_threshold",
+    "/sys/class/power_supply/*/charge_control_end_threshold",
+    "/sys/class/power_supply/*/charge_behaviour",
+    "/sys/devices/system/cpu/cpufreq/boost",
+    "/sys/devices/system/cpu/intel_pstate/no_turbo",
+    "/sys/class/rfkill/*/soft",
+    "/sys/class/backlight/*/brightness",
+];
+
+/// Longest value accepted.
+const MAX_VALUE_LEN: usize = 32;
+
+/// Write `value` to an allowed settings file, asking polkit for
+/// authorization when the user may not write it. Blocks until the
+/// authorization prompt is answered, so widgets call it from a task.
+pub fn write(path: &str, value: &str) -> Result<(), String> {
+    check(path, value)?;
+    match fs::write(path, value) {
+        Ok(()) => return Ok(()),
+        Err(e) if e.kind() == ErrorKind::PermissionDenied => {}
+        Err(e) => return Err(format!("{}: {}", path, e)),
+    }
+
+    let exe = std::env::current_exe().map_err(|e| format!("clammy executable: {}", e))?;
+    let output = Command::new("pkexec")
+        .arg(exe)
+        .args([HELPER_FLAG, path, value])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .map_err(|e| format!("pkexec: {}", e))?;
+
+    match output.status.code() {
+        Some(0) => Ok(()),
+        // pkexec's own exit codes for a dismissed or refused prompt
+        Some(126) => Err("authorization dismissed".to_string()),
+        Some(127) => Err("not authorized".to_string()),
+        _ => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Err(match stderr.lines().next().map(str::trim) {
+                Some(line) if !line.is_empty() => line.to_string(),
+                _ => format!("privileged write failed ({})", output.status),
+            })
+        }
+    }
+}
+
+/// Handle `--set` and the helper flag: perform the write and exit. Returns
+/// when clammy was started as the bar.
+pub fn run_if_requested() {
+    let args = cli_args();
+    let result = match args.as_slice() {
+        [flag, path, value] if flag == SET_FLAG => write(path, value),
+        // Running as root: only the checked write, never `pkexec` again
+        [flag, path, value] if flag == HELPER_FLAG => check(path, value)
+            .and_then(|()| fs::write(path, value).map_err(|e| format!("{}: {}", path, e))),
+        [flag, ..] if flag == SET_FLAG || flag == HELPER_FLAG => {
+            Err(format!("usage: clammy {} <path> <value>", flag))
+        }
+        _ => return,
+    };
+
+    match result {
+        Ok(()) => std::process::exit(0),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Reject files outside [`ALLOWED_PATHS`] and values that are not short
+/// alphanumeric words.
+fn check(path: &str, value: &str) -> Result<(), String> {
+    if !ALLOWED_PATHS.iter().any(|pattern| matches(pattern, path)) {
+        return Err(format!("{} is not a writable setting", path));
+    }
+    let valid_value = !value.is_empty()
+        && value.len() <= MAX_VALUE_LEN
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid_value {
+        return Err(format!("invalid value {:?} for {}", value, path));
+    }
+    Ok(())
+}
+
+/// Match a path against a pattern whose `*` components match any single
+/// component except `.` and `..`.
+fn matches(pattern: &str, path: &str) -> bool {
+    let mut pattern = pattern.split('/');
+    let mut path = path.split('/');
+    loop {
+        match (pattern.next(), path.next()) {
+            (None, None) => return true,
+            (Some("*"), Some(component)) => {
+                if component.is_empty() || component == "." || component == ".." {
+                    return false;
+                }
+            }
+            (Some(expected), Some(component)) if expected == component => {}
+            _ => return false,
+        }
+    }
+}
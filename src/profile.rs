@@ -0,0 +1,162 @@
+//! Config profiles: `[profiles.<name>]` tables of settings that replace the
+//! ones outside `[profiles]`, so one config can drive a laptop, a docked
+//! laptop and a desktop differently.
+//!
+//! The profile is the one named with `--profile <name>` (or
+//! `$CLAMMY_PROFILE`), else the first one, in alphabetical order, whose
+//! `when` conditions all hold. It is picked on start and on every reload.
+
+use std::collections::BTreeMap;
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+use toml::{Table, Value};
+
+/// Argument that picks the profile.
+pub const PROFILE_FLAG: &str = "--profile";
+
+/// Environment variable that picks the profile; `--profile` wins.
+pub const PROFILE_ENV: &str = "CLAMMY_PROFILE";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    // When the profile applies without being named; never if unset
+    #[serde(default)]
+    pub when: Option<Condition>,
+    // Settings replacing the ones outside `[profiles]`, e.g. `[profiles.desktop.layout]`
+    #[serde(flatten)]
+    pub settings: Table,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Condition {
+    // Host name of the machine
+    #[serde(default)]
+    pub hostname: Option<String>,
+    // Whether the machine runs on mains power (always true without a
+    // mains power supply, like most desktops)
+    #[serde(default)]
+    pub ac: Option<bool>,
+}
+
+impl Condition {
+    fn holds(&self) -> bool {
+        self.hostname
+            .as_ref()
+            .is_none_or(|name| *name == hostname())
+            && self.ac.is_none_or(|ac| ac == on_ac())
+    }
+}
+
+/// The profile named with `--profile` or `$CLAMMY_PROFILE`.
+pub fn chosen() -> Option<String> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.iter().position(|arg| arg == PROFILE_FLAG) {
+        Some(index) => args.get(index + 1).cloned(),
+        None => std::env::var(PROFILE_ENV)
+            .ok()
+            .filter(|name| !name.is_empty()),
+    }
+}
+
+/// Merge the settings of the profile that applies into a config. Returns
+/// the name of the profile.
+pub fn apply(config: &mut Table) -> Result<Option<String>, toml::de::Error> {
+    let Some(profiles) = config.get("profiles") else {
+        return Ok(None);
+    };
+    let profiles: BTreeMap<String, Profile> = profiles.clone().try_into()?;
+    let Some((name, profile)) = select(profiles, chosen()) else {
+        return Ok(None);
+    };
+    merge(config, profile.settings);
+    Ok(Some(name))
+}
+
+fn select(
+    mut profiles: BTreeMap<String, Profile>,
+    chosen: Option<String>,
+) -> Option<(String, Profile)> {
+    if let Some(name) = chosen {
+        let profile = profiles.remove(&name);
+        if profile.is_none() {
+            eprintln!("Profile `{}` is not defined in [profiles]", name);
+        }
+        return profile.map(|profile| (name, profile));
+    }
+    profiles
+        .into_iter()
+        .find(|(_, profile)| profile.when.as_ref().is_some_and(Condition::holds))
+}
+
+/// Replace the values of `base` with those of `overrides`, merging tables
+/// key by key.
+pub fn merge(base: &mut Table, overrides: Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base)), Value::Table(overrides)) => merge(base, overrides),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn hostname() -> String {
+    ["/proc/sys/kernel/hostname", "/etc/hostname"]
+        .iter()
+        .find_map(|path| fs::read_to_string(path).ok())
+        .map(|name| name.trim().to_string())
+        .unwrap_or_default()
+}
+
+/// Whether a mains power supply is online, or there is none.
+fn on_ac() -> bool {
+    let Ok(supplies) = fs::read_dir("/sys/class/power_supply") else {
+        return true;
+    };
+    let mut online = Vec::new();
+    for supply in supplies.flatten() {
+        let path = supply.path();
+        let is_mains =
+            fs::read_to_string(path.join("type")).is_ok_and(|kind| kind.trim() == "Mains");
+        if is_mains {
+            online.push(
+                fs::read_to_string(path.join("online")).is_ok_and(|value| value.trim() == "1"),
+            );
+        }
+    }
+    online.is_empty() || online.contains(&true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn named_profile_overrides_settings() {
+        let mut config: Table = toml::from_str(
+            "[bar]\nheight = 30\nposition = \"top\"\n\
+             [profiles.desktop.bar]\nheight = 40\n\
+             [profiles.laptop]\nwhen = { ac = false }\ntooltips = false",
+        )
+        .unwrap();
+        let profiles = config["profiles"].clone().try_into().unwrap();
+        let (name, profile) = select(profiles, Some("desktop".to_string())).unwrap();
+        assert_eq!(name, "desktop");
+        merge(&mut config, profile.settings);
+        assert_eq!(config["bar"]["height"].as_integer(), Some(40));
+        assert_eq!(config["bar"]["position"].as_str(), Some("top"));
+        assert!(config.get("tooltips").is_none());
+    }
+
+    #[test]
+    fn profiles_without_conditions_need_a_name() {
+        let profiles: BTreeMap<String, Profile> = toml::from_str(
+            "[desktop]\ntooltips = false\n[elsewhere]\nwhen = { hostname = \"no such host\" }",
+        )
+        .unwrap();
+        assert!(select(profiles.clone(), Some("laptop".to_string())).is_none());
+        assert!(select(profiles, None).is_none());
+    }
+}
@@ -0,0 +1,39 @@
+//! `clammy --schema`: print the JSON Schema of the config, for editors that
+//! complete and validate `config.toml` (e.g. Taplo, with a
+//! `#:schema ./config.schema.json` line at the top of the config).
+
+use crate::config::cli_args;
+
+/// Argument that prints the schema and exits.
+pub const SCHEMA_FLAG: &str = "--schema";
+
+/// The schema, maintained by hand in `docs/` along with the config.
+const SCHEMA: &str = include_str!("../docs/config.schema.json");
+
+/// Handle `--schema`. Returns when clammy was started as the bar.
+pub fn run_if_requested() {
+    if cli_args().first().map(String::as_str) != Some(SCHEMA_FLAG) {
+        return;
+    }
+    print!("{}", SCHEMA);
+    std::process::exit(0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn schema_covers_the_top_level_keys() {
+        let schema: serde_json::Value = serde_json::from_str(SCHEMA).unwrap();
+        let config = toml::Table::try_from(Config::default()).unwrap();
+        for key in config.keys() {
+            assert!(
+                schema["properties"].get(key).is_some(),
+                "`{}` is missing from the schema",
+                key
+            );
+        }
+    }
+}
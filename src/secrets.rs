@@ -0,0 +1,192 @@
+//! Credentials for widgets that talk to network services.
+//!
+//! Secrets are never written into `config.toml` itself. A [`Secret`] is
+//! either the output of a command (`{ command = "pass show ipinfo" }`) or an
+//! item in the Secret Service keyring, looked up by its attributes over
+//! D-Bus (`{ keyring = { service = "ipinfo", user = "me" } }`).
+//!
+//! Secrets are resolved right before they are used and are not cached, so a
+//! locked keyring or an expired agent prompts as usual.
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+use iced::futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value};
+
+use crate::exec::Exec;
+
+/// How long a secret command may take, including an agent's passphrase
+/// prompt.
+const PROMPT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// A secret as the Secret Service hands it out: session, parameters, value
+/// and content type.
+type SecretValue = (OwnedObjectPath, Vec<u8>, Vec<u8>, String);
+
+#[zbus::proxy(
+    interface = "org.freedesktop.Secret.Service",
+    default_service = "org.freedesktop.secrets",
+    default_path = "/org/freedesktop/secrets"
+)]
+trait SecretService {
+    fn open_session(
+        &self,
+        algorithm: &str,
+        input: &Value<'_>,
+    ) -> zbus::Result<(OwnedValue, OwnedObjectPath)>;
+
+    /// Unlocked and locked items with the attributes
+    fn search_items(
+        &self,
+        attributes: &BTreeMap<String, String>,
+    ) -> zbus::Result<(Vec<OwnedObjectPath>, Vec<OwnedObjectPath>)>;
+
+    /// Items unlocked right away, and a prompt (`/` if none) for the rest
+    fn unlock(
+        &self,
+        objects: &[&ObjectPath<'_>],
+    ) -> zbus::Result<(Vec<OwnedObjectPath>, OwnedObjectPath)>;
+
+    fn get_secrets(
+        &self,
+        items: &[&ObjectPath<'_>],
+        session: &ObjectPath<'_>,
+    ) -> zbus::Result<HashMap<OwnedObjectPath, SecretValue>>;
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.Secret.Session",
+    default_service = "org.freedesktop.secrets"
+)]
+trait SecretSession {
+    fn close(&self) -> zbus::Result<()>;
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.Secret.Prompt",
+    default_service = "org.freedesktop.secrets"
+)]
+trait SecretPrompt {
+    fn prompt(&self, window_id: &str) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn completed(&self, dismissed: bool, result: Value<'_>) -> zbus::Result<()>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Secret {
+    /// First line printed by a shell command
+    Command { command: String },
+    /// Secret Service item with these attributes
+    Keyring { keyring: BTreeMap<String, String> },
+}
+
+impl Secret {
+    /// Resolve the secret. Error messages never contain the secret.
+    pub async fn resolve(&self) -> Result<String, String> {
+        let output = match self {
+            Secret::Command { command } => {
+                Exec::new(command.as_str())
+                    .timeout(PROMPT_TIMEOUT)
+                    .output()
+                    .await?
+            }
+            Secret::Keyring { keyring } => {
+                match tokio::time::timeout(PROMPT_TIMEOUT, keyring_lookup(keyring)).await {
+                    Ok(Ok(Some(secret))) => String::from_utf8(secret)
+                        .map_err(|_| format!("{} returned invalid UTF-8", self))?,
+                    Ok(Ok(None)) => {
+                        return Err(format!(
+                            "{} found nothing, or the keyring stayed locked",
+                            self
+                        ));
+                    }
+                    Ok(Err(e)) => return Err(format!("{}: {}", self, e)),
+                    Err(_) => return Err(format!("{} timed out", self)),
+                }
+            }
+        };
+        first_line(&output).ok_or_else(|| format!("{} returned nothing", self))
+    }
+}
+
+/// The secret of the first Secret Service item with the attributes, unlocking
+/// it (which may prompt for the keyring's password) if needed. `None` if
+/// there is no such item or the prompt was dismissed.
+async fn keyring_lookup(attributes: &BTreeMap<String, String>) -> zbus::Result<Option<Vec<u8>>> {
+    let connection = zbus::Connection::session().await?;
+    let service = SecretServiceProxy::new(&connection).await?;
+    let (unlocked, locked) = service.search_items(attributes).await?;
+    let item = match (unlocked.into_iter().next(), locked.into_iter().next()) {
+        (Some(item), _) => item,
+        (None, Some(item)) => {
+            if !unlock(&connection, &service, &item).await? {
+                return Ok(None);
+            }
+            item
+        }
+        (None, None) => return Ok(None),
+    };
+
+    // Secrets are handed out within a session; "plain" leaves them unencrypted
+    // on the local bus
+    let (_, session) = service.open_session("plain", &Value::from("")).await?;
+    let secrets = service.get_secrets(&[&item], &session).await;
+    if let Ok(builder) = SecretSessionProxy::builder(&connection).path(&session)
+        && let Ok(session) = builder.build().await
+    {
+        let _ = session.close().await;
+    }
+    Ok(secrets?.remove(&item).map(|(_, _, value, _)| value))
+}
+
+/// Unlock an item, prompting if the service asks to. Returns whether it
+/// was unlocked.
+async fn unlock(
+    connection: &zbus::Connection,
+    service: &SecretServiceProxy<'_>,
+    item: &OwnedObjectPath,
+) -> zbus::Result<bool> {
+    let (unlocked, prompt) = service.unlock(&[item]).await?;
+    if prompt.as_str() == "/" {
+        return Ok(unlocked.contains(item));
+    }
+    let prompt = SecretPromptProxy::builder(connection)
+        .path(prompt)?
+        .build()
+        .await?;
+    let mut completed = prompt.receive_completed().await?;
+    prompt.prompt("").await?;
+    match completed.next().await {
+        Some(signal) => Ok(!signal.args()?.dismissed),
+        None => Ok(false),
+    }
+}
+
+/// The secret on the first line of a command's output.
+fn first_line(output: &str) -> Option<String> {
+    output
+        .lines()
+        .next()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+impl std::fmt::Display for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Secret::Command { command } => write!(f, "secret command `{}`", command),
+            Secret::Keyring { keyring } => {
+                write!(f, "keyring lookup")?;
+                for (key, value) in keyring {
+                    write!(f, " {}={}", key, value)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
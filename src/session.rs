@@ -0,0 +1,75 @@
+//! Session lock state from logind.
+//!
+//! Lockers set the session's `LockedHint` when the screen locks. The bar
+//! can stay visible above the lock screen layer, so sensitive widgets are
+//! blanked while it is set (`bar.blank_when_locked`). logind announces
+//! changes of the hint on the system bus, so the bar hears about them right
+//! away.
+
+use iced::futures::channel::mpsc::Sender;
+use iced::futures::{SinkExt, Stream, StreamExt};
+use iced::{Subscription, stream};
+use zbus::zvariant::OwnedObjectPath;
+
+#[zbus::proxy(
+    interface = "org.freedesktop.login1.Manager",
+    default_service = "org.freedesktop.login1",
+    default_path = "/org/freedesktop/login1"
+)]
+trait Manager {
+    fn get_session(&self, session_id: &str) -> zbus::Result<OwnedObjectPath>;
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.login1.Session",
+    default_service = "org.freedesktop.login1"
+)]
+trait Session {
+    #[zbus(property)]
+    fn id(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn locked_hint(&self) -> zbus::Result<bool>;
+}
+
+/// Whether our logind session is locked: first the current state, then
+/// each change.
+pub fn subscription() -> Subscription<bool> {
+    Subscription::run(watch)
+}
+
+fn watch() -> impl Stream<Item = bool> {
+    stream::channel(1, |mut output| async move {
+        if let Err(e) = forward_lock_state(&mut output).await {
+            eprintln!("Failed to watch the session lock state: {}", e);
+        }
+        std::future::pending::<()>().await;
+    })
+}
+
+async fn forward_lock_state(output: &mut Sender<bool>) -> zbus::Result<()> {
+    let connection = zbus::Connection::system().await?;
+    // `auto` stands for our session, but changes are announced on the
+    // session's own path
+    let auto = SessionProxy::builder(&connection)
+        .path("/org/freedesktop/login1/session/auto")?
+        .build()
+        .await?;
+    let path = ManagerProxy::new(&connection)
+        .await?
+        .get_session(&auto.id().await?)
+        .await?;
+    let session = SessionProxy::builder(&connection)
+        .path(path)?
+        .build()
+        .await?;
+
+    let mut changes = session.receive_locked_hint_changed().await;
+    let _ = output.send(session.locked_hint().await?).await;
+    while let Some(change) = changes.next().await {
+        if let Ok(locked) = change.get().await {
+            let _ = output.send(locked).await;
+        }
+    }
+    Ok(())
+}
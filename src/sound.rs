@@ -0,0 +1,45 @@
+//! Sound effects for notable events.
+//!
+//! Sounds are either names from the freedesktop sound theme, played with
+//! `canberra-gtk-play`, or paths to sound files, played with `pw-play`.
+//! Nothing plays while do-not-disturb is on.
+
+use crate::config::{SoundsConfig, expand_home};
+use crate::exec::Exec;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoundEvent {
+    BatteryCritical,
+    TimerFinished,
+    Notification,
+}
+
+/// Play the sound configured for an event, if any.
+pub fn play(event: SoundEvent, config: &SoundsConfig, dnd: bool) {
+    if !config.enabled || dnd {
+        return;
+    }
+    let sound = match event {
+        SoundEvent::BatteryCritical => &config.battery_critical,
+        SoundEvent::TimerFinished => &config.timer_finished,
+        SoundEvent::Notification => &config.notification,
+    };
+    let sound = sound.trim();
+    if sound.is_empty() {
+        return;
+    }
+
+    let command = if sound.contains('/') {
+        format!("pw-play {}", shell_quote(&expand_home(sound).to_string_lossy()))
+    } else {
+        format!("canberra-gtk-play --id={}", shell_quote(sound))
+    };
+    if let Err(e) = Exec::new(command).launch() {
+        eprintln!("Failed to play sound: {}", e);
+    }
+}
+
+/// Quote a value for `sh`.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
@@ -0,0 +1,88 @@
+//! Driving components with synthetic messages in tests.
+//!
+//! Components are updated the way the runtime does, one message at a time.
+//! Follow-up tasks are dropped instead of run, so a test sends the messages
+//! those tasks would have produced (e.g. the workspace list Hyprland would
+//! report, or an SNI item appearing) and stays independent of the
+//! compositor and D-Bus.
+//!
+//! [`drive`] updates a single component. [`TestBar`] drives every module
+//! through the bar's routing instead: messages reach a widget by its
+//! [`ModuleKind`], the [`Action`]s widgets ask for are collected, and
+//! [`TestBar::reload`] applies a changed config the way a file reload does.
+//! What the widgets show is checked through [`Modules::state`], the JSON
+//! `clammy --state` serves.
+//!
+//! Not covered: there is no fake compositor, since widgets query Hyprland
+//! through `hyprland-rs` directly rather than through a trait a test could
+//! replace; tests send the messages its events and lookups produce
+//! instead. Rendered widget trees are not inspected either, as iced 0.13
+//! has no way to query them outside a running window.
+
+use std::fmt;
+
+use iced::Task;
+
+use crate::config::Config;
+use crate::layout::ModuleKind;
+use crate::modules::{Action, Context, ModuleMessage, Modules};
+
+/// Feed `messages` to `update` in order, dropping the follow-up tasks.
+pub fn drive<S, M>(
+    state: &mut S,
+    messages: impl IntoIterator<Item = M>,
+    mut update: impl FnMut(&mut S, M) -> Task<M>,
+) {
+    for message in messages {
+        drop(update(state, message));
+    }
+}
+
+/// The bar's modules and config, without any windows.
+pub struct TestBar {
+    pub config: Config,
+    pub modules: Modules,
+    /// What the widgets asked of the bar, oldest first
+    pub actions: Vec<Action>,
+}
+
+impl TestBar {
+    pub fn new(config: Config) -> Self {
+        Self {
+            modules: Modules::new(&config),
+            config,
+            actions: Vec::new(),
+        }
+    }
+
+    /// Route `messages` to the widget registered under `kind`, dropping
+    /// the follow-up tasks.
+    pub fn send<M: fmt::Debug + Clone + Send + 'static>(
+        &mut self,
+        kind: ModuleKind,
+        messages: impl IntoIterator<Item = M>,
+    ) {
+        for message in messages {
+            let context = Context {
+                config: &self.config,
+                click_position: (0, 0),
+            };
+            let (task, actions) = self
+                .modules
+                .update(ModuleMessage::widget(kind.clone(), message), context);
+            drop(task);
+            self.actions.extend(actions);
+        }
+    }
+
+    /// Apply a reloaded config, as the bar does when the file changes.
+    pub fn reload(&mut self, config: Config) {
+        self.modules.set_config(&config);
+        self.config = config;
+    }
+
+    /// What the widgets currently show.
+    pub fn state(&self) -> serde_json::Value {
+        self.modules.state()
+    }
+}
@@ -1,7 +1,17 @@
-use iced::{Color, Theme};
-use std::sync::RwLock;
+use iced::font::Family;
+use iced::{Color, Font, Theme};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock, RwLock};
 
-use crate::config::{parse_hex_color, parse_hex_color_with_alpha, Config};
+use crate::appearance::ColorScheme;
+use crate::color;
+use crate::config::{Config, config_path, expand_home};
+use crate::icons::IconStyle;
+use crate::layout::ModuleKind;
 
 // Global theme for component access
 static GLOBAL_THEME: RwLock<Option<AppTheme>> = RwLock::new(None);
@@ -13,13 +23,396 @@ pub fn set_global_theme(theme: &AppTheme) {
     }
 }
 
-/// Get a copy of the current global theme
+thread_local! {
+    /// Module being rendered, whose `[theme.<module>]` overrides
+    /// `get_theme` applies
+    static CURRENT_COMPONENT: RefCell<Option<ModuleKind>> = const { RefCell::new(None) };
+}
+
+/// Get a copy of the current global theme, with the overrides of the module
+/// being rendered applied
 pub fn get_theme() -> AppTheme {
-    GLOBAL_THEME
+    let theme = GLOBAL_THEME
         .read()
         .ok()
         .and_then(|guard| guard.clone())
-        .unwrap_or_default()
+        .unwrap_or_default();
+    CURRENT_COMPONENT.with_borrow(|kind| match kind {
+        Some(kind) => theme.for_component(kind),
+        None => theme,
+    })
+}
+
+/// Run a module's view with its `[theme.<module>]` overrides applied to
+/// [`get_theme`], so widget helpers and component views pick them up.
+pub fn with_component<T>(kind: &ModuleKind, view: impl FnOnce() -> T) -> T {
+    let previous = CURRENT_COMPONENT.replace(Some(kind.clone()));
+    let result = view();
+    CURRENT_COMPONENT.set(previous);
+    result
+}
+
+/// Font families known to fontconfig, lowercased; read once, `None` if
+/// `fc-list` could not be run.
+fn installed_families() -> Option<&'static HashSet<String>> {
+    static FAMILIES: OnceLock<Option<HashSet<String>>> = OnceLock::new();
+    FAMILIES
+        .get_or_init(|| {
+            let output = std::process::Command::new("fc-list")
+                .args([":", "family"])
+                .output()
+                .ok()?;
+            let families = String::from_utf8_lossy(&output.stdout)
+                .lines()
+                // Fonts with several names list them comma-separated
+                .flat_map(|line| line.split(','))
+                .map(|family| family.trim().to_lowercase())
+                .collect();
+            Some(families)
+        })
+        .as_ref()
+}
+
+/// The first installed font of `families`, or the first one when none is
+/// (or fontconfig can't tell). "monospace", "sans-serif" and "serif" are the
+/// generic families. `None` for an empty list.
+pub fn resolve_font(families: &[String]) -> Option<Font> {
+    let generic = |family: &str| match family.trim().to_lowercase().as_str() {
+        "monospace" => Some(Font::MONOSPACE),
+        "sans-serif" => Some(Font::DEFAULT),
+        "serif" => Some(Font {
+            family: Family::Serif,
+            ..Font::DEFAULT
+        }),
+        _ => None,
+    };
+    let family = families
+        .iter()
+        .find(|family| {
+            generic(family).is_some()
+                || installed_families()
+                    .is_some_and(|installed| installed.contains(&family.trim().to_lowercase()))
+        })
+        .or(families.first())?;
+    if let Some(font) = generic(family) {
+        return Some(font);
+    }
+
+    // Font names must live for the whole run; leak each one only once
+    static NAMES: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+    let mut names = NAMES.lock().ok()?;
+    let name = match names.iter().find(|name| **name == family.trim()) {
+        Some(name) => *name,
+        None => {
+            let name: &'static str = Box::leak(family.trim().to_string().into_boxed_str());
+            names.push(name);
+            name
+        }
+    };
+    Some(Font::with_name(name))
+}
+
+/// Bundled color schemes, selected with `theme.preset`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemePreset {
+    #[default]
+    TokyoNight,
+    CatppuccinMocha,
+    Gruvbox,
+    Nord,
+    Dracula,
+    /// Light schemes, e.g. for `[theme.light]`
+    CatppuccinLatte,
+    GruvboxLight,
+}
+
+/// Which theme color the bar and popup borders are drawn in, selected with
+/// `theme.border_color`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BorderColor {
+    #[default]
+    Accent,
+    Border,
+}
+
+/// Hex colors of a preset, in the order of the `[theme]` keys.
+struct Palette {
+    background: &'static str,
+    text: &'static str,
+    success: &'static str,
+    danger: &'static str,
+    warning: &'static str,
+    accent: &'static str,
+    accent2: &'static str,
+    info: &'static str,
+    surface: &'static str,
+    border: &'static str,
+    muted: &'static str,
+    hover: &'static str,
+}
+
+const TOKYO_NIGHT: Palette = Palette {
+    background: "#1a1b26",
+    text: "#c0caf5",
+    success: "#9ece6a",
+    danger: "#f7768e",
+    warning: "#e0af68",
+    accent: "#7aa2f7",
+    accent2: "#bb9af7",
+    info: "#7dcfff",
+    surface: "#24283b",
+    border: "#414868",
+    muted: "#565f89",
+    hover: "#414868",
+};
+
+const CATPPUCCIN_MOCHA: Palette = Palette {
+    background: "#1e1e2e",
+    text: "#cdd6f4",
+    success: "#a6e3a1",
+    danger: "#f38ba8",
+    warning: "#f9e2af",
+    accent: "#89b4fa",
+    accent2: "#cba6f7",
+    info: "#89dceb",
+    surface: "#313244",
+    border: "#45475a",
+    muted: "#6c7086",
+    hover: "#45475a",
+};
+
+const GRUVBOX: Palette = Palette {
+    background: "#282828",
+    text: "#ebdbb2",
+    success: "#b8bb26",
+    danger: "#fb4934",
+    warning: "#fabd2f",
+    accent: "#83a598",
+    accent2: "#d3869b",
+    info: "#8ec07c",
+    surface: "#3c3836",
+    border: "#504945",
+    muted: "#928374",
+    hover: "#504945",
+};
+
+const NORD: Palette = Palette {
+    background: "#2e3440",
+    text: "#eceff4",
+    success: "#a3be8c",
+    danger: "#bf616a",
+    warning: "#ebcb8b",
+    accent: "#88c0d0",
+    accent2: "#b48ead",
+    info: "#8fbcbb",
+    surface: "#3b4252",
+    border: "#4c566a",
+    muted: "#616e88",
+    hover: "#434c5e",
+};
+
+const DRACULA: Palette = Palette {
+    background: "#282a36",
+    text: "#f8f8f2",
+    success: "#50fa7b",
+    danger: "#ff5555",
+    warning: "#f1fa8c",
+    accent: "#bd93f9",
+    accent2: "#ff79c6",
+    info: "#8be9fd",
+    surface: "#44475a",
+    border: "#6272a4",
+    muted: "#6272a4",
+    hover: "#44475a",
+};
+
+const CATPPUCCIN_LATTE: Palette = Palette {
+    background: "#eff1f5",
+    text: "#4c4f69",
+    success: "#40a02b",
+    danger: "#d20f39",
+    warning: "#df8e1d",
+    accent: "#1e66f5",
+    accent2: "#8839ef",
+    info: "#04a5e5",
+    surface: "#e6e9ef",
+    border: "#bcc0cc",
+    muted: "#9ca0b0",
+    hover: "#ccd0da",
+};
+
+const GRUVBOX_LIGHT: Palette = Palette {
+    background: "#fbf1c7",
+    text: "#3c3836",
+    success: "#79740e",
+    danger: "#9d0006",
+    warning: "#b57614",
+    accent: "#076678",
+    accent2: "#8f3f71",
+    info: "#427b58",
+    surface: "#ebdbb2",
+    border: "#d5c4a1",
+    muted: "#928374",
+    hover: "#d5c4a1",
+};
+
+impl ThemePreset {
+    fn palette(self) -> &'static Palette {
+        match self {
+            ThemePreset::TokyoNight => &TOKYO_NIGHT,
+            ThemePreset::CatppuccinMocha => &CATPPUCCIN_MOCHA,
+            ThemePreset::Gruvbox => &GRUVBOX,
+            ThemePreset::Nord => &NORD,
+            ThemePreset::Dracula => &DRACULA,
+            ThemePreset::CatppuccinLatte => &CATPPUCCIN_LATTE,
+            ThemePreset::GruvboxLight => &GRUVBOX_LIGHT,
+        }
+    }
+}
+
+/// Theme keys filled from base16 slots (`base00`..`base0F`).
+const BASE16_KEYS: &[(&str, &str)] = &[
+    ("background", "base00"),
+    ("surface", "base01"),
+    ("hover", "base02"),
+    ("border", "base02"),
+    ("muted", "base03"),
+    ("text", "base05"),
+    ("danger", "base08"),
+    ("warning", "base0A"),
+    ("success", "base0B"),
+    ("info", "base0C"),
+    ("accent", "base0D"),
+    ("accent2", "base0E"),
+];
+
+/// Theme keys filled from pywal's `colors.json` (`colorN` or a `special`
+/// entry).
+const WAL_KEYS: &[(&str, &str)] = &[
+    ("background", "background"),
+    ("text", "foreground"),
+    ("surface", "color0"),
+    ("hover", "color0"),
+    ("border", "color8"),
+    ("muted", "color8"),
+    ("danger", "color1"),
+    ("success", "color2"),
+    ("warning", "color3"),
+    ("accent", "color4"),
+    ("accent2", "color5"),
+    ("info", "color6"),
+];
+
+/// Theme keys filled from matugen's `--json hex` output, by Material role;
+/// the same mapping as the matugen template in `docs/config.toml`.
+const MATUGEN_KEYS: &[(&str, &str)] = &[
+    ("background", "surface"),
+    ("text", "on_surface"),
+    ("surface", "surface_container"),
+    ("hover", "surface_container_high"),
+    ("border", "outline"),
+    ("muted", "outline_variant"),
+    ("danger", "error"),
+    ("success", "tertiary"),
+    ("warning", "tertiary"),
+    ("accent", "primary"),
+    ("accent2", "secondary"),
+    ("info", "tertiary"),
+];
+
+/// Read the colors of a `theme.import` file, by theme key: pywal's
+/// `colors.json` or matugen's JSON output for `.json` files, a base16
+/// scheme (YAML) otherwise.
+fn import_colors(path: &Path) -> Result<HashMap<&'static str, String>, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let is_json = path
+        .extension()
+        .is_some_and(|extension| extension == "json");
+    let colors = if is_json {
+        json_colors(&content).map_err(|e| format!("{}: {}", path.display(), e))?
+    } else {
+        base16_colors(&content)
+    };
+    if colors.is_empty() {
+        return Err(format!("{}: no colors found", path.display()));
+    }
+    Ok(colors)
+}
+
+/// Colors of a base16 scheme. Only `baseXX: "rrggbb"` lines are read, at
+/// any indentation, so both the flat and the `palette:` layout work.
+fn base16_colors(content: &str) -> HashMap<&'static str, String> {
+    let slots: HashMap<String, String> = content
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let value = value.trim();
+            let value = match value.strip_prefix(['"', '\'']) {
+                Some(quoted) => quoted.split(['"', '\'']).next()?,
+                None => value.split_whitespace().next()?,
+            };
+            Some((key.trim().to_ascii_lowercase(), hex_color(value)?))
+        })
+        .collect();
+    BASE16_KEYS
+        .iter()
+        .filter_map(|(key, slot)| Some((*key, slots.get(&slot.to_ascii_lowercase())?.clone())))
+        .collect()
+}
+
+/// Colors of pywal's `colors.json` (it has `colors.color0`), or else of
+/// matugen's JSON output.
+fn json_colors(content: &str) -> Result<HashMap<&'static str, String>, String> {
+    let json: serde_json::Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+    let colors = &json["colors"];
+    if colors["color0"].is_string() {
+        return Ok(WAL_KEYS
+            .iter()
+            .filter_map(|(key, name)| {
+                let value = json["special"][*name]
+                    .as_str()
+                    .or_else(|| colors[*name].as_str())?;
+                Some((*key, hex_color(value)?))
+            })
+            .collect());
+    }
+
+    // Depending on the matugen version a color is `colors.<scheme>.<role>`
+    // or `colors.<role>.<scheme>`, optionally as `{ "hex": ... }`
+    let role = |role: &str| {
+        ["default", "dark", "light"].iter().find_map(|scheme| {
+            colors[*scheme][role]
+                .as_str()
+                .or_else(|| colors[role][*scheme].as_str())
+                .or_else(|| colors[role][*scheme]["hex"].as_str())
+        })
+    };
+    Ok(MATUGEN_KEYS
+        .iter()
+        .filter_map(|(key, name)| Some((*key, hex_color(role(name)?)?)))
+        .collect())
+}
+
+/// Normalize `rrggbb` or `#rrggbb` to `#rrggbb`.
+fn hex_color(value: &str) -> Option<String> {
+    let hex = value.trim().trim_start_matches('#');
+    (hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit())).then(|| format!("#{}", hex))
+}
+
+/// Parsed `[theme.<module>]` overrides.
+#[derive(Clone, Debug, Default)]
+pub struct ComponentTheme {
+    pub text: Option<Color>,
+    pub background: Option<Color>,
+    pub padding: Option<f32>,
+    pub border: Option<Color>,
+    pub border_width: f32,
+    pub border_radius: f32,
+    pub font: Option<Font>,
+    pub font_size: Option<f32>,
+    pub icons: Option<IconStyle>,
 }
 
 /// Cached theme with pre-parsed colors for performance.
@@ -37,44 +430,156 @@ pub struct AppTheme {
     text: Color,
     success: Color,
     danger: Color,
+    warning: Color,
     background: Color,
 
     // Non-color settings
+    font: Font,
     font_size: f32,
     tray_widget_spacing: f32,
     tray_widget_padding: f32,
+    bar_radius: f32,
+    popup_radius: f32,
+    border_width: f32,
+    border_color: BorderColor,
+    icons: IconStyle,
+    icon_dir: String,
+    tooltips: bool,
+
+    // Per-module overrides
+    components: HashMap<ModuleKind, ComponentTheme>,
 }
 
 impl Default for AppTheme {
     fn default() -> Self {
-        Self::from_config(&Config::default())
+        Self::from_config(&Config::default(), None)
     }
 }
 
 impl AppTheme {
-    pub fn from_config(config: &Config) -> Self {
-        let theme = &config.theme;
+    /// The theme of `config`, with the light or dark variant for `scheme`.
+    pub fn from_config(config: &Config, scheme: Option<ColorScheme>) -> Self {
+        let theme = &config.theme.for_scheme(scheme);
+        let palette = theme.preset.palette();
+        let imported = match theme.import_path() {
+            Some(path) => import_colors(&path).unwrap_or_else(|e| {
+                eprintln!("Failed to import theme: {}", e);
+                HashMap::new()
+            }),
+            None => HashMap::new(),
+        };
+        // A color set in the config wins over an imported one, which wins
+        // over the preset. Invalid colors (reported when the config is
+        // loaded) fall through to the next
+        let color_with_alpha =
+            |configured: &Option<String>, key: &str, preset: &'static str, alpha: f32| {
+                configured
+                    .iter()
+                    .chain(imported.get(key))
+                    .find_map(|value| color::parse_with_alpha(value, alpha).ok())
+                    .or_else(|| color::parse_with_alpha(preset, alpha).ok())
+                    .unwrap_or(Color::WHITE)
+            };
+        let color = |configured: &Option<String>, key: &str, preset: &'static str| {
+            color_with_alpha(configured, key, preset, 1.0)
+        };
         Self {
-            accent: parse_hex_color(&theme.accent),
-            accent2: parse_hex_color(&theme.accent2),
-            info: parse_hex_color(&theme.info),
-            surface: parse_hex_color_with_alpha(&theme.surface, theme.surface_alpha),
-            border: parse_hex_color(&theme.border),
-            muted: parse_hex_color(&theme.muted),
-            hover: parse_hex_color_with_alpha(&theme.hover, theme.hover_alpha),
-            text: parse_hex_color(&theme.text),
-            success: parse_hex_color(&theme.success),
-            danger: parse_hex_color(&theme.danger),
-            background: parse_hex_color_with_alpha(&theme.background, theme.background_alpha),
+            accent: color(&theme.accent, "accent", palette.accent),
+            accent2: color(&theme.accent2, "accent2", palette.accent2),
+            info: color(&theme.info, "info", palette.info),
+            surface: color_with_alpha(
+                &theme.surface,
+                "surface",
+                palette.surface,
+                theme.surface_alpha,
+            ),
+            border: color(&theme.border, "border", palette.border),
+            muted: color(&theme.muted, "muted", palette.muted),
+            hover: color_with_alpha(&theme.hover, "hover", palette.hover, theme.hover_alpha),
+            text: color(&theme.text, "text", palette.text),
+            success: color(&theme.success, "success", palette.success),
+            danger: color(&theme.danger, "danger", palette.danger),
+            warning: color(&theme.warning, "warning", palette.warning),
+            background: color_with_alpha(
+                &theme.background,
+                "background",
+                palette.background,
+                theme.background_alpha,
+            ),
+            font: theme
+                .font
+                .as_ref()
+                .and_then(|font| resolve_font(font.families()))
+                .unwrap_or(Font::MONOSPACE),
             font_size: theme.font_size,
             tray_widget_spacing: theme.tray_widget_spacing,
             tray_widget_padding: theme.tray_widget_padding,
+            bar_radius: theme.bar_radius,
+            popup_radius: theme.popup_radius,
+            border_width: theme.border_width,
+            border_color: theme.border_color,
+            icons: theme.icons,
+            icon_dir: match &theme.icon_dir {
+                Some(dir) => expand_home(dir),
+                None => config_path().with_file_name("icons"),
+            }
+            .to_string_lossy()
+            .into_owned(),
+            tooltips: config.tooltips,
+            components: theme
+                .components
+                .iter()
+                .map(|(kind, style)| {
+                    let component = ComponentTheme {
+                        text: style.text.as_deref().and_then(|text| color::parse(text).ok()),
+                        background: style.background.as_deref().and_then(|background| {
+                            color::parse_with_alpha(
+                                background,
+                                style.background_alpha.unwrap_or(1.0),
+                            )
+                            .ok()
+                        }),
+                        padding: style.padding,
+                        border: style
+                            .border
+                            .as_deref()
+                            .and_then(|border| color::parse(border).ok()),
+                        border_width: style.border_width.unwrap_or(1.0),
+                        border_radius: style.border_radius.unwrap_or(0.0),
+                        font: style
+                            .font
+                            .as_ref()
+                            .and_then(|font| resolve_font(font.families())),
+                        font_size: style.font_size,
+                        icons: style.icons,
+                    };
+                    (kind.clone(), component)
+                })
+                .collect(),
+        }
+    }
+
+    /// The `[theme.<module>]` overrides of a module, if it has any.
+    pub fn component(&self, kind: &ModuleKind) -> Option<&ComponentTheme> {
+        self.components.get(kind)
+    }
+
+    /// This theme with a module's text color, font, padding and icon style
+    /// overrides applied.
+    fn for_component(mut self, kind: &ModuleKind) -> Self {
+        if let Some(component) = self.components.get(kind) {
+            self.text = component.text.unwrap_or(self.text);
+            self.font = component.font.unwrap_or(self.font);
+            self.font_size = component.font_size.unwrap_or(self.font_size);
+            self.tray_widget_padding = component.padding.unwrap_or(self.tray_widget_padding);
+            self.icons = component.icons.unwrap_or(self.icons);
         }
+        self
     }
 
-    /// Update theme from new config (re-parses all colors)
-    pub fn update(&mut self, config: &Config) {
-        *self = Self::from_config(config);
+    /// Update theme from new config or scheme (re-parses all colors)
+    pub fn update(&mut self, config: &Config, scheme: Option<ColorScheme>) {
+        *self = Self::from_config(config, scheme);
     }
 
     /// Blue accent color
@@ -127,11 +632,21 @@ impl AppTheme {
         self.danger
     }
 
+    /// Warning/yellow color
+    pub fn warning(&self) -> Color {
+        self.warning
+    }
+
     /// Background color with alpha
     pub fn background(&self) -> Color {
         self.background
     }
 
+    /// Font of bar text
+    pub fn font(&self) -> Font {
+        self.font
+    }
+
     /// Font size in pixels
     pub fn font_size(&self) -> f32 {
         self.font_size
@@ -146,6 +661,44 @@ impl AppTheme {
     pub fn tray_widget_padding(&self) -> f32 {
         self.tray_widget_padding
     }
+
+    /// Corner radius of the bar in pixels
+    pub fn bar_radius(&self) -> f32 {
+        self.bar_radius
+    }
+
+    /// Corner radius of popups in pixels
+    pub fn popup_radius(&self) -> f32 {
+        self.popup_radius
+    }
+
+    /// Border width of the bar and popups in pixels
+    pub fn border_width(&self) -> f32 {
+        self.border_width
+    }
+
+    /// Color of the bar and popup borders
+    pub fn frame_border(&self) -> Color {
+        match self.border_color {
+            BorderColor::Accent => self.accent,
+            BorderColor::Border => self.border,
+        }
+    }
+
+    /// Style of the widget icons
+    pub fn icons(&self) -> IconStyle {
+        self.icons
+    }
+
+    /// Directory of the image icons
+    pub fn icon_dir(&self) -> &str {
+        &self.icon_dir
+    }
+
+    /// Whether widgets show hover tooltips
+    pub fn tooltips(&self) -> bool {
+        self.tooltips
+    }
 }
 
 impl From<&AppTheme> for Theme {
@@ -162,3 +715,58 @@ impl From<&AppTheme> for Theme {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(toml: &str) -> Config {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn invalid_colors_fall_back_to_the_preset() {
+        let theme = AppTheme::from_config(
+            &config("[theme]\npreset = \"nord\"\naccent = \"#12\"\ntext = \"white\""),
+            None,
+        );
+        assert_eq!(Some(theme.accent()), color::parse(NORD.accent).ok());
+        assert_eq!(theme.text(), Color::WHITE);
+    }
+
+    #[test]
+    fn variant_applies_for_its_scheme() {
+        let config = config("[theme]\naccent = \"red\"\n[theme.dark]\naccent = \"blue\"");
+        let dark = AppTheme::from_config(&config, Some(ColorScheme::Dark));
+        let light = AppTheme::from_config(&config, Some(ColorScheme::Light));
+        assert_eq!(dark.accent(), Color::from_rgb8(0, 0, 255));
+        assert_eq!(light.accent(), Color::from_rgb8(255, 0, 0));
+    }
+
+    #[test]
+    fn component_overrides_apply_in_its_scope() {
+        let theme = AppTheme::from_config(
+            &config("[theme]\nfont_size = 14\n[theme.clock]\nfont_size = 20\nfont = \"serif\""),
+            None,
+        );
+        set_global_theme(&theme);
+        assert_eq!(get_theme().font_size(), 14.0);
+        let (font_size, font) = with_component(&ModuleKind::Clock, || {
+            let theme = get_theme();
+            (theme.font_size(), theme.font())
+        });
+        assert_eq!(font_size, 20.0);
+        assert_eq!(font.family, Family::Serif);
+        assert_eq!(
+            with_component(&ModuleKind::Battery, || get_theme().font_size()),
+            14.0
+        );
+    }
+
+    #[test]
+    fn font_lists_skip_missing_families() {
+        let families = ["Surely Not Installed".to_string(), "monospace".to_string()];
+        assert_eq!(resolve_font(&families), Some(Font::MONOSPACE));
+        assert_eq!(resolve_font(&[]), None);
+    }
+}